@@ -1,12 +1,19 @@
+use std::fs;
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
 use std::fmt;
 use std::error::Error;
 use serde_json;
-use regex::Regex;
-use termc_model::math_context::MathContext;
-use termc_ui::FormatType;
+use serde_json::{Map, Value};
+use regex::{Regex, Captures};
+use termc_model::{get_result, get_statement_results, get_latex, get_sympy, from_sympy, get_derivative, get_simplified, iterate,
+                   check_identity, sample_function, freeze_user_function, split_statements, create_location_string};
+use termc_model::math_context::{MathContext, ComparisonMode, PrecisionMode, AngleMode};
+use termc_ui::{FormatType, get_format_names, normalize_text};
 use termc_ui::TerminalUI;
+use session::AutosaveMode;
+use settings;
+use plot;
 
 
 /// Defines the commands.
@@ -19,19 +26,171 @@ pub enum CommandType {
     Save(String),
     /// The format command (number format).
     Format(FormatType),
-    /// The Info command that lists all user defined constants and functions.
-    Info
+    /// The compare command (comparison mode for complex numbers).
+    Compare(ComparisonMode),
+    /// The precision command (precision mode for real numbers).
+    Precision(PrecisionMode),
+    /// The mode command (unit of angles of the trigonometric functions).
+    Mode(AngleMode),
+    /// The Info command that lists all user defined constants and functions (JSON output).
+    Info(bool),
+    /// The latex command that prints the LaTeX representation of a user function or an expression (input).
+    Latex(String),
+    /// The gc command that lists (and deletes) the unused user definitions (prune).
+    Gc(bool),
+    /// The del command that deletes user defined constants and functions (names).
+    Del(Vec<String>),
+    /// The freeze command that replaces the constants in the definition of a user function by their current values (name).
+    Freeze(String),
+    /// The sympy command that prints the SymPy representation of a user function or an expression
+    /// or evaluates a SymPy-style expression (input, import).
+    Sympy(String, bool),
+    /// The diff command that prints the derivative of a user function or an expression (input, variable).
+    Diff(String, String),
+    /// The simplify command that prints the algebraically simplified expression (input).
+    Simplify(String),
+    /// The iterate command that repeatedly evaluates an assignment (name, expression, start value, maximum number of
+    /// iterations, tolerance).
+    Iterate(String, String, String, usize, Option<f64>),
+    /// The "++" and "--" commands that increment or decrement the previous result "ans" by 1 (step).
+    AnsStep(i32),
+    /// The identity command that checks an equation numerically at random sample points (equation).
+    Identity(String),
+    /// The plot command that plots an expression in a single variable between two bounds (expression, lower bound,
+    /// upper bound, number of samples).
+    Plot(String, String, String, usize),
+    /// The whatis command that describes a constant, function or operation (name).
+    WhatIs(String),
+    /// The numbering command that enables or disables the numbering of the evaluations (numbering).
+    Numbering(bool),
+    /// The get savepath command that prints the default file path of the save and load commands (path).
+    SavePath(String),
+    /// The set dualdisplay command that enables or disables the additional decimal output of non-decimal formats (dual display).
+    DualDisplay(bool),
+    /// The set optimize command that enables or disables the constant folding of user function definitions (optimize).
+    Optimize(bool),
+    /// The set permissive command that enables or disables the permissive mode of the built-in functions (permissive).
+    Permissive(bool),
+    /// The set autosave command that sets the interval of the automatic snapshots of the context (autosave mode).
+    Autosave(AutosaveMode),
+    /// The set startup command that sets the file path of the context that is loaded at startup (path).
+    Startup(String),
+    /// The set saveonexit command that enables or disables saving the context to the startup file on exit (save on exit).
+    SaveOnExit(bool),
+    /// The set normalizehistory command that enables or disables the normalization of the history entries (normalize).
+    NormalizeHistory(bool),
+    /// The set historysize command that sets the maximum number of history entries during a session (number of entries).
+    HistorySize(usize),
+    /// The set historyfilesize command that sets the maximum number of entries of the history file (number of entries).
+    HistoryFileSize(usize),
+    /// The set historyignoredups command that enables or disables ignoring duplicate history entries (ignore duplicates).
+    HistoryIgnoreDups(bool),
+    /// The set historyignorespace command that enables or disables ignoring the inputs that start with a whitespace in the
+    /// history (ignore space).
+    HistoryIgnoreSpace(bool),
+    /// The set autoans command that enables or disables the continuation of the previous result by inputs that start with
+    /// a binary operation (auto ans).
+    AutoAns(bool),
+    /// The set assignvalue command that enables or disables the assignments inside of expressions, which yield the assigned
+    /// value (assignment values).
+    AssignValue(bool),
+    /// The set nantracking command that enables or disables the tracking of the origins of NaN and infinite values (tracking).
+    NanTracking(bool),
+    /// The why command that prints the origin of the first NaN or infinite value of the last evaluation.
+    Why,
+    /// The set grouping command that sets the digit separator of the decimal and radix formats (None if the digits are not grouped).
+    Grouping(Option<char>),
+    /// The set command that changes a setting that is applied by the command of the same name (key, value).
+    Set(String, String),
+    /// The replay command that evaluates the inputs of a range of previous evaluations again (first, last).
+    Replay(usize, usize),
+    /// The run command that evaluates the expressions and commands of a script file line by line (path).
+    Run(String),
+    /// The history command that lists or searches the previous user inputs or runs one of them again
+    /// (index of the entry that is run again).
+    History(Option<usize>)
 }
 
+/// Defines the maximum number of iterations of the iterate command if no number is specified.
+const DEFAULT_MAX_ITERATIONS : usize = 1000;
+
+/// Defines the maximum number of samples of the plot command.
+const MAX_PLOT_SAMPLES : usize = 100000;
+
+/// Defines the maximum nesting depth of scripts that are run by scripts (guards against scripts that run themselves).
+const MAX_RUN_DEPTH : usize = 16;
+
 /// The CommandError enum.
 #[derive(Debug)]
 pub enum CommandError {
-    /// Error that occurs when an unknown format is requested (e.g. the user types: "format abc")
+    /// Error that occurs when an unknown format or an unexpected argument is specified (e.g. the user types: "format abc")
+    /// (formatted error).
     FormatError(String),
+    /// Error that occurs when an unknown comparison mode is requested (e.g. the user types: "compare abc")
+    /// (formatted error).
+    CompareError(String),
+    /// Error that occurs when an unknown precision mode is requested (e.g. the user types: "precision abc")
+    /// (formatted error).
+    PrecisionError(String),
+    /// Error that occurs when an unknown angle mode is requested (e.g. the user types: "mode abc")
+    /// (formatted error).
+    ModeError(String),
     /// Error that occurs when the loading of a serialized MathContext from a file or the deseialization process fails.
     LoadSerError(String),
     /// Error that occurs when the serialization of the MathContext or the writing of the target file fails.
-    SaveSerError(String)
+    SaveSerError(String),
+    /// Error that occurs when a command line option (e.g. "--define" or "--timeout") is invalid.
+    OptionError(String),
+    /// Error that occurs when the expression of the latex command can not be parsed (formatted parse error).
+    LatexError(String),
+    /// Error that occurs when the expression of the sympy command can not be parsed or evaluated (formatted error).
+    SympyError(String),
+    /// Error that occurs when the expression of the diff command can not be differentiated (formatted error).
+    DiffError(String),
+    /// Error that occurs when the expression of the simplify command can not be parsed (formatted parse error).
+    SimplifyError(String),
+    /// Error that occurs when the iterate command has an invalid tolerance or its assignment can not be evaluated (formatted error).
+    IterateError(String),
+    /// Error that occurs when there is no previous result to increment or decrement or it can not be changed by 1
+    /// (e.g. a matrix) (formatted error).
+    AnsStepError(String),
+    /// Error that occurs when the equation of the identity command can not be parsed or evaluated (formatted error).
+    IdentityError(String),
+    /// Error that occurs when the expression or the bounds of the plot command can not be evaluated (formatted error).
+    PlotError(String),
+    /// Error that occurs when an unknown numbering mode is requested (e.g. the user types: "numbering abc")
+    /// (formatted error).
+    NumberingError(String),
+    /// Error that occurs when an invalid autosave interval is requested (e.g. the user types: "set autosave abc")
+    /// (formatted error).
+    AutosaveError(String),
+    /// Error that occurs when the why command is used while the origins of NaN and infinite values are not tracked
+    /// (formatted error).
+    WhyError(String),
+    /// Error that occurs when an unknown digit grouping mode is requested (e.g. the user types: "set grouping abc")
+    /// (formatted error).
+    GroupingError(String),
+    /// Error that occurs when an unknown mode of an on/off setting is requested (e.g. the user types: "set optimize abc")
+    /// (formatted error).
+    SwitchError(String),
+    /// Error that occurs when a command that accesses the file system is used in sandbox mode (e.g. the user types: "save")
+    /// (formatted error).
+    PermissionDenied(String),
+    /// Error that occurs when an unknown setting is changed or the settings file can not be read or written
+    /// (formatted error).
+    SettingsError(String),
+    /// Error that occurs when the range of the replay command is invalid or a replayed input fails.
+    ReplayError(String),
+    /// Error that occurs when the script file of the run command can not be read or one of its lines fails (formatted error).
+    RunError(String),
+    /// Error that occurs when a name of the del command is built-in or not defined (formatted error).
+    DelError(String),
+    /// Error that occurs when the name of the freeze command is not a user defined function or the value of one of its
+    /// constants can not be inserted (formatted error).
+    FreezeError(String),
+    /// Error that occurs when the history is not available (e.g. in call mode) or the history entry is invalid
+    /// (formatted error).
+    HistoryError(String)
 }
 
 impl Error for CommandError {
@@ -39,8 +198,32 @@ impl Error for CommandError {
     fn description(& self) -> & str {
         match *self {
             CommandError::FormatError(_) => "Unknown number format.",
+            CommandError::CompareError(_) => "Unknown comparison mode.",
+            CommandError::PrecisionError(_) => "Unknown precision mode.",
+            CommandError::ModeError(_) => "Unknown angle mode.",
             CommandError::LoadSerError(_) => "Loading of serialization file failed.",
-            CommandError::SaveSerError(_) => "Saving of serialization file failed."
+            CommandError::SaveSerError(_) => "Saving of serialization file failed.",
+            CommandError::OptionError(_) => "Invalid command line option.",
+            CommandError::LatexError(_) => "Conversion to LaTeX failed.",
+            CommandError::SympyError(_) => "Conversion from or to SymPy failed.",
+            CommandError::DiffError(_) => "Differentiation failed.",
+            CommandError::SimplifyError(_) => "Simplification failed.",
+            CommandError::IterateError(_) => "Iteration failed.",
+            CommandError::AnsStepError(_) => "Increment or decrement of the previous result failed.",
+            CommandError::IdentityError(_) => "Identity check failed.",
+            CommandError::PlotError(_) => "Plot failed.",
+            CommandError::NumberingError(_) => "Unknown numbering mode.",
+            CommandError::AutosaveError(_) => "Invalid autosave interval.",
+            CommandError::GroupingError(_) => "Unknown digit grouping mode.",
+            CommandError::SwitchError(_) => "Unknown switch mode.",
+            CommandError::WhyError(_) => "Origin of NaN or infinite values not available.",
+            CommandError::PermissionDenied(_) => "Command not permitted in sandbox mode.",
+            CommandError::SettingsError(_) => "Changing the settings failed.",
+            CommandError::ReplayError(_) => "Replay failed.",
+            CommandError::RunError(_) => "Running the script failed.",
+            CommandError::DelError(_) => "Deletion of the definition failed.",
+            CommandError::FreezeError(_) => "Freezing of the function failed.",
+            CommandError::HistoryError(_) => "History not available."
         }
     }
 
@@ -48,8 +231,32 @@ impl Error for CommandError {
     fn cause(& self) -> Option<& Error> {
         match *self {
             CommandError::FormatError(_) => None,
+            CommandError::CompareError(_) => None,
+            CommandError::PrecisionError(_) => None,
+            CommandError::ModeError(_) => None,
             CommandError::LoadSerError(_) => None,
-            CommandError::SaveSerError(_) => None
+            CommandError::SaveSerError(_) => None,
+            CommandError::OptionError(_) => None,
+            CommandError::LatexError(_) => None,
+            CommandError::SympyError(_) => None,
+            CommandError::DiffError(_) => None,
+            CommandError::SimplifyError(_) => None,
+            CommandError::IterateError(_) => None,
+            CommandError::AnsStepError(_) => None,
+            CommandError::IdentityError(_) => None,
+            CommandError::PlotError(_) => None,
+            CommandError::NumberingError(_) => None,
+            CommandError::AutosaveError(_) => None,
+            CommandError::GroupingError(_) => None,
+            CommandError::SwitchError(_) => None,
+            CommandError::WhyError(_) => None,
+            CommandError::PermissionDenied(_) => None,
+            CommandError::SettingsError(_) => None,
+            CommandError::ReplayError(_) => None,
+            CommandError::RunError(_) => None,
+            CommandError::DelError(_) => None,
+            CommandError::FreezeError(_) => None,
+            CommandError::HistoryError(_) => None
         }
     }
 }
@@ -58,41 +265,503 @@ impl fmt::Display for CommandError {
     /// Implements the Display trait for CommandError.
     fn fmt(& self, f: & mut fmt::Formatter) -> fmt::Result {
         match self {
-            &CommandError::FormatError(ref form) => {
-                let c = (form.chars().count() as i32) - 1;
-                let mut spaces = String::new();
-                for _ in 0..c {
-                    spaces.push(' ');
-                }
-                write!(f, "           {0}^~~~ Error: Unknown format \"{1}\"", spaces, form)
-            },
+            &CommandError::LoadSerError(ref err) | &CommandError::SaveSerError(ref err)
+            | &CommandError::OptionError(ref err) | &CommandError::ReplayError(ref err) => write!(f, "Error: {0}.", err),
 
-            &CommandError::LoadSerError(ref err) | &CommandError::SaveSerError(ref err) => write!(f, "Error: {0}.", err)
+            &CommandError::FormatError(ref err) | &CommandError::AutosaveError(ref err)
+            | &CommandError::SettingsError(ref err)
+            | &CommandError::GroupingError(ref err) | &CommandError::SwitchError(ref err)
+            | &CommandError::ModeError(ref err)
+            | &CommandError::NumberingError(ref err)
+            | &CommandError::PrecisionError(ref err)
+            | &CommandError::CompareError(ref err)
+            | &CommandError::WhyError(ref err)
+            | &CommandError::PermissionDenied(ref err)
+            | &CommandError::LatexError(ref err) | &CommandError::SympyError(ref err)
+            | &CommandError::DiffError(ref err) | &CommandError::SimplifyError(ref err) | &CommandError::IterateError(ref err) | &CommandError::AnsStepError(ref err)
+            | &CommandError::IdentityError(ref err)
+            | &CommandError::PlotError(ref err)
+            | &CommandError::RunError(ref err) | &CommandError::DelError(ref err) | &CommandError::FreezeError(ref err)
+            | &CommandError::HistoryError(ref err) => write!(f, "{0}", err)
         }
     }
 }
 
 /// Checks whether the specified input string represents a command.
 pub fn check_for_command(s: & str, context: & mut MathContext, terminal: & mut TerminalUI, default_file: String) -> Result<Option<CommandType>, CommandError> {
+    check_for_nested_command(s, context, terminal, default_file, 0)
+}
+
+/// Checks whether the specified input string represents a command. The run depth is the number of scripts that are
+/// currently running.
+fn check_for_nested_command(s: & str, context: & mut MathContext, terminal: & mut TerminalUI, default_file: String,
+                            run_depth: usize) -> Result<Option<CommandType>, CommandError> {
 
     lazy_static!{
         static ref REGEX_EXIT : Regex = Regex::new("^exit$").unwrap();
         static ref REGEX_SAVE : Regex = Regex::new(r"^save(\s+(?P<path>.*))?$").unwrap();
+        static ref REGEX_RUN : Regex = Regex::new(r"^run\s+(?P<path>.+)$").unwrap();
         static ref REGEX_LOAD : Regex = Regex::new(r"^load(\s+(?P<path>.*))?$").unwrap();
         static ref REGEX_FORMAT : Regex = Regex::new(r"^format(\s+(?P<format>.*))?$").unwrap();
-        static ref REGEX_INFO : Regex = Regex::new(r"^info$").unwrap();
+        static ref REGEX_COMPARE : Regex = Regex::new(r"^compare(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_PRECISION : Regex = Regex::new(r"^precision(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_MODE : Regex = Regex::new(r"^mode(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_INFO : Regex = Regex::new(r"^info(?P<json>\s+--json)?$").unwrap();
+        static ref REGEX_GC : Regex = Regex::new(r"^gc(?P<prune>\s+--prune)?$").unwrap();
+        static ref REGEX_DEL : Regex = Regex::new(r"^del(\s+[\w\s]*)?$").unwrap();
+        static ref REGEX_FREEZE : Regex = Regex::new(r"^freeze(\s+(?P<name>\S+))?$").unwrap();
+        static ref REGEX_LATEX : Regex = Regex::new(r"^latex\s+(?P<input>.+)$").unwrap();
+        static ref REGEX_SYMPY : Regex = Regex::new(r"^sympy(?P<import>\s+--import)?\s+(?P<input>.+)$").unwrap();
+        static ref REGEX_DIFF : Regex = Regex::new(r"^diff\s*\((?P<args>.+)\)$").unwrap();
+        static ref REGEX_SIMPLIFY : Regex = Regex::new(r"^simplify\s*\((?P<input>.+)\)$").unwrap();
+        static ref REGEX_IDENTITY : Regex = Regex::new(r"^identity\s+(?P<input>.+)$").unwrap();
+        static ref REGEX_PLOT : Regex = Regex::new(
+            r"^plot\s+(?P<expr>.+?)\s+from\s+(?P<from>.+?)\s+to\s+(?P<to>.+?)(\s+samples\s+(?P<samples>\d+))?$").unwrap();
+        static ref REGEX_WHATIS : Regex = Regex::new(r"^whatis\s+(?P<name>\S+)$").unwrap();
+        static ref REGEX_NUMBERING : Regex = Regex::new(r"^numbering(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_SAVE_PATH : Regex = Regex::new(r"^get\s+savepath$").unwrap();
+        static ref REGEX_DUAL_DISPLAY : Regex = Regex::new(r"^set\s+dualdisplay(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_OPTIMIZE : Regex = Regex::new(r"^set\s+optimize(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_PERMISSIVE : Regex = Regex::new(r"^set\s+permissive(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_AUTOSAVE : Regex = Regex::new(r"^set\s+autosave(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_STARTUP : Regex = Regex::new(r"^set\s+startup(\s+(?P<path>.*))?$").unwrap();
+        static ref REGEX_SAVE_ON_EXIT : Regex = Regex::new(r"^set\s+saveonexit(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_NORMALIZE_HISTORY : Regex = Regex::new(r"^set\s+normalizehistory(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_HISTORY_SIZE : Regex = Regex::new(r"^set\s+historysize(\s+(?P<n>.*))?$").unwrap();
+        static ref REGEX_HISTORY_FILE_SIZE : Regex = Regex::new(r"^set\s+historyfilesize(\s+(?P<n>.*))?$").unwrap();
+        static ref REGEX_HISTORY_IGNORE_DUPS : Regex = Regex::new(r"^set\s+historyignoredups(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_HISTORY_IGNORE_SPACE : Regex = Regex::new(r"^set\s+historyignorespace(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_AUTO_ANS : Regex = Regex::new(r"^set\s+autoans(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_ASSIGN_VALUE : Regex = Regex::new(r"^set\s+assignvalue(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_NAN_TRACKING : Regex = Regex::new(r"^set\s+nantracking(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_WHY : Regex = Regex::new("^why$").unwrap();
+        static ref REGEX_GROUPING : Regex = Regex::new(r"^set\s+grouping(\s+(?P<mode>.*))?$").unwrap();
+        static ref REGEX_SET : Regex = Regex::new(r"^set\s+(?P<key>\S+)(\s+(?P<value>.*))?$").unwrap();
+        static ref REGEX_AUTOSAVE_INTERVAL : Regex = Regex::new(r"^(?P<n>\d+)\s*(?P<unit>min|assignments)$").unwrap();
+        static ref REGEX_HISTORY : Regex = Regex::new(r"^history(\s+(?P<arg>.*))?$").unwrap();
+        static ref REGEX_REPLAY : Regex = Regex::new(r"^replay\s+(?P<first>\d+)(\s*\.\.\s*(?P<last>\d+))?$").unwrap();
+        static ref REGEX_ANS_STEP : Regex = Regex::new(r"^(ans)?\s*(?P<op>\+\+|--)$").unwrap();
+        static ref REGEX_ITERATE : Regex = Regex::new(
+            r"^iterate\s+(?P<name>\w+)\s*=\s*(?P<expr>.+?)\s+from\s+(?P<start>.+?)(\s+times\s+(?P<times>\d+))?(\s+until\s+(?P<tol>\S+))?$").unwrap();
+    }
+
+    // the commands that access the file system are rejected in sandbox mode
+    let is_file_command = REGEX_SAVE.is_match(s) || REGEX_LOAD.is_match(s) || REGEX_RUN.is_match(s) || REGEX_AUTOSAVE.is_match(s)
+        || REGEX_STARTUP.is_match(s) || REGEX_SAVE_ON_EXIT.is_match(s);
+    if context.get_sandbox() && is_file_command {
+        let command = s.split_whitespace().take(if s.starts_with("set") { 2 } else { 1 }).collect::<Vec<&str>>().join(" ");
+        return Err(CommandError::PermissionDenied(format!("Error: The command \"{0}\" is not permitted in sandbox mode.\n{1}",
+            command, create_location_string(s, 0))));
     }
 
     if REGEX_EXIT.is_match(s) {
         Ok(Some(CommandType::Exit)) // signal exit
     }
-    else if REGEX_INFO.is_match(s) {
-        print_info(context, terminal); // print information about user defined symbols
-        Ok(Some(CommandType::Info))
+    else if let Some(cap) = REGEX_INFO.captures(s) {
+        let json = cap.name("json").is_some();
+        print_info(context, terminal, json); // print information about user defined symbols
+        Ok(Some(CommandType::Info(json)))
+    }
+    else if let Some(cap) = REGEX_GC.captures(s) {
+        let prune = cap.name("prune").is_some();
+        collect_garbage(context, terminal, prune);
+        Ok(Some(CommandType::Gc(prune)))
+    }
+    else if REGEX_DEL.is_match(s) {
+        let names = delete_definitions(s, context)?;
+        Ok(Some(CommandType::Del(names)))
+    }
+    else if let Some(cap) = REGEX_FREEZE.captures(s) {
+        let name = match cap.name("name") {
+            Some(m) if context.is_user_function(m.as_str()) => m.as_str(),
+            Some(m) => return Err(CommandError::FreezeError(format!("Error: Unknown user defined function \"{0}\".\n{1}",
+                                                                     m.as_str(), create_location_string(s, s.chars().count() - 1)))),
+            None => return Err(CommandError::FreezeError(format!("Error: Expected the name of a user defined function.\n{0}",
+                                                                  create_location_string(s, s.chars().count()))))
+        };
+        let frozen = freeze_user_function(name, context).map_err(|e| CommandError::FreezeError(e.to_string()))?;
+        if frozen.is_empty() {
+            terminal.print(&format!("The function \"{0}\" does not depend on constants.\n", name));
+        }
+        else {
+            let names : Vec<String> = frozen.iter().map(|c| format!("\"{0}\"", c)).collect();
+            terminal.print(&format!("Inserted the current value(s) of {0} into the function \"{1}\".\n", names.join(", "), name));
+        }
+        Ok(Some(CommandType::Freeze(name.to_string())))
+    }
+    else if let Some(cap) = REGEX_LATEX.captures(s) {
+        let input = cap.name("input").unwrap().as_str();
+        let latex = get_latex(input, context).map_err(|e| CommandError::LatexError(e.to_string()))?;
+        terminal.print(&format!("{0}\n", latex)); // print the LaTeX representation
+        Ok(Some(CommandType::Latex(input.to_string())))
+    }
+    else if let Some(cap) = REGEX_SYMPY.captures(s) {
+        let input = cap.name("input").unwrap().as_str();
+        let import = cap.name("import").is_some();
+        if import {
+            // evaluate the SymPy-style expression like a regular user input
+            let result = get_result(&from_sympy(input), context);
+            print_warnings(context, terminal);
+            match result {
+                Ok(Some(result)) => terminal.print_result(&result),
+                Ok(None) => terminal.print_empty_result(),
+                Err(e) => return Err(CommandError::SympyError(e.to_string()))
+            }
+        }
+        else {
+            let sympy = get_sympy(input, context).map_err(|e| CommandError::SympyError(e.to_string()))?;
+            terminal.print(&format!("{0}\n", sympy)); // print the SymPy representation
+        }
+        Ok(Some(CommandType::Sympy(input.to_string(), import)))
+    }
+    else if let Some(cap) = REGEX_DIFF.captures(s) {
+        let args = cap.name("args").unwrap().as_str();
+        let (input, var) = match split_diff_args(args) {
+            Some(x) => x,
+            None => return Err(CommandError::DiffError(String::from("Error: Expected the variable of differentiation (e.g. \"diff(x^2, x)\").")))
+        };
+        let derivative = get_derivative(input, var, context).map_err(|e| CommandError::DiffError(e.to_string()))?;
+        terminal.print(&format!("{0}\n", derivative)); // print the derivative
+        Ok(Some(CommandType::Diff(input.to_string(), var.trim().to_string())))
+    }
+    else if let Some(cap) = REGEX_SIMPLIFY.captures(s) {
+        let input = cap.name("input").unwrap().as_str();
+        let simplified = get_simplified(input, context).map_err(|e| CommandError::SimplifyError(e.to_string()))?;
+        terminal.print(&format!("{0}\n", simplified)); // print the simplified expression
+        Ok(Some(CommandType::Simplify(input.to_string())))
+    }
+    else if let Some(cap) = REGEX_ANS_STEP.captures(s) {
+        let op = cap.name("op").unwrap();
+        let step = if op.as_str() == "++" { 1 } else { -1 };
+        if context.get_constant_value("ans").is_none() {
+            return Err(CommandError::AnsStepError(format!("Error: Expected a previous result \"ans\" to {0}.\n{1}",
+                if step > 0 { "increment" } else { "decrement" }, create_location_string(s, op.start()))));
+        }
+        // the command is a shorthand for "ans + 1" or "ans - 1", so the result becomes the new previous result
+        let result = get_result(if step > 0 { "ans + 1" } else { "ans - 1" }, context);
+        print_warnings(context, terminal);
+        match result {
+            Ok(Some(result)) => terminal.print_result(&result),
+            Ok(None) => terminal.print_empty_result(),
+            Err(e) => return Err(CommandError::AnsStepError(e.to_string()))
+        }
+        Ok(Some(CommandType::AnsStep(step)))
+    }
+    else if let Some(cap) = REGEX_ITERATE.captures(s) {
+        let name = cap.name("name").unwrap().as_str();
+        let expr = cap.name("expr").unwrap().as_str();
+        let start = cap.name("start").unwrap().as_str();
+        let times = match cap.name("times").map(|t| t.as_str().parse::<usize>()) {
+            Some(Ok(t)) => t,
+            Some(Err(_)) => return Err(CommandError::IterateError(String::from("Error: The number of iterations is too large."))),
+            None => DEFAULT_MAX_ITERATIONS
+        };
+        let tolerance = match cap.name("tol").map(|t| t.as_str().parse::<f64>()) {
+            Some(Ok(t)) if t >= 0.0 => Some(t),
+            Some(_) => return Err(CommandError::IterateError(format!("Error: Expected a non-negative tolerance (found \"{0}\").",
+                                                                      cap.name("tol").unwrap().as_str()))),
+            None => None
+        };
+        let (value, n, converged) = iterate(name, expr, start, times, tolerance, context)
+            .map_err(|e| CommandError::IterateError(e.to_string()))?;
+        if converged {
+            terminal.print(&format!("Converged after {0} iteration(s).\n", n));
+        }
+        else if tolerance.is_some() {
+            terminal.print(&format!("Not converged after {0} iteration(s).\n", n));
+        }
+        else {
+            terminal.print(&format!("Stopped after {0} iteration(s).\n", n));
+        }
+        terminal.print_result(&value);
+        Ok(Some(CommandType::Iterate(name.to_string(), expr.to_string(), start.to_string(), times, tolerance)))
+    }
+    else if let Some(cap) = REGEX_IDENTITY.captures(s) {
+        let input = cap.name("input").unwrap().as_str();
+        let check = check_identity(input, context).map_err(|e| CommandError::IdentityError(e.to_string()))?;
+        if check.failures == 0 {
+            terminal.print(&format!("The identity holds at {0} sample point(s).\n", check.samples));
+        }
+        else {
+            terminal.print(&format!("The identity does not hold at {0} of {1} sample point(s), e.g.:\n", check.failures, check.samples));
+            for &(ref point, ref lhs, ref rhs) in check.counterexamples.iter() {
+                let values : Vec<String> = check.variables.iter().zip(point.iter()).map(|(v, x)| format!("{0} = {1}", v, x)).collect();
+                if values.is_empty() {
+                    terminal.print(&format!("{0} != {1}\n", lhs, rhs));
+                }
+                else {
+                    terminal.print(&format!("{0}: {1} != {2}\n", values.join(", "), lhs, rhs));
+                }
+            }
+        }
+        Ok(Some(CommandType::Identity(input.to_string())))
+    }
+    else if let Some(cap) = REGEX_PLOT.captures(s) {
+        let expr = cap.name("expr").unwrap().as_str();
+        let from = cap.name("from").unwrap().as_str();
+        let to = cap.name("to").unwrap().as_str();
+        let (columns, lines) = plot::get_terminal_size();
+        let samples = match cap.name("samples").map(|n| n.as_str().parse::<usize>()) {
+            Some(Ok(n)) if n >= 2 && n <= MAX_PLOT_SAMPLES => n,
+            Some(_) => return Err(CommandError::PlotError(format!("Error: Expected 2 to {0} samples (found \"{1}\").",
+                                                                    MAX_PLOT_SAMPLES, cap.name("samples").unwrap().as_str()))),
+            None => 2 * columns // one sample per column of braille dots
+        };
+        let function = sample_function(expr, from, to, samples, context).map_err(|e| CommandError::PlotError(e.to_string()))?;
+        match plot::render(&function.points, columns, lines) {
+            Some(p) => terminal.print(&p),
+            None => return Err(CommandError::PlotError(format!(
+                "Error: The expression \"{0}\" has no finite real values from {1} to {2}.", expr, from, to)))
+        }
+        Ok(Some(CommandType::Plot(expr.to_string(), from.to_string(), to.to_string(), samples)))
+    }
+    else if let Some(cap) = REGEX_WHATIS.captures(s) {
+        let name = cap.name("name").unwrap().as_str();
+        let description = context.get_symbol_description(name).unwrap_or(String::from("unknown symbol"));
+        terminal.print(&format!("{0}\n", description));
+        Ok(Some(CommandType::WhatIs(name.to_string())))
+    }
+    else if let Some(cap) = REGEX_NUMBERING.captures(s) {
+        let numbering = match cap.name("mode") {
+            Some(m) if m.as_str() == "on" => true,
+            Some(m) if m.as_str() == "off" => false,
+            Some(m) => {
+                return Err(CommandError::NumberingError(format!(
+                    "Error: Unknown numbering mode \"{0}\" (expected \"on\" or \"off\").\n{1}",
+                    m.as_str(), create_location_string(s, s[..m.end()].chars().count().saturating_sub(1)))));
+            },
+            None => {
+                return Err(CommandError::NumberingError(format!(
+                    "Error: Expected numbering mode \"on\" or \"off\".\n{0}", create_location_string(s, s.chars().count()))));
+            }
+        };
+        terminal.set_numbering(numbering);
+        Ok(Some(CommandType::Numbering(numbering)))
+    }
+    else if REGEX_SAVE_PATH.is_match(s) {
+        terminal.print(&format!("{0}\n", default_file)); // print the active default file path
+        Ok(Some(CommandType::SavePath(default_file)))
+    }
+    else if let Some(cap) = REGEX_DUAL_DISPLAY.captures(s) {
+        let dual_display = parse_on_off(s, &cap, "dual display")?;
+        terminal.set_dual_display(dual_display);
+        Ok(Some(CommandType::DualDisplay(dual_display)))
+    }
+    else if let Some(cap) = REGEX_OPTIMIZE.captures(s) {
+        let optimize = parse_on_off(s, &cap, "optimization")?;
+        context.set_optimize(optimize);
+        Ok(Some(CommandType::Optimize(optimize)))
+    }
+    else if let Some(cap) = REGEX_PERMISSIVE.captures(s) {
+        let permissive = parse_on_off(s, &cap, "permissive")?;
+        context.set_permissive(permissive);
+        Ok(Some(CommandType::Permissive(permissive)))
+    }
+    else if let Some(cap) = REGEX_AUTOSAVE.captures(s) {
+        let m = match cap.name("mode") {
+            Some(m) => m,
+            None => {
+                return Err(CommandError::AutosaveError(format!(
+                    "Error: Expected autosave interval \"<n> min\", \"<k> assignments\" or \"off\".\n{0}",
+                    create_location_string(s, s.chars().count()))));
+            }
+        };
+        let mode = if m.as_str() == "off" {
+            Some(AutosaveMode::Off)
+        }
+        else {
+            REGEX_AUTOSAVE_INTERVAL.captures(m.as_str()).and_then(|c| {
+                match (c.name("n").unwrap().as_str().parse::<usize>(), c.name("unit").unwrap().as_str()) {
+                    (Ok(n), "min") if n > 0 => Some(AutosaveMode::Minutes(n as u64)),
+                    (Ok(k), "assignments") if k > 0 => Some(AutosaveMode::Assignments(k)),
+                    _ => None
+                }
+            })
+        };
+        match mode {
+            Some(mode) => Ok(Some(CommandType::Autosave(mode))),
+            None => Err(CommandError::AutosaveError(format!(
+                "Error: Unknown autosave interval \"{0}\" (expected \"<n> min\", \"<k> assignments\" or \"off\" with n, k > 0).\n{1}",
+                m.as_str(), create_location_string(s, s[..m.end()].chars().count().saturating_sub(1)))))
+        }
+    }
+    else if let Some(cap) = REGEX_STARTUP.captures(s) {
+        match cap.name("path") {
+            Some(p) if !p.as_str().trim().is_empty() => Ok(Some(CommandType::Startup(unquote_path(p.as_str())))),
+            _ => Err(CommandError::SettingsError(format!(
+                "Error: Expected a value of the setting \"startup\".\n{0}", create_location_string(s, s.chars().count()))))
+        }
+    }
+    else if let Some(cap) = REGEX_SAVE_ON_EXIT.captures(s) {
+        let save_on_exit = parse_on_off(s, &cap, "save on exit")?;
+        Ok(Some(CommandType::SaveOnExit(save_on_exit)))
+    }
+    else if let Some(cap) = REGEX_NORMALIZE_HISTORY.captures(s) {
+        let normalize = parse_on_off(s, &cap, "history normalization")?;
+        terminal.set_history_normalization(normalize);
+        Ok(Some(CommandType::NormalizeHistory(normalize)))
+    }
+    else if let Some(cap) = REGEX_HISTORY_SIZE.captures(s) {
+        let size = parse_history_size(s, &cap, "historysize")?;
+        terminal.set_history_size(size);
+        Ok(Some(CommandType::HistorySize(size)))
+    }
+    else if let Some(cap) = REGEX_HISTORY_FILE_SIZE.captures(s) {
+        let size = parse_history_size(s, &cap, "historyfilesize")?;
+        terminal.set_history_file_size(size);
+        Ok(Some(CommandType::HistoryFileSize(size)))
+    }
+    else if let Some(cap) = REGEX_HISTORY_IGNORE_DUPS.captures(s) {
+        let ignore_dups = parse_on_off(s, &cap, "history ignore duplicates")?;
+        terminal.set_history_ignore_dups(ignore_dups);
+        Ok(Some(CommandType::HistoryIgnoreDups(ignore_dups)))
+    }
+    else if let Some(cap) = REGEX_HISTORY_IGNORE_SPACE.captures(s) {
+        let ignore_space = parse_on_off(s, &cap, "history ignore space")?;
+        terminal.set_history_ignore_space(ignore_space);
+        Ok(Some(CommandType::HistoryIgnoreSpace(ignore_space)))
+    }
+    else if let Some(cap) = REGEX_AUTO_ANS.captures(s) {
+        let auto_ans = parse_on_off(s, &cap, "auto ans")?;
+        context.set_auto_ans(auto_ans);
+        Ok(Some(CommandType::AutoAns(auto_ans)))
+    }
+    else if let Some(cap) = REGEX_ASSIGN_VALUE.captures(s) {
+        let assignment_values = parse_on_off(s, &cap, "assignment value")?;
+        context.set_assignment_values(assignment_values);
+        Ok(Some(CommandType::AssignValue(assignment_values)))
+    }
+    else if let Some(cap) = REGEX_NAN_TRACKING.captures(s) {
+        let tracking = parse_on_off(s, &cap, "NaN tracking")?;
+        context.set_non_finite_tracking(tracking);
+        Ok(Some(CommandType::NanTracking(tracking)))
+    }
+    else if REGEX_WHY.is_match(s) {
+        if !context.get_non_finite_tracking() {
+            return Err(CommandError::WhyError(String::from(
+                "Error: The origins of NaN and infinite values are not tracked (see \"set nantracking\").")));
+        }
+        match context.get_non_finite_origin() {
+            Some(origin) => terminal.print(&format!("{0}\n", origin)),
+            None => terminal.print("No operation or function call of the last evaluation produced a NaN or infinite value from finite operands.\n")
+        }
+        Ok(Some(CommandType::Why))
+    }
+    else if let Some(cap) = REGEX_GROUPING.captures(s) {
+        let separator = match cap.name("mode") {
+            Some(m) if m.as_str() == "off" => None,
+            Some(m) if m.as_str() == "_" => Some('_'),
+            Some(m) if m.as_str() == "," => Some(','),
+            Some(m) => {
+                return Err(CommandError::GroupingError(format!(
+                    "Error: Unknown digit grouping mode \"{0}\" (expected \"_\", \",\" or \"off\").\n{1}",
+                    m.as_str(), create_location_string(s, s[..m.end()].chars().count().saturating_sub(1)))));
+            },
+            None => {
+                return Err(CommandError::GroupingError(format!(
+                    "Error: Expected digit grouping mode \"_\", \",\" or \"off\".\n{0}", create_location_string(s, s.chars().count()))));
+            }
+        };
+        terminal.set_digit_grouping(separator);
+        Ok(Some(CommandType::Grouping(separator)))
+    }
+    else if let Some(cap) = REGEX_SET.captures(s) {
+        let key = cap.name("key").unwrap();
+        let value = match cap.name("value") {
+            Some(v) => v.as_str().trim(),
+            None => ""
+        };
+        match settings::get_setting_command(key.as_str(), value) {
+            Some(ref command) if !value.is_empty() => {
+                check_for_nested_command(command, context, terminal, default_file, run_depth)?;
+                Ok(Some(CommandType::Set(key.as_str().to_string(), value.to_string())))
+            },
+            Some(_) => Err(CommandError::SettingsError(format!(
+                "Error: Expected a value of the setting \"{0}\".\n{1}", key.as_str(), create_location_string(s, s.chars().count())))),
+            None => {
+                let mut keys : Vec<String> = settings::get_setting_keys().iter().map(|k| format!("\"{0}\"", k)).collect();
+                let last = keys.pop().unwrap();
+                Err(CommandError::SettingsError(format!(
+                    "Error: Unknown setting \"{0}\" (expected {1} or {2}).\n{3}", key.as_str(), keys.join(", "), last,
+                    create_location_string(s, s[..key.end()].chars().count().saturating_sub(1)))))
+            }
+        }
+    }
+    else if let Some(cap) = REGEX_REPLAY.captures(s) {
+        let first = cap.name("first").unwrap().as_str().parse::<usize>().unwrap_or(0);
+        let last = cap.name("last").map_or(Some(first), |l| l.as_str().parse::<usize>().ok()).unwrap_or(0);
+        let n = context.get_transcript_len();
+        if first == 0 || last < first || last > n {
+            return Err(CommandError::ReplayError(format!("Expected a range of evaluations within 1..{0} (found \"{1}\")",
+                                                         n, &s[6..].trim())));
+        }
+        for i in first..last + 1 {
+            let input = context.get_transcript_input(i).unwrap();
+            terminal.set_input_number(context.get_transcript_len() + 1);
+            terminal.print_input(&input);
+            if !evaluate_numbered(&input, context, terminal) {
+                return Err(CommandError::ReplayError(format!("The replay stopped at evaluation {0}", i)));
+            }
+        }
+        Ok(Some(CommandType::Replay(first, last)))
+    }
+    else if let Some(cap) = REGEX_HISTORY.captures(s) {
+        let entries = match terminal.get_history_entries() {
+            Some(x) => x,
+            None => return Err(CommandError::HistoryError(format!(
+                "Error: The history is only available in interactive mode.\n{0}", create_location_string(s, 0))))
+        };
+        let arg = cap.name("arg").map_or("", |m| m.as_str().trim());
+        let pattern = if arg.starts_with("search") && arg[6..].starts_with(char::is_whitespace) { Some(arg[6..].trim()) } else { None };
+
+        if let Ok(n) = arg.parse::<usize>() {
+            let entry = match entries.get(n.wrapping_sub(1)) {
+                Some(x) => x.trim().to_string(),
+                None => return Err(CommandError::HistoryError(format!(
+                    "Error: Expected a history entry within 1..{0} (found \"{1}\").\n{2}", entries.len(), n,
+                    create_location_string(s, s.chars().count() - 1))))
+            };
+            // the history command itself is added to the history, so running a history command again could run itself
+            if REGEX_HISTORY.is_match(&entry) {
+                return Err(CommandError::HistoryError(format!(
+                    "Error: The history entry {0} (\"{1}\") is a history command, which is not run again.\n{2}", n, entry,
+                    create_location_string(s, s.chars().count() - 1))));
+            }
+
+            terminal.print_input(&entry);
+            if let Some(command) = check_for_nested_command(&entry, context, terminal, default_file, run_depth)? {
+                return Ok(Some(command));
+            }
+            for statement in split_statements(&entry) {
+                if !evaluate_numbered(statement, context, terminal) {
+                    break;
+                }
+            }
+            Ok(Some(CommandType::History(Some(n))))
+        }
+        else if arg.is_empty() || pattern.is_some() {
+            let width = entries.len().to_string().len();
+            let mut listing = String::new();
+            for (i, entry) in entries.iter().enumerate().filter(|&(_, e)| e.contains(pattern.unwrap_or(""))) {
+                listing.push_str(&format!("{0:>1$}  {2}\n", i + 1, width, entry));
+            }
+            terminal.print(&listing);
+            Ok(Some(CommandType::History(None)))
+        }
+        else {
+            Err(CommandError::HistoryError(format!(
+                "Error: Expected \"history\", \"history <n>\" or \"history search <text>\".\n{0}",
+                create_location_string(s, s.chars().count() - 1))))
+        }
+    }
+    else if let Some(cap) = REGEX_RUN.captures(s) {
+        let path = unquote_path(cap.name("path").unwrap().as_str());
+        run_script(&path, context, terminal, default_file, run_depth + 1)?;
+        Ok(Some(CommandType::Run(path)))
     }
     else if let Some(cap) = REGEX_LOAD.captures(s) {
         let path = match cap.name("path") {
-            Some(g) => g.as_str().to_string(), // take user specified file
+            Some(g) => unquote_path(g.as_str()), // take user specified file
             None => default_file // take default file
         };
         load_context(&path, context)?;
@@ -100,56 +769,248 @@ pub fn check_for_command(s: & str, context: & mut MathContext, terminal: & mut T
     }
     else if let Some(cap) = REGEX_SAVE.captures(s) {
         let path = match cap.name("path") {
-            Some(g) => g.as_str().to_string(), // take user specified file
+            Some(g) => unquote_path(g.as_str()), // take user specified file
             None => default_file // take default file
         };
         save_context(&path, context)?;
         Ok(Some(CommandType::Save(path)))
     }
     else if let Some(cap) = REGEX_FORMAT.captures(s) {
-        let form = cap.name("format");
-        if form.is_some() {
-            // find out which format is specified in the command
-            let ft = FormatType::from(form.unwrap().as_str());
-            match ft {
-                FormatType::Undefined => Err(CommandError::FormatError(form.unwrap().as_str().to_string())),
-                _ => {
-                    // set the specified format
-                    switch_format(terminal, ft.clone());
-                    Ok(Some(CommandType::Format(ft)))
-                }
+        match parse_format_args(s, cap.name("format").map_or(s.len(), |m| m.start()))? {
+            Some(ft) => {
+                // set the specified format
+                switch_format(terminal, ft.clone());
+                Ok(Some(CommandType::Format(ft)))
+            },
+            None => {
+                // print the current format
+                let ft = terminal.get_format_type();
+                terminal.print(&format!("format = {0}\n", ft));
+                Ok(Some(CommandType::Format(ft)))
             }
         }
-        else {
-            Err(CommandError::FormatError(String::new()))
-        }
+    }
+    else if let Some(cap) = REGEX_COMPARE.captures(s) {
+        let mode = match cap.name("mode") {
+            Some(m) if m.as_str() == "strict" => ComparisonMode::Strict,
+            Some(m) if m.as_str() == "modulus" => ComparisonMode::Modulus,
+            Some(m) => {
+                return Err(CommandError::CompareError(format!(
+                    "Error: Unknown comparison mode \"{0}\" (expected \"strict\" or \"modulus\").\n{1}",
+                    m.as_str(), create_location_string(s, s[..m.end()].chars().count().saturating_sub(1)))));
+            },
+            None => {
+                return Err(CommandError::CompareError(format!(
+                    "Error: Expected comparison mode \"strict\" or \"modulus\".\n{0}", create_location_string(s, s.chars().count()))));
+            }
+        };
+        context.set_comparison_mode(mode.clone());
+        Ok(Some(CommandType::Compare(mode)))
+    }
+    else if let Some(cap) = REGEX_PRECISION.captures(s) {
+        let mode = match cap.name("mode") {
+            Some(m) if m.as_str() == "float" => PrecisionMode::Float,
+            Some(m) if m.as_str() == "exact" => PrecisionMode::Exact,
+            Some(m) => {
+                return Err(CommandError::PrecisionError(format!(
+                    "Error: Unknown precision mode \"{0}\" (expected \"float\" or \"exact\").\n{1}",
+                    m.as_str(), create_location_string(s, s[..m.end()].chars().count().saturating_sub(1)))));
+            },
+            None => {
+                return Err(CommandError::PrecisionError(format!(
+                    "Error: Expected precision mode \"float\" or \"exact\".\n{0}", create_location_string(s, s.chars().count()))));
+            }
+        };
+        context.set_precision_mode(mode.clone());
+        Ok(Some(CommandType::Precision(mode)))
+    }
+    else if let Some(cap) = REGEX_MODE.captures(s) {
+        let mode = match cap.name("mode") {
+            Some(m) if m.as_str() == "rad" => AngleMode::Rad,
+            Some(m) if m.as_str() == "deg" => AngleMode::Deg,
+            Some(m) if m.as_str() == "grad" => AngleMode::Grad,
+            Some(m) => {
+                return Err(CommandError::ModeError(format!(
+                    "Error: Unknown angle mode \"{0}\" (expected \"rad\", \"deg\" or \"grad\").\n{1}",
+                    m.as_str(), create_location_string(s, s[..m.end()].chars().count().saturating_sub(1)))));
+            },
+            None => {
+                return Err(CommandError::ModeError(format!(
+                    "Error: Expected angle mode \"rad\", \"deg\" or \"grad\".\n{0}", create_location_string(s, s.chars().count()))));
+            }
+        };
+        context.set_angle_mode(mode.clone());
+        Ok(Some(CommandType::Mode(mode)))
     }
     else {
         Ok(None)
     }
 }
 
-/// Saves the MathContext object to the specified file.
-fn save_context(p: & str, context: & mut MathContext) -> Result<(), CommandError> {
+/// Evaluates the specified input, adds it to the transcript of the session and prints its (numbered) result or the error.
+/// Returns true if the evaluation succeeded.
+pub fn evaluate_numbered(input: & str, context: & mut MathContext, terminal: & TerminalUI) -> bool {
+    let result = get_result(input, context);
+    print_warnings(context, terminal);
+    match result {
+        Ok(result) => {
+            let n = context.add_transcript_entry(input, result.clone());
+            match result {
+                Some(ref y) => terminal.print_numbered_result(n, y),
+                None => terminal.print_empty_result()
+            }
+            true
+        },
+        Err(err) => {
+            terminal.print_error(err);
+            false
+        }
+    }
+}
+
+/// Prints the warnings of the last evaluations (e.g. unused function parameters).
+pub fn print_warnings(context: & mut MathContext, terminal: & TerminalUI) {
+    for warning in context.take_warnings() {
+        terminal.print_warning(&warning);
+    }
+}
+
+/// Parses the "on" or "off" mode of the capture group "mode" of an on/off setting command.
+fn parse_on_off(s: & str, cap: & Captures, setting_name: & str) -> Result<bool, CommandError> {
+    match cap.name("mode") {
+        Some(m) if m.as_str() == "on" => Ok(true),
+        Some(m) if m.as_str() == "off" => Ok(false),
+        Some(m) => Err(CommandError::SwitchError(format!(
+            "Error: Unknown {0} mode \"{1}\" (expected \"on\" or \"off\").\n{2}",
+            setting_name, m.as_str(), create_location_string(s, s[..m.end()].chars().count().saturating_sub(1))))),
+        None => Err(CommandError::SwitchError(format!(
+            "Error: Expected {0} mode \"on\" or \"off\".\n{1}", setting_name, create_location_string(s, s.chars().count()))))
+    }
+}
+
+/// Parses the number of history entries of the specified history size setting (e.g. "set historysize 100").
+fn parse_history_size(s: & str, cap: & Captures, setting_name: & str) -> Result<usize, CommandError> {
+    let n = match cap.name("n") {
+        Some(n) => n,
+        None => {
+            return Err(CommandError::SettingsError(format!(
+                "Error: Expected a value of the setting \"{0}\".\n{1}", setting_name, create_location_string(s, s.chars().count()))));
+        }
+    };
+    match n.as_str().parse::<usize>() {
+        Ok(size) => Ok(size),
+        Err(_) => Err(CommandError::SettingsError(format!(
+            "Error: Unknown history size \"{0}\" (expected a number of entries).\n{1}",
+            n.as_str(), create_location_string(s, s[..n.end()].chars().count().saturating_sub(1)))))
+    }
+}
+
+/// Splits the arguments of the diff command at the last comma that is not enclosed in parentheses
+/// into the expression and the variable of differentiation.
+fn split_diff_args(args: & str) -> Option<(& str, & str)> {
+    let mut depth = 0;
+    let mut split = None;
+    for (idx, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => split = Some(idx),
+            _ => ()
+        }
+    }
+    split.map(|idx| (&args[..idx], &args[idx + 1..]))
+}
+
+/// Removes the double quotes around the specified path argument (e.g. "My Work/ctx.json").
+/// Paths that are not fully enclosed in double quotes are returned unchanged.
+fn unquote_path(p: & str) -> String {
+    let p = p.trim();
+    if p.len() >= 2 && p.starts_with('"') && p.ends_with('"') {
+        p[1..p.len() - 1].to_string()
+    }
+    else {
+        p.to_string()
+    }
+}
+
+/// Evaluates the expressions and commands of the specified script file line by line in the current context.
+/// Empty lines and lines starting with '#' are skipped. A line may consist of several statements separated by ";".
+/// The script stops at the first failing line or at an exit command.
+fn run_script(p: & str, context: & mut MathContext, terminal: & mut TerminalUI, default_file: String,
+              run_depth: usize) -> Result<(), CommandError> {
+    if run_depth > MAX_RUN_DEPTH {
+        return Err(CommandError::RunError(format!(
+            "Error: The scripts are nested too deeply (at most {0} levels, does \"{1}\" run itself?).", MAX_RUN_DEPTH, p)));
+    }
+    let mut f = match File::open(p) {
+        Ok(x) => x,
+        Err(e) => return Err(CommandError::RunError(format!("Error: Unable to open the specified file ({0}).", e)))
+    };
+    let mut s = String::new();
+    match f.read_to_string(& mut s) {
+        Ok(_) => (),
+        Err(e) => return Err(CommandError::RunError(format!("Error: Unable to read the specified file ({0}).", e)))
+    }
+
+    // scripts created on Windows start with a byte order mark and end their lines with "\r\n"
+    for (i, line) in normalize_text(&s).lines().enumerate() {
+        let line = line.trim();
+        if line.len() == 0 || line.starts_with('#') {
+            continue;
+        }
+
+        let err = match check_for_nested_command(line, context, terminal, default_file.clone(), run_depth) {
+            Ok(Some(CommandType::Exit)) => break,
+            Ok(Some(_)) => continue,
+            Ok(None) => {
+                // the statements of the line (separated by ";") are evaluated like the statements of the user inputs
+                let results = get_statement_results(line, context);
+                print_warnings(context, terminal);
+                match results {
+                    Ok(results) => {
+                        for result in results.iter().filter_map(|r| r.as_ref()) {
+                            terminal.print_result(result);
+                        }
+                        continue;
+                    },
+                    Err(e) => e.to_string()
+                }
+            },
+            Err(e) => e.to_string()
+        };
+        return Err(CommandError::RunError(format!("Error in \"{0}\", line {1}:\n{2}", p, i + 1, err)));
+    }
+    Ok(())
+}
+
+/// Saves the MathContext object to the specified file. The file is replaced crash-safely (see write_file_synced).
+pub fn save_context(p: & str, context: & mut MathContext) -> Result<(), CommandError> {
 
     let serialization = match serde_json::to_string_pretty(&context) {
         Ok(s) => s,
         Err(e) => return Err(CommandError::SaveSerError(format!("Unable to serialize the current conext ({0})", e)))
     };
 
-    let mut f = match File::create(p) {
-        Ok(x) => x,
-        Err(e) => return Err(CommandError::SaveSerError(format!("Unable to save the serialized context ({0})", e)))
-    };
+    write_file_synced(p, serialization.as_ref())
+        .map_err(|e| CommandError::SaveSerError(format!("Unable to write the serialized context to the specified file ({0})", e)))
+}
 
-    match f.write_all(serialization.as_ref()) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(CommandError::SaveSerError(format!("Unable to write the serialized context to the specified file ({0})", e)))
+/// Writes the specified content to the specified file.
+/// The content is written to a temporary file in the same directory, which replaces the target file after it has
+/// been synchronized to the disk. Thus, the target file is never left partially written (e.g. if termc crashes).
+pub fn write_file_synced(p: & str, content: & [u8]) -> io::Result<()> {
+    let tmp_path = format!("{0}.tmp", p);
+    let result = File::create(&tmp_path)
+        .and_then(|mut f| f.write_all(content).and_then(|_| f.sync_all()))
+        .and_then(|_| fs::rename(&tmp_path, p));
+    if result.is_err() {
+        fs::remove_file(&tmp_path).ok(); // do not leave a partially written file behind
     }
+    result
 }
 
 /// Loads the MathContext object from the specified file.
-fn load_context(p: & str, context: & mut MathContext) -> Result<(), CommandError> {
+pub fn load_context(p: & str, context: & mut MathContext) -> Result<(), CommandError> {
     let mut f = match File::open(p) {
         Ok(x) => x,
         Err(e) => return Err(CommandError::LoadSerError(format!("Unable to open the specified file ({0})", e)))
@@ -161,30 +1022,92 @@ fn load_context(p: & str, context: & mut MathContext) -> Result<(), CommandError
     }
 
     let mut result : Result<(), CommandError> = Ok(());
-    *context = match serde_json::from_str(&s) {
+    // the settings (e.g. the comparison mode or the sandbox mode) are not part of the serialization
+    let settings = context.get_settings();
+    let mut new_context : MathContext = match serde_json::from_str(&normalize_text(&s)) {
         Ok(c) => c,
         Err(e) => {
             result = Err(CommandError::LoadSerError(format!("Unable deserialize the specified serialization file ({0})", e)));
             MathContext::new()
         }
     };
+    // the transcript of the session is kept, so that the evaluation numbers stay valid
+    new_context.take_transcript(context);
+    *context = new_context;
     context.initialize();
+    context.set_settings(settings);
     
     result
 }
 
+/// Parses the arguments of the format command that start at the specified position of the command.
+/// Returns None if no format is specified. The errors mark the position of the invalid argument in the command.
+fn parse_format_args(s: & str, start: usize) -> Result<Option<FormatType>, CommandError> {
+    lazy_static!{
+        static ref REGEX_ARG : Regex = Regex::new(r"\S+").unwrap();
+    }
+
+    // the marker is set at the last character of the argument
+    let location = |end: usize| create_location_string(s, s[..end].chars().count() - 1);
+
+    let mut args = REGEX_ARG.find_iter(&s[start..]);
+    let form = match args.next() {
+        Some(m) => m,
+        None => return Ok(None)
+    };
+    let mut ft = FormatType::from(form.as_str());
+    if let FormatType::Undefined = ft {
+        let mut names : Vec<String> = get_format_names().iter().map(|n| format!("\"{0}\"", n)).collect();
+        let last = names.pop().unwrap();
+        return Err(CommandError::FormatError(format!("Error: Unknown format \"{0}\" (expected {1} or {2}).\n{3}",
+            form.as_str(), names.join(", "), last, location(start + form.end()))));
+    }
+    if let FormatType::Frac(_) = ft {
+        // the fraction format takes an optional maximum denominator
+        if let Some(m) = args.next() {
+            match m.as_str().parse::<u64>() {
+                Ok(d) if d > 0 => ft = FormatType::Frac(d),
+                _ => return Err(CommandError::FormatError(format!("Error: Expected positive integer maximum denominator.\n{0} Found: \"{1}\"",
+                                                                  location(start + m.end()), m.as_str())))
+            }
+        }
+    }
+    if let FormatType::Base(_) = ft {
+        // the base format takes an optional radix
+        if let Some(m) = args.next() {
+            match m.as_str().parse::<u32>() {
+                Ok(r) if r >= 2 && r <= 36 => ft = FormatType::Base(r),
+                _ => return Err(CommandError::FormatError(format!("Error: Expected radix from 2 to 36.\n{0} Found: \"{1}\"",
+                                                                  location(start + m.end()), m.as_str())))
+            }
+        }
+    }
+    if let Some(m) = args.next() {
+        return Err(CommandError::FormatError(format!("Error: Unexpected argument \"{0}\" of the format command.\n{1}",
+                                                     m.as_str(), location(start + m.end()))));
+    }
+    Ok(Some(ft))
+}
+
 /// Switches the output print format of the numbers.
 fn switch_format(terminal: & mut TerminalUI, t: FormatType) {
     terminal.set_format_type(t);
 }
 
 /// Prints all user defined constants and functions.
-fn print_info(context: &MathContext, terminal: & TerminalUI) {
+/// The constants are printed before the functions, both sorted by their names.
+fn print_info(context: &MathContext, terminal: & TerminalUI, json: bool) {
+
+    if json {
+        terminal.print(&format!("{0}\n", build_info_json(context)));
+        return;
+    }
 
-    let user_constants = context.get_user_constants();
     let mut constants_vec = Vec::new();
-    for (ident, value) in user_constants {
-        constants_vec.push(format!("{0} = {1}", ident, value));
+    for ident in context.get_user_constant_names() {
+        if let Some(value) = context.get_constant_value(&ident) {
+            constants_vec.push(format!("{0} = {1}", ident, value));
+        }
     }
 
     let mut functions_vec = context.get_user_function_definitions();
@@ -196,3 +1119,110 @@ fn print_info(context: &MathContext, terminal: & TerminalUI) {
         terminal.print(&format!("{0}\n", all_definitions));
     }
 }
+
+/// Prints all user defined constants and functions that are neither referenced by a user function nor have been used
+/// in this session. If prune is true, the printed definitions are deleted after the user confirmed it.
+fn collect_garbage(context: & mut MathContext, terminal: & mut TerminalUI, prune: bool) {
+
+    let unused = context.get_unused_user_definitions();
+    if unused.is_empty() {
+        terminal.print("No unused definitions.\n");
+        return;
+    }
+
+    let mut definitions = Vec::new();
+    for ident in unused.iter() {
+        match context.get_user_function_canonical_input(ident) {
+            Some(def) => definitions.push(def),
+            None => definitions.push(format!("{0} = {1}", ident, context.get_constant_value(ident).unwrap()))
+        }
+    }
+    terminal.print(&format!("Unused definitions:\n{0}\n", definitions.join("\n")));
+
+    if prune {
+        if terminal.confirm(&format!("Delete {0} unused definition(s)?", unused.len())) {
+            for ident in unused.iter() {
+                if context.is_user_function(ident) {
+                    context.remove_user_function(ident.as_str());
+                }
+                else {
+                    context.remove_user_constant(ident.as_str());
+                }
+            }
+        }
+        else {
+            terminal.print("Nothing deleted.\n");
+        }
+    }
+}
+
+/// Deletes the user defined constants and functions whose names are the arguments of the specified del command.
+/// Nothing is deleted if any of the names is built-in or not defined. The errors mark the position of the invalid name.
+fn delete_definitions(s: & str, context: & mut MathContext) -> Result<Vec<String>, CommandError> {
+    lazy_static!{
+        static ref REGEX_ARG : Regex = Regex::new(r"\S+").unwrap();
+    }
+
+    // the marker is set at the last character of the name
+    let location = |end: usize| create_location_string(s, s[..end].chars().count() - 1);
+
+    let start = "del".len();
+    let mut names = Vec::new();
+    for m in REGEX_ARG.find_iter(&s[start..]) {
+        let name = m.as_str();
+        if context.is_built_in_constant(name) || context.is_built_in_function(name) {
+            let kind = if context.is_built_in_function(name) {"function"} else {"constant"};
+            return Err(CommandError::DelError(format!("Error: Can not delete the built-in {0} \"{1}\".\n{2}",
+                                                      kind, name, location(start + m.end()))));
+        }
+        if !context.is_user_constant(name) && !context.is_user_function(name) {
+            return Err(CommandError::DelError(format!("Error: Unknown user defined constant or function \"{0}\".\n{1}",
+                                                      name, location(start + m.end()))));
+        }
+        names.push(name.to_string());
+    }
+
+    if names.is_empty() {
+        return Err(CommandError::DelError(format!("Error: Expected the name of a user defined constant or function.\n{0}",
+                                                  create_location_string(s, s.chars().count()))));
+    }
+
+    for name in names.iter() {
+        if context.is_user_function(name) {
+            context.remove_user_function(name.as_str());
+        }
+        else {
+            context.remove_user_constant(name.as_str());
+        }
+    }
+    Ok(names)
+}
+
+/// Builds the JSON representation of all user defined constants and functions.
+/// Example: {"constants":[{"name":"c","value":{"im":0.0,"re":2.0,"result_type":"Real"}}],"functions":[{"definition":"f(x) = x^2","name":"f"}]}
+fn build_info_json(context: &MathContext) -> String {
+
+    let mut constants = Vec::new();
+    for ident in context.get_user_constant_names() {
+        if let Some(value) = context.get_constant_value(&ident) {
+            let mut entry = Map::new();
+            entry.insert(String::from("name"), Value::String(ident));
+            entry.insert(String::from("value"), serde_json::to_value(&value).unwrap_or(Value::Null));
+            constants.push(Value::Object(entry));
+        }
+    }
+
+    let mut functions = Vec::new();
+    for ident in context.get_user_function_names() {
+        let mut entry = Map::new();
+        let definition = context.get_user_function_canonical_input(&ident).unwrap_or(String::new());
+        entry.insert(String::from("name"), Value::String(ident));
+        entry.insert(String::from("definition"), Value::String(definition));
+        functions.push(Value::Object(entry));
+    }
+
+    let mut info = Map::new();
+    info.insert(String::from("constants"), Value::Array(constants));
+    info.insert(String::from("functions"), Value::Array(functions));
+    Value::Object(info).to_string()
+}
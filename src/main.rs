@@ -6,24 +6,67 @@ extern crate serde_json;
 extern crate regex;
 
 mod command_library;
+mod session;
+mod settings;
+mod plot;
+#[cfg(test)]
+mod test;
 
 use std::env;
+use std::io::{self, Read, IsTerminal};
 use std::path::Path;
-use termc_model::get_result;
-use termc_model::math_context::MathContext;
-use termc_model::math_result::MathResult;
-use termc_ui::{TerminalUI, TerminalMode};
-use command_library::{CommandType, check_for_command};
+use std::process;
+use std::thread;
+use std::time::Duration;
+use termc_ui::{TerminalUI, TerminalMode, HistorySettings, get_user_data_dir, get_settings_file_path, normalize_text};
+use command_library::CommandError;
+use session::Session;
+use settings::Settings;
+
+/// Defines the file name of the default serialization file.
+static CONTEXT_FILE_NAME : &'static str = "termc_context.json";
+
+/// Defines the command line option for definitions that are evaluated before the expressions in call mode.
+static DEFINE_OPTION : &'static str = "--define";
+
+/// Defines the command line option for the maximum duration (in seconds) of the evaluation in call mode.
+static TIMEOUT_OPTION : &'static str = "--timeout";
+
+/// Defines the command line option that prints the results of the call mode with evaluation statistics as JSON.
+static JSON_OPTION : &'static str = "--json";
+
+/// Defines the command line option that prints the results of the call mode with the numbers of their expressions.
+static NUMBERED_OPTION : &'static str = "--numbered";
+
+/// Defines the command line option that prints the results of the call mode after their statements.
+static ECHO_OPTION : &'static str = "--echo";
+
+/// Defines the command line option that rejects the commands accessing the file system in call mode.
+static SANDBOX_OPTION : &'static str = "--sandbox";
+
+/// Defines the command line option that loads the settings and the startup context in call mode.
+static LOAD_OPTION : &'static str = "--load";
+
+/// Defines the exit code if the evaluation in call mode exceeds the timeout.
+static TIMEOUT_EXIT_CODE : i32 = 124;
+
+/// Defines the command line argument that lets call mode read the expressions from stdin.
+static STDIN_ARGUMENT : &'static str = "-";
 
 /// The main entry point.
 pub fn main() {
     let mut args = get_arguments();
 
     // If there are command line arguments given, start in call mode.
-    // Otherwise start in interactive mode.
+    // Otherwise start in interactive mode or in batch mode if stdin or stdout is not a terminal (e.g. a pipe or a file),
+    // so that no prompts and no line editing sequences are written to a redirected output.
     if args.len() > 1 {
         start_call(& mut args);
     }
+    else if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        let path = args.pop().unwrap(); // get path of this executable
+        start_batch(path);
+    }
     else {
         let path = args.pop().unwrap(); // get path of this executable
         start_interactive(path);
@@ -37,63 +80,205 @@ fn get_arguments() -> Vec<String> {
     args_it.collect()
 }
 
+/// Builds the default file path of the serialization file.
+/// The file is located in the user data directory, unless it already exists next to the executable (portable install).
+/// If the user data directory is not available, the directory of the executable is used.
 fn build_default_ser_path(exe_path: &str) -> String {
-    let default_fd = Path::new(exe_path).parent().unwrap(); // remove termc executable name
-    let default_fn = Path::new("termc_context.json"); // define default file name
-    default_fd.join(default_fn).to_str().unwrap().to_string() // join current path and default file name
+    let exe_fd = Path::new(exe_path).parent().unwrap(); // remove termc executable name
+    let portable_file = exe_fd.join(CONTEXT_FILE_NAME);
+    if portable_file.exists() {
+        return portable_file.to_string_lossy().into_owned();
+    }
+
+    match get_user_data_dir() {
+        Ok(data_fd) => data_fd.join(CONTEXT_FILE_NAME).to_string_lossy().into_owned(),
+        Err(_) => portable_file.to_string_lossy().into_owned()
+    }
+}
+
+/// Creates a session with a terminal of the specified mode and loads the user settings file and then the startup context.
+/// The terminal is created with the stored history settings, as it loads the history file.
+/// If the user config directory is not available, the session keeps the default settings.
+fn prepare_session(mode: TerminalMode, default_file: String) -> Session {
+    let settings_path = get_settings_file_path().ok().map(|p| p.to_string_lossy().into_owned());
+    // errors of the settings file are reported when the session loads it
+    let history_settings = match settings_path {
+        Some(ref path) => Settings::load(path).map(|s| s.get_history_settings()).unwrap_or_default(),
+        None => HistorySettings::default()
+    };
+
+    let mut session = Session::new(TerminalUI::with_history_settings(mode, history_settings), default_file);
+    if let Some(ref path) = settings_path {
+        session.load_settings(path);
+    }
+    session.load_startup_context();
+    session
+}
+
+/// Defines the options of the call mode.
+struct CallOptions {
+    /// The definitions that are evaluated before the expressions.
+    definitions: Vec<String>,
+    /// The maximum duration of the evaluation (in seconds).
+    timeout: Option<f64>,
+    /// Whether the results are printed with evaluation statistics as JSON.
+    json: bool,
+    /// Whether the commands that access the file system are rejected.
+    sandbox: bool,
+    /// Whether the settings and the startup context are loaded.
+    load: bool,
+    /// Whether the results are printed on separate lines with the numbers of their expressions.
+    numbered: bool,
+    /// Whether the results are printed on separate lines after their statements.
+    echo: bool,
+    /// The expressions to evaluate.
+    expressions: Vec<String>
+}
+
+/// Parses the specified command line arguments into the definitions (given with "--define <definition>"
+/// or "--define=<definition>"), the timeout (given with "--timeout <seconds>" or "--timeout=<seconds>"),
+/// the JSON output (given with "--json"), the sandbox mode (given with "--sandbox"),
+/// the loading of the settings and the startup context (given with "--load"), the numbered output (given with "--numbered"), the echo of the statements (given with "--echo") and the remaining expressions.
+fn parse_call_options(args: &[String]) -> Result<CallOptions, CommandError> {
+    let mut options = CallOptions {definitions: Vec::new(), timeout: None, json: false, sandbox: false, load: false, numbered: false,
+                                   echo: false, expressions: Vec::new()};
+    let define_prefix = format!("{0}=", DEFINE_OPTION);
+    let timeout_prefix = format!("{0}=", TIMEOUT_OPTION);
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == DEFINE_OPTION {
+            options.definitions.push(get_option_value(iter.next(), DEFINE_OPTION)?);
+        }
+        else if arg.starts_with(&define_prefix) {
+            options.definitions.push(arg[define_prefix.len()..].to_string());
+        }
+        else if arg == TIMEOUT_OPTION {
+            options.timeout = Some(parse_timeout(&get_option_value(iter.next(), TIMEOUT_OPTION)?)?);
+        }
+        else if arg.starts_with(&timeout_prefix) {
+            options.timeout = Some(parse_timeout(&arg[timeout_prefix.len()..])?);
+        }
+        else if arg == JSON_OPTION {
+            options.json = true;
+        }
+        else if arg == SANDBOX_OPTION {
+            options.sandbox = true;
+        }
+        else if arg == LOAD_OPTION {
+            options.load = true;
+        }
+        else if arg == NUMBERED_OPTION {
+            options.numbered = true;
+        }
+        else if arg == ECHO_OPTION {
+            options.echo = true;
+        }
+        else {
+            options.expressions.push(arg.clone());
+        }
+    }
+
+    Ok(options)
+}
+
+/// Returns the value of the specified command line option or an error if the value is missing.
+fn get_option_value(value: Option<&String>, option: & str) -> Result<String, CommandError> {
+    match value {
+        Some(v) => Ok(v.clone()),
+        None => Err(CommandError::OptionError(format!("Missing value after option \"{0}\"", option)))
+    }
+}
+
+/// Parses the specified timeout in seconds (e.g. "2.5").
+fn parse_timeout(s: & str) -> Result<f64, CommandError> {
+    match s.parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Ok(secs),
+        _ => Err(CommandError::OptionError(format!("Invalid timeout \"{0}\" (expected a positive number of seconds)", s)))
+    }
+}
+
+/// Starts a watchdog that terminates the process with TIMEOUT_EXIT_CODE if it is still running after the specified timeout (in seconds).
+/// The watchdog does not prevent the process from exiting earlier.
+fn start_watchdog(timeout: f64) {
+    thread::spawn(move || {
+        thread::sleep(Duration::new(timeout.trunc() as u64, (timeout.fract() * 1e9) as u32));
+        let terminal = TerminalUI::new(TerminalMode::Call);
+        terminal.print_error(CommandError::OptionError(format!("Evaluation exceeded the timeout of {0} seconds", timeout)));
+        process::exit(TIMEOUT_EXIT_CODE);
+    });
+}
+
+/// Reads the expressions from stdin (one expression per line). Empty lines are skipped.
+/// A byte order mark and the line endings of Windows are accepted (see normalize_text).
+fn read_stdin_expressions() -> io::Result<Vec<String>> {
+    let mut s = String::new();
+    io::stdin().read_to_string(& mut s)?;
+    Ok(normalize_text(&s).lines().filter(|line| !line.trim().is_empty()).map(|line| line.to_string()).collect())
 }
 
 /// Starts termc in command line call mode.
-/// Prints a ';'-separated list with the results of the specified mathematical expressions.
+/// The definitions given with "--define" are evaluated first.
+/// If a timeout is given with "--timeout", the process exits with TIMEOUT_EXIT_CODE when the evaluation exceeds it.
+/// If the only expression argument is "-", the expressions are read from stdin.
+/// The settings and the startup context are only loaded if "--load" is given, so that the results of a call do not
+/// depend on the state of previous interactive sessions.
+/// If "--sandbox" is given, they are not loaded in any case and the commands that access the file system are rejected.
+/// Prints a ';'-separated list with the results of the specified mathematical expressions
+/// (or the results with the numbers of their expressions or after their statements on separate lines if "--numbered"
+/// or "--echo" is given,
+/// or a JSON array with the results and evaluation statistics if "--json" is given).
 fn start_call(args: & mut Vec<String>) {
 
     // compute default file-path for the serialization file
-    let mut iter = args.iter();
-    let path_str : String = iter.next().unwrap().to_string(); // get path of this executable
+    let path_str : String = args[0].to_string(); // get path of this executable
     let default_file = build_default_ser_path(&path_str);
 
     // create terminal handle
-    let mut terminal = TerminalUI::new(TerminalMode::Call);
-
-    let mut results : Vec<MathResult> = Vec::new();
-    let mut context = MathContext::new();
-
-    // for each argument given, evaluate it and store the results
-    // if an error occurs for any of the given arguments, the evaluation of all arguments will be aborted
-    for (i, arg) in iter.enumerate() {
-
-        match check_for_command(arg, &mut context, &mut terminal, default_file.clone()) {
-            Ok(k) => {
-                match k {
-                    Some(command_type) => {
-                        match command_type {
-                            CommandType::Exit => break,
-                            _ => ()
-                        }
-                    },
-
-                    None => {
-                        match get_result(arg.trim(), & mut context) {
-                            Ok(result) => {
-                                match result {
-                                    Some(y) => results.push(y),
-                                    None => ()
-                                }
-                            },
-                            Err(err) => {
-                                terminal.print(&format!("In input {0}:\n", i+1));
-                                terminal.print_error(err);
-                                break;
-                            }
-                        }
-                    }
-                }
-            },
-            Err(e) => terminal.print_error(e)
+    let terminal = TerminalUI::new(TerminalMode::Call);
+
+    let options = match parse_call_options(&args[1..]) {
+        Ok(x) => x,
+        Err(e) => {
+            terminal.print_error(e);
+            return;
         }
+    };
+    if let Some(timeout) = options.timeout {
+        start_watchdog(timeout);
     }
 
-   terminal.print_results(&results);
+    let expressions = options.expressions;
+    let expressions = if expressions.len() == 1 && expressions[0] == STDIN_ARGUMENT {
+        match read_stdin_expressions() {
+            Ok(x) => x,
+            Err(e) => {
+                terminal.print_error(e);
+                return;
+            }
+        }
+    }
+    else {
+        expressions
+    };
+
+    // the definitions are evaluated before the expressions
+    let mut session = if options.load && !options.sandbox {
+        prepare_session(TerminalMode::Call, default_file)
+    }
+    else {
+        let mut session = Session::new(terminal, default_file);
+        session.set_sandbox(options.sandbox);
+        session
+    };
+    session.set_numbered_results(options.numbered);
+    session.set_echo(options.echo);
+    if options.json {
+        session.run_call_json(&options.definitions, &expressions);
+    }
+    else {
+        session.run_call(&options.definitions, &expressions);
+    }
 }
 
 /// Starts termc in command line interactive mode.
@@ -102,51 +287,19 @@ fn start_interactive(path_str: String) {
     // compute default file-path for the serialization file
     let default_file = build_default_ser_path(&path_str);
 
-    // create terminal handle
-    let mut terminal = TerminalUI::new(TerminalMode::Interactive);
-    // terminal.init();
-    let mut context = MathContext::new();
-
     // REPL: take user input, evaluate it and print results / errors
-    loop {
-        let user_input = terminal.get_user_input();
-        let user_input = user_input.trim();
+    let mut session = prepare_session(TerminalMode::Interactive, default_file);
+    session.run();
+}
 
-        if user_input.len() == 0 {
-            continue;
-        }
+/// Starts termc in batch mode.
+/// The user inputs are read line by line from stdin and each result is printed on a separate line.
+/// Errors are printed to stderr and do not abort the evaluation of the following lines.
+fn start_batch(path_str: String) {
 
-        match check_for_command(user_input, &mut context, &mut terminal, default_file.clone()) {
-            Ok(k) => {
-                match k {
-                    Some(command_type) => {
-                        match command_type {
-                            CommandType::Exit => break,
-                            _ => terminal.print_cmd_ack()
-                        }
-                    },
-
-                    None => {
-                        match get_result(& user_input, & mut context) {
-                            Ok(result) => {
-                                match result {
-                                    Some(y) => terminal.print_result(&y),
-                                    None => ()
-                                }
-                            },
-                            Err(err) => {
-                                terminal.print_error(err);
-                            }
-                        }
-                    }
-                }
-            },
-            Err(e) => terminal.print_error(e)
-        }
-    }
+    // compute default file-path for the serialization file
+    let default_file = build_default_ser_path(&path_str);
 
-    match terminal.save_history_file() {
-        Ok(_) => (),
-        Err(e) => terminal.print_error(e)
-    }
+    let mut session = prepare_session(TerminalMode::Batch, default_file);
+    session.run();
 }
@@ -0,0 +1,146 @@
+use std::env;
+
+/// Defines the number of columns of the terminal if it is unknown.
+const DEFAULT_COLUMNS : usize = 80;
+
+/// Defines the number of lines of the terminal if it is unknown.
+const DEFAULT_LINES : usize = 24;
+
+/// Defines the minimum width (in characters) of the plot area.
+const MIN_WIDTH : usize = 10;
+
+/// Defines the minimum height (in lines) of the plot area.
+const MIN_HEIGHT : usize = 4;
+
+/// Defines the number of lines below the plot area: the x axis, the labels of the x axis and the next prompt.
+const RESERVED_LINES : usize = 3;
+
+/// Defines the bits of the dots of a braille character (index: column and row of the dot in the character).
+const BRAILLE_DOTS : [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Defines the code point of the empty braille character.
+const BRAILLE_BASE : u32 = 0x2800;
+
+/// A canvas of braille characters. Each character consists of 2x4 dots, so the resolution of the canvas is twice the
+/// number of characters horizontally and four times the number of lines vertically.
+struct BrailleCanvas {
+    /// The width of the canvas (in characters).
+    width: usize,
+    /// The dots of the characters (row by row).
+    cells: Vec<u32>
+}
+
+impl BrailleCanvas {
+
+    /// Creates an empty canvas of the specified width and height (in characters).
+    fn new(width: usize, height: usize) -> BrailleCanvas {
+        BrailleCanvas {width: width, cells: vec![0; width * height]}
+    }
+
+    /// Sets the dot at the specified position (in dots, the origin is the upper left corner).
+    fn set(& mut self, x: usize, y: usize) {
+        self.cells[(y / 4) * self.width + x / 2] |= BRAILLE_DOTS[x % 2][y % 4];
+    }
+
+    /// Sets the dots of the straight line between the specified positions (in dots).
+    fn line(& mut self, from: (usize, usize), to: (usize, usize)) {
+        let (dx, dy) = (to.0 as f64 - from.0 as f64, to.1 as f64 - from.1 as f64);
+        let steps = dx.abs().max(dy.abs()) as usize;
+        for i in 0..steps + 1 {
+            let t = if steps == 0 { 0.0 } else { i as f64 / steps as f64 };
+            self.set((from.0 as f64 + t * dx).round() as usize, (from.1 as f64 + t * dy).round() as usize);
+        }
+    }
+
+    /// Returns the characters of the specified line of the canvas.
+    fn get_line(& self, row: usize) -> String {
+        self.cells[row * self.width..(row + 1) * self.width].iter()
+            .map(|dots| ::std::char::from_u32(BRAILLE_BASE + dots).unwrap()).collect()
+    }
+}
+
+/// Returns the size of the terminal (columns and lines). The size is taken from the environment variables
+/// COLUMNS and LINES, which are set by most shells. Otherwise, the size of a standard terminal (80x24) is assumed.
+pub fn get_terminal_size() -> (usize, usize) {
+    let get = |name: & str, default: usize| env::var(name).ok().and_then(|x| x.trim().parse::<usize>().ok())
+        .filter(|x| *x > 0).unwrap_or(default);
+    (get("COLUMNS", DEFAULT_COLUMNS), get("LINES", DEFAULT_LINES))
+}
+
+/// Renders the plot of the specified sample points (with increasing x values) that fits into a terminal of the specified
+/// size. Consecutive sample points with values are connected by lines, sample points without values leave gaps.
+/// The y axis is labeled with the maximum, the minimum and zero (if in range), the x axis with the bounds.
+/// Returns None if no sample point has a value.
+pub fn render(points: & [(f64, Option<f64>)], columns: usize, lines: usize) -> Option<String> {
+    let values : Vec<f64> = points.iter().filter_map(|p| p.1).collect();
+    if values.is_empty() {
+        return None;
+    }
+    let (mut y_min, mut y_max) = values.iter().fold((values[0], values[0]), |(lo, hi), y| (lo.min(*y), hi.max(*y)));
+    if y_min == y_max {
+        // a constant is plotted in the middle of the plot area
+        y_min -= 1.0;
+        y_max += 1.0;
+    }
+    let (x_min, x_max) = (points[0].0, points[points.len() - 1].0);
+
+    let labels = (format_label(y_max), format_label(y_min));
+    let label_width = labels.0.chars().count().max(labels.1.chars().count());
+    let width = columns.saturating_sub(label_width + 2).max(MIN_WIDTH);
+    let height = lines.saturating_sub(RESERVED_LINES).max(MIN_HEIGHT);
+    let (dots_x, dots_y) = (2 * width - 1, 4 * height - 1);
+
+    let to_dot = |x: f64, y: f64| {
+        let dot_x = if x_max == x_min { 0.0 } else { (x - x_min) / (x_max - x_min) * dots_x as f64 };
+        (dot_x.round() as usize, ((y_max - y) / (y_max - y_min) * dots_y as f64).round() as usize)
+    };
+    let mut canvas = BrailleCanvas::new(width, height);
+    let mut previous = None;
+    for &(x, y) in points.iter() {
+        previous = match y {
+            Some(y) => {
+                let dot = to_dot(x, y);
+                match previous {
+                    Some(p) => canvas.line(p, dot),
+                    None => canvas.set(dot.0, dot.1)
+                }
+                Some(dot)
+            },
+            None => None
+        };
+    }
+
+    let zero_row = if y_min < 0.0 && y_max > 0.0 { Some(to_dot(x_min, 0.0).1 / 4) } else { None };
+    let mut plot = String::new();
+    for row in 0..height {
+        let label = if row == 0 {
+            labels.0.as_str()
+        }
+        else if row == height - 1 {
+            labels.1.as_str()
+        }
+        else if Some(row) == zero_row {
+            "0"
+        }
+        else {
+            ""
+        };
+        plot.push_str(&format!("{0:>1$} │{2}\n", label, label_width, canvas.get_line(row)));
+    }
+    plot.push_str(&format!("{0:1$} └{2}\n", "", label_width, "─".repeat(width)));
+    let x_labels = (format_label(x_min), format_label(x_max));
+    let gap = width.saturating_sub(x_labels.0.chars().count() + x_labels.1.chars().count()).max(1);
+    plot.push_str(&format!("{0:1$}  {2}{3}{4}\n", "", label_width, x_labels.0, " ".repeat(gap), x_labels.1));
+    Some(plot)
+}
+
+/// Formats the label of an axis with at most three decimal places (e.g. "3.142").
+/// Very large and very small numbers are formatted in scientific notation (e.g. "1.50e7").
+fn format_label(x: f64) -> String {
+    if x != 0.0 && (x.abs() >= 1e6 || x.abs() < 1e-3) {
+        return format!("{0:.2e}", x);
+    }
+    let s = format!("{0:.3}", x);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { String::from("0") } else { s.to_string() }
+}
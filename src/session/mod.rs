@@ -0,0 +1,399 @@
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use serde_json;
+use serde_json::{Map, Value};
+use termc_model::{get_result, get_statement_results, get_result_with_stats, split_statements, EvaluationStats};
+use termc_model::math_context::MathContext;
+use termc_model::math_result::MathResult;
+use termc_ui::TerminalUI;
+use command_library::{CommandType, CommandError, check_for_command, evaluate_numbered, print_warnings, load_context, save_context};
+use settings::{Settings, get_changed_setting};
+
+/// Defines the number of autosave files that are written in rotation.
+const AUTOSAVE_FILES : usize = 3;
+
+/// Defines when the context is saved automatically.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AutosaveMode {
+    /// The context is not saved automatically.
+    Off,
+    /// The context is saved when the specified number of minutes has passed since the last autosave.
+    Minutes(u64),
+    /// The context is saved after the specified number of successful assignments.
+    Assignments(usize)
+}
+
+impl fmt::Display for AutosaveMode {
+    /// Writes the autosave mode as it is used in the set autosave command (e.g. "5 min").
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AutosaveMode::Off => write!(f, "off"),
+            AutosaveMode::Minutes(n) => write!(f, "{0} min", n),
+            AutosaveMode::Assignments(k) => write!(f, "{0} assignments", k)
+        }
+    }
+}
+
+/// Defines a session of termc that evaluates the user inputs and commands of a terminal in a mathematical context.
+/// The terminal determines where the inputs are read from and where the results are written to,
+/// so that a session can also be run with scripted inputs (see TerminalUI::with_io).
+pub struct Session {
+    /// The terminal that reads the user inputs and prints the results.
+    terminal: TerminalUI,
+    /// The mathematical context of the session.
+    context: MathContext,
+    /// The default file path of the save and load commands.
+    default_file: String,
+    /// The autosave mode of the session.
+    autosave: AutosaveMode,
+    /// The time of the last autosave (or of the start of the session).
+    last_autosave: Instant,
+    /// The number of successful assignments since the last autosave.
+    assignments: usize,
+    /// The number of autosaves of the session.
+    autosave_count: usize,
+    /// The user settings that are changed with the set command.
+    settings: Settings,
+    /// The file path of the context that is loaded at startup (None if the default file is used).
+    startup_file: Option<String>,
+    /// Whether the context is saved to the startup file when the session ends.
+    save_on_exit: bool,
+    /// Whether the results of the call mode are printed on separate lines with the number of their input.
+    numbered_results: bool,
+    /// Whether the results of the call mode are printed on separate lines after their statements (e.g. "1+2 = 3").
+    echo: bool
+}
+
+impl Session {
+
+    /// Creates a new session with an empty context. The settings of the session are not stored (see load_settings).
+    pub fn new(terminal: TerminalUI, default_file: String) -> Session {
+        Session {terminal: terminal, context: MathContext::new(), default_file: default_file, autosave: AutosaveMode::Off,
+                 last_autosave: Instant::now(), assignments: 0, autosave_count: 0, settings: Settings::new(),
+                 startup_file: None, save_on_exit: false, numbered_results: false,
+                 echo: false}
+    }
+
+    /// Loads the user settings from the specified file and applies them to the session. The settings that are changed
+    /// with the set command are written back to the file. Invalid settings are reported and skipped.
+    /// If the file can not be read, the error is reported and the settings are not stored.
+    pub fn load_settings(&mut self, path: &str) {
+        let settings = match Settings::load(path) {
+            Ok(s) => s,
+            Err(e) => {
+                self.terminal.print_error(e);
+                return;
+            }
+        };
+
+        for command in settings.get_commands() {
+            match check_for_command(&command, &mut self.context, &mut self.terminal, self.default_file.clone()) {
+                Ok(Some(command)) => self.apply_session_command(&command),
+                Ok(None) => (),
+                Err(e) => {
+                    self.terminal.print(&format!("In settings file \"{0}\":\n", path));
+                    self.terminal.print_error(e);
+                }
+            }
+        }
+        self.settings = settings;
+    }
+
+    /// Returns the file path of the context that is loaded at startup and saved on exit: the startup file that is set
+    /// with the set startup command or the default file.
+    pub fn get_startup_path(&self) -> String {
+        self.startup_file.clone().unwrap_or(self.default_file.clone())
+    }
+
+    /// Loads the context from the startup file if the file exists. If the file can not be loaded, the error is reported.
+    pub fn load_startup_context(&mut self) {
+        let path = self.get_startup_path();
+        if Path::new(&path).exists() {
+            if let Err(e) = load_context(&path, &mut self.context) {
+                self.terminal.print_error(e);
+            }
+        }
+    }
+
+    /// Returns the mathematical context of the session.
+    #[cfg(test)]
+    pub fn get_context(&self) -> &MathContext {
+        &self.context
+    }
+
+    /// Returns the terminal of the session.
+    #[cfg(test)]
+    pub fn get_terminal(&self) -> &TerminalUI {
+        &self.terminal
+    }
+
+    /// Runs the read-eval-print loop of the interactive mode and the batch mode until the exit command is entered
+    /// (or the input ends). Empty inputs are skipped. The statements of an input (separated by ";") are evaluated in order
+    /// and each result is printed.
+    pub fn run(&mut self) {
+        loop {
+            self.terminal.set_input_number(self.context.get_transcript_len() + 1);
+            self.terminal.set_completion_symbols(self.context.get_symbol_names(), self.context.get_function_signatures());
+            let user_input = self.terminal.get_user_input();
+            let user_input = user_input.trim();
+
+            if user_input.len() == 0 {
+                continue;
+            }
+
+            match check_for_command(user_input, &mut self.context, &mut self.terminal, self.default_file.clone()) {
+                Ok(Some(CommandType::Exit)) => break,
+                Ok(Some(command)) => {
+                    self.apply_session_command(&command);
+                    self.store_setting(&command);
+                    self.terminal.print_cmd_ack();
+                },
+                Ok(None) => {
+                    // the statements of the input (separated by ";") are numbered evaluations of their own
+                    for statement in split_statements(user_input) {
+                        if !evaluate_numbered(statement, &mut self.context, &self.terminal) {
+                            break;
+                        }
+                        // evaluations without result are assignments
+                        let n = self.context.get_transcript_len();
+                        if self.context.get_output(n).is_none() {
+                            self.assignments += 1;
+                        }
+                    }
+                },
+                Err(e) => self.terminal.print_error(e)
+            }
+
+            self.check_autosave();
+        }
+
+        match self.terminal.save_history_file() {
+            Ok(_) => (),
+            Err(e) => self.terminal.print_error(e)
+        }
+        self.check_save_on_exit();
+    }
+
+    /// Evaluates the specified definitions and expressions of the call mode and prints the results as a ';'-separated list
+    /// (or on separate lines with the number of their expression or after their statements, see set_numbered_results and
+    /// set_echo).
+    /// An expression may consist of several statements separated by ";" (e.g. "a = 2; a^2"), which are evaluated as a batch.
+    /// The evaluation of the following expressions is aborted at the first error. Each result is printed with the format
+    /// that is active when it is computed (e.g. "format hex" only changes the following results).
+    pub fn run_call(&mut self, definitions: &[String], expressions: &[String]) {
+        if !self.apply_definitions(definitions) {
+            return;
+        }
+
+        // the results are formatted when they are produced, so that a later format command does not change them
+        let mut results : Vec<String> = Vec::new();
+        for (i, arg) in expressions.iter().enumerate() {
+
+            match check_for_command(arg, &mut self.context, &mut self.terminal, self.default_file.clone()) {
+                Ok(Some(CommandType::Exit)) => break,
+                Ok(Some(command)) => {
+                    self.apply_session_command(&command);
+                    self.store_setting(&command);
+                },
+                Ok(None) => {
+                    let statements = split_statements(arg);
+                    let statement_results = get_statement_results(arg, &mut self.context);
+                    print_warnings(&mut self.context, &self.terminal);
+                    match statement_results {
+                        Ok(y) => {
+                            let n = if self.numbered_results { Some(i + 1) } else { None };
+                            if self.echo {
+                                for (statement, x) in statements.iter().zip(y.iter()) {
+                                    self.terminal.print_echo(n, statement, x.as_ref());
+                                }
+                            }
+                            else if self.numbered_results {
+                                self.terminal.print_indexed_results(&y.into_iter().filter_map(|x| x).map(|x| (i + 1, x)).collect::<Vec<_>>());
+                            }
+                            else {
+                                results.extend(y.iter().filter_map(|x| x.as_ref()).map(|x| self.terminal.format_result(x)));
+                            }
+                        },
+                        Err(err) => {
+                            self.terminal.print(&format!("In input {0}:\n", i+1));
+                            self.terminal.print_error(err);
+                            break;
+                        }
+                    }
+                },
+                Err(e) => self.terminal.print_error(e)
+            }
+        }
+
+        if !self.echo && !self.numbered_results {
+            self.terminal.print_formatted_results(&results);
+        }
+        self.check_save_on_exit();
+    }
+
+    /// Sets whether the results of the call mode are printed on separate lines after their statements (e.g. "1+2 = 3").
+    /// The statements without result (e.g. assignments) are printed alone.
+    pub fn set_echo(&mut self, echo: bool) {
+        self.echo = echo;
+    }
+
+    /// Sets whether the results of the call mode are printed on separate lines with the number of their expression
+    /// (e.g. "1: 12") instead of a ';'-separated list.
+    pub fn set_numbered_results(&mut self, numbered_results: bool) {
+        self.numbered_results = numbered_results;
+    }
+
+    /// Evaluates the specified definitions and expressions of the call mode like run_call, but prints a JSON array with an
+    /// entry per statement: the input, the formatted result and its type (null for assignments), the durations of the
+    /// parsing and the evaluation (in microseconds), the warnings and the error (null if the evaluation succeeded).
+    /// Example: [{"error":null,"eval_time_us":12,"input":"2^10","parse_time_us":8,"result":"1024","type":"Real","warnings":[]}]
+    pub fn run_call_json(&mut self, definitions: &[String], expressions: &[String]) {
+        if !self.apply_definitions(definitions) {
+            return;
+        }
+
+        let mut entries : Vec<Value> = Vec::new();
+        'expressions: for arg in expressions.iter() {
+
+            match check_for_command(arg, &mut self.context, &mut self.terminal, self.default_file.clone()) {
+                Ok(Some(CommandType::Exit)) => break,
+                Ok(Some(command)) => {
+                    self.apply_session_command(&command);
+                    self.store_setting(&command);
+                },
+                Ok(None) => {
+                    for statement in split_statements(arg) {
+                        let (result, stats) = get_result_with_stats(statement, &mut self.context);
+                        let warnings = self.context.take_warnings();
+                        let failed = result.is_err();
+                        entries.push(self.build_stats_entry(statement, result, &stats, warnings));
+                        if failed {
+                            break 'expressions;
+                        }
+                    }
+                },
+                Err(e) => self.terminal.print_error(e)
+            }
+        }
+
+        self.terminal.print(&format!("{0}\n", Value::Array(entries)));
+        self.check_save_on_exit();
+    }
+
+    /// Sets the autosave mode of the session. The interval starts again at the time of the call.
+    pub fn set_autosave(&mut self, mode: AutosaveMode) {
+        self.autosave = mode;
+        self.last_autosave = Instant::now();
+        self.assignments = 0;
+    }
+
+    /// Sets whether the session runs in sandbox mode. In sandbox mode, the commands that access the file system are rejected.
+    pub fn set_sandbox(&mut self, sandbox: bool) {
+        self.context.set_sandbox(sandbox);
+    }
+
+    /// Applies the specified command if it changes a setting of the session (e.g. the autosave mode).
+    fn apply_session_command(&mut self, command: &CommandType) {
+        match *command {
+            CommandType::Autosave(mode) => self.set_autosave(mode),
+            CommandType::Startup(ref path) => self.startup_file = Some(path.clone()),
+            CommandType::SaveOnExit(save_on_exit) => self.save_on_exit = save_on_exit,
+            _ => ()
+        }
+    }
+
+    /// Saves the context to the startup file if saving on exit is enabled.
+    fn check_save_on_exit(&mut self) {
+        if self.save_on_exit {
+            if let Err(e) = save_context(&self.get_startup_path(), &mut self.context) {
+                self.terminal.print_error(e);
+            }
+        }
+    }
+
+    /// Stores the setting that is changed by the specified command (e.g. "set format hex") in the user settings.
+    fn store_setting(&mut self, command: &CommandType) {
+        if let Some((key, value)) = get_changed_setting(command) {
+            if let Err(e) = self.settings.set(&key, &value) {
+                self.terminal.print_error(e);
+            }
+        }
+    }
+
+    /// Gets the file path of the autosave file with the specified number (1 to AUTOSAVE_FILES), which is located next to
+    /// the default file (e.g. "termc_context.autosave1.json").
+    pub fn get_autosave_path(&self, n: usize) -> String {
+        let default_path = Path::new(&self.default_file);
+        let stem = default_path.file_stem().map_or(String::from("termc_context"), |s| s.to_string_lossy().into_owned());
+        default_path.with_file_name(format!("{0}.autosave{1}.json", stem, n)).to_string_lossy().into_owned()
+    }
+
+    /// Saves the context to the next autosave file if the interval of the autosave mode has passed.
+    /// The autosave files are written in rotation, so that the previous snapshots are kept if the session crashes while saving.
+    fn check_autosave(&mut self) {
+        let due = match self.autosave {
+            AutosaveMode::Off => false,
+            AutosaveMode::Minutes(n) => self.last_autosave.elapsed() >= Duration::from_secs(n * 60),
+            AutosaveMode::Assignments(k) => self.assignments >= k
+        };
+        if !due {
+            return;
+        }
+
+        let path = self.get_autosave_path(self.autosave_count % AUTOSAVE_FILES + 1);
+        match save_context(&path, &mut self.context) {
+            Ok(_) => self.autosave_count += 1,
+            Err(e) => self.terminal.print_error(e)
+        }
+        self.last_autosave = Instant::now();
+        self.assignments = 0;
+    }
+
+    /// Builds the JSON entry of the specified statement of the call mode with its result, durations and warnings.
+    fn build_stats_entry<E: fmt::Display>(&self, input: &str, result: Result<Option<MathResult>, E>, stats: &EvaluationStats,
+                                          warnings: Vec<String>) -> Value {
+        let micros = |d: Duration| serde_json::to_value(d.as_secs() * 1_000_000 + (d.subsec_nanos() / 1000) as u64).unwrap_or(Value::Null);
+        let (value, result_type, error) = match result {
+            Ok(Some(ref x)) => {
+                let result_type = if x.is_matrix() { String::from("Matrix") } else { format!("{0:?}", x.result_type) };
+                (Value::String(self.terminal.format_result(x)), Value::String(result_type), Value::Null)
+            },
+            Ok(None) => (Value::Null, Value::Null, Value::Null),
+            Err(err) => (Value::Null, Value::Null, Value::String(err.to_string()))
+        };
+
+        let mut entry = Map::new();
+        entry.insert(String::from("input"), Value::String(input.to_string()));
+        entry.insert(String::from("result"), value);
+        entry.insert(String::from("type"), result_type);
+        entry.insert(String::from("parse_time_us"), micros(stats.parse_time));
+        entry.insert(String::from("eval_time_us"), micros(stats.eval_time));
+        entry.insert(String::from("warnings"), Value::Array(warnings.into_iter().map(Value::String).collect()));
+        entry.insert(String::from("error"), error);
+        Value::Object(entry)
+    }
+
+    /// Evaluates the specified definitions (e.g. "g=9.81" or "f(x)=x^2").
+    /// Returns false if any of the definitions could not be evaluated or is not a definition.
+    fn apply_definitions(&mut self, definitions: &[String]) -> bool {
+        for (i, def) in definitions.iter().enumerate() {
+            let result = get_result(def.trim(), &mut self.context);
+            print_warnings(&mut self.context, &self.terminal);
+            match result {
+                Ok(None) => (),
+                Ok(Some(_)) => {
+                    self.terminal.print(&format!("In definition {0}:\n", i+1));
+                    self.terminal.print_error(CommandError::OptionError(format!("\"{0}\" does not define a constant or function", def)));
+                    return false;
+                },
+                Err(err) => {
+                    self.terminal.print(&format!("In definition {0}:\n", i+1));
+                    self.terminal.print_error(err);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
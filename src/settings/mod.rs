@@ -0,0 +1,149 @@
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use serde_json;
+use serde_json::{Map, Value};
+use command_library::{CommandType, CommandError, write_file_synced};
+use termc_ui::{HistorySettings, normalize_text};
+
+/// Defines the keys of the settings that can be changed with the set command.
+static SETTING_KEYS : [&'static str; 20] = ["format", "mode", "precision", "compare", "numbering", "dualdisplay", "optimize",
+    "permissive", "autosave", "startup", "saveonexit", "normalizehistory", "historysize", "historyfilesize", "historyignoredups",
+    "historyignorespace", "grouping", "autoans", "assignvalue", "nantracking"];
+
+/// Defines the keys of the settings that are applied by the command of the same name (e.g. "set format hex" applies "format hex").
+static COMMAND_SETTING_KEYS : [&'static str; 5] = ["format", "mode", "precision", "compare", "numbering"];
+
+/// Returns the keys of the settings that can be changed with the set command.
+pub fn get_setting_keys() -> Vec<&'static str> {
+    SETTING_KEYS.to_vec()
+}
+
+/// Returns the command that applies the specified value of the setting (e.g. "format hex" for the key "format").
+/// Returns None if the setting is not applied by a command of the same name.
+pub fn get_setting_command(key: & str, value: & str) -> Option<String> {
+    if COMMAND_SETTING_KEYS.contains(&key) {
+        Some(format!("{0} {1}", key, value))
+    }
+    else {
+        None
+    }
+}
+
+/// Returns the key and the value of the setting that is changed by the specified command (e.g. "optimize" and "on" for
+/// the command "set optimize on"). Returns None if the command does not change a setting.
+pub fn get_changed_setting(command: & CommandType) -> Option<(String, String)> {
+    let on_off = |b: bool| String::from(if b { "on" } else { "off" });
+    match *command {
+        CommandType::Set(ref key, ref value) => Some((key.clone(), value.clone())),
+        CommandType::DualDisplay(b) => Some((String::from("dualdisplay"), on_off(b))),
+        CommandType::Optimize(b) => Some((String::from("optimize"), on_off(b))),
+        CommandType::Permissive(b) => Some((String::from("permissive"), on_off(b))),
+        CommandType::Autosave(mode) => Some((String::from("autosave"), mode.to_string())),
+        CommandType::Startup(ref path) => Some((String::from("startup"), path.clone())),
+        CommandType::SaveOnExit(b) => Some((String::from("saveonexit"), on_off(b))),
+        CommandType::NormalizeHistory(b) => Some((String::from("normalizehistory"), on_off(b))),
+        CommandType::HistorySize(n) => Some((String::from("historysize"), n.to_string())),
+        CommandType::HistoryFileSize(n) => Some((String::from("historyfilesize"), n.to_string())),
+        CommandType::HistoryIgnoreDups(b) => Some((String::from("historyignoredups"), on_off(b))),
+        CommandType::HistoryIgnoreSpace(b) => Some((String::from("historyignorespace"), on_off(b))),
+        CommandType::AutoAns(b) => Some((String::from("autoans"), on_off(b))),
+        CommandType::AssignValue(b) => Some((String::from("assignvalue"), on_off(b))),
+        CommandType::NanTracking(b) => Some((String::from("nantracking"), on_off(b))),
+        CommandType::Grouping(separator) => Some((String::from("grouping"), separator.map_or(String::from("off"), |c| c.to_string()))),
+        _ => None
+    }
+}
+
+/// Defines the user settings of termc (e.g. the number format and the angle mode), which are stored in a JSON file
+/// as the values of the set command (e.g. {"format": "hex", "mode": "deg"}).
+pub struct Settings {
+    /// The path of the settings file (None if the settings are not stored).
+    path: Option<String>,
+    /// The values of the settings.
+    values: Map<String, Value>
+}
+
+impl Settings {
+
+    /// Creates new settings without values that are not stored in a file.
+    pub fn new() -> Settings {
+        Settings {path: None, values: Map::new()}
+    }
+
+    /// Loads the settings from the specified file. If the file does not exist, the settings are empty and the file is
+    /// created when the first setting is changed.
+    pub fn load(path: & str) -> Result<Settings, CommandError> {
+        let mut s = String::new();
+        match File::open(path).and_then(|mut f| f.read_to_string(& mut s)) {
+            Ok(_) => (),
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(Settings {path: Some(path.to_string()), values: Map::new()}),
+            Err(e) => return Err(CommandError::SettingsError(format!(
+                "Error: Unable to read the settings file \"{0}\" ({1}).", path, e)))
+        }
+
+        match serde_json::from_str::<Map<String, Value>>(&normalize_text(&s)) {
+            Ok(values) => Ok(Settings {path: Some(path.to_string()), values: values}),
+            Err(e) => Err(CommandError::SettingsError(format!(
+                "Error: Unable to parse the settings file \"{0}\" ({1}).", path, e)))
+        }
+    }
+
+    /// Returns the set commands that apply the values of the settings (e.g. "set format hex").
+    pub fn get_commands(& self) -> Vec<String> {
+        self.values.keys().map(|key| format!("set {0} {1}", key, self.get_value(key).unwrap())).collect()
+    }
+
+    /// Returns the history settings of the terminal (e.g. the maximum numbers of entries of the settings "historysize" and
+    /// "historyfilesize").
+    /// The terminal needs them when it is created, as it loads the history file. Missing and invalid values are replaced by
+    /// the default settings (the invalid values are reported when the set commands are applied).
+    pub fn get_history_settings(& self) -> HistorySettings {
+        let mut history_settings = HistorySettings::default();
+        if let Some(n) = self.get_value("historysize").and_then(|v| v.parse::<usize>().ok()) {
+            history_settings.max_entries = n;
+        }
+        if let Some(n) = self.get_value("historyfilesize").and_then(|v| v.parse::<usize>().ok()) {
+            history_settings.max_file_entries = n;
+        }
+        let get_on_off = |key: & str| match self.get_value(key) {
+            Some(ref v) if v == "on" => Some(true),
+            Some(ref v) if v == "off" => Some(false),
+            _ => None
+        };
+        history_settings.ignore_dups = get_on_off("historyignoredups").unwrap_or(history_settings.ignore_dups);
+        history_settings.ignore_space = get_on_off("historyignorespace").unwrap_or(history_settings.ignore_space);
+        history_settings.normalize = get_on_off("normalizehistory").unwrap_or(history_settings.normalize);
+        history_settings
+    }
+
+    /// Returns the value of the specified setting as it is written in the set command (None if it is not set).
+    fn get_value(& self, key: & str) -> Option<String> {
+        self.values.get(key).map(|value| match *value {
+            Value::String(ref s) => s.clone(),
+            ref v => v.to_string()
+        })
+    }
+
+    /// Sets the value of the specified setting and writes the settings to the settings file (if the settings are stored).
+    pub fn set(& mut self, key: & str, value: & str) -> Result<(), CommandError> {
+        self.values.insert(key.to_string(), Value::String(value.to_string()));
+        match self.path {
+            Some(ref p) => write_settings(p, &self.values),
+            None => Ok(())
+        }
+    }
+}
+
+/// Writes the specified values of the settings to the specified file.
+fn write_settings(p: & str, values: & Map<String, Value>) -> Result<(), CommandError> {
+    let serialization = match serde_json::to_string_pretty(values) {
+        Ok(s) => s,
+        Err(e) => return Err(CommandError::SettingsError(format!("Error: Unable to serialize the settings ({0}).", e)))
+    };
+
+    // the settings file is replaced crash-safely like the saved contexts
+    match write_file_synced(p, serialization.as_ref()) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(CommandError::SettingsError(format!("Error: Unable to write the settings file \"{0}\" ({1}).", p, e)))
+    }
+}
@@ -0,0 +1,495 @@
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io::{self, Cursor, Write};
+use std::path::Path;
+use std::rc::Rc;
+use termc_ui::{TerminalUI, TerminalMode};
+use session::Session;
+use settings::Settings;
+use plot;
+use serde_json;
+use serde_json::Value;
+
+/// An output that can be inspected after it has been moved into a terminal.
+#[derive(Clone)]
+struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl SharedOutput {
+    fn new() -> SharedOutput {
+        SharedOutput(Rc::new(RefCell::new(Vec::new())))
+    }
+
+    fn get_string(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs a batch mode session with the specified inputs and returns the session, the output and the error output.
+fn run_batch(inputs: &'static str) -> (Session, String, String) {
+    let output = SharedOutput::new();
+    let error_output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new(inputs)), Box::new(output.clone()),
+                                       Box::new(error_output.clone()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.run();
+    (session, output.get_string(), error_output.get_string())
+}
+
+#[test]
+fn tst_session_run() {
+    let (session, output, error_output) = run_batch("x = 2\n\nx^10\nformat hex\n255\nf(y) = y + 1\nf(x)\n");
+    assert!(output == "1024\n0xff\n0x3\n");
+    assert!(error_output == "");
+    assert!(session.get_context().is_user_function("f"));
+
+    // errors do not abort the session, the exit command does
+    let (_, output, error_output) = run_batch("1/0 +\n2*3\nexit\n4\n");
+    assert!(output == "6\n");
+    assert!(error_output == "Error: Expression is incomplete.\n");
+
+    // a byte order mark and the line endings of Windows are accepted
+    let (_, output, error_output) = run_batch("\u{feff}x = 3\r\nformat hex\r\nx^2\r\n");
+    assert!(output == "0x9\n");
+    assert!(error_output == "");
+}
+
+#[test]
+fn tst_session_run_nested() {
+    let dir = env::temp_dir().join("termc_tst_session_run_nested");
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("script.termc").to_string_lossy().into_owned();
+    fs::write(&script, format!("x = x + 1\nrun \"{0}\"\n", script)).unwrap();
+
+    // a script that runs itself stops at the maximum nesting depth
+    let output = SharedOutput::new();
+    let error_output = SharedOutput::new();
+    let inputs = format!("x = 0\nrun \"{0}\"\nx\n", script);
+    let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new(inputs)), Box::new(output.clone()),
+                                       Box::new(error_output.clone()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.run();
+    assert!(output.get_string() == "16\n");
+    assert!(error_output.get_string().contains("Error: The scripts are nested too deeply (at most 16 levels"));
+}
+
+#[test]
+fn tst_session_run_statements() {
+    let dir = env::temp_dir().join("termc_tst_session_run_statements");
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("script.termc").to_string_lossy().into_owned();
+    fs::write(&script, "a = 2; b = 3; a*b\nm = [1, 2; 3, 4]; det(m);\nc = 1; c +; d = 4\n").unwrap();
+
+    let output = SharedOutput::new();
+    let error_output = SharedOutput::new();
+    let inputs = format!("run \"{0}\"\n", script);
+    let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new(inputs)), Box::new(output.clone()),
+                                       Box::new(error_output.clone()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.run();
+    assert!(output.get_string() == "6\n-2\n");
+    // the statements before the failing statement are kept
+    assert!(error_output.get_string().contains("line 3:"));
+    assert!(session.get_context().is_user_constant("c") && !session.get_context().is_user_constant("d"));
+}
+
+#[test]
+fn tst_session_run_call() {
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+
+    let definitions = vec![String::from("g = 9.81")];
+    let expressions = vec![String::from("2*g"), String::from("precision exact"), String::from("1/3 + 1/6")];
+    session.run_call(&definitions, &expressions);
+    assert!(output.get_string() == "19.62;1/2\n");
+
+    // the statements after a failing statement do not change the context
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(io::sink()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.run_call(&[], &[String::from("a = 1; 1/; b = 2")]);
+    assert!(session.get_context().is_user_constant("a") && !session.get_context().is_user_constant("b"));
+}
+
+#[test]
+fn tst_session_ans_step() {
+    let (_, output, error_output) = run_batch("++\n5\n++\nans++\n--\nans * 2\n");
+    assert!(output == "5\n6\n7\n6\n12\n");
+    assert!(error_output.contains("Error: Expected a previous result \"ans\" to increment.\n++\n^~~~"));
+}
+
+#[test]
+fn tst_session_freeze() {
+    let (_, output, error_output) = run_batch("a = 2\nf(x) = x + a\nfreeze f\na = 5\nf(1)\nfreeze f\nfreeze q\nfreeze\n");
+    assert!(output == "Inserted the current value(s) of \"a\" into the function \"f\".\n3\nThe function \"f\" does not depend on constants.\n");
+    assert!(error_output.contains("Error: Unknown user defined function \"q\".\nfreeze q\n       ^~~~"));
+    assert!(error_output.contains("Error: Expected the name of a user defined function.\nfreeze\n      ^~~~"));
+}
+
+#[test]
+fn tst_session_simplify() {
+    let (_, output, error_output) = run_batch("simplify(2*x + 3*x)\nsimplify (y*1 + 0)\nsimplify(2*(x)\n");
+    assert!(output == "5*x\ny\n");
+    assert!(error_output.starts_with("Error:"));
+}
+
+#[test]
+fn tst_session_why() {
+    let (session, output, error_output) = run_batch("why\nset nantracking on\nexp(1000) - exp(1000)\nwhy\n1 + 2\nwhy\nset nantracking\n");
+    assert!(session.get_context().get_non_finite_tracking());
+    assert!(output == "NaN\nThe value inf was first produced by the function \"exp\" with the argument 1000.\nexp(1000) - exp(1000)\n  ^~~~\n\
+        3\nNo operation or function call of the last evaluation produced a NaN or infinite value from finite operands.\n");
+    assert!(error_output.starts_with("Error: The origins of NaN and infinite values are not tracked (see \"set nantracking\")."));
+    assert!(error_output.contains("Error: Expected NaN tracking mode \"on\" or \"off\".\nset nantracking\n               ^~~~"));
+}
+
+#[test]
+fn tst_session_del() {
+    let (session, output, error_output) = run_batch("x = 2\nf(y) = y\nc = 3\ndel x f\nc\n");
+    assert!(output == "3\n");
+    assert!(error_output == "");
+    assert!(!session.get_context().is_user_constant("x"));
+    assert!(!session.get_context().is_user_function("f"));
+
+    // nothing is deleted if any of the names is built-in or undefined
+    let (session, _, error_output) = run_batch("x = 2\ndel x pi\ndel x z\n");
+    assert!(error_output.contains("Error: Can not delete the built-in constant \"pi\".\ndel x pi\n       ^~~~"));
+    assert!(error_output.contains("Error: Unknown user defined constant or function \"z\".\ndel x z\n      ^~~~"));
+    assert!(session.get_context().is_user_constant("x"));
+
+    let (_, _, error_output) = run_batch("del sin\ndel\n");
+    assert!(error_output.contains("Error: Can not delete the built-in function \"sin\"."));
+    assert!(error_output.contains("Error: Expected the name of a user defined constant or function.\ndel\n   ^~~~"));
+}
+
+#[test]
+fn tst_session_save_path() {
+    let (_, output, error_output) = run_batch("get savepath\n");
+    assert!(output == "termc_context.json\n");
+    assert!(error_output == "");
+}
+
+#[test]
+fn tst_session_autosave() {
+    let dir = env::temp_dir().join("termc_tst_session_autosave");
+    fs::create_dir_all(&dir).unwrap();
+    let default_file = dir.join("ctx.json").to_string_lossy().into_owned();
+
+    let inputs = "set autosave 2 assignments\nx = 1\n2*x\nf(a) = a\ny = 3\nz = 4\nset autosave off\nu = 5\nv = 6\n";
+    let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new(inputs)), Box::new(io::sink()),
+                                       Box::new(io::sink()));
+    let mut session = Session::new(terminal, default_file.clone());
+    for n in 1..4 {
+        fs::remove_file(session.get_autosave_path(n)).ok();
+    }
+    session.run();
+
+    // the assignments "x" and "f" as well as "y" and "z" trigger an autosave, evaluations with results do not
+    assert!(session.get_autosave_path(1) == dir.join("ctx.autosave1.json").to_string_lossy());
+    let first = fs::read_to_string(session.get_autosave_path(1)).unwrap();
+    assert!(first.contains("\"f\"") && !first.contains("\"z\""));
+    let second = fs::read_to_string(session.get_autosave_path(2)).unwrap();
+    assert!(second.contains("\"z\"") && !second.contains("\"u\""));
+    assert!(!Path::new(&session.get_autosave_path(3)).exists());
+
+    let error_output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new("set autosave 0 min\nset autosave\n")),
+                                       Box::new(io::sink()), Box::new(error_output.clone()));
+    Session::new(terminal, default_file).run();
+    assert!(error_output.get_string().contains("Error: Unknown autosave interval \"0 min\""));
+    assert!(error_output.get_string().contains("Error: Expected autosave interval"));
+}
+
+#[test]
+fn tst_session_warnings() {
+    let (_, output, error_output) = run_batch("f(x, y) = 2*x\nf(3, 4)\n");
+    assert!(output == "6\n");
+    assert!(error_output == "Warning: The parameter \"y\" of the function \"f\" is not used.\n");
+}
+
+#[test]
+fn tst_plot_render() {
+    // a line from the lower left to the upper right corner of a plot area of 11x4 characters
+    let plot = plot::render(&[(0.0, Some(0.0)), (1.0, Some(1.0))], 14, 7).unwrap();
+    let lines : Vec<&str> = plot.lines().collect();
+    assert!(lines.len() == 6);
+    assert!(lines[0].starts_with("1 │") && lines[0].ends_with("⠊"));
+    assert!(lines[3].starts_with("0 │⡠"));
+    assert!(lines[4] == "  └───────────");
+    assert!(lines[5] == "   0         1");
+
+    // sample points without values are not plotted
+    assert!(plot::render(&[(0.0, None), (1.0, None)], 80, 24).is_none());
+}
+
+#[test]
+fn tst_session_plot() {
+    let (_, output, error_output) = run_batch("plot sin(x) from -pi to pi samples 50
+");
+    assert!(output.contains("-3.142") && output.contains("3.142"));
+    assert!(error_output == "");
+
+    let (_, _, error_output) = run_batch("plot ln(x) from -2 to -1
+plot x from 0 to 1 samples 1
+");
+    assert!(error_output.contains("Error: The expression \"ln(x)\" has no finite real values from -2 to -1."));
+    assert!(error_output.contains("Error: Expected 2 to 100000 samples (found \"1\")."));
+}
+
+#[test]
+fn tst_session_format_base() {
+    let (_, output, error_output) = run_batch("format base 36\n1261\nformat base\n1/4\nformat\nformat base 37\n");
+    assert!(output == "0r36:z1\n0r36:0.9\nformat = base 36\n");
+    assert!(error_output.contains("Error: Expected radix from 2 to 36."));
+}
+
+#[test]
+fn tst_session_settings() {
+    let dir = env::temp_dir().join("termc_tst_session_settings");
+    fs::create_dir_all(&dir).unwrap();
+    let settings_file = dir.join("settings.json").to_string_lossy().into_owned();
+    fs::write(&settings_file, "{\"format\": \"hex\", \"mode\": \"deg\"}").unwrap();
+
+    // the settings are applied before the first input and the set command changes the settings file
+    let output = SharedOutput::new();
+    let error_output = SharedOutput::new();
+    let inputs = "255\nsin(90)\nset optimize on\nset precision exact\nset format\nset colour on\n";
+    let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new(inputs)), Box::new(output.clone()),
+                                       Box::new(error_output.clone()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.load_settings(&settings_file);
+    session.run();
+    assert!(output.get_string() == "0xff\n0x1\n");
+    assert!(error_output.get_string().contains("Error: Expected a value of the setting \"format\".\nset format\n          ^~~~"));
+    assert!(error_output.get_string().contains("Error: Unknown setting \"colour\" (expected \"format\", \"mode\", \"precision\", \
+        \"compare\", \"numbering\", \"dualdisplay\", \"optimize\", \"permissive\", \"autosave\", \"startup\", \"saveonexit\", \"normalizehistory\", \"historysize\", \"historyfilesize\", \"historyignoredups\", \"historyignorespace\", \"grouping\", \"autoans\", \"assignvalue\" or \"nantracking\").\nset colour on\n         ^~~~"));
+
+    let settings = fs::read_to_string(&settings_file).unwrap();
+    assert!(settings.contains("\"format\": \"hex\"") && settings.contains("\"mode\": \"deg\""));
+    assert!(settings.contains("\"optimize\": \"on\"") && settings.contains("\"precision\": \"exact\""));
+    assert!(!settings.contains("colour"));
+    // the settings file is replaced by a synchronized temporary file, which does not remain
+    assert!(!Path::new(&format!("{0}.tmp", settings_file)).exists());
+
+    // a new session starts with the stored settings
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new("255\n")), Box::new(output.clone()),
+                                       Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.load_settings(&settings_file);
+    session.run();
+    assert!(output.get_string() == "0xff\n");
+    assert!(session.get_context().get_optimize());
+}
+
+#[test]
+fn tst_session_history_settings() {
+    let dir = env::temp_dir().join("termc_tst_session_history_settings");
+    fs::create_dir_all(&dir).unwrap();
+    let settings_file = dir.join("settings.json").to_string_lossy().into_owned();
+    fs::remove_file(&settings_file).ok();
+    let entries : Vec<String> = vec!["1+1", "sin(2)", "cos(0)", "cos(0)"].iter().map(|s| s.to_string()).collect();
+    assert!(Settings::new().get_history_settings().prune(&entries) == vec!["1+1", "sin(2)", "cos(0)"]);
+
+    // the set commands change the history settings of the terminal and store them
+    let error_output = SharedOutput::new();
+    let inputs = "set historysize 5\nset historyfilesize 3\nset historyignoredups off\nset historyignorespace\nset historysize many\n\
+                  set historyfilesize\n";
+    let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new(inputs)), Box::new(io::sink()),
+                                       Box::new(error_output.clone()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.load_settings(&settings_file);
+    session.run();
+    assert!(session.get_terminal().get_history_settings().prune(&entries) == vec!["sin(2)", "cos(0)", "cos(0)"]);
+    assert!(session.get_terminal().get_history_settings().max_entries == 5);
+    assert!(error_output.get_string().contains("Error: Expected a value of the setting \"historyfilesize\".\nset historyfilesize\n                   ^~~~"));
+    assert!(error_output.get_string().contains("Error: Expected history ignore space mode \"on\" or \"off\"."));
+    assert!(error_output.get_string().contains("Error: Unknown history size \"many\" (expected a number of entries).\nset historysize many\n                   ^~~~"));
+
+    // the stored settings change the pruning of the history file of the next session
+    let settings = Settings::load(&settings_file).unwrap();
+    assert!(settings.get_history_settings().prune(&entries) == vec!["sin(2)", "cos(0)", "cos(0)"]);
+    assert!(settings.get_history_settings().max_entries == 5);
+}
+
+#[test]
+fn tst_session_whatis() {
+    let (_, output, error_output) = run_batch("c = 4.1\nwhatis tan\nwhatis c\nwhatis q\n");
+    assert!(output == "built-in function, 1 argument, trigonometric\nuser constant = 4.1 (Real), defined this session\nunknown symbol\n");
+    assert!(error_output == "");
+}
+
+#[test]
+fn tst_session_startup_context() {
+    let dir = env::temp_dir().join("termc_tst_session_startup_context");
+    fs::create_dir_all(&dir).unwrap();
+    let default_file = dir.join("ctx.json").to_string_lossy().into_owned();
+    let startup_file = dir.join("startup.json").to_string_lossy().into_owned();
+    fs::remove_file(&default_file).ok();
+    fs::remove_file(&startup_file).ok();
+
+    // runs a batch mode session that loads the startup context and returns the session and the output
+    let run_startup = |inputs: &'static str| {
+        let output = SharedOutput::new();
+        let terminal = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new(inputs)), Box::new(output.clone()),
+                                           Box::new(output.clone()));
+        let mut session = Session::new(terminal, default_file.clone());
+        session.load_startup_context();
+        session.run();
+        (session, output.get_string())
+    };
+
+    // nothing is loaded if the startup file does not exist, the context is saved on exit if enabled
+    let (session, output) = run_startup("x = 2\nset saveonexit on\nf(a) = a + x\n");
+    assert!(output == "");
+    assert!(session.get_startup_path() == default_file);
+    let (_, output) = run_startup("f(3)\n");
+    assert!(output == "5\n");
+
+    // another startup file can be set
+    let (session, output) = run_startup("set startup \"startup.json\"\nset startup\nset saveonexit maybe\n");
+    assert!(output.contains("Error: Expected a value of the setting \"startup\".\nset startup\n           ^~~~"));
+    assert!(output.contains("Error: Unknown save on exit mode \"maybe\" (expected \"on\" or \"off\")."));
+    assert!(session.get_startup_path() == "startup.json");
+}
+
+#[test]
+fn tst_session_statements() {
+    let (session, output, error_output) = run_batch("a = 2; b = 3; a*b\nm = [1, 2; 3, 4]; det(m);\nc = 1; c +; d = 4\n");
+    assert!(output == "6\n-2\n");
+    assert!(error_output == "Error: Expression is incomplete.\n");
+    // the statements before the error are evaluated, the statements after the error are not
+    assert!(session.get_context().is_user_constant("c") && !session.get_context().is_user_constant("d"));
+    assert!(session.get_context().get_transcript_input(3) == Some(String::from("a*b")));
+
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.run_call(&[], &[String::from("x = 2; x^2; x^3"), String::from("x + 1")]);
+    assert!(output.get_string() == "4;8;3\n");
+}
+
+#[test]
+fn tst_session_run_call_numbered() {
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.set_numbered_results(true);
+    session.run_call(&[], &[String::from("2*6"), String::from("x = 2"), String::from("format hex"), String::from("x^2; x^3")]);
+    assert!(output.get_string() == "1: 12\n4: 0x4\n4: 0x8\n");
+}
+
+#[test]
+fn tst_session_run_call_echo() {
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.set_echo(true);
+    session.run_call(&[], &[String::from("1+2"), String::from("x = 2; x^10")]);
+    assert!(output.get_string() == "1+2 = 3\nx = 2\nx^10 = 1024\n");
+
+    // the echo can be numbered
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.set_echo(true);
+    session.set_numbered_results(true);
+    session.run_call(&[], &[String::from("format hex"), String::from("255")]);
+    assert!(output.get_string() == "2: 255 = 0xff\n");
+}
+
+#[test]
+fn tst_session_sandbox() {
+    let path = env::temp_dir().join("termc_tst_session_sandbox.json");
+    let path_str = path.to_string_lossy().into_owned();
+    let _ = fs::remove_file(&path);
+
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, path_str.clone());
+    session.set_sandbox(true);
+    session.run_call(&[], &[String::from("save"), String::from("2+3")]);
+    // errors are printed to the output in call mode
+    assert!(output.get_string().contains("Error: The command \"save\" is not permitted in sandbox mode.\nsave\n^~~~"));
+    assert!(!path.exists());
+
+    // the evaluation is not restricted
+    let output = SharedOutput::new();
+    let error_output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()),
+                                       Box::new(error_output.clone()));
+    let mut session = Session::new(terminal, path_str);
+    session.set_sandbox(true);
+    session.run_call(&[], &[String::from("x = 2"), String::from("x + 3")]);
+    assert!(output.get_string() == "5\n");
+    assert!(error_output.get_string() == "");
+}
+
+#[test]
+fn tst_session_history_unavailable() {
+    // the history is only available in interactive mode
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.run_call(&[], &[String::from("history search sin"), String::from("1+2")]);
+    assert!(output.get_string().contains("Error: The history is only available in interactive mode.\nhistory search sin\n^~~~"));
+    assert!(output.get_string().ends_with("3\n"));
+}
+
+#[test]
+fn tst_session_run_call_json() {
+    let output = SharedOutput::new();
+    let terminal = TerminalUI::with_io(TerminalMode::Call, Box::new(io::empty()), Box::new(output.clone()), Box::new(io::sink()));
+    let mut session = Session::new(terminal, String::from("termc_context.json"));
+    session.run_call_json(&[], &[String::from("x = 2; x^10"), String::from("format hex"), String::from("255"),
+                                 String::from("1/"), String::from("3")]);
+
+    let entries : Value = serde_json::from_str(&output.get_string()).unwrap();
+    let entries = entries.as_array().unwrap();
+    // the commands have no entries and the evaluation stops at the first error
+    assert!(entries.len() == 4);
+    assert!(entries[0]["input"] == Value::String(String::from("x = 2")) && entries[0]["result"].is_null());
+    assert!(entries[1]["result"] == Value::String(String::from("1024")) && entries[1]["type"] == Value::String(String::from("Real")));
+    assert!(entries[2]["result"] == Value::String(String::from("0xff")));
+    assert!(entries[1]["parse_time_us"].is_u64() && entries[1]["eval_time_us"].is_u64());
+    // the error is the text that the interactive mode prints
+    assert!(entries[3]["error"] == Value::String(String::from("Error: Expression is incomplete.")));
+}
+
+#[test]
+fn tst_session_compare_error() {
+    let (_, _, error_output) = run_batch("compare abc\ncompare\n");
+    assert!(error_output.contains("Error: Unknown comparison mode \"abc\" (expected \"strict\" or \"modulus\").\ncompare abc\n          ^~~~"));
+    assert!(error_output.contains("Error: Expected comparison mode \"strict\" or \"modulus\".\ncompare\n       ^~~~"));
+}
+
+#[test]
+fn tst_session_precision_error() {
+    let (_, _, error_output) = run_batch("precision abc\nprecision\n");
+    assert!(error_output.contains("Error: Unknown precision mode \"abc\" (expected \"float\" or \"exact\").\nprecision abc\n            ^~~~"));
+    assert!(error_output.contains("Error: Expected precision mode \"float\" or \"exact\".\nprecision\n         ^~~~"));
+}
+
+#[test]
+fn tst_session_numbering_error() {
+    let (_, _, error_output) = run_batch("numbering abc\nnumbering\n");
+    assert!(error_output.contains("Error: Unknown numbering mode \"abc\" (expected \"on\" or \"off\").\nnumbering abc\n            ^~~~"));
+    assert!(error_output.contains("Error: Expected numbering mode \"on\" or \"off\".\nnumbering\n         ^~~~"));
+}
+
+#[test]
+fn tst_session_mode_error() {
+    let (_, _, error_output) = run_batch("mode abc\nmode\n");
+    assert!(error_output.contains("Error: Unknown angle mode \"abc\" (expected \"rad\", \"deg\" or \"grad\").\nmode abc\n       ^~~~"));
+    assert!(error_output.contains("Error: Expected angle mode \"rad\", \"deg\" or \"grad\".\nmode\n    ^~~~"));
+}
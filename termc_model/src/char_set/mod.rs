@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+use std::iter::FromIterator;
+
+/// Defines a set of characters with constant time lookup.
+/// ASCII characters are stored in a bitmap, all other characters are stored in a fallback HashSet.
+#[derive(Clone, Default, Debug)]
+pub struct CharSet {
+    /// The bitmap of the ASCII characters (bit n of the bitmap represents the character with code n).
+    ascii: [u64; 2],
+    /// The set of non-ASCII characters.
+    other: HashSet<char>
+}
+
+impl CharSet {
+
+    /// Creates a new empty CharSet instance.
+    pub fn new() -> CharSet {
+        CharSet {ascii: [0; 2], other: HashSet::new()}
+    }
+
+    /// Adds the specified character to the set.
+    pub fn insert(& mut self, c: char) {
+        let code = c as u32;
+        if code < 128 {
+            self.ascii[(code / 64) as usize] |= 1 << (code % 64);
+        }
+        else {
+            self.other.insert(c);
+        }
+    }
+
+    /// Checks whether the specified character is an element of the set.
+    pub fn contains(& self, c: & char) -> bool {
+        let code = *c as u32;
+        if code < 128 {
+            self.ascii[(code / 64) as usize] & (1 << (code % 64)) != 0
+        }
+        else {
+            self.other.contains(c)
+        }
+    }
+}
+
+impl FromIterator<char> for CharSet {
+
+    /// Creates a CharSet from the characters of the specified iterator.
+    fn from_iter<I: IntoIterator<Item=char>>(iter: I) -> CharSet {
+        let mut set = CharSet::new();
+        for c in iter {
+            set.insert(c);
+        }
+        set
+    }
+}
@@ -0,0 +1,127 @@
+use token::{Token, TokenType, SymbolicTokenType};
+use math_context::{MathContext, OperationType};
+use tree::TreeNode;
+
+/// Creates the canonical string representation of the specified expression tree.
+/// The canonical form does not depend on the whitespace or the redundant parentheses of the
+/// original user input: operations with a lower precedence than "*" (e.g. "+", "-" and "=") are
+/// surrounded by single spaces, all other operations are not, function arguments are separated
+/// by ", " and parentheses are only inserted where the precedence of the operations requires them.
+pub fn deparse(tree: & TreeNode<Token>, context: & MathContext) -> String {
+
+    match tree.content.get_type() {
+        TokenType::Operation => {
+            if is_quantity(tree, context) {
+                // a number with a unit is written like the user input (e.g. "3 km"), so that it can be read again
+                format!("{0} {1}", deparse(tree.successors[0].as_ref(), context), tree.successors[1].content)
+            }
+            else if tree.successors.len() == 1 {
+                deparse_unary(tree, context)
+            }
+            else if tree.successors.len() == 2 {
+                deparse_binary(tree, context)
+            }
+            else {
+                tree.content.get_value().to_string()
+            }
+        },
+
+        TokenType::Function | TokenType::UserFunction | TokenType::Symbol(SymbolicTokenType::UnknownFunction) => {
+            let args : Vec<String> = tree.successors.iter().map(|s| deparse(s, context)).collect();
+            format!("{0}({1})", tree.content, args.join(", "))
+        },
+
+        TokenType::Punctuation if tree.content.get_value() == "[" => {
+            // matrix: the successors are the rows, which have the elements as successors
+            let rows : Vec<String> = tree.successors.iter().map(|row| {
+                let elements : Vec<String> = row.successors.iter().map(|s| deparse(s, context)).collect();
+                elements.join(", ")
+            }).collect();
+            format!("[{0}]", rows.join("; "))
+        },
+
+        TokenType::Keyword => {
+            // scoped expression: the last successor is the body, the others are the temporary definitions
+            let (body, definitions) = tree.successors.split_last().unwrap();
+            let definitions : Vec<String> = definitions.iter().map(|s| deparse(s, context)).collect();
+            format!("{0}({1}) {2}", tree.content, definitions.join(", "), deparse(body, context))
+        },
+
+        _ => {
+            let repr : String = (& tree.content).into();
+            repr
+        }
+    }
+}
+
+/// Creates the canonical string representation of an unary operation.
+fn deparse_unary(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let operand = tree.successors[0].as_ref();
+    let operand_repr = deparse(operand, context);
+
+    // A postfix operation (e.g. "!") follows its operand, which must be put into parentheses if it is an operation.
+    if context.is_postfix_operation(tree.content.get_value()) {
+        return if operand.content.get_type() == TokenType::Operation && !context.is_postfix_operation(operand.content.get_value()) {
+            format!("({0}){1}", operand_repr, tree.content)
+        }
+        else {
+            format!("{0}{1}", operand_repr, tree.content)
+        };
+    }
+
+    // An unary operation only modifies a single operand, so a binary operand must be put into parentheses.
+    if is_binary(operand) {
+        format!("{0}({1})", tree.content, operand_repr)
+    }
+    else {
+        format!("{0}{1}", tree.content, operand_repr)
+    }
+}
+
+/// Creates the canonical string representation of a binary operation.
+fn deparse_binary(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let prec = get_precedence(tree, context);
+    let lhs = tree.successors[0].as_ref();
+    let rhs = tree.successors[1].as_ref();
+
+    // All binary operations except for the assignment are left associative, so the right operand needs parentheses if its
+    // precedence is equal to the precedence of the current operation (e.g. "a - (b - c)", but "a = b = c").
+    let mut lhs_repr = deparse(lhs, context);
+    if is_binary(lhs) && get_precedence(lhs, context) < prec {
+        lhs_repr = format!("({0})", lhs_repr);
+    }
+    let mut rhs_repr = deparse(rhs, context);
+    let right_associative = context.is_right_associative(tree.content.get_value());
+    if is_binary(rhs) && (get_precedence(rhs, context) < prec || (get_precedence(rhs, context) == prec && !right_associative)) {
+        rhs_repr = format!("({0})", rhs_repr);
+    }
+
+    // aliases of operations are printed with their canonical symbol (e.g. "**" as "^")
+    let op = match context.get_operation_type(tree.content.get_value()) {
+        Some(OperationType::Pow) => "^",
+        _ => tree.content.get_value()
+    };
+
+    if prec < context.get_operation_precedence("*").unwrap_or(0) {
+        format!("{0} {1} {2}", lhs_repr, op, rhs_repr)
+    }
+    else {
+        format!("{0}{1}{2}", lhs_repr, op, rhs_repr)
+    }
+}
+
+/// Checks whether the specified tree node is a number with a unit, which is read as the product of the number and the unit.
+fn is_quantity(tree: & TreeNode<Token>, context: & MathContext) -> bool {
+    is_binary(tree) && context.get_operation_type(tree.content.get_value()) == Some(OperationType::Mul)
+        && tree.successors[1].content.get_type() == TokenType::Unit
+}
+
+/// Checks whether the specified tree node is a binary operation.
+pub(crate) fn is_binary(tree: & TreeNode<Token>) -> bool {
+    tree.content.get_type() == TokenType::Operation && tree.successors.len() == 2
+}
+
+/// Returns the precedence of the operation of the specified tree node.
+pub(crate) fn get_precedence(tree: & TreeNode<Token>, context: & MathContext) -> u32 {
+    context.get_operation_precedence(tree.content.get_value()).unwrap_or(0)
+}
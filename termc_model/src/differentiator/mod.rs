@@ -0,0 +1,375 @@
+use token::{Token, TokenType, NumberType, SymbolicTokenType};
+use math_context::{MathContext, OperationType, FunctionType, AngleMode};
+use tree::TreeNode;
+use evaluator::EvaluationError;
+use error_templates::ExpectedErrorTemplate;
+
+/// Computes the derivative of the specified expression tree with respect to the specified variable.
+/// All constants and unknown symbols except the variable are treated as constants. Calls of user defined
+/// functions are substituted by their definitions. The resulting tree is simplified (e.g. "1*x" becomes "x").
+pub fn differentiate(tree: & TreeNode<Token>, var: & str, context: & MathContext, input: & str) -> Result<TreeNode<Token>, EvaluationError> {
+
+    match tree.content.get_type() {
+        TokenType::Number(_) | TokenType::Unit => Ok(number(0.0)),
+
+        TokenType::Constant | TokenType::UserConstant | TokenType::FunctionArg | TokenType::Symbol(SymbolicTokenType::UnknownConstant) => {
+            if tree.content.get_value() == var { Ok(number(1.0)) } else { Ok(number(0.0)) }
+        },
+
+        TokenType::Operation => {
+            let op_type = context.get_operation_type(tree.content.get_value());
+            if tree.successors.len() == 1 {
+                let du = differentiate(tree.successors[0].as_ref(), var, context, input)?;
+                match op_type {
+                    Some(OperationType::Sub) => Ok(neg(du)),
+                    Some(OperationType::Add) => Ok(du),
+                    _ => Err(error_not_differentiable(tree, input))
+                }
+            }
+            else if tree.successors.len() == 2 {
+                let u = tree.successors[0].as_ref();
+                let v = tree.successors[1].as_ref();
+                match op_type {
+                    Some(OperationType::Add) => Ok(add(differentiate(u, var, context, input)?, differentiate(v, var, context, input)?)),
+                    Some(OperationType::Sub) => Ok(sub(differentiate(u, var, context, input)?, differentiate(v, var, context, input)?)),
+                    Some(OperationType::Mul) => {
+                        // product rule: (u*v)' = u'*v + u*v'
+                        let du = differentiate(u, var, context, input)?;
+                        let dv = differentiate(v, var, context, input)?;
+                        Ok(add(mul(du, v.clone()), mul(u.clone(), dv)))
+                    },
+                    Some(OperationType::Div) => {
+                        // quotient rule: (u/v)' = (u'*v - u*v')/v^2
+                        let du = differentiate(u, var, context, input)?;
+                        let dv = differentiate(v, var, context, input)?;
+                        Ok(div(sub(mul(du, v.clone()), mul(u.clone(), dv)), pow(v.clone(), number(2.0))))
+                    },
+                    Some(OperationType::Pow) => differentiate_power(u, v, var, context, input),
+                    _ => Err(error_not_differentiable(tree, input))
+                }
+            }
+            else {
+                Err(error_not_differentiable(tree, input))
+            }
+        },
+
+        TokenType::Function => differentiate_function(tree, var, context, input),
+
+        TokenType::UserFunction => {
+            let args : Vec<& TreeNode<Token>> = tree.successors.iter().map(|s| s.as_ref()).collect();
+            match context.substitute_user_function_tree(tree.content.get_value(), args) {
+                Some(t) => differentiate(& t, var, context, input),
+                None => Err(error_not_differentiable(tree, input))
+            }
+        },
+
+        _ => Err(error_not_differentiable(tree, input))
+    }
+}
+
+/// Computes the derivative of the power u^v.
+fn differentiate_power(u: & TreeNode<Token>, v: & TreeNode<Token>, var: & str, context: & MathContext, input: & str) -> Result<TreeNode<Token>, EvaluationError> {
+    let du = differentiate(u, var, context, input)?;
+    let dv = differentiate(v, var, context, input)?;
+
+    if is_zero(& dv) {
+        // power rule: (u^c)' = c*u^(c-1)*u'
+        Ok(mul(mul(v.clone(), pow(u.clone(), sub(v.clone(), number(1.0)))), du))
+    }
+    else if is_zero(& du) {
+        // exponential rule: (c^v)' = c^v*ln(c)*v'
+        if u.content.get_type() == TokenType::Constant && u.content.get_value() == "e" {
+            Ok(mul(pow(u.clone(), v.clone()), dv))
+        }
+        else {
+            Ok(mul(mul(pow(u.clone(), v.clone()), function("ln", vec![u.clone()])), dv))
+        }
+    }
+    else {
+        // general rule: (u^v)' = u^v*(v'*ln(u) + v*u'/u)
+        let inner = add(mul(dv, function("ln", vec![u.clone()])), div(mul(v.clone(), du), u.clone()));
+        Ok(mul(pow(u.clone(), v.clone()), inner))
+    }
+}
+
+/// Computes the derivative of a built-in function call by applying the chain rule.
+fn differentiate_function(tree: & TreeNode<Token>, var: & str, context: & MathContext, input: & str) -> Result<TreeNode<Token>, EvaluationError> {
+    let f_type = context.get_function_type(tree.content.get_value());
+
+    // functions with two arguments are rewritten as powers
+    match f_type {
+        Some(FunctionType::Pow) => {
+            return differentiate_power(tree.successors[0].as_ref(), tree.successors[1].as_ref(), var, context, input);
+        },
+        Some(FunctionType::Root) => {
+            let exponent = div(number(1.0), tree.successors[1].as_ref().clone());
+            return differentiate_power(tree.successors[0].as_ref(), & exponent, var, context, input);
+        },
+        Some(FunctionType::Log) | Some(FunctionType::Log2) | Some(FunctionType::Log10) => {
+            let base = match f_type {
+                Some(FunctionType::Log2) => number(2.0),
+                Some(FunctionType::Log10) => number(10.0),
+                _ => tree.successors[1].as_ref().clone()
+            };
+            return differentiate_log(tree.successors[0].as_ref(), & base, var, context, input);
+        },
+        Some(FunctionType::If) => {
+            // the derivative of a conditional expression is taken branch by branch
+            let da = differentiate(tree.successors[1].as_ref(), var, context, input)?;
+            let db = differentiate(tree.successors[2].as_ref(), var, context, input)?;
+            return Ok(function("if", vec![tree.successors[0].as_ref().clone(), da, db]));
+        },
+        _ => ()
+    }
+
+    let u = tree.successors[0].as_ref().clone();
+    let du = differentiate(& u, var, context, input)?;
+    let u_sqr = || pow(u.clone(), number(2.0));
+
+    // the derivative of the outer function, evaluated at u
+    let outer = match f_type {
+        Some(FunctionType::Sin) => function("cos", vec![u.clone()]),
+        Some(FunctionType::Cos) => neg(function("sin", vec![u.clone()])),
+        Some(FunctionType::Tan) => div(number(1.0), pow(function("cos", vec![u.clone()]), number(2.0))),
+        Some(FunctionType::Cot) => neg(div(number(1.0), pow(function("sin", vec![u.clone()]), number(2.0)))),
+        Some(FunctionType::Sinh) => function("cosh", vec![u.clone()]),
+        Some(FunctionType::Cosh) => function("sinh", vec![u.clone()]),
+        Some(FunctionType::Tanh) => div(number(1.0), pow(function("cosh", vec![u.clone()]), number(2.0))),
+        Some(FunctionType::Coth) => neg(div(number(1.0), pow(function("sinh", vec![u.clone()]), number(2.0)))),
+        Some(FunctionType::ArcSin) => div(number(1.0), function("sqrt", vec![sub(number(1.0), u_sqr())])),
+        Some(FunctionType::ArcCos) => neg(div(number(1.0), function("sqrt", vec![sub(number(1.0), u_sqr())]))),
+        Some(FunctionType::ArcTan) => div(number(1.0), add(number(1.0), u_sqr())),
+        Some(FunctionType::ArcCot) => neg(div(number(1.0), add(number(1.0), u_sqr()))),
+        Some(FunctionType::ArcSinh) => div(number(1.0), function("sqrt", vec![add(u_sqr(), number(1.0))])),
+        Some(FunctionType::ArcCosh) => div(number(1.0), function("sqrt", vec![sub(u_sqr(), number(1.0))])),
+        Some(FunctionType::ArcTanh) | Some(FunctionType::ArcCoth) => div(number(1.0), sub(number(1.0), u_sqr())),
+        Some(FunctionType::Exp) => function("exp", vec![u.clone()]),
+        Some(FunctionType::Ln) => div(number(1.0), u.clone()),
+        Some(FunctionType::Abs) => function("sign", vec![u.clone()]),
+        Some(FunctionType::Sqrt) => div(number(1.0), mul(number(2.0), function("sqrt", vec![u.clone()]))),
+        _ => return Err(error_not_differentiable(tree, input))
+    };
+
+    // the trigonometric functions of degrees or gradians are functions of the angle scaled by pi/180 or pi/200
+    let mode = context.get_angle_mode();
+    if mode != AngleMode::Rad {
+        let to_rad = div(constant("pi"), number(mode.get_half_circle()));
+        match f_type {
+            Some(FunctionType::Sin) | Some(FunctionType::Cos) | Some(FunctionType::Tan) | Some(FunctionType::Cot) => {
+                return Ok(mul(mul(outer, to_rad), du));
+            },
+            Some(FunctionType::ArcSin) | Some(FunctionType::ArcCos) | Some(FunctionType::ArcTan) | Some(FunctionType::ArcCot) => {
+                return Ok(mul(div(outer, to_rad), du));
+            },
+            _ => ()
+        }
+    }
+
+    Ok(mul(outer, du))
+}
+
+/// Differentiates the logarithm of u to the base b.
+fn differentiate_log(u: & TreeNode<Token>, b: & TreeNode<Token>, var: & str, context: & MathContext, input: & str) -> Result<TreeNode<Token>, EvaluationError> {
+    let db = differentiate(b, var, context, input)?;
+    if is_zero(& db) {
+        // logarithm rule: (log_b(u))' = u'/(u*ln(b))
+        let du = differentiate(u, var, context, input)?;
+        Ok(div(du, mul(u.clone(), function("ln", vec![b.clone()]))))
+    }
+    else {
+        // change of base: log_b(u) = ln(u)/ln(b)
+        let quotient = div(function("ln", vec![u.clone()]), function("ln", vec![b.clone()]));
+        differentiate(& quotient, var, context, input)
+    }
+}
+
+/// Creates the error for expressions that can not be differentiated.
+fn error_not_differentiable(tree: & TreeNode<Token>, input: & str) -> EvaluationError {
+    EvaluationError::from(ExpectedErrorTemplate::new(input, "differentiable expression", Some(
+        format!("expression \"{0}\"", tree.content)), tree.content.get_end_pos()))
+}
+
+/// Creates a tree node of the specified real number. Negative numbers are created as unary operations.
+fn number(x: f64) -> TreeNode<Token> {
+    if x < 0.0 {
+        neg(number(-x))
+    }
+    else {
+        TreeNode::new(Token::new(TokenType::Number(NumberType::Real), format!("{0}", x), 0))
+    }
+}
+
+/// Creates a tree node of the specified built-in constant.
+fn constant(name: & str) -> TreeNode<Token> {
+    TreeNode::new(Token::new(TokenType::Constant, name.to_string(), 0))
+}
+
+/// Creates a call of the specified built-in function.
+fn function(name: & str, args: Vec<TreeNode<Token>>) -> TreeNode<Token> {
+    let mut node = TreeNode::new(Token::new(TokenType::Function, name.to_string(), 0));
+    node.successors = args.into_iter().map(Box::new).collect();
+    node
+}
+
+/// Creates the specified binary operation.
+fn operation(op: & str, lhs: TreeNode<Token>, rhs: TreeNode<Token>) -> TreeNode<Token> {
+    let mut node = TreeNode::new(Token::new(TokenType::Operation, op.to_string(), 0));
+    node.successors = vec![Box::new(lhs), Box::new(rhs)];
+    node
+}
+
+/// Returns the value of the specified tree node if it is a real number (or a negated real number).
+fn get_number(t: & TreeNode<Token>) -> Option<f64> {
+    match t.content.get_type() {
+        TokenType::Number(NumberType::Real) => t.content.get_value().parse::<f64>().ok(),
+        TokenType::Operation if t.content.get_value() == "-" && t.successors.len() == 1 => get_number(t.successors[0].as_ref()).map(|x| -x),
+        _ => None
+    }
+}
+
+/// Returns the operand of the specified tree node if it is a negation.
+fn get_negated(t: & TreeNode<Token>) -> Option<& TreeNode<Token>> {
+    if t.content.get_type() == TokenType::Operation && t.content.get_value() == "-" && t.successors.len() == 1 {
+        Some(t.successors[0].as_ref())
+    }
+    else {
+        None
+    }
+}
+
+/// Returns the denominator of the specified tree node if it is a quotient with the numerator 1.
+fn get_reciprocal(t: & TreeNode<Token>) -> Option<& TreeNode<Token>> {
+    if t.content.get_type() == TokenType::Operation && t.content.get_value() == "/" && t.successors.len() == 2
+        && is_one(t.successors[0].as_ref()) {
+        Some(t.successors[1].as_ref())
+    }
+    else {
+        None
+    }
+}
+
+/// Checks whether the specified tree node is a product.
+fn is_product(t: & TreeNode<Token>) -> bool {
+    t.content.get_type() == TokenType::Operation && t.content.get_value() == "*" && t.successors.len() == 2
+}
+
+/// Checks whether the specified tree node is the number 0.
+fn is_zero(t: & TreeNode<Token>) -> bool {
+    get_number(t) == Some(0.0)
+}
+
+/// Checks whether the specified tree node is the number 1.
+fn is_one(t: & TreeNode<Token>) -> bool {
+    get_number(t) == Some(1.0)
+}
+
+/// Creates the simplified negation of the specified tree.
+fn neg(t: TreeNode<Token>) -> TreeNode<Token> {
+    if is_zero(& t) {
+        return t;
+    }
+    if let Some(x) = get_negated(& t) {
+        return x.clone();
+    }
+    let mut node = TreeNode::new(Token::new(TokenType::Operation, String::from("-"), 0));
+    node.successors = vec![Box::new(t)];
+    node
+}
+
+/// Creates the simplified sum of the specified trees.
+fn add(lhs: TreeNode<Token>, rhs: TreeNode<Token>) -> TreeNode<Token> {
+    if let (Some(a), Some(b)) = (get_number(& lhs), get_number(& rhs)) {
+        return number(a + b);
+    }
+    if is_zero(& lhs) {
+        return rhs;
+    }
+    if is_zero(& rhs) {
+        return lhs;
+    }
+    if let Some(x) = get_negated(& rhs) {
+        return sub(lhs, x.clone());
+    }
+    operation("+", lhs, rhs)
+}
+
+/// Creates the simplified difference of the specified trees.
+fn sub(lhs: TreeNode<Token>, rhs: TreeNode<Token>) -> TreeNode<Token> {
+    if let (Some(a), Some(b)) = (get_number(& lhs), get_number(& rhs)) {
+        return number(a - b);
+    }
+    if is_zero(& rhs) {
+        return lhs;
+    }
+    if is_zero(& lhs) {
+        return neg(rhs);
+    }
+    if let Some(x) = get_negated(& rhs) {
+        return add(lhs, x.clone());
+    }
+    operation("-", lhs, rhs)
+}
+
+/// Creates the simplified product of the specified trees.
+fn mul(lhs: TreeNode<Token>, rhs: TreeNode<Token>) -> TreeNode<Token> {
+    if let (Some(a), Some(b)) = (get_number(& lhs), get_number(& rhs)) {
+        return number(a * b);
+    }
+    if is_zero(& lhs) || is_zero(& rhs) {
+        return number(0.0);
+    }
+    if is_one(& lhs) {
+        return rhs;
+    }
+    if is_one(& rhs) {
+        return lhs;
+    }
+    // negations are moved to the front of the product
+    if let Some(x) = get_negated(& lhs) {
+        return neg(mul(x.clone(), rhs));
+    }
+    if let Some(x) = get_negated(& rhs) {
+        return neg(mul(lhs, x.clone()));
+    }
+    // reciprocals are turned into quotients: (1/a)*b = b/a
+    if let Some(x) = get_reciprocal(& lhs) {
+        return div(rhs, x.clone());
+    }
+    if let Some(x) = get_reciprocal(& rhs) {
+        return div(lhs, x.clone());
+    }
+    // numbers are moved to the front of the product
+    if get_number(& rhs).is_some() {
+        return mul(rhs, lhs);
+    }
+    if is_product(& rhs) && get_number(rhs.successors[0].as_ref()).is_some() {
+        let (c, x) = (rhs.successors[0].as_ref().clone(), rhs.successors[1].as_ref().clone());
+        return mul(mul(c, lhs), x);
+    }
+    operation("*", lhs, rhs)
+}
+
+/// Creates the simplified quotient of the specified trees.
+fn div(lhs: TreeNode<Token>, rhs: TreeNode<Token>) -> TreeNode<Token> {
+    if is_zero(& lhs) {
+        return number(0.0);
+    }
+    if is_one(& rhs) {
+        return lhs;
+    }
+    if let Some(x) = get_negated(& lhs) {
+        return neg(div(x.clone(), rhs));
+    }
+    operation("/", lhs, rhs)
+}
+
+/// Creates the simplified power of the specified trees.
+fn pow(lhs: TreeNode<Token>, rhs: TreeNode<Token>) -> TreeNode<Token> {
+    if is_zero(& rhs) {
+        return number(1.0);
+    }
+    if is_one(& rhs) {
+        return lhs;
+    }
+    operation("^", lhs, rhs)
+}
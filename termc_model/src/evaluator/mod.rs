@@ -5,13 +5,40 @@ use std::f64;
 use std::str::FromStr;
 use std::fmt;
 use std::error::Error;
-use std::collections::HashSet;
+use std::mem;
+use std::collections::{HashSet, HashMap};
 use error_templates::ExpectedErrorTemplate;
 use num::complex::Complex;
-use math_context::{MathContext, OperationType, FunctionType};
-use token::{Token, TokenType, SymbolicTokenType, NumberType};
-use math_result::MathResult;
+use num::{BigInt, Num, Zero, pow};
+use math_context::{MathContext, OperationType, FunctionType, ComparisonMode, PrecisionMode, AngleMode, NonFiniteOrigin};
+use token::{Token, TokenType, SymbolicTokenType, NumberType, split_radix_literal};
+use math_result::{MathResult, Matrix, BigRational, Unit, Dimension};
 use tree::TreeNode;
+use deparser::deparse;
+
+/// The maximum number of nested user function calls (e.g. of recursive functions).
+const MAX_CALL_DEPTH : usize = 64;
+
+/// The maximum number of terms of a sum or a product (e.g. "sum(1/k^2, k, 1, 1000)").
+const MAX_SERIES_TERMS : i64 = 1_000_000;
+
+/// The absolute error tolerance of the numerical integration.
+const INTEGRATION_TOLERANCE : f64 = 1e-10;
+
+/// The maximum recursion depth of the adaptive Simpson quadrature.
+const MAX_INTEGRATION_DEPTH : u32 = 40;
+
+/// The maximum number of evaluations of the integrand of a numerical integration.
+const MAX_INTEGRAND_EVALUATIONS : usize = 100_000;
+
+/// The relative tolerance of the root search of the solve function.
+const SOLVE_TOLERANCE : f64 = 1e-12;
+
+/// The maximum number of secant iterations of the root search of the solve function.
+const MAX_SOLVE_ITERATIONS : usize = 100;
+
+/// The maximum number of decimal places or the maximum absolute exponent of number literals that are parsed exactly.
+const MAX_EXACT_LITERAL_SCALE : usize = 10_000;
 
 /// Defines the errors that may occur in the evaluation process.
 #[derive(Clone, Debug)]
@@ -90,7 +117,7 @@ impl<'a> From<MathResult> for EvaluationResult {
     fn from(res: MathResult) -> EvaluationResult {
 
         // Check if a complex MathResult object can be reduced to a real MathResult object
-        if res.result_type == NumberType::Complex && res.value.im == 0.0_f64 {
+        if res.result_type == NumberType::Complex && res.value.im == 0.0_f64 && !res.is_matrix() {
             EvaluationResult::Numerical(MathResult::from(res.value.re))
         }
         else {   
@@ -126,7 +153,11 @@ impl<'a> From<Complex<f64>> for EvaluationResult {
 /// The evaluator.
 pub struct Evaluator<'a> {
     /// The math context defining the mathematical environment.
-    context: &'a mut MathContext
+    context: &'a mut MathContext,
+    /// The stack of scopes with temporary definitions and function argument bindings (innermost scope last).
+    scopes: Vec<HashMap<String, MathResult>>,
+    /// The number of nested user function calls.
+    call_depth: usize
 }
 
 /// Provides parse-interface from strings.
@@ -139,15 +170,13 @@ trait RadixParse {
 }
 
 macro_rules! parse_radix {
-    ($s:ident, $base:expr, $end_pos:ident) => {{
+    ($s:ident, $digits:ident, $base:expr, $end_pos:ident) => {{
     // s: the string to be parsed
-    // base: the base of the number system (e.g. 2 (binary), 8 (octal) or 16 (hexadecimal))
+    // digits: the digits of s without the format prefix (e.g. "0x" or "0r36:")
+    // base: the base of the number system (e.g. 2 (binary), 8 (octal), 16 (hexadecimal) or 36)
     // end_pos: the end position of s in the user input string (for error message generation)
 
-        // remove the format prefix ("0b", "0x" or "0o")
-        let mut counter = 2;
-        let s_clean : String = $s.chars().skip_while(|_| {let ret = counter > 0; counter -= 1; ret} ).collect();
-        let mut v : Vec<&str> = s_clean.split('.').collect();
+        let mut v : Vec<&str> = $digits.split('.').collect();
 
         // initialise parsed result with 0
         let mut result : f64 = 0.0_f64;
@@ -199,39 +228,46 @@ impl RadixParse for f64 {
     type Output = Self;
     /// Implements the RadixParse trait for the f64 type.
     fn parse_float(s: String, input: & str , end_pos: usize) -> Result<Self::Output, EvaluationError> {
-        if s.starts_with("0x") {
-            parse_radix!(s, 16_u32, end_pos)
-        }
-        else if s.starts_with("0o") {
-            parse_radix!(s, 8_u32, end_pos)
-        }
-        else if s.starts_with("0b") {
-            parse_radix!(s, 2_u32, end_pos)
+        if let Some((radix, digits)) = split_radix_literal(&s) {
+            return parse_radix!(s, digits, radix, end_pos);
         }
-        else {
-            match f64::from_str(&s) {
-                Ok(f) => Ok(f),
-                Err(_) => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "literal number", Some("Invalid literal symbol(s)".to_string()),
-                                                                                   end_pos)))
-            }
+        match f64::from_str(&s) {
+            Ok(f) => Ok(f),
+            Err(_) => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "literal number", Some("Invalid literal symbol(s)".to_string()),
+                                                                               end_pos)))
         }
     }
 }
 
+/// The integrand of a numerical integration: a built-in or user defined function with one argument.
+struct Integrand<'b> {
+    /// The type of the function.
+    f_type: FunctionType,
+    /// The function name of the integrated expression.
+    f: &'b TreeNode<Token>,
+    /// The user input of the integrated expression.
+    input: &'b str,
+    /// The number of evaluations of the function so far.
+    evaluations: usize
+}
+
 impl<'a> Evaluator<'a> {
 
     /// Creates a new Evaluator instance.
     pub fn new(context: &'a mut MathContext) -> Evaluator {
-        Evaluator {context: context}
+        Evaluator {context: context, scopes: Vec::new(), call_depth: 0}
     }
 
     /// Evaluates the specified expression tree.
     /// The result is None if the evaluated expression is an assignment which returns no numerical value.
     pub fn evaluate(&'a mut self, tree: & TreeNode<Token>, input: &'a str) -> Result<Option<MathResult>, EvaluationError> { // Option<MathResult>: if none, then no result (e.g. assignment)
-        let result = self.recursive_evaluate(tree, &input)?;
+        self.context.set_non_finite_origin(None);
+        // an assignment statement yields no result (unlike an assignment inside of an expression)
+        let result = if self.is_assignment(tree) { self.evaluate_assignment(tree, &input)? } else { self.recursive_evaluate(tree, &input)? };
         match result {
             EvaluationResult::Numerical(x) => {
                 self.context.add_user_constant("ans", x.clone());
+                self.context.add_result(x.clone());
                 Ok(Some(x))
             },
             EvaluationResult::Symbolical(sym) => {
@@ -258,6 +294,15 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// Evaluates the specified expression tree with the specified temporary values of constants (e.g. the sample points
+    /// of an identity check). The temporary values shadow the constants of the context and are discarded afterwards.
+    pub fn evaluate_with(& mut self, tree: & TreeNode<Token>, values: HashMap<String, MathResult>, input: & str) -> Result<MathResult, EvaluationError> {
+        self.scopes.push(values);
+        let result = self.recursive_evaluate(tree, input);
+        self.scopes.pop(); // the temporary values must not leak, even in case of an error
+        Evaluator::error_if_symbolic(result?, input)
+    }
+
     /// Evaluates the specified subtree recursively by further splitting it into subtrees.
     /// Returns a numerical or symbolical evaluation result.
     pub fn recursive_evaluate(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
@@ -266,7 +311,13 @@ impl<'a> Evaluator<'a> {
 
         match token_type {
             TokenType::Number(num_type) => {
+                if num_type == NumberType::Real && self.context.get_precision_mode() == PrecisionMode::Exact {
+                    if let Some(r) = Evaluator::parse_exact(subtree.content.get_value()) {
+                        return Ok(EvaluationResult::from(MathResult::from(r)));
+                    }
+                }
                 let x = f64::parse_float(subtree.content.get_value().to_string(), input, subtree.content.get_end_pos())?;
+                self.check_literal_precision(subtree.content.get_value(), x);
                 match num_type {
                             NumberType::Real => Ok(EvaluationResult::from(x)),
                             NumberType::Complex => Ok(EvaluationResult::from(x * self.context.get_constant_value("i").unwrap().value))
@@ -274,165 +325,1295 @@ impl<'a> Evaluator<'a> {
             },
 
             TokenType::Constant | TokenType::UserConstant => {
-                let c_val = self.context.get_constant_value(subtree.content.get_value()).ok_or(
-                    EvaluationError::from(ExpectedErrorTemplate::new(input, "constant", Some(subtree.content.get_value().to_string()), subtree.content.get_end_pos())))?;
+                if let Some(x) = self.get_scoped_value(subtree.content.get_value()) {
+                    return Ok(EvaluationResult::from(x));
+                }
+                if token_type == TokenType::UserConstant {
+                    self.context.mark_user_definition_used(subtree.content.get_value());
+                }
+                let name = subtree.content.get_value();
+                let c_val = match self.context.get_constant_value(name) {
+                    Some(x) => x,
+                    None => {
+                        // a previous result "ans<n>" is only available after n results
+                        let error = match MathContext::parse_result_name(name) {
+                            Some(n) => ExpectedErrorTemplate::new(input, format!("{0} previous result(s)", n), Some(
+                                format!("{0} previous result(s)", self.context.get_previous_result_count())), subtree.content.get_end_pos()),
+                            None => ExpectedErrorTemplate::new(input, "constant", Some(name.to_string()), subtree.content.get_end_pos())
+                        };
+                        return Err(EvaluationError::from(error));
+                    }
+                };
                 Ok(EvaluationResult::from(c_val))
             },
 
-            TokenType::Operation => {
-                let op_type = self.context.get_operation_type(subtree.content.get_value().as_ref());
-                let op_type = op_type.unwrap(); // the parser ensures that this is a valid operation type
+            TokenType::Unit => {
+                // the tokenizer only creates valid unit expressions
+                let unit = Unit::parse(subtree.content.get_value()).unwrap();
+                Ok(EvaluationResult::from(MathResult::from(unit.scale).with_unit(Some(unit))))
+            },
 
-                if !(subtree.successors.len() > 0) {
-                    // this operation has no operands => error
-                    return Err(EvaluationError::from(ExpectedErrorTemplate::new(
-                        input, "operands", Some(format!("operation \"{0}\" without any operands", subtree.content)), subtree.content.get_end_pos())))
-                }
+            TokenType::Operation => self.evaluate_operation(subtree, input),
 
-                if op_type == OperationType::Assign {
-                    if subtree.successors.len() != 2 {
-                        return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "2 arguments", Some(
-                            format!("{0} arguments", subtree.successors.len())), subtree.content.get_end_pos())))
-                    }
+            TokenType::Function | TokenType::UserFunction => self.evaluate_function(subtree, input),
 
-                    let left_val_sym = self.error_if_built_in(subtree.successors[0].as_ref(), input)?;
-                    match left_val_sym.content.get_type() {
-                        TokenType::Symbol(SymbolicTokenType::UnknownConstant) | TokenType::UserConstant => {
-                            self.context.remove_user_constant(left_val_sym.content.get_value());
-                            let right_val = self.recursive_evaluate(subtree.successors[1].as_ref(), input)?;
-                            let right_val_num = Evaluator::error_if_symbolic(right_val, input)?;
-                            self.context.add_user_constant(left_val_sym.content.get_value(), right_val_num);
-                            Ok(EvaluationResult::from(subtree))
-                        },
-
-                        TokenType::Symbol(SymbolicTokenType::UnknownFunction) | TokenType::UserFunction => {
-                            let f_name = left_val_sym.content.get_value();
-                            self.context.remove_user_function(f_name);
-                            let f_args = Evaluator::get_function_args(left_val_sym, input)?;
-                            self.check_function_definition(subtree.successors[1].as_ref(), & f_args, input)?;
-                            self.context.add_user_function(f_name, subtree.successors[1].as_ref().clone(), f_args, input);
-                            Ok(EvaluationResult::from(subtree))
-                        },
-
-                        _ => {
-                            Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "constant or function definition", Some(
-                            format!("expression \"{0}\"", left_val_sym.content)), left_val_sym.content.get_end_pos())))
-                        }
+            TokenType::Keyword => self.evaluate_scope(subtree, input),
+
+            TokenType::Punctuation if subtree.content.get_value() == "[" => self.evaluate_matrix(subtree, input),
+
+            TokenType::Symbol(sym) => {
+                if let Some(x) = self.get_scoped_value(subtree.content.get_value()) {
+                    return Ok(EvaluationResult::from(x));
+                }
+                let name = subtree.content.get_value();
+                if sym == SymbolicTokenType::UnknownFunction && (self.context.is_built_in_constant(name) || self.context.is_user_constant(name)) {
+                    return Err(self.constant_call_error(subtree, input));
+                }
+                match sym {
+                    SymbolicTokenType::UnknownConstant | SymbolicTokenType::UnknownFunction => {
+                        Ok(EvaluationResult::from(subtree))
                     }
                 }
+            }
+
+            _ => {  // punctuation and unknown tokens should not occur in the evaluation method
+                Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "function or operation", Some(
+                    format!("symbol {0}", subtree.content)), subtree.content.get_end_pos())))
+            }
+        }
+    }
+
+    /// Evaluates an operation (an assignment, a binary or an unary operation).
+    fn evaluate_operation(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let op_type = self.context.get_operation_type(subtree.content.get_value().as_ref());
+        let op_type = op_type.unwrap(); // the parser ensures that this is a valid operation type
+
+        if !(subtree.successors.len() > 0) {
+            // this operation has no operands => error
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(
+                input, "operands", Some(format!("operation \"{0}\" without any operands", subtree.content)), subtree.content.get_end_pos())))
+        }
+
+        if op_type == OperationType::Assign {
+            // an assignment inside of an expression (e.g. "(x = 5) + 2") yields the assigned value if this is enabled
+            if !self.context.get_assignment_values() {
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "expression", Some(
+                    String::from("assignment inside of an expression (see \"set assignvalue\")")), subtree.content.get_end_pos())));
+            }
+            return Ok(EvaluationResult::from(self.evaluate_assigned_value(subtree, input)?));
+        }
+
+        // The operands are evaluated before the operation is applied, so the (large) stack frames of the
+        // operation implementations do not pile up in deeply nested (e.g. recursive) expressions.
+        let left_val = self.recursive_evaluate(subtree.successors[0].as_ref(), input)?;
+        let left_val_num = Evaluator::error_if_symbolic(left_val, input)?;
+        if subtree.successors.len() == 2 {
+            // binary operation
+            let right_val = self.recursive_evaluate(subtree.successors[1].as_ref(), input)?;
+            let right_val_num = Evaluator::error_if_symbolic(right_val, input)?;
+            let result = if left_val_num.unit.is_some() || right_val_num.unit.is_some() {
+                self.apply_unit_operation(op_type, & left_val_num, Some(& right_val_num), subtree, input)
+            }
+            else if left_val_num.is_matrix() || right_val_num.is_matrix() {
+                Evaluator::evaluate_matrix_operation(op_type, & left_val_num, & right_val_num, subtree, input)
+            }
+            else {
+                self.apply_binary_operation(op_type, & left_val_num, & right_val_num, subtree, input)
+            };
+            self.track_non_finite(result, & [left_val_num, right_val_num], false, subtree, input)
+        }
+        else {
+            let result = if left_val_num.unit.is_some() {
+                self.apply_unit_operation(op_type, & left_val_num, None, subtree, input)
+            }
+            else {
+                self.check_unary_operation_domain(& op_type, & left_val_num, subtree, input)?;
+                Evaluator::apply_unary_operation(op_type, & left_val_num, subtree, input)
+            };
+            self.track_non_finite(result, & [left_val_num], false, subtree, input)
+        }
+    }
+
+    /// Records the specified operation or function call as the origin of a NaN or infinite value if the tracking is
+    /// enabled, no origin has been recorded during the current evaluation yet, the result is not finite and all operands
+    /// are finite (see MathContext::set_non_finite_tracking). The result is returned unchanged.
+    fn track_non_finite(& mut self, result: Result<EvaluationResult, EvaluationError>, operands: & [MathResult], is_function: bool,
+                        subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        if !self.context.get_non_finite_tracking() || self.context.get_non_finite_origin().is_some() {
+            return result;
+        }
+        if let Ok(EvaluationResult::Numerical(ref x)) = result {
+            if !Evaluator::is_finite(x) && operands.iter().all(Evaluator::is_finite) {
+                self.context.set_non_finite_origin(Some(NonFiniteOrigin {value: format!("{0}", x), name: subtree.content.get_value().to_string(),
+                    is_function: is_function, operands: operands.iter().map(|o| format!("{0}", o)).collect(), input: input.to_string(),
+                    pos: subtree.content.get_end_pos()}));
+            }
+        }
+        result
+    }
+
+    /// Checks whether the specified result is finite (neither NaN nor infinite). Only the real part of a real number is
+    /// checked and matrices are regarded as finite.
+    fn is_finite(x: & MathResult) -> bool {
+        match x.result_type {
+            _ if x.is_matrix() => true,
+            NumberType::Real => x.value.re.is_finite(),
+            NumberType::Complex => x.value.re.is_finite() && x.value.im.is_finite()
+        }
+    }
+
+    /// Applies the specified unary or binary operation to operands of which at least one has a unit (e.g. "3 m + 2 km").
+    /// The operation is applied to the values in SI base units and the unit of the result is derived from the units of
+    /// the operands: sums, differences, remainders and comparisons need operands of the same dimension, products and
+    /// quotients combine the dimensions and powers need exponents that yield integer powers of the SI base units.
+    fn apply_unit_operation(& self, op_type: OperationType, lhs: & MathResult, rhs: Option<& MathResult>, subtree: & TreeNode<Token>,
+                            input: & str) -> Result<EvaluationResult, EvaluationError> {
+        if lhs.is_matrix() || rhs.map_or(false, |x| x.is_matrix()) {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "scalar operands", Some(
+                format!("matrix operand of operation \"{0}\" with a unit", subtree.content)), subtree.content.get_end_pos())));
+        }
+
+        let unit = match (op_type.clone(), rhs) {
+            (OperationType::Add, None) | (OperationType::Sub, None) => lhs.unit.clone(),
+            (OperationType::Add, Some(r)) | (OperationType::Sub, Some(r)) | (OperationType::Mod, Some(r)) => {
+                Evaluator::error_if_different_dimensions(lhs, r, subtree, input)?;
+                lhs.unit.clone().or(r.unit.clone()) // the unit of the left operand is kept (e.g. "1 km + 200 m" is "1.2 km")
+            },
+            (OperationType::Less, Some(r)) | (OperationType::Greater, Some(r)) | (OperationType::LessEqual, Some(r))
+            | (OperationType::GreaterEqual, Some(r)) | (OperationType::Equal, Some(r)) | (OperationType::NotEqual, Some(r)) => {
+                Evaluator::error_if_different_dimensions(lhs, r, subtree, input)?;
+                None
+            },
+            // scaling a quantity by a number keeps its unit (e.g. "3 km" is the product of 3 and the unit "km")
+            (OperationType::Mul, Some(r)) if lhs.unit.is_none() || r.unit.is_none() => lhs.unit.clone().or(r.unit.clone()),
+            (OperationType::Div, Some(r)) | (OperationType::IntDiv, Some(r)) if r.unit.is_none() => lhs.unit.clone(),
+            (OperationType::Mul, Some(r)) => Unit::from_dimension(lhs.get_dimension().mul(& r.get_dimension())),
+            (OperationType::Div, Some(r)) | (OperationType::IntDiv, Some(r)) => Unit::from_dimension(lhs.get_dimension().div(& r.get_dimension())),
+            (OperationType::Pow, Some(r)) => Evaluator::get_unit_power(lhs, r, subtree, input)?,
+            _ => {
+                let unit = lhs.unit.as_ref().or(rhs.and_then(|r| r.unit.as_ref())).unwrap();
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "dimensionless operands", Some(
+                    format!("operand with the unit \"{0}\" of operation \"{1}\"", unit.name, subtree.content)), subtree.content.get_end_pos())));
+            }
+        };
+
+        let lhs = MathResult::new(lhs.result_type.clone(), lhs.value);
+        let result = match rhs {
+            Some(r) => self.apply_binary_operation(op_type, & lhs, & MathResult::new(r.result_type.clone(), r.value), subtree, input)?,
+            None => Evaluator::apply_unary_operation(op_type, & lhs, subtree, input)?
+        };
+        match result {
+            EvaluationResult::Numerical(x) => Ok(EvaluationResult::from(x.with_unit(unit))),
+            res => Ok(res)
+        }
+    }
+
+    /// Returns the unit of the specified base raised to the specified exponent (e.g. "m^2" for "(3 m)^2").
+    /// The exponent must be a dimensionless real number, for which the powers of the SI base units of the base are integers.
+    fn get_unit_power(base: & MathResult, exponent: & MathResult, op: & TreeNode<Token>, input: & str) -> Result<Option<Unit>, EvaluationError> {
+        if exponent.unit.is_some() || exponent.result_type != NumberType::Real {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "dimensionless real exponent", Some(
+                format!("exponent {0}", exponent)), op.content.get_end_pos())));
+        }
+        match base.get_dimension().pow(exponent.value.re) {
+            Some(d) => Ok(Unit::from_dimension(d)),
+            None => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, format!(
+                "exponent for which the powers of \"{0}\" are integers", base.get_dimension()), Some(
+                format!("exponent {0}", exponent)), op.content.get_end_pos())))
+        }
+    }
+
+    /// Adds a warning if the specified integer literal can not be represented exactly by its floating point value x
+    /// (e.g. "9007199254740993", which is evaluated as 9007199254740992).
+    fn check_literal_precision(& mut self, literal: & str, x: f64) {
+        if !x.is_finite() || !literal.chars().all(|c| c.is_ascii_digit()) {
+            return;
+        }
+        let digits = literal.trim_start_matches('0');
+        if format!("{0:.0}", x) != if digits.is_empty() { "0" } else { digits } {
+            self.context.add_warning(format!("Warning: The number \"{0}\" can not be represented exactly (precision lost, \
+                                              evaluated as {1:.0}).", literal, x));
+        }
+    }
+
+    /// Evaluates the assignment of a user defined constant inside of an expression or a chained assignment and returns the
+    /// assigned value. Definitions of user defined functions do not have a value and are an error.
+    fn evaluate_assigned_value(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<MathResult, EvaluationError> {
+        match subtree.successors.first().map(|n| n.content.get_type()) {
+            Some(TokenType::Symbol(SymbolicTokenType::UnknownFunction)) | Some(TokenType::UserFunction) => {
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "constant assignment", Some(
+                    String::from("function definition inside of an expression")), subtree.content.get_end_pos())));
+            },
+            _ => ()
+        }
+        self.evaluate_assignment(subtree, input)?;
+        Ok(self.context.get_constant_value(subtree.successors[0].content.get_value()).unwrap()) // the constant has just been assigned
+    }
+
+    /// Checks whether the specified tree node is an assignment (or a function definition).
+    fn is_assignment(& self, n: & TreeNode<Token>) -> bool {
+        n.content.get_type() == TokenType::Operation && self.context.get_operation_type(n.content.get_value()) == Some(OperationType::Assign)
+    }
+
+    /// Evaluates the assignment of a user defined constant or the definition of a user defined function.
+    fn evaluate_assignment(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        if subtree.successors.len() != 2 {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "2 arguments", Some(
+                format!("{0} arguments", subtree.successors.len())), subtree.content.get_end_pos())))
+        }
+
+        let left_val_sym = self.error_if_built_in(subtree.successors[0].as_ref(), input)?;
+        match left_val_sym.content.get_type() {
+            TokenType::Symbol(SymbolicTokenType::UnknownConstant) | TokenType::UserConstant => {
+                Evaluator::error_if_number_like(left_val_sym, input)?;
+
+                // the right hand side is evaluated first, so that the constant may refer to its current value (e.g. "x = x/2 + 1/x")
+                // in a chained assignment (e.g. "a = b = 3"), the right hand side is an assignment that yields the assigned value
+                let rhs = subtree.successors[1].as_ref();
+                let right_val_num = if self.is_assignment(rhs) {
+                    self.evaluate_assigned_value(rhs, input)?
+                }
                 else {
-                    let left_val = self.recursive_evaluate(subtree.successors[0].as_ref(), input)?;
-                    let left_val_num = Evaluator::error_if_symbolic(left_val, input)?;
-                    if subtree.successors.len() == 2 {
-                        // binary operation
-                        let right_val = self.recursive_evaluate(subtree.successors[1].as_ref(), input)?;
-                        let right_val_num = Evaluator::error_if_symbolic(right_val, input)?;
-                        match op_type {
-                            OperationType::Add => Ok(EvaluationResult::from(MathContext::operation_add(& left_val_num, & right_val_num))),
-                            OperationType::Sub => Ok(EvaluationResult::from(MathContext::operation_sub(& left_val_num, & right_val_num))),
-                            OperationType::Mul => Ok(EvaluationResult::from(MathContext::operation_mul(& left_val_num, & right_val_num))),
-                            OperationType::Div => Ok(EvaluationResult::from(MathContext::operation_div(& left_val_num, & right_val_num))),
-                            OperationType::Pow => Ok(EvaluationResult::from(MathContext::operation_pow(& left_val_num, & right_val_num))),
-                            OperationType::Mod => Ok(EvaluationResult::from(MathContext::operation_mod(& left_val_num, & right_val_num))),
-                            _ => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "binary mathematical operation",
-                                                                                      Some(format!("operation \"{0}\"", subtree.content)),
-                                                                                      subtree.content.get_end_pos())))
-                        }
+                    let right_val = self.recursive_evaluate(rhs, input)?;
+                    Evaluator::error_if_symbolic(right_val, input)?
+                };
+                self.context.add_user_constant(left_val_sym.content.get_value(), right_val_num);
+                Ok(EvaluationResult::from(subtree))
+            },
+
+            TokenType::Symbol(SymbolicTokenType::UnknownFunction) | TokenType::UserFunction => {
+                let f_name = left_val_sym.content.get_value();
+                self.context.remove_user_function(f_name);
+                let f_args = Evaluator::get_function_args(left_val_sym, input)?;
+                let mut f_tree = subtree.successors[1].as_ref().clone();
+                Evaluator::mark_recursive_calls(& mut f_tree, f_name);
+                let mut used_args = HashSet::new();
+                self.check_function_definition(& f_tree, & f_args, & mut used_args, input)?;
+                for arg in f_args.iter().filter(|a| !used_args.contains(a.as_str())) {
+                    self.context.add_warning(format!("Warning: The parameter \"{0}\" of the function \"{1}\" is not used.", arg, f_name));
+                }
+                let shadowing_args : Vec<& String> = f_args.iter().filter(|a| self.context.is_constant(a)).collect();
+                for arg in shadowing_args {
+                    self.context.add_warning(format!("Warning: The parameter \"{0}\" of the function \"{1}\" shadows the constant \"{0}\".",
+                                                     arg, f_name));
+                }
+                if self.context.get_optimize() {
+                    self.fold_constants(& mut f_tree, input);
+                }
+                self.context.add_user_function(f_name, f_tree, f_args, input);
+                Ok(EvaluationResult::from(subtree))
+            },
+
+            _ => {
+                Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "constant or function definition", Some(
+                format!("expression \"{0}\"", left_val_sym.content)), left_val_sym.content.get_end_pos())))
+            }
+        }
+    }
+
+    /// Applies the specified binary operation to the specified numbers.
+    fn apply_binary_operation(& self, op_type: OperationType, left_val_num: & MathResult, right_val_num: & MathResult,
+                              subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        self.check_operation_domain(& op_type, left_val_num, right_val_num, subtree, input)?;
+        match op_type {
+            OperationType::Add => Ok(EvaluationResult::from(MathContext::operation_add(left_val_num, right_val_num))),
+            OperationType::Sub => Ok(EvaluationResult::from(MathContext::operation_sub(left_val_num, right_val_num))),
+            OperationType::Mul => Ok(EvaluationResult::from(MathContext::operation_mul(left_val_num, right_val_num))),
+            OperationType::Div => Ok(EvaluationResult::from(MathContext::operation_div(left_val_num, right_val_num))),
+            OperationType::IntDiv => {
+                Evaluator::error_if_complex_operands(left_val_num, right_val_num, subtree, "integer division", input)?;
+                Ok(EvaluationResult::from(MathContext::operation_int_div(left_val_num, right_val_num)))
+            },
+            OperationType::Pow => Ok(EvaluationResult::from(MathContext::operation_pow(left_val_num, right_val_num))),
+            OperationType::Mod => Ok(EvaluationResult::from(MathContext::operation_mod(left_val_num, right_val_num))),
+            OperationType::BitAnd | OperationType::BitOr | OperationType::BitXor | OperationType::ShiftLeft | OperationType::ShiftRight => {
+                Evaluator::error_if_non_integer_operand(left_val_num, subtree, 0, input)?;
+                Evaluator::error_if_non_integer_operand(right_val_num, subtree, 1, input)?;
+                let result = match op_type {
+                    OperationType::BitAnd => MathContext::operation_bit_and(left_val_num, right_val_num),
+                    OperationType::BitOr => MathContext::operation_bit_or(left_val_num, right_val_num),
+                    OperationType::BitXor => MathContext::operation_bit_xor(left_val_num, right_val_num),
+                    OperationType::ShiftLeft => MathContext::operation_shift_left(left_val_num, right_val_num),
+                    _ => MathContext::operation_shift_right(left_val_num, right_val_num)
+                };
+                Ok(EvaluationResult::from(result))
+            },
+            OperationType::Less | OperationType::Greater | OperationType::LessEqual | OperationType::GreaterEqual => {
+                let is_true = match (left_val_num.exact.as_ref(), right_val_num.exact.as_ref()) {
+                    (Some(lhs), Some(rhs)) => Evaluator::compare(op_type, lhs, rhs),
+                    _ => {
+                        let (lhs, rhs) = self.get_comparison_operands(left_val_num, right_val_num, subtree, input)?;
+                        Evaluator::compare(op_type, & lhs, & rhs)
                     }
-                    else {
-                        match op_type {
-                        OperationType::Add => Ok(EvaluationResult::from(MathContext::operation_add(& MathResult::from(0.0), & left_val_num))),
-                        OperationType::Sub => Ok(EvaluationResult::from(MathContext::operation_sub(& MathResult::from(0.0), & left_val_num))),
-                        _ => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "unary operation",
-                                                                                  Some(format!("non-unary operation \"{0}\"", subtree.content)),
-                                                                                  subtree.content.get_end_pos())))
+                };
+                Ok(EvaluationResult::from(if is_true { 1.0_f64 } else { 0.0_f64 }))
+            },
+            OperationType::Equal | OperationType::NotEqual => {
+                // equality is defined for complex numbers, so the values are compared regardless of the comparison mode
+                let is_equal = match (left_val_num.exact.as_ref(), right_val_num.exact.as_ref()) {
+                    (Some(lhs), Some(rhs)) => lhs == rhs,
+                    _ => left_val_num.value == right_val_num.value
+                };
+                let is_true = if op_type == OperationType::Equal { is_equal } else { !is_equal };
+                Ok(EvaluationResult::from(if is_true { 1.0_f64 } else { 0.0_f64 }))
+            },
+            _ => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "binary mathematical operation",
+                                                                      Some(format!("operation \"{0}\"", subtree.content)),
+                                                                      subtree.content.get_end_pos())))
+        }
+    }
+
+    /// Applies the specified unary operation to the specified number or matrix.
+    fn apply_unary_operation(op_type: OperationType, left_val_num: & MathResult, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        match op_type {
+            OperationType::Add => Ok(EvaluationResult::from(left_val_num.clone())),
+            OperationType::Sub => {
+                match left_val_num.matrix {
+                    Some(ref m) => Ok(EvaluationResult::from(MathResult::from(m.map(MathContext::operation_neg)))),
+                    None => Ok(EvaluationResult::from(MathContext::operation_neg(left_val_num)))
+                }
+            },
+            OperationType::Factorial => {
+                if left_val_num.is_matrix() {
+                    return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "scalar operand", Some(
+                        format!("matrix operand of operation \"{0}\"", subtree.content)), subtree.content.get_end_pos())));
+                }
+                Ok(EvaluationResult::from(MathContext::operation_factorial(left_val_num)))
+            },
+            OperationType::BitNot => {
+                Evaluator::error_if_non_integer_operand(left_val_num, subtree, 0, input)?;
+                Ok(EvaluationResult::from(MathContext::operation_bit_not(left_val_num)))
+            },
+            _ => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "unary operation",
+                                                                      Some(format!("non-unary operation \"{0}\"", subtree.content)),
+                                                                      subtree.content.get_end_pos())))
+        }
+    }
+
+    /// Evaluates a function call of a built-in or user defined function.
+    fn evaluate_function(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+
+        // get type of function (cos, sin, exp,..., or a user defined function)
+        let f_type = self.context.get_function_type(subtree.content.get_value().as_ref());
+        let f_type = f_type.unwrap();
+
+        // get arguments of the function and check if the number of provided arguments matches the number of needed arguments
+        // functions with any number of arguments (e.g. "mean") need at least one argument (the variance at least two)
+        // and the number of decimal places of "round" is optional
+        let n_successors = subtree.successors.len() as u32;
+        let n_args = self.context.get_function_arg_num(subtree.content.get_value()).unwrap();
+        if n_args == 0 {
+            let n_min = if f_type == FunctionType::StdDev || f_type == FunctionType::Var { 2 } else { 1 };
+            if n_successors < n_min {
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, format!("at least {0} argument(s)", n_min),
+                                                                            Some(format!("{0} argument(s)", n_successors)),
+                                                                            subtree.content.get_end_pos())));
+            }
+        }
+        else if n_successors != n_args && !(f_type == FunctionType::Round && n_successors == 1) {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, format!("{0} argument(s)", n_args),
+                                                                        Some(format!("{0} argument(s)", n_successors)),
+                                                                        subtree.content.get_end_pos())));
+        }
+
+        if f_type == FunctionType::Old {
+            return self.evaluate_previous_value(subtree, input);
+        }
+        if f_type == FunctionType::Out {
+            return self.evaluate_output(subtree, input);
+        }
+        if self.context.is_series(subtree) {
+            return self.evaluate_series(f_type, subtree, input);
+        }
+        if f_type == FunctionType::Integrate {
+            return self.evaluate_integral(subtree, input);
+        }
+        if f_type == FunctionType::Solve {
+            return self.evaluate_solve(subtree, input);
+        }
+        if f_type == FunctionType::If {
+            return self.evaluate_conditional(subtree, input);
+        }
+        if f_type == FunctionType::Convert {
+            return self.evaluate_conversion(subtree, input);
+        }
+
+        // evaluate the provided arguments
+        let mut args : Vec<MathResult> = Vec::new();
+        for s in subtree.successors.iter() {
+            let x = self.recursive_evaluate(s.as_ref(), input)?;
+            let x_num = Evaluator::error_if_symbolic(x, input)?;
+            args.push(x_num);
+        }
+        if f_type == FunctionType::Round && args.len() == 1 {
+            // "round(x)" rounds to an integer
+            args.push(self.create_integer(0));
+        }
+
+        // only the matrix functions and user defined functions accept matrix arguments
+        match f_type {
+            FunctionType::Det | FunctionType::Transpose | FunctionType::Inv => {
+                self.evaluate_matrix_function(f_type, & args[0], subtree, input)
+            },
+            FunctionType::UserFunction => {
+                self.context.mark_user_definition_used(subtree.content.get_value());
+                self.evaluate_user_function(subtree, args, input)
+            },
+            _ => {
+                if let Some(pos) = args.iter().position(|x| x.is_matrix()) {
+                    return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "scalar argument", Some(
+                        format!("matrix argument {0} of function \"{1}\"", pos + 1, subtree.content)), subtree.content.get_end_pos())));
+                }
+                match f_type {
+                    FunctionType::Gcd | FunctionType::Lcm | FunctionType::IsPrime | FunctionType::Factor | FunctionType::Ncr
+                    | FunctionType::Npr | FunctionType::Binomial => {
+                        for (idx, arg) in args.iter().enumerate() {
+                            Evaluator::error_if_non_integer_argument(arg, subtree, idx, input)?;
                         }
-                    }
+                    },
+                    _ => ()
                 }
+                self.check_function_domain(& f_type, & args, subtree, input)?;
+                let result = if args.iter().any(|x| x.unit.is_some()) {
+                    Evaluator::apply_unit_function(f_type, args.clone(), subtree, input)
+                }
+                else {
+                    Ok(EvaluationResult::from(Evaluator::apply_angle_mode(f_type, args.clone(), self.context.get_angle_mode())))
+                };
+                self.track_non_finite(result, & args, true, subtree, input)
+            }
+        }
+    }
+
+    /// Applies the specified built-in function to arguments of which at least one has a unit (e.g. "sqrt(16 m^2)").
+    /// The absolute value and the real and imaginary parts keep the unit, the roots and powers take the roots and powers
+    /// of the unit and the sign is dimensionless. All other functions need dimensionless arguments.
+    fn apply_unit_function(f_type: FunctionType, args: Vec<MathResult>, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let unit = match f_type {
+            FunctionType::Abs | FunctionType::Re | FunctionType::Im => args[0].unit.clone(),
+            FunctionType::Sign | FunctionType::Arg => None,
+            FunctionType::Conj => args[0].unit.clone(),
+            FunctionType::Sqrt => Evaluator::get_unit_power(& args[0], & MathResult::from(0.5), subtree, input)?,
+            FunctionType::Pow => Evaluator::get_unit_power(& args[0], & args[1], subtree, input)?,
+            FunctionType::Root if args[1].unit.is_none() => {
+                let exponent = MathResult::new(args[1].result_type.clone(), 1.0 / args[1].value);
+                Evaluator::get_unit_power(& args[0], & exponent, subtree, input)?
+            },
+            _ => {
+                let pos = args.iter().position(|x| x.unit.is_some()).unwrap();
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "dimensionless argument", Some(
+                    format!("argument {0} with the unit \"{1}\" of function \"{2}\"", pos + 1, args[pos].unit.as_ref().unwrap().name,
+                            subtree.content)), subtree.content.get_end_pos())));
+            }
+        };
+
+        let args : Vec<MathResult> = args.iter().map(|x| MathResult::new(x.result_type.clone(), x.value)).collect();
+        Ok(EvaluationResult::from(Evaluator::apply_built_in_function(f_type, & args).with_unit(unit)))
+    }
+
+    /// Calls the specified built-in function like Evaluator::apply_built_in_function. The arguments of the trigonometric
+    /// functions and the angle of polar are converted from the specified angle mode to radians and the results of the
+    /// inverse trigonometric functions and arg are converted from radians to the specified angle mode.
+    fn apply_angle_mode(f_type: FunctionType, mut args: Vec<MathResult>, mode: AngleMode) -> MathResult {
+        if mode == AngleMode::Rad {
+            return Evaluator::apply_built_in_function(f_type, & args);
+        }
+
+        let to_rad = f64::consts::PI / mode.get_half_circle();
+        match f_type {
+            FunctionType::Cos | FunctionType::Sin | FunctionType::Tan | FunctionType::Cot => {
+                args[0] = MathResult::new(args[0].result_type.clone(), args[0].value * to_rad);
+                Evaluator::apply_built_in_function(f_type, & args)
+            },
+            FunctionType::Polar => {
+                args[1] = MathResult::new(args[1].result_type.clone(), args[1].value * to_rad);
+                Evaluator::apply_built_in_function(f_type, & args)
+            },
+            FunctionType::ArcCos | FunctionType::ArcSin | FunctionType::ArcTan | FunctionType::ArcCot | FunctionType::Arg => {
+                let x = Evaluator::apply_built_in_function(f_type, & args);
+                MathResult::new(x.result_type.clone(), x.value / to_rad)
+            },
+            _ => Evaluator::apply_built_in_function(f_type, & args)
+        }
+    }
+
+    /// Calls the specified built-in function (regarding the function type) with the specified evaluated arguments.
+    fn apply_built_in_function(f_type: FunctionType, args: & Vec<MathResult>) -> MathResult {
+        match f_type {
+            FunctionType::Cos => MathContext::function_cos(& args[0]),
+            FunctionType::Sin => MathContext::function_sin(& args[0]),
+            FunctionType::Tan => MathContext::function_tan(& args[0]),
+            FunctionType::Cot => MathContext::function_cot(& args[0]),
+            FunctionType::Exp => MathContext::function_exp(& args[0]),
+            FunctionType::Cosh => MathContext::function_cosh(& args[0]),
+            FunctionType::Sinh => MathContext::function_sinh(& args[0]),
+            FunctionType::Tanh => MathContext::function_tanh(& args[0]),
+            FunctionType::Coth => MathContext::function_coth(& args[0]),
+            FunctionType::ArcCosh => MathContext::function_arccosh(& args[0]),
+            FunctionType::ArcSinh => MathContext::function_arcsinh(& args[0]),
+            FunctionType::ArcTanh => MathContext::function_arctanh(& args[0]),
+            FunctionType::ArcCoth => MathContext::function_arccoth(& args[0]),
+            FunctionType::Sqrt => MathContext::function_sqrt(& args[0]),
+            FunctionType::Ln => MathContext::function_ln(& args[0]),
+            FunctionType::Log => MathContext::function_log(& args[0], & args[1]),
+            FunctionType::Log2 => MathContext::function_log2(& args[0]),
+            FunctionType::Log10 => MathContext::function_log10(& args[0]),
+            FunctionType::Gamma => MathContext::function_gamma(& args[0]),
+            FunctionType::Pow => MathContext::operation_pow(& args[0], & args[1]),
+            FunctionType::Root => MathContext::operation_root(& args[0], & args[1]),
+            FunctionType::ArcCos => MathContext::function_arccos(& args[0]),
+            FunctionType::ArcSin => MathContext::function_arcsin(& args[0]),
+            FunctionType::ArcTan => MathContext::function_arctan(& args[0]),
+            FunctionType::ArcCot => MathContext::function_arccot(& args[0]),
+            FunctionType::Im => MathContext::function_im(& args[0]),
+            FunctionType::Re => MathContext::function_re(& args[0]),
+            FunctionType::Abs => MathContext::function_abs(& args[0]),
+            FunctionType::Sign => MathContext::function_sign(& args[0]),
+            FunctionType::Conj => MathContext::function_conj(& args[0]),
+            FunctionType::Arg => MathContext::function_arg(& args[0]),
+            FunctionType::Polar => MathContext::function_polar(& args[0], & args[1]),
+            FunctionType::Floor => MathContext::function_floor(& args[0]),
+            FunctionType::Ceil => MathContext::function_ceil(& args[0]),
+            FunctionType::Trunc => MathContext::function_trunc(& args[0]),
+            FunctionType::Frac => MathContext::function_frac(& args[0]),
+            FunctionType::Round => MathContext::function_round(& args[0], & args[1]),
+            FunctionType::DivMod => MathContext::function_divmod(& args[0], & args[1]),
+            FunctionType::Gcd => MathContext::function_gcd(& args[0], & args[1]),
+            FunctionType::Lcm => MathContext::function_lcm(& args[0], & args[1]),
+            FunctionType::IsPrime => MathContext::function_isprime(& args[0]),
+            FunctionType::Factor => MathContext::function_factor(& args[0]),
+            FunctionType::Ncr | FunctionType::Binomial => MathContext::function_ncr(& args[0], & args[1]),
+            FunctionType::Npr => MathContext::function_npr(& args[0], & args[1]),
+            FunctionType::Sum => MathContext::function_sum(args),
+            FunctionType::Min => MathContext::function_min(args),
+            FunctionType::Max => MathContext::function_max(args),
+            FunctionType::Mean => MathContext::function_mean(args),
+            FunctionType::Median => MathContext::function_median(args),
+            FunctionType::StdDev => MathContext::function_stddev(args),
+            FunctionType::Var => MathContext::function_var(args),
+            FunctionType::Det | FunctionType::Transpose | FunctionType::Inv | FunctionType::Old | FunctionType::Out
+            | FunctionType::Prod | FunctionType::Integrate | FunctionType::Solve | FunctionType::If
+            | FunctionType::Convert | FunctionType::UserFunction => {
+                unreachable!() // these functions are evaluated by the evaluator itself
+            }
+        }
+    }
+
+    /// Checks whether the operands of the specified binary operation are in the domain of the operation (e.g. the divisor
+    /// of a division must not be zero). If not, then an EvaluationError that points at the offending operand is returned.
+    /// The check is skipped in the permissive mode, in which the operation returns infinity or NaN instead.
+    fn check_operation_domain(& self, op_type: & OperationType, lhs: & MathResult, rhs: & MathResult, subtree: & TreeNode<Token>,
+                              input: & str) -> Result<(), EvaluationError> {
+        if self.context.get_permissive() {
+            return Ok(());
+        }
+        match *op_type {
+            OperationType::Div | OperationType::IntDiv | OperationType::Mod if rhs.value.is_zero() => {
+                Err(Evaluator::domain_error(subtree, 1, "non-zero divisor", String::from("divisor 0"), input))
+            },
+            OperationType::Pow => Evaluator::check_power_domain(lhs, rhs, subtree, input),
+            OperationType::ShiftLeft | OperationType::ShiftRight if rhs.to_i64().map_or(true, |n| n < 0 || n > 63) => {
+                Err(Evaluator::domain_error(subtree, 1, "shift count between 0 and 63", format!("shift count {0}", rhs), input))
             },
+            _ => Ok(())
+        }
+    }
 
-            TokenType::Function | TokenType::UserFunction => {
+    /// Checks whether the operand of the specified unary operation is in the domain of the operation (e.g. the factorial
+    /// of a negative integer is undefined). If not, then an EvaluationError that points at the operand is returned.
+    /// The check is skipped in the permissive mode, in which the operation returns infinity or NaN instead.
+    fn check_unary_operation_domain(& self, op_type: & OperationType, operand: & MathResult, subtree: & TreeNode<Token>,
+                                    input: & str) -> Result<(), EvaluationError> {
+        if self.context.get_permissive() {
+            return Ok(());
+        }
+        match *op_type {
+            OperationType::Factorial if Evaluator::is_non_positive_integer(operand) && !operand.value.is_zero() => {
+                Err(Evaluator::domain_error(subtree, 0, "operand other than a negative integer", format!("operand {0}", operand), input))
+            },
+            _ => Ok(())
+        }
+    }
 
-                // get type of function (cos, sin, exp,..., or a user defined function)
-                let f_type = self.context.get_function_type(subtree.content.get_value().as_ref());
-                let f_type = f_type.unwrap();
+    /// Checks whether the specified number is a real integer that is zero or negative (the poles of the gamma function).
+    fn is_non_positive_integer(x: & MathResult) -> bool {
+        x.value.im == 0.0 && x.value.re <= 0.0 && x.value.re.fract() == 0.0
+    }
 
-                // get arguments of the function and check if the number of provided arguments matches the number of needed arguments
-                let n_successors = subtree.successors.len() as u32;
-                let n_args = self.context.get_function_arg_num(subtree.content.get_value()).unwrap();
-                if n_successors != n_args {
-                    return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, format!("{0} argument(s)", n_args),
-                                                                                Some(format!("{0} argument(s)", n_successors)),
-                                                                                subtree.content.get_end_pos())));
+    /// Checks whether the arguments of the specified built-in function are in the domain of the function (e.g. the
+    /// argument of a logarithm must not be zero). If not, then an EvaluationError that points at the offending argument
+    /// is returned. The check is skipped in the permissive mode, in which the function returns infinity or NaN instead.
+    fn check_function_domain(& self, f_type: & FunctionType, args: & Vec<MathResult>, subtree: & TreeNode<Token>,
+                             input: & str) -> Result<(), EvaluationError> {
+        if self.context.get_permissive() {
+            return Ok(());
+        }
+        match *f_type {
+            FunctionType::Ln | FunctionType::Log | FunctionType::Log2 | FunctionType::Log10 if args[0].value.is_zero() => {
+                Err(Evaluator::domain_error(subtree, 0, "non-zero argument", String::from("argument 0"), input))
+            },
+            FunctionType::Log if args[1].value.is_zero() || args[1].value == Complex::new(1.0, 0.0) => {
+                Err(Evaluator::domain_error(subtree, 1, "base other than 0 and 1", format!("base {0}", args[1]), input))
+            },
+            FunctionType::Pow => Evaluator::check_power_domain(& args[0], & args[1], subtree, input),
+            FunctionType::Gamma if Evaluator::is_non_positive_integer(& args[0]) => {
+                Err(Evaluator::domain_error(subtree, 0, "argument other than 0 and the negative integers", format!("argument {0}", args[0]), input))
+            },
+            FunctionType::Cot | FunctionType::Coth if args[0].value.is_zero() => {
+                Err(Evaluator::domain_error(subtree, 0, "non-zero argument", String::from("argument 0"), input))
+            },
+            FunctionType::ArcTanh | FunctionType::ArcCoth if args[0].value.im == 0.0 && args[0].value.re.abs() == 1.0 => {
+                Err(Evaluator::domain_error(subtree, 0, "argument other than -1 and 1", format!("argument {0}", args[0]), input))
+            },
+            FunctionType::DivMod if args[1].value.is_zero() => {
+                Err(Evaluator::domain_error(subtree, 1, "non-zero divisor", String::from("divisor 0"), input))
+            },
+            FunctionType::Factor if args[0].to_i64().map_or(true, |n| n < 2) => {
+                Err(Evaluator::domain_error(subtree, 0, "integer greater than 1", format!("argument {0}", args[0]), input))
+            },
+            FunctionType::Ncr | FunctionType::Npr | FunctionType::Binomial => {
+                match args.iter().position(|x| x.value.re < 0.0) {
+                    Some(pos) => Err(Evaluator::domain_error(subtree, pos, "non-negative argument", format!("argument {0}", args[pos]), input)),
+                    None => Ok(())
                 }
+            },
+            FunctionType::Root if args[1].value.is_zero() => {
+                Err(Evaluator::domain_error(subtree, 1, "non-zero degree", String::from("degree 0"), input))
+            },
+            FunctionType::Root if args[0].value.is_zero() && args[1].value.re < 0.0 => {
+                Err(Evaluator::domain_error(subtree, 0, "non-zero radicand of a root with a negative degree", format!(
+                    "radicand 0 of a root with the degree {0}", args[1]), input))
+            },
+            FunctionType::Min | FunctionType::Max | FunctionType::Median | FunctionType::StdDev | FunctionType::Var
+            | FunctionType::Polar => {
+                match args.iter().position(|x| x.result_type == NumberType::Complex) {
+                    Some(pos) => Err(Evaluator::domain_error(subtree, pos, "real argument", format!("complex argument {0}", args[pos]), input)),
+                    None => Ok(())
+                }
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Checks whether zero is raised to a negative power (e.g. "0^-1" or "pow(0, -1)"), which is undefined.
+    /// The base and the exponent are the first and the second successor of the specified operation or function.
+    fn check_power_domain(base: & MathResult, exponent: & MathResult, subtree: & TreeNode<Token>, input: & str) -> Result<(), EvaluationError> {
+        if base.value.is_zero() && exponent.value.re < 0.0 {
+            Err(Evaluator::domain_error(subtree, 0, "non-zero base of a negative exponent", format!(
+                "base 0 with the exponent {0}", exponent), input))
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    /// Creates the error for an operand or argument (the successor of the specified node at index idx) that is not in the
+    /// domain of the operation or function. The error points at the operand or argument.
+    fn domain_error(subtree: & TreeNode<Token>, idx: usize, expected: & str, found: String, input: & str) -> EvaluationError {
+        EvaluationError::from(ExpectedErrorTemplate::new(input, expected, Some(found), subtree.successors[idx].content.get_end_pos()))
+    }
+
+    /// Evaluates the previous value of a user constant (e.g. "old(x, 1)").
+    /// The first argument is the name of the constant and not evaluated.
+    fn evaluate_previous_value(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let name = subtree.successors[0].as_ref();
+        if name.content.get_type() != TokenType::UserConstant {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "user defined constant", Some(
+                format!("expression \"{0}\"", name.content)), name.content.get_end_pos())));
+        }
+
+        let k_node = subtree.successors[1].as_ref();
+        let k = self.recursive_evaluate(k_node, input)?;
+        let k = Evaluator::error_if_symbolic(k, input)?;
+        if k.result_type != NumberType::Real || k.value.re < 1.0 || k.value.re.fract() != 0.0 {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "positive integer", Some(
+                format!("{0}", k)), k_node.content.get_end_pos())));
+        }
+
+        let repr = name.content.get_value();
+        self.context.mark_user_definition_used(repr);
+        match self.context.get_previous_constant_value(repr, k.value.re as usize) {
+            Some(x) => Ok(EvaluationResult::from(x)),
+            None => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, format!("{0} previous value(s) of \"{1}\"", k, repr), Some(
+                format!("{0} previous value(s)", self.context.get_previous_constant_value_count(repr))), subtree.content.get_end_pos())))
+        }
+    }
+
+    /// Replaces the constant subtrees of the specified tree (subtrees without symbols, e.g. "2*pi") by their values.
+    /// Subtrees whose values depend on the settings (e.g. the trigonometric functions depend on the angle mode) or that can
+    /// not be represented exactly by a real floating point number are kept. Returns whether the whole tree is constant.
+    fn fold_constants(& mut self, tree: & mut TreeNode<Token>, input: & str) -> bool {
+        let name = tree.content.get_value().to_string();
+        let mut is_constant = match tree.content.get_type() {
+            TokenType::Number(_) => true,
+            TokenType::Constant => MathContext::parse_result_name(&name).is_none(),
+            TokenType::Operation => self.context.get_operation_type(&name) != Some(OperationType::Assign),
+            TokenType::Function => match self.context.get_function_type(&name) {
+                Some(FunctionType::Cos) | Some(FunctionType::Sin) | Some(FunctionType::Tan) | Some(FunctionType::Cot)
+                | Some(FunctionType::ArcCos) | Some(FunctionType::ArcSin) | Some(FunctionType::ArcTan) | Some(FunctionType::ArcCot)
+                | Some(FunctionType::Arg) | Some(FunctionType::Polar) | Some(FunctionType::Old) | Some(FunctionType::Out) | Some(FunctionType::Sum) | Some(FunctionType::Prod)
+                | Some(FunctionType::Integrate) | Some(FunctionType::Solve) | Some(FunctionType::Convert) | None => false,
+                _ => true
+            },
+            _ => false
+        };
+        for s in tree.successors.iter_mut() {
+            is_constant = self.fold_constants(s, input) && is_constant; // all subtrees are folded
+        }
+        if !is_constant || tree.successors.is_empty() {
+            return is_constant;
+        }
 
-                // evaluate the provided arguments
-                let mut args : Vec<MathResult> = Vec::new();
-                for s in subtree.successors.iter() {
-                    let x = self.recursive_evaluate(s.as_ref(), input)?;
-                    let x_num = Evaluator::error_if_symbolic(x, input)?;
-                    args.push(x_num);
+        let folded = match self.recursive_evaluate(tree, input) {
+            Ok(EvaluationResult::Numerical(ref x)) if x.exact.is_none() && x.unit.is_none() && !x.is_matrix()
+                && x.result_type == NumberType::Real && x.value.re.is_finite() => {
+                let number = TreeNode::new(Token::new(TokenType::Number(NumberType::Real), format!("{0}", x.value.re.abs()),
+                                                      tree.content.get_end_pos()));
+                if x.value.re < 0.0 {
+                    let mut minus = TreeNode::new(Token::new(TokenType::Operation, String::from("-"), tree.content.get_end_pos()));
+                    minus.successors.push(Box::new(number));
+                    minus
                 }
+                else {
+                    number
+                }
+            },
+            _ => return false // e.g. a division by zero is reported when the function is called
+        };
+        *tree = folded;
+        true
+    }
 
-                // call the correct function (regarding the function type) with the evaluated arguments
-                match f_type {
-                    FunctionType::Cos => Ok(EvaluationResult::from(MathContext::function_cos(& args[0]))),
-                    FunctionType::Sin => Ok(EvaluationResult::from(MathContext::function_sin(& args[0]))),
-                    FunctionType::Tan => Ok(EvaluationResult::from(MathContext::function_tan(& args[0]))),
-                    FunctionType::Cot => Ok(EvaluationResult::from(MathContext::function_cot(& args[0]))),
-                    FunctionType::Exp => Ok(EvaluationResult::from(MathContext::function_exp(& args[0]))),
-                    FunctionType::Cosh => Ok(EvaluationResult::from(MathContext::function_cosh(& args[0]))),
-                    FunctionType::Sinh => Ok(EvaluationResult::from(MathContext::function_sinh(& args[0]))),
-                    FunctionType::Tanh => Ok(EvaluationResult::from(MathContext::function_tanh(& args[0]))),
-                    FunctionType::Coth => Ok(EvaluationResult::from(MathContext::function_coth(& args[0]))),
-                    FunctionType::ArcCosh => Ok(EvaluationResult::from(MathContext::function_arccosh(& args[0]))),
-                    FunctionType::ArcSinh => Ok(EvaluationResult::from(MathContext::function_arcsinh(& args[0]))),
-                    FunctionType::ArcTanh => Ok(EvaluationResult::from(MathContext::function_arctanh(& args[0]))),
-                    FunctionType::ArcCoth => Ok(EvaluationResult::from(MathContext::function_arccoth(& args[0]))),
-                    FunctionType::Sqrt => Ok(EvaluationResult::from(MathContext::function_sqrt(& args[0]))),
-                    FunctionType::Ln => Ok(EvaluationResult::from(MathContext::function_ln(& args[0]))),
-                    FunctionType::Pow => Ok(EvaluationResult::from(MathContext::operation_pow(& args[0], & args[1]))),
-                    FunctionType::Root => Ok(EvaluationResult::from(MathContext::operation_root(& args[0], & args[1]))),
-                    FunctionType::ArcCos => Ok(EvaluationResult::from(MathContext::function_arccos(& args[0]))),
-                    FunctionType::ArcSin => Ok(EvaluationResult::from(MathContext::function_arcsin(& args[0]))),
-                    FunctionType::ArcTan => Ok(EvaluationResult::from(MathContext::function_arctan(& args[0]))),
-                    FunctionType::ArcCot => Ok(EvaluationResult::from(MathContext::function_arccot(& args[0]))),
-                    FunctionType::Im => Ok(EvaluationResult::from(MathContext::function_im(& args[0]))),
-                    FunctionType::Re => Ok(EvaluationResult::from(MathContext::function_re(& args[0]))),
-                    FunctionType::UserFunction => {
-                        let slice = subtree.successors.as_slice();
-                        let mut args_token : Vec<& TreeNode<Token>> = Vec::new();
-                        for succ in slice {
-                            args_token.push(succ);
-                        }
-                        let f_tree = self.context.substitute_user_function_tree(subtree.content.get_value(), args_token);
-                        match f_tree {
-                            Some(x) => {
-                                let f_input = self.context.get_user_function_input(subtree.content.get_value()).unwrap_or(String::new());
-                                self.recursive_evaluate(& x, & f_input)
-                            },
-                            None => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "function call of user defined function", Some(
-                                format!("expression {0}", subtree.content)), subtree.content.get_end_pos())))
+    /// Evaluates a conditional expression (e.g. "if(x < 0, -x, x)").
+    /// Only the branch that is selected by the condition is evaluated, so that the other branch may be undefined
+    /// (e.g. "if(x > 0, ln(x), 0)"). The condition must be a real number, any value other than 0 selects the first branch.
+    fn evaluate_conditional(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let cond_node = subtree.successors[0].as_ref();
+        let cond = self.recursive_evaluate(cond_node, input)?;
+        let cond = Evaluator::error_if_symbolic(cond, input)?;
+        if cond.result_type != NumberType::Real || cond.is_matrix() {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "real condition", Some(
+                format!("{0}", cond)), cond_node.content.get_end_pos())));
+        }
+
+        let branch = if cond.value.re != 0.0 { 1 } else { 2 };
+        self.recursive_evaluate(subtree.successors[branch].as_ref(), input)
+    }
+
+    /// Evaluates the conversion of a quantity to the specified unit (e.g. "convert(36 km/h, m/s)").
+    /// The unit expression (the second argument) is not evaluated. The value is kept, only the unit of the output changes.
+    fn evaluate_conversion(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let x = self.recursive_evaluate(subtree.successors[0].as_ref(), input)?;
+        let x = Evaluator::error_if_symbolic(x, input)?;
+        if x.is_matrix() {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "scalar argument", Some(
+                format!("matrix argument 1 of function \"{0}\"", subtree.content)), subtree.content.get_end_pos())));
+        }
+
+        let target = subtree.successors[1].as_ref();
+        let (scale, dimension) = self.evaluate_unit(target, input)?;
+        if x.get_dimension() != dimension {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, format!("quantity of dimension \"{0}\"", dimension), Some(
+                format!("dimension \"{0}\"", x.get_dimension())), target.content.get_end_pos())));
+        }
+        let unit = Unit::new(deparse(target, self.context), scale, dimension);
+        Ok(EvaluationResult::from(MathResult::new(x.result_type.clone(), x.value).with_unit(Some(unit))))
+    }
+
+    /// Evaluates the scale and the dimension of the specified unit expression (e.g. "km/h"), which consists of units that
+    /// are combined by multiplications, divisions and integer powers.
+    fn evaluate_unit(& self, n: & TreeNode<Token>, input: & str) -> Result<(f64, Dimension), EvaluationError> {
+        match n.content.get_type() {
+            TokenType::Unit | TokenType::Constant | TokenType::UserConstant | TokenType::Symbol(SymbolicTokenType::UnknownConstant) => {
+                if let Some(u) = Unit::parse(n.content.get_value()) {
+                    return Ok((u.scale, u.dimension));
+                }
+            },
+            TokenType::Operation if n.successors.len() == 2 => {
+                let (a, da) = self.evaluate_unit(n.successors[0].as_ref(), input)?;
+                match self.context.get_operation_type(n.content.get_value()) {
+                    Some(OperationType::Mul) => {
+                        let (b, db) = self.evaluate_unit(n.successors[1].as_ref(), input)?;
+                        return Ok((a * b, da.mul(& db)));
+                    },
+                    Some(OperationType::Div) => {
+                        let (b, db) = self.evaluate_unit(n.successors[1].as_ref(), input)?;
+                        return Ok((a / b, da.div(& db)));
+                    },
+                    Some(OperationType::Pow) => {
+                        if let Some(p) = Evaluator::get_integer_literal(n.successors[1].as_ref()) {
+                            return Ok((a.powi(p), da.powi(p)));
                         }
-                    }
+                    },
+                    _ => ()
                 }
             },
+            _ => ()
+        }
+        Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "unit", Some(
+            format!("expression \"{0}\"", n.content)), n.content.get_end_pos())))
+    }
 
-            TokenType::Symbol(sym) => {
-                match sym {
-                    SymbolicTokenType::UnknownConstant | SymbolicTokenType::UnknownFunction => {
-                        Ok(EvaluationResult::from(subtree))
-                    }
+    /// Returns the value of the specified integer literal, which may be negated (e.g. "-2"), or None for other expressions.
+    fn get_integer_literal(n: & TreeNode<Token>) -> Option<i32> {
+        match n.content.get_type() {
+            TokenType::Number(NumberType::Real) => n.content.get_value().parse::<i32>().ok(),
+            TokenType::Operation if n.content.get_value() == "-" && n.successors.len() == 1 => {
+                Evaluator::get_integer_literal(n.successors[0].as_ref()).map(|p| -p)
+            },
+            _ => None
+        }
+    }
+
+    /// Evaluates the result of a previous evaluation of this session (e.g. "Out(3)").
+    fn evaluate_output(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let n_node = subtree.successors[0].as_ref();
+        let n = self.recursive_evaluate(n_node, input)?;
+        let n = Evaluator::error_if_symbolic(n, input)?;
+        if n.result_type != NumberType::Real || n.value.re < 1.0 || n.value.re.fract() != 0.0 {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "positive integer", Some(
+                format!("{0}", n)), n_node.content.get_end_pos())));
+        }
+
+        let idx = n.value.re as usize;
+        match self.context.get_output(idx) {
+            Some(x) => Ok(EvaluationResult::from(x)),
+            None => {
+                let found = if idx > self.context.get_transcript_len() {
+                    format!("{0} evaluation(s)", self.context.get_transcript_len())
                 }
+                else {
+                    format!("no result of evaluation {0}", n)
+                };
+                Err(EvaluationError::from(ExpectedErrorTemplate::new(input, format!("result of evaluation {0}", n), Some(found),
+                    subtree.content.get_end_pos())))
             }
+        }
+    }
 
-            _ => {  // punctuation and unknown tokens should not occur in the evaluation method
-                Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "function or operation", Some(
-                    format!("symbol {0}", subtree.content)), subtree.content.get_end_pos())))
+    /// Evaluates a sum or a product of an expression over an integer range (e.g. "sum(1/k^2, k, 1, 1000)").
+    /// The second argument is the name of the bound variable and not evaluated. The bound variable is only visible in the
+    /// expression, which is evaluated once for each value of the range. An empty range results in 0 or 1, respectively.
+    fn evaluate_series(& mut self, f_type: FunctionType, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let var = self.get_bound_variable(subtree.successors[1].as_ref(), input)?;
+        let first = self.evaluate_integer(subtree.successors[2].as_ref(), input)?;
+        let last = self.evaluate_integer(subtree.successors[3].as_ref(), input)?;
+        // the bounds may be far apart (e.g. "sum(k, k, -1E18, 1E18)")
+        if last.saturating_sub(first) >= MAX_SERIES_TERMS {
+            return Err(EvaluationError::from(format!("Error: Maximum number of {0} terms exceeded in \"{1}\".",
+                                                     MAX_SERIES_TERMS, subtree.content)));
+        }
+
+        self.scopes.push(HashMap::new());
+        let result = self.evaluate_series_terms(f_type, subtree, var, first, last, input);
+        self.scopes.pop(); // the bound variable must not leak, even in case of an error
+        result
+    }
+
+    /// Accumulates the terms of a sum or a product for the bound variable values first to last in the innermost scope.
+    fn evaluate_series_terms(& mut self, f_type: FunctionType, subtree: & TreeNode<Token>, var: & str, first: i64, last: i64,
+                             input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let expression = subtree.successors[0].as_ref();
+        let mut result = self.create_integer(if f_type == FunctionType::Sum { 0 } else { 1 });
+
+        for k in first..=last {
+            let k_num = self.create_integer(k);
+            let term_num = self.evaluate_bound(expression, var, k_num, input)?;
+            if term_num.is_matrix() {
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "scalar expression", Some(
+                    format!("matrix term of function \"{0}\"", subtree.content)), subtree.content.get_end_pos())));
+            }
+
+            result = if f_type == FunctionType::Sum {
+                MathContext::operation_add(& result, & term_num)
+            }
+            else {
+                MathContext::operation_mul(& result, & term_num)
+            };
+        }
+
+        Ok(EvaluationResult::from(result))
+    }
+
+    /// Finds a root of an expression in a variable near the specified guess with the secant method (e.g. "solve(x^2 - 2, x, 1)").
+    /// The second argument is the name of the bound variable and not evaluated. The bound variable is only visible in the expression.
+    fn evaluate_solve(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let var = self.get_bound_variable(subtree.successors[1].as_ref(), input)?;
+        let guess_node = subtree.successors[2].as_ref();
+        let guess = self.recursive_evaluate(guess_node, input)?;
+        let guess = Evaluator::error_if_symbolic(guess, input)?;
+        if guess.is_matrix() {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "scalar initial guess", Some(
+                format!("{0}", guess)), guess_node.content.get_end_pos())));
+        }
+
+        self.scopes.push(HashMap::new());
+        let root = self.find_root(subtree.successors[0].as_ref(), var, guess.value, input);
+        self.scopes.pop(); // the bound variable must not leak, even in case of an error
+
+        match root? {
+            Some(x) => Ok(EvaluationResult::from(MathResult::from((x.re, x.im)))),
+            None => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "convergent root search", Some(
+                format!("no root found from the initial guess {0} within {1} iterations", guess, MAX_SOLVE_ITERATIONS)),
+                subtree.content.get_end_pos())))
+        }
+    }
+
+    /// Searches a root of the specified expression in the bound variable of the innermost scope with the secant method.
+    /// Returns None if the iteration does not converge.
+    fn find_root(& mut self, expression: & TreeNode<Token>, var: & str, guess: Complex<f64>, input: & str) -> Result<Option<Complex<f64>>, EvaluationError> {
+        let mut x0 = guess;
+        let mut x1 = guess + 1e-4 * guess.norm().max(1.0);
+        let mut f0 = self.evaluate_bound(expression, var, MathResult::from(x0), input)?.value;
+        let mut f1 = self.evaluate_bound(expression, var, MathResult::from(x1), input)?.value;
+
+        for _ in 0..MAX_SOLVE_ITERATIONS {
+            if f0.is_zero() {
+                return Ok(Some(x0));
+            }
+            if f1.is_zero() {
+                return Ok(Some(x1));
+            }
+
+            let x2 = x1 - f1 * (x1 - x0) / (f1 - f0);
+            if x2.is_nan() || x2.is_infinite() {
+                return Ok(None);
+            }
+            if (x2 - x1).norm() <= SOLVE_TOLERANCE * x2.norm().max(1.0) {
+                return Ok(Some(x2));
+            }
+
+            x0 = x1;
+            f0 = f1;
+            x1 = x2;
+            f1 = self.evaluate_bound(expression, var, MathResult::from(x1), input)?.value;
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the name of the bound variable of a sum, a product or the solve function.
+    fn get_bound_variable<'b>(& self, n: &'b TreeNode<Token>, input: & str) -> Result<&'b str, EvaluationError> {
+        let var = self.error_if_built_in(n, input)?;
+        match var.content.get_type() {
+            TokenType::Symbol(SymbolicTokenType::UnknownConstant) | TokenType::UserConstant => {
+                Evaluator::error_if_number_like(var, input)?;
+                Ok(var.content.get_value())
+            },
+            _ => {
+                Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "variable name", Some(
+                    format!("expression \"{0}\"", var.content)), var.content.get_end_pos())))
+            }
+        }
+    }
+
+    /// Evaluates the specified expression with the specified value of the bound variable, which is defined in the innermost scope.
+    fn evaluate_bound(& mut self, expression: & TreeNode<Token>, var: & str, value: MathResult, input: & str) -> Result<MathResult, EvaluationError> {
+        self.scopes.last_mut().unwrap().insert(var.to_string(), value);
+        let result = self.recursive_evaluate(expression, input)?;
+        Evaluator::error_if_symbolic(result, input)
+    }
+
+    /// Evaluates the specified subtree, which must result in a real 64 bit integer (e.g. the bounds of a sum).
+    /// The number of terms is checked separately by the caller.
+    fn evaluate_integer(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<i64, EvaluationError> {
+        let x = self.recursive_evaluate(subtree, input)?;
+        let x = Evaluator::error_if_symbolic(x, input)?;
+        match x.to_i64() {
+            Some(n) => Ok(n),
+            None => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "integer", Some(
+                format!("{0}", x)), subtree.content.get_end_pos())))
+        }
+    }
+
+    /// Creates the MathResult of the specified integer, which is exact in the exact precision mode.
+    fn create_integer(& self, x: i64) -> MathResult {
+        if self.context.get_precision_mode() == PrecisionMode::Exact {
+            MathResult::from(BigRational::from_integer(BigInt::from(x)))
+        }
+        else {
+            MathResult::from(x as f64)
+        }
+    }
+
+    /// Evaluates the definite integral of a built-in or user defined function with one argument (e.g. "integrate(sin, 0, pi)")
+    /// by adaptive Simpson quadrature. The first argument is the name of the function and not evaluated.
+    fn evaluate_integral(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let f = subtree.successors[0].as_ref();
+        let f_type = self.context.get_function_type(f.content.get_value());
+        let is_integrable = f.content.get_type() == TokenType::Symbol(SymbolicTokenType::UnknownConstant)
+            && self.context.get_function_arg_num(f.content.get_value()) == Some(1)
+            && match f_type {
+                Some(FunctionType::Det) | Some(FunctionType::Transpose) | Some(FunctionType::Inv) | Some(FunctionType::Out) | None => false,
+                _ => true
+            };
+        if !is_integrable {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "function with 1 argument", Some(
+                format!("expression \"{0}\"", f.content)), f.content.get_end_pos())));
+        }
+        let f_type = f_type.unwrap();
+        if f_type == FunctionType::UserFunction {
+            self.context.mark_user_definition_used(f.content.get_value());
+        }
+
+        let a = self.evaluate_real(subtree.successors[1].as_ref(), input)?;
+        let b = self.evaluate_real(subtree.successors[2].as_ref(), input)?;
+
+        let mut integrand = Integrand {f_type: f_type, f: f, input: input, evaluations: 0};
+        let fa = self.evaluate_integrand(&mut integrand, a)?;
+        let fm = self.evaluate_integrand(&mut integrand, (a + b) / 2.0)?;
+        let fb = self.evaluate_integrand(&mut integrand, b)?;
+        let whole = (fa + fm * 4.0 + fb) * ((b - a) / 6.0);
+        let integral = self.integrate_simpson(&mut integrand, (a, b), (fa, fm, fb), whole, INTEGRATION_TOLERANCE, MAX_INTEGRATION_DEPTH)?;
+        Ok(EvaluationResult::from(MathResult::from((integral.re, integral.im))))
+    }
+
+    /// Integrates the specified integrand over the interval (a, b) by adaptive Simpson quadrature.
+    /// The values of the integrand at a, (a + b) / 2 and b and the Simpson estimate of the whole interval are already known.
+    fn integrate_simpson(& mut self, integrand: & mut Integrand, (a, b): (f64, f64), (fa, fm, fb): (Complex<f64>, Complex<f64>, Complex<f64>),
+                         whole: Complex<f64>, tolerance: f64, depth: u32) -> Result<Complex<f64>, EvaluationError> {
+        let m = (a + b) / 2.0;
+        let flm = self.evaluate_integrand(integrand, (a + m) / 2.0)?;
+        let frm = self.evaluate_integrand(integrand, (m + b) / 2.0)?;
+        let left = (fa + flm * 4.0 + fm) * ((m - a) / 6.0);
+        let right = (fm + frm * 4.0 + fb) * ((b - m) / 6.0);
+        let delta = left + right - whole;
+
+        if delta.norm() <= 15.0 * tolerance || depth == 0 {
+            // Richardson extrapolation of the two estimates
+            return Ok(left + right + delta / 15.0);
+        }
+        let left = self.integrate_simpson(integrand, (a, m), (fa, flm, fm), left, tolerance / 2.0, depth - 1)?;
+        let right = self.integrate_simpson(integrand, (m, b), (fm, frm, fb), right, tolerance / 2.0, depth - 1)?;
+        Ok(left + right)
+    }
+
+    /// Evaluates the specified integrand at x.
+    /// Returns an error if the result is not a finite number or if the maximum number of evaluations is exceeded.
+    fn evaluate_integrand(& mut self, integrand: & mut Integrand, x: f64) -> Result<Complex<f64>, EvaluationError> {
+        let f = integrand.f;
+        let input = integrand.input;
+        integrand.evaluations += 1;
+        if integrand.evaluations > MAX_INTEGRAND_EVALUATIONS {
+            return Err(EvaluationError::from(format!("Error: The integration of \"{0}\" does not converge within {1} evaluations.",
+                                                     f.content, MAX_INTEGRAND_EVALUATIONS)));
+        }
+
+        let args = vec![MathResult::from(x)];
+        let y = if integrand.f_type == FunctionType::UserFunction {
+            let call = TreeNode::new(Token::new(TokenType::UserFunction, f.content.get_value().to_string(), f.content.get_end_pos()));
+            let y = self.evaluate_user_function(& call, args, input)?;
+            Evaluator::error_if_symbolic(y, input)?
+        }
+        else {
+            Evaluator::apply_angle_mode(integrand.f_type.clone(), args, self.context.get_angle_mode())
+        };
+
+        if y.is_matrix() || y.value.is_nan() || y.value.is_infinite() {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "finite integrand", Some(
+                format!("{0}({1}) = {2}", f.content, x, y)), f.content.get_end_pos())));
+        }
+        Ok(y.value)
+    }
+
+    /// Evaluates the specified subtree, which must result in a real number (e.g. the bounds of an integral).
+    fn evaluate_real(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<f64, EvaluationError> {
+        let x = self.recursive_evaluate(subtree, input)?;
+        let x = Evaluator::error_if_symbolic(x, input)?;
+        if x.result_type != NumberType::Real || x.is_matrix() || !x.value.re.is_finite() {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "finite real number", Some(
+                format!("{0}", x)), subtree.content.get_end_pos())));
+        }
+        Ok(x.value.re)
+    }
+
+    /// Evaluates a matrix (e.g. "[1, 2; 3, 4]"). All rows must have the same number of scalar elements.
+    fn evaluate_matrix(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let cols = subtree.successors[0].successors.len(); // the parser ensures that there is at least one row
+        let mut elements = Vec::new();
+
+        for (r, row) in subtree.successors.iter().enumerate() {
+            if row.successors.len() != cols {
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, format!("{0} element(s) in row {1}", cols, r + 1), Some(
+                    format!("{0} element(s)", row.successors.len())), row.successors.last().unwrap().content.get_end_pos())));
+            }
+            for elem in row.successors.iter() {
+                let x = self.recursive_evaluate(elem.as_ref(), input)?;
+                let x_num = Evaluator::error_if_symbolic(x, input)?;
+                if x_num.is_matrix() {
+                    return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "scalar matrix element", Some(
+                        String::from("nested matrix")), elem.content.get_end_pos())));
+                }
+                if let Some(ref u) = x_num.unit {
+                    return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "dimensionless matrix element", Some(
+                        format!("element with the unit \"{0}\"", u.name)), elem.content.get_end_pos())));
+                }
+                elements.push(x_num);
+            }
+        }
+
+        Ok(EvaluationResult::from(MathResult::from(Matrix::new(subtree.successors.len(), cols, elements))))
+    }
+
+    /// Evaluates a binary operation of which at least one operand is a matrix.
+    /// Matrices can be added, subtracted, multiplied, compared for equality and raised to integer powers.
+    /// Scalars can be multiplied with matrices and matrices can be divided by scalars.
+    fn evaluate_matrix_operation(op_type: OperationType, lhs: & MathResult, rhs: & MathResult, op: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let result = match (op_type.clone(), & lhs.matrix, & rhs.matrix) {
+            (OperationType::Add, & Some(ref a), & Some(ref b)) => a.add(b),
+            (OperationType::Sub, & Some(ref a), & Some(ref b)) => a.sub(b),
+            (OperationType::Mul, & Some(ref a), & Some(ref b)) => a.mul(b),
+            (OperationType::Mul, & Some(ref a), & None) => Some(a.map(|x| MathContext::operation_mul(x, rhs))),
+            (OperationType::Mul, & None, & Some(ref b)) => Some(b.map(|x| MathContext::operation_mul(lhs, x))),
+            (OperationType::Div, & Some(ref a), & None) => Some(a.map(|x| MathContext::operation_div(x, rhs))),
+            (OperationType::Pow, & Some(ref a), & None) => {
+                if rhs.result_type == NumberType::Complex || rhs.value.re.fract() != 0.0 {
+                    return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "integer exponent", Some(
+                        format!("exponent {0} of a matrix", rhs)), op.content.get_end_pos())))
+                }
+                a.pow(rhs.value.re as i64)
+            },
+            (OperationType::Equal, _, _) => return Ok(EvaluationResult::from(if lhs == rhs { 1.0_f64 } else { 0.0_f64 })),
+            (OperationType::NotEqual, _, _) => return Ok(EvaluationResult::from(if lhs != rhs { 1.0_f64 } else { 0.0_f64 })),
+            _ => {
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "matrix operation", Some(
+                    format!("operation \"{0}\" with operands of type {1} and {2}", op.content, Evaluator::get_shape(lhs),
+                            Evaluator::get_shape(rhs))), op.content.get_end_pos())))
+            }
+        };
+
+        match result {
+            Some(m) => Ok(EvaluationResult::from(MathResult::from(m))),
+            None => {
+                let expected = if op_type == OperationType::Pow { "square matrix" } else { "matrices with matching dimensions" };
+                Err(EvaluationError::from(ExpectedErrorTemplate::new(input, expected, Some(
+                    format!("operation \"{0}\" with operands of type {1} and {2}", op.content, Evaluator::get_shape(lhs),
+                            Evaluator::get_shape(rhs))), op.content.get_end_pos())))
             }
         }
     }
 
+    /// Evaluates the matrix functions det, transpose and inv.
+    fn evaluate_matrix_function(& self, f_type: FunctionType, arg: & MathResult, f: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let m = match arg.matrix {
+            Some(ref m) => m,
+            None => return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "matrix argument", Some(
+                format!("scalar argument of function \"{0}\"", f.content)), f.content.get_end_pos())))
+        };
+        if f_type != FunctionType::Transpose && !m.is_square() {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "square matrix", Some(
+                format!("{0} matrix", Evaluator::get_shape(arg))), f.content.get_end_pos())))
+        }
+
+        match f_type {
+            FunctionType::Det => Ok(EvaluationResult::from(m.det().unwrap())),
+            FunctionType::Transpose => Ok(EvaluationResult::from(MathResult::from(m.transpose()))),
+            _ => match m.inv() {
+                Some(x) => Ok(EvaluationResult::from(MathResult::from(x))),
+                None => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "invertible matrix", Some(
+                    String::from("singular matrix")), f.content.get_end_pos())))
+            }
+        }
+    }
+
+    /// Returns a description of the type of the specified value (e.g. "2x3" for matrices).
+    fn get_shape(x: & MathResult) -> String {
+        match x.matrix {
+            Some(ref m) => format!("{0}x{1}", m.rows, m.cols),
+            None => String::from("scalar")
+        }
+    }
+
+    /// Evaluates the call of a user defined function with the specified evaluated arguments.
+    /// The function body is evaluated in a new environment that only contains the bindings of the
+    /// function arguments, so the scopes of the caller are not visible in the function body.
+    fn evaluate_user_function(& mut self, subtree: & TreeNode<Token>, args: Vec<MathResult>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let (f_tree, f_args) = match self.context.get_user_function_body(subtree.content.get_value()) {
+            Some(x) => x,
+            None => return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "function call of user defined function", Some(
+                format!("expression {0}", subtree.content)), subtree.content.get_end_pos())))
+        };
+
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(EvaluationError::from(format!("Error: Maximum depth of {0} nested user function calls exceeded in \"{1}\".",
+                                                     MAX_CALL_DEPTH, subtree.content)));
+        }
+
+        let bindings : HashMap<String, MathResult> = f_args.into_iter().zip(args.into_iter()).collect();
+        let f_input = self.context.get_user_function_input(subtree.content.get_value()).unwrap_or(String::new());
+
+        let caller_scopes = mem::replace(& mut self.scopes, vec![bindings]);
+        self.call_depth += 1;
+        let result = self.recursive_evaluate(& f_tree, & f_input);
+        self.call_depth -= 1;
+        self.scopes = caller_scopes;
+        result
+    }
+
+    /// Evaluates a scoped expression (e.g. "with(a=2, b=3) a*b").
+    /// The temporary definitions are only visible in the body expression and do not modify the context.
+    fn evaluate_scope(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        self.scopes.push(HashMap::new());
+        let result = self.evaluate_in_scope(subtree, input);
+        self.scopes.pop(); // the temporary definitions must not leak, even in case of an error
+        result
+    }
+
+    /// Evaluates the temporary definitions and the body expression of a scoped expression in the innermost scope.
+    fn evaluate_in_scope(& mut self, subtree: & TreeNode<Token>, input: & str) -> Result<EvaluationResult, EvaluationError> {
+        let (body, definitions) = subtree.successors.split_last().unwrap(); // the parser ensures that there is a body
+
+        for definition in definitions {
+            let is_assignment = self.context.get_operation_type(definition.content.get_value()) == Some(OperationType::Assign)
+                && definition.content.get_type() == TokenType::Operation && definition.successors.len() == 2;
+            if !is_assignment {
+                return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "temporary definition (e.g. \"a=2\")", Some(
+                    format!("expression \"{0}\"", definition.content)), definition.content.get_end_pos())))
+            }
+
+            let name = self.error_if_built_in(definition.successors[0].as_ref(), input)?;
+            match name.content.get_type() {
+                TokenType::Symbol(SymbolicTokenType::UnknownConstant) | TokenType::UserConstant => {
+                    Evaluator::error_if_number_like(name, input)?;
+                },
+                _ => {
+                    return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "constant name", Some(
+                        format!("expression \"{0}\"", name.content)), name.content.get_end_pos())))
+                }
+            }
+
+            // earlier definitions of the same scope are visible in later definitions
+            let value = self.recursive_evaluate(definition.successors[1].as_ref(), input)?;
+            let value_num = Evaluator::error_if_symbolic(value, input)?;
+            self.scopes.last_mut().unwrap().insert(name.content.get_value().to_string(), value_num);
+        }
+
+        if self.context.get_operation_type(body.content.get_value()) == Some(OperationType::Assign) && body.content.get_type() == TokenType::Operation {
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "expression", Some(
+                String::from("assignment in scoped expression")), body.content.get_end_pos())))
+        }
+        let result = self.recursive_evaluate(body.as_ref(), input)?;
+        Ok(EvaluationResult::from(Evaluator::error_if_symbolic(result, input)?))
+    }
+
+    /// Returns the value of the specified temporary definition of the innermost scope that defines it.
+    /// Returns None if no scope defines the specified name.
+    fn get_scoped_value(& self, name: & str) -> Option<MathResult> {
+        self.scopes.iter().rev().filter_map(|s| s.get(name)).next().cloned()
+    }
+
+    /// Creates the error for a constant that is called like a function (e.g. "pi(2)"). If the constant has a single
+    /// argument, then the error suggests the multiplication of the constant and the argument (e.g. "pi*2").
+    fn constant_call_error(& self, n: & TreeNode<Token>, input: & str) -> EvaluationError {
+        let hint = match n.successors.len() {
+            1 if n.successors[0].successors.is_empty() => format!(" (did you mean \"{0}*{1}\"?)", n.content, deparse(& n.successors[0], & self.context)),
+            1 => format!(" (did you mean \"{0}*({1})\"?)", n.content, deparse(& n.successors[0], & self.context)),
+            _ => String::new()
+        };
+        EvaluationError::from(ExpectedErrorTemplate::new(input, "built-in or user defined function", Some(
+            format!("constant \"{0}\" that is not callable{1}", n.content, hint)), n.content.get_end_pos()))
+    }
+
     /// Checks whether the specified EvaluationResult is of symbolic type.
     /// If so, then an EvaluationError is returned, otherwise the numerical MathResult is returned.
     fn error_if_symbolic(res: EvaluationResult, input: & str) -> Result<MathResult, EvaluationError> {
@@ -469,6 +1650,113 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// Returns the real operands of the specified comparison operation.
+    /// Real numbers are compared by their values. Complex numbers can not be ordered, so comparing them
+    /// is an error unless the comparison mode of the context is ComparisonMode::Modulus.
+    fn get_comparison_operands(& self, lhs: & MathResult, rhs: & MathResult, op: & TreeNode<Token>, input: & str) -> Result<(f64, f64), EvaluationError> {
+        if lhs.result_type == NumberType::Real && rhs.result_type == NumberType::Real {
+            return Ok((lhs.value.re, rhs.value.re));
+        }
+
+        match self.context.get_comparison_mode() {
+            ComparisonMode::Modulus => Ok((lhs.value.norm(), rhs.value.norm())),
+            ComparisonMode::Strict => Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "real operands", Some(
+                format!("comparison \"{0}\" of complex numbers", op.content)), op.content.get_end_pos())))
+        }
+    }
+
+    /// Applies the specified ordering comparison operation (<, >, <= or >=) to the specified operands.
+    fn compare<T: PartialOrd>(op_type: OperationType, lhs: & T, rhs: & T) -> bool {
+        match op_type {
+            OperationType::Less => lhs < rhs,
+            OperationType::Greater => lhs > rhs,
+            OperationType::LessEqual => lhs <= rhs,
+            _ => lhs >= rhs
+        }
+    }
+
+    /// Parses the specified real number literal (e.g. "0.1", "2.5e-3", "0xFF" or "0r36:z1") into an exact rational number.
+    /// Returns None if the literal can not be represented exactly, so that it is parsed as floating point number.
+    fn parse_exact(s: & str) -> Option<BigRational> {
+        let (radix, digits) = split_radix_literal(s).unwrap_or((10, s));
+
+        // only decimal literals have an exponent (e.g. "1.5e3"), as "e" is a hexadecimal digit
+        let (mantissa, exponent) = match digits.find(|c| c == 'e' || c == 'E') {
+            Some(idx) if radix == 10 => (& digits[..idx], i64::from_str(& digits[idx + 1..]).ok()?),
+            _ => (digits, 0)
+        };
+        let mut parts = mantissa.splitn(2, '.');
+        let pre_dp = parts.next().unwrap_or("");
+        let post_dp = parts.next().unwrap_or("");
+        if pre_dp.is_empty() && post_dp.is_empty() {
+            return None;
+        }
+        let numer = BigInt::from_str_radix(& format!("{0}{1}", pre_dp, post_dp), radix).ok()?;
+
+        let scale = exponent - post_dp.len() as i64;
+        if scale.abs() as usize > MAX_EXACT_LITERAL_SCALE {
+            return None;
+        }
+        let factor : BigInt = pow(BigInt::from(radix), scale.abs() as usize);
+        if scale < 0 {
+            Some(BigRational::new(numer, factor))
+        }
+        else {
+            Some(BigRational::from_integer(numer * factor))
+        }
+    }
+
+    /// Checks whether one of the operands of the specified operation is a complex number.
+    /// If so, then an EvaluationError is returned, as the operation is only defined for real numbers.
+    fn error_if_complex_operands(lhs: & MathResult, rhs: & MathResult, op: & TreeNode<Token>, op_name: & str, input: & str) -> Result<(), EvaluationError> {
+        if lhs.result_type == NumberType::Real && rhs.result_type == NumberType::Real {
+            Ok(())
+        }
+        else {
+            Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "real operands", Some(
+                format!("{0} \"{1}\" of complex numbers", op_name, op.content)), op.content.get_end_pos())))
+        }
+    }
+
+    /// Checks whether the specified operand of a bitwise operation is an integer in the range of a 64 bit integer.
+    /// If not, then an EvaluationError that points at the operand is returned.
+    fn error_if_non_integer_operand(operand: & MathResult, op: & TreeNode<Token>, idx: usize, input: & str) -> Result<(), EvaluationError> {
+        if operand.to_i64().is_some() {
+            Ok(())
+        }
+        else {
+            Err(Evaluator::domain_error(op, idx, "64 bit integer operand", format!("operand {0} of operation \"{1}\"", operand, op.content), input))
+        }
+    }
+
+    /// Checks whether the specified argument of a number theory or combinatorics function (e.g. "gcd" or "ncr") is an
+    /// integer in the range of a 64 bit integer. If not, then an EvaluationError that points at the argument is returned.
+    fn error_if_non_integer_argument(arg: & MathResult, f: & TreeNode<Token>, idx: usize, input: & str) -> Result<(), EvaluationError> {
+        if arg.to_i64().is_some() {
+            Ok(())
+        }
+        else {
+            Err(Evaluator::domain_error(f, idx, "64 bit integer argument", format!("argument {0} of function \"{1}\"", arg, f.content), input))
+        }
+    }
+
+    /// Checks whether the specified constant name resembles a number literal.
+    /// This is the case for names that look like the exponent of a number in scientific notation
+    /// (e.g. "E" or "E5"). If so, then an EvaluationError is returned.
+    /// Names like "x1" or "b0" are accepted, as radix literals always start with "0".
+    fn error_if_number_like(n: & TreeNode<Token>, input: & str) -> Result<(), EvaluationError> {
+        let name = n.content.get_value();
+        let number_like = name.starts_with('E') && name[1..].chars().all(|c| c.is_digit(10));
+
+        if number_like {
+            Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "new constant name or function name", Some(
+                format!("constant name \"{0}\" that resembles a number literal", n.content)), n.content.get_end_pos())))
+        }
+        else {
+            Ok(())
+        }
+    }
+
     /// Returns the list of arguments of the specified function call tree.
     fn get_function_args(n: & TreeNode<Token>, input: & str) -> Result<Vec<String>, EvaluationError> {
         let mut args_set : HashSet<String> = HashSet::new();
@@ -499,18 +1787,66 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// Marks the calls of the specified function in its own definition tree as user function calls,
+    /// so that the function can call itself recursively.
+    fn mark_recursive_calls(n: & mut TreeNode<Token>, f_name: & str) {
+        if n.content.get_type() == TokenType::Symbol(SymbolicTokenType::UnknownFunction) && n.content.get_value() == f_name {
+            n.content = Token::new(TokenType::UserFunction, f_name.to_string(), n.content.get_end_pos());
+        }
+        for succ in n.successors.iter_mut() {
+            Evaluator::mark_recursive_calls(succ, f_name);
+        }
+    }
+
+    /// Checks whether the specified operands of an operation have the same physical dimension (e.g. "3 m + 2 km").
+    fn error_if_different_dimensions(lhs: & MathResult, rhs: & MathResult, op: & TreeNode<Token>, input: & str) -> Result<(), EvaluationError> {
+        if lhs.get_dimension() == rhs.get_dimension() {
+            return Ok(());
+        }
+        Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "operands of the same dimension", Some(
+            format!("dimensions \"{0}\" and \"{1}\"", lhs.get_dimension(), rhs.get_dimension())), op.content.get_end_pos())))
+    }
+
     /// Checks a user function definition tree.
-    /// Checks if every symbol is defined.
-    fn check_function_definition(& self, n: & TreeNode<Token>, args: & Vec<String>, input: & str) -> Result<(), EvaluationError> {
+    /// Checks if every symbol is defined and collects the parameters that are referenced in used_args.
+    fn check_function_definition(& self, n: & TreeNode<Token>, args: & Vec<String>, used_args: & mut HashSet<String>,
+                                 input: & str) -> Result<(), EvaluationError> {
+        if n.content.get_type() == TokenType::Operation && self.context.get_operation_type(n.content.get_value()) == Some(OperationType::Assign) {
+            // assignments inside of function bodies are rejected, as they would redefine the parameters or global definitions
+            let found = match n.successors.first() {
+                Some(lhs) if args.iter().any(|x| x == lhs.content.get_value()) => format!("assignment to parameter \"{0}\" in function definition", lhs.content),
+                _ => String::from("assignment in function definition")
+            };
+            return Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "expression", Some(found), n.content.get_end_pos())));
+        }
+        let f_type = if n.content.get_type() == TokenType::Function { self.context.get_function_type(n.content.get_value()) } else { None };
+        let has_bound_variable = (f_type.is_some() && self.context.is_series(n)) || f_type == Some(FunctionType::Solve);
+        if f_type == Some(FunctionType::Convert) && n.successors.len() == 2 {
+            // the unit expression (the second argument) is not evaluated
+            return self.check_function_definition(n.successors[0].as_ref(), args, used_args, input);
+        }
+        if has_bound_variable && n.successors.len() >= 2 {
+            // the bound variable (the second argument) is defined in the expression (the first argument)
+            let mut bound_args = args.clone();
+            bound_args.push(n.successors[1].content.get_value().to_string());
+            for (idx, succ) in n.successors.iter().enumerate() {
+                self.check_function_definition(succ, if idx < 2 { & bound_args } else { args }, used_args, input)?;
+            }
+            return Ok(());
+        }
         if !(n.content.get_type() == TokenType::Number(NumberType::Real) || n.content.get_type() == TokenType::Number(NumberType::Complex)
+            || n.content.get_type() == TokenType::UserFunction || n.content.get_type() == TokenType::Punctuation || n.content.get_type() == TokenType::Unit
             || self.context.is_constant(n.content.get_value()) || self.context.is_function(n.content.get_value()) || self.context.is_operation(n.content.get_value())
             || args.iter().any(|x| x == n.content.get_value())) {
             Err(EvaluationError::from(ExpectedErrorTemplate::new(input, "non-symbolic expression", Some(
                     format!("symbolic expression \"{0}\"", n.content)), n.content.get_end_pos())))
         }
         else {
+            if args.iter().any(|x| x == n.content.get_value()) {
+                used_args.insert(n.content.get_value().to_string());
+            }
             for succ in  &n.successors {
-                self.check_function_definition(succ, args, input)?;
+                self.check_function_definition(succ, args, used_args, input)?;
             }
 
             Ok(())
@@ -3,6 +3,12 @@ use num::traits::{Zero, One, Num, ParseFloatError};
 use std::ops::{Add, Div, Mul, Sub, Rem};
 use std::collections::HashMap;
 
+/// The marker that is appended to the digits after the radix point if they have been truncated.
+pub const INEXACT_MARKER : &'static str = "…";
+
+/// The default number of digits after the radix point.
+const DEFAULT_PRECISION : usize = 10;
+
 /// Formatter for the f64 type that implements the formatting traits for several different number formats.
 #[derive(PartialEq, Copy, Clone, Debug, Default, PartialOrd)]
 pub struct F64Formatter(pub f64);
@@ -156,6 +162,7 @@ macro_rules! format_post_dp {
         // val: the value after the decimal point
         // base: the base of the number system to format
         // prec: the precision (number of decimal places)
+        // returns the digits and whether the digits have been truncated
 
         let abs = $val.abs();
         let mut post_dp : f64 = abs - ((abs as u64) as f64);
@@ -173,7 +180,7 @@ macro_rules! format_post_dp {
             n += 1;
         }
 
-        repr
+        (repr, post_dp != 0.0_f64)
     }}
 }
 
@@ -189,20 +196,21 @@ macro_rules! write_formatter {
             return write!($f, "{0}", $formatter.0);
         }
 
+        // the digits are formatted from the absolute value, so the sign has to be written separately
+        let sign = if $formatter.0 < 0.0_f64 {"-"} else {""};
         let abs = $formatter.0.abs();
         let pre_repr = format_pre_dp!($f, abs, $fmt_type);
-        let post_repr = if let Some(prec) = $f.precision() {
-            format_post_dp!(abs, $base, $lookup, prec)
-        }
-        else {
-            format_post_dp!(abs, $base, $lookup, 10)
-        };
+        let prec = $f.precision().unwrap_or(DEFAULT_PRECISION);
+        let (post_repr, truncated) = format_post_dp!(abs, $base, $lookup, prec);
 
-        if post_repr != "" {
-            write!($f, "{0}.{1}", pre_repr, post_repr)
+        if truncated {
+            write!($f, "{0}{1}.{2}{3}", sign, pre_repr, post_repr, INEXACT_MARKER)
+        }
+        else if post_repr != "" {
+            write!($f, "{0}{1}.{2}", sign, pre_repr, post_repr)
         }
         else {
-            write!($f, "{0}", pre_repr)
+            write!($f, "{0}{1}", sign, pre_repr)
         }
     }}
 }
@@ -239,6 +247,41 @@ impl fmt::UpperHex for F64Formatter {
     }
 }
 
+/// Formats the specified number in the specified radix (from 2 to 36) like a number literal of an arbitrary radix
+/// (e.g. "0r36:z1"). Like in the binary, octal and hexadecimal formats, at most 10 digits after the radix point are shown.
+pub fn format_radix(x: f64, radix: u32) -> String {
+    // NaN and Infinity are handled without concerning the radix
+    if x.is_nan() || x.is_infinite() {
+        return format!("{0}", x);
+    }
+
+    // the digits are formatted from the absolute value, so the sign has to be written separately
+    let sign = if x < 0.0_f64 {"-"} else {""};
+    let abs = x.abs();
+    let mut pre_dp = abs as u64;
+    let mut pre_repr = String::new();
+    loop {
+        pre_repr.insert(0, ::std::char::from_digit((pre_dp % radix as u64) as u32, radix).unwrap());
+        pre_dp /= radix as u64;
+        if pre_dp == 0 {
+            break;
+        }
+    }
+
+    let mut post_dp = abs - ((abs as u64) as f64);
+    let mut post_repr = String::new();
+    while post_repr.len() < DEFAULT_PRECISION && post_dp != 0.0_f64 {
+        post_dp *= radix as f64;
+        let digit = post_dp as u32;
+        post_repr.push(::std::char::from_digit(digit, radix).unwrap());
+        post_dp -= digit as f64;
+    }
+
+    let point = if post_repr.is_empty() {""} else {"."};
+    let marker = if post_dp != 0.0_f64 {INEXACT_MARKER} else {""};
+    format!("{0}0r{1}:{2}{3}{4}{5}", sign, radix, pre_repr, point, post_repr, marker)
+}
+
 impl From<f64> for F64Formatter {
     fn from(val: f64) -> Self {
         F64Formatter(val)
@@ -0,0 +1,301 @@
+use token::{Token, TokenType, NumberType, SymbolicTokenType, split_radix_literal};
+use math_context::{MathContext, OperationType, FunctionType};
+use tree::TreeNode;
+use deparser::{is_binary, get_precedence};
+
+/// Creates the LaTeX representation of the specified expression tree.
+/// Divisions are rendered as fractions (\frac{}{}), powers as superscripts and roots with \sqrt{}.
+/// Like the deparser, parentheses are only inserted where the precedence of the operations requires them.
+pub fn render(tree: & TreeNode<Token>, context: & MathContext) -> String {
+
+    match tree.content.get_type() {
+        TokenType::Operation => {
+            if tree.successors.len() == 1 {
+                render_unary(tree, context)
+            }
+            else if tree.successors.len() == 2 {
+                render_binary(tree, context)
+            }
+            else {
+                tree.content.get_value().to_string()
+            }
+        },
+
+        TokenType::Function => render_function(tree, context),
+
+        TokenType::UserFunction | TokenType::Symbol(SymbolicTokenType::UnknownFunction) => {
+            format!("{0}{1}", render_identifier(tree.content.get_value()), render_args(tree, context))
+        },
+
+        TokenType::Number(number_type) => render_number(tree.content.get_value(), number_type),
+
+        TokenType::Constant if tree.content.get_value() == "pi" => String::from("\\pi"),
+
+        TokenType::Unit => render_unit(tree.content.get_value()),
+
+        TokenType::Punctuation if tree.content.get_value() == "[" => {
+            let rows : Vec<String> = tree.successors.iter().map(|row| {
+                let elements : Vec<String> = row.successors.iter().map(|s| render(s, context)).collect();
+                elements.join(" & ")
+            }).collect();
+            format!("\\begin{{pmatrix}} {0} \\end{{pmatrix}}", rows.join(" \\\\ "))
+        },
+
+        _ => render_identifier(tree.content.get_value())
+    }
+}
+
+/// Creates the LaTeX representation of the definition of the specified user function, e.g. "f\left(x\right) = x^{2}".
+pub fn render_definition(repr: & str, vars: & Vec<String>, tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let vars : Vec<String> = vars.iter().map(|v| render_identifier(v)).collect();
+    format!("{0}{1} = {2}", render_identifier(repr), parenthesize(&vars.join(", ")), render(tree, context))
+}
+
+/// Creates the LaTeX representation of an unary operation.
+fn render_unary(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let operand = tree.successors[0].as_ref();
+    let operand_repr = render(operand, context);
+    // "~" is a space in LaTeX, so the complement is rendered as a negation
+    let op = match context.get_operation_type(tree.content.get_value()) {
+        Some(OperationType::BitNot) => "\\lnot ",
+        _ => tree.content.get_value()
+    };
+
+    if context.is_postfix_operation(tree.content.get_value()) {
+        if operand.content.get_type() == TokenType::Operation && !context.is_postfix_operation(operand.content.get_value()) {
+            format!("{0}{1}", parenthesize(&operand_repr), tree.content)
+        }
+        else {
+            format!("{0}{1}", operand_repr, tree.content)
+        }
+    }
+    else if is_binary(operand) {
+        format!("{0}{1}", op, parenthesize(&operand_repr))
+    }
+    else {
+        format!("{0}{1}", op, operand_repr)
+    }
+}
+
+/// Creates the LaTeX representation of a binary operation.
+fn render_binary(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let lhs = tree.successors[0].as_ref();
+    let rhs = tree.successors[1].as_ref();
+
+    if rhs.content.get_type() == TokenType::Unit && context.get_operation_type(tree.content.get_value()) == Some(OperationType::Mul) {
+        // a number with a unit (e.g. "3 \, \mathrm{km}")
+        return format!("{0} \\, {1}", render(lhs, context), render(rhs, context));
+    }
+
+    match context.get_operation_type(tree.content.get_value()) {
+        // fractions and superscripts group their operands themselves
+        Some(OperationType::Div) => format!("\\frac{{{0}}}{{{1}}}", render(lhs, context), render(rhs, context)),
+        Some(OperationType::IntDiv) => format!("\\left\\lfloor \\frac{{{0}}}{{{1}}} \\right\\rfloor", render(lhs, context), render(rhs, context)),
+        Some(OperationType::Pow) => render_power(lhs, rhs, context),
+        Some(OperationType::Mul) => render_infix(tree, "\\cdot", context),
+        Some(OperationType::Mod) => render_infix(tree, "\\bmod", context),
+        Some(OperationType::LessEqual) => render_infix(tree, "\\leq", context),
+        Some(OperationType::GreaterEqual) => render_infix(tree, "\\geq", context),
+        Some(OperationType::Equal) => render_infix(tree, "=", context),
+        Some(OperationType::NotEqual) => render_infix(tree, "\\neq", context),
+        Some(OperationType::BitAnd) => render_infix(tree, "\\mathbin{\\&}", context),
+        Some(OperationType::BitOr) => render_infix(tree, "\\mathbin{|}", context),
+        Some(OperationType::BitXor) => render_infix(tree, "\\oplus", context),
+        Some(OperationType::ShiftLeft) => render_infix(tree, "\\ll", context),
+        Some(OperationType::ShiftRight) => render_infix(tree, "\\gg", context),
+        _ => render_infix(tree, tree.content.get_value(), context)
+    }
+}
+
+/// Creates the LaTeX representation of a unit expression, whose powers are grouped (e.g. "\mathrm{m/s^{2}}").
+fn render_unit(unit: & str) -> String {
+    let mut repr = String::new();
+    let mut in_power = false;
+    for c in unit.chars() {
+        if in_power && !(c.is_digit(10) || c == '-') {
+            repr.push('}');
+            in_power = false;
+        }
+        if c == '^' {
+            repr.push_str("^{");
+            in_power = true;
+        }
+        else {
+            repr.push(c);
+        }
+    }
+    if in_power {
+        repr.push('}');
+    }
+    format!("\\mathrm{{{0}}}", repr)
+}
+
+/// Creates the LaTeX representation of a binary infix operation with the specified LaTeX operator.
+fn render_infix(tree: & TreeNode<Token>, op: & str, context: & MathContext) -> String {
+    let prec = get_precedence(tree, context);
+    let lhs = tree.successors[0].as_ref();
+    let rhs = tree.successors[1].as_ref();
+
+    // All binary operations except for the assignment are left associative, so the right operand needs parentheses if its
+    // precedence is equal to the precedence of the current operation.
+    let mut lhs_repr = render(lhs, context);
+    if is_binary(lhs) && get_precedence(lhs, context) < prec {
+        lhs_repr = parenthesize(&lhs_repr);
+    }
+    let mut rhs_repr = render(rhs, context);
+    let arithmetic = prec >= context.get_operation_precedence("+").unwrap_or(0);
+    let right_associative = context.is_right_associative(tree.content.get_value());
+    if (is_binary(rhs) && (get_precedence(rhs, context) < prec || (get_precedence(rhs, context) == prec && !right_associative)))
+        || (arithmetic && is_unary(rhs)) {
+        rhs_repr = parenthesize(&rhs_repr);
+    }
+
+    format!("{0} {1} {2}", lhs_repr, op, rhs_repr)
+}
+
+/// Creates the LaTeX representation of the specified power.
+fn render_power(base: & TreeNode<Token>, exponent: & TreeNode<Token>, context: & MathContext) -> String {
+    let mut base_repr = render(base, context);
+    if is_binary(base) || is_unary(base) || is_power_function(base, context) {
+        base_repr = parenthesize(&base_repr);
+    }
+    format!("{0}^{{{1}}}", base_repr, render(exponent, context))
+}
+
+/// Creates the LaTeX representation of a built-in function call.
+fn render_function(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let name = tree.content.get_value();
+    let arg = |i: usize| render(tree.successors[i].as_ref(), context);
+
+    let command = match context.get_function_type(name) {
+        Some(FunctionType::Sqrt) => return format!("\\sqrt{{{0}}}", arg(0)),
+        Some(FunctionType::Root) => return format!("\\sqrt[{1}]{{{0}}}", arg(0), arg(1)),
+        Some(FunctionType::Pow) => return render_power(tree.successors[0].as_ref(), tree.successors[1].as_ref(), context),
+        Some(FunctionType::Exp) => return format!("e^{{{0}}}", arg(0)),
+        Some(FunctionType::Abs) => return format!("\\left| {0} \\right|", arg(0)),
+        Some(FunctionType::Sign) => "\\operatorname{sgn}",
+        Some(FunctionType::Conj) => return format!("\\overline{{{0}}}", arg(0)),
+        Some(FunctionType::Arg) => "\\arg",
+        Some(FunctionType::Floor) => return format!("\\left\\lfloor {0} \\right\\rfloor", arg(0)),
+        Some(FunctionType::Ceil) => return format!("\\left\\lceil {0} \\right\\rceil", arg(0)),
+        Some(FunctionType::Cos) => "\\cos",
+        Some(FunctionType::Sin) => "\\sin",
+        Some(FunctionType::Tan) => "\\tan",
+        Some(FunctionType::Cot) => "\\cot",
+        Some(FunctionType::Cosh) => "\\cosh",
+        Some(FunctionType::Sinh) => "\\sinh",
+        Some(FunctionType::Tanh) => "\\tanh",
+        Some(FunctionType::Coth) => "\\coth",
+        Some(FunctionType::ArcCos) => "\\arccos",
+        Some(FunctionType::ArcSin) => "\\arcsin",
+        Some(FunctionType::ArcTan) => "\\arctan",
+        Some(FunctionType::ArcCot) => "\\operatorname{arccot}",
+        Some(FunctionType::ArcCosh) => "\\operatorname{arcosh}",
+        Some(FunctionType::ArcSinh) => "\\operatorname{arsinh}",
+        Some(FunctionType::ArcTanh) => "\\operatorname{artanh}",
+        Some(FunctionType::ArcCoth) => "\\operatorname{arcoth}",
+        Some(FunctionType::Ln) => "\\ln",
+        Some(FunctionType::Log) => return format!("\\log_{{{0}}}{1}", arg(1), parenthesize(&arg(0))),
+        Some(FunctionType::Log2) => "\\log_{2}",
+        Some(FunctionType::Log10) => "\\log_{10}",
+        Some(FunctionType::Gamma) => "\\Gamma",
+        Some(FunctionType::Det) => "\\det",
+        Some(FunctionType::Inv) => return format!("{0}^{{-1}}", parenthesize(&arg(0))),
+        Some(FunctionType::Transpose) => return format!("{0}^{{T}}", parenthesize(&arg(0))),
+        Some(FunctionType::Sum) if context.is_series(tree) => return render_series("\\sum", tree, context),
+        Some(FunctionType::Min) => "\\min",
+        Some(FunctionType::Max) => "\\max",
+        Some(FunctionType::Prod) => return render_series("\\prod", tree, context),
+        Some(FunctionType::Integrate) => {
+            let integrand = render(& apply_to_variable(tree.successors[0].as_ref(), context), context);
+            return format!("\\int_{{{0}}}^{{{1}}} {2} \\, dx", arg(1), arg(2), integrand);
+        },
+        Some(FunctionType::If) => {
+            return format!("\\begin{{cases}} {1} & \\text{{if }} {0} \\\\ {2} & \\text{{otherwise}} \\end{{cases}}", arg(0), arg(1), arg(2));
+        },
+        Some(FunctionType::Im) => "\\operatorname{Im}",
+        Some(FunctionType::Re) => "\\operatorname{Re}",
+        _ => return format!("\\operatorname{{{0}}}{1}", escape(name), render_args(tree, context))
+    };
+
+    format!("{0}{1}", command, render_args(tree, context))
+}
+
+/// Creates the LaTeX representation of a sum or a product (e.g. "\sum_{k=1}^{n} k").
+fn render_series(command: & str, tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let arg = |i: usize| render(tree.successors[i].as_ref(), context);
+    let expression = tree.successors[0].as_ref();
+    let expression = if is_binary(expression) && get_precedence(expression, context) < context.get_operation_precedence("*").unwrap_or(0) {
+        parenthesize(&arg(0))
+    }
+    else {
+        arg(0)
+    };
+    format!("{0}_{{{1}={2}}}^{{{3}}} {4}", command, arg(1), arg(2), arg(3), expression)
+}
+
+/// Creates the call of the specified function name with the variable "x" (e.g. the integrand of an integral).
+fn apply_to_variable(f: & TreeNode<Token>, context: & MathContext) -> TreeNode<Token> {
+    let name = f.content.get_value().to_string();
+    let f_type = if context.is_built_in_function(&name) { TokenType::Function } else { TokenType::UserFunction };
+    let mut call = TreeNode::new(Token::new(f_type, name, f.content.get_end_pos()));
+    call.successors.push(Box::new(TreeNode::new(Token::new(TokenType::Symbol(SymbolicTokenType::UnknownConstant), String::from("x"), 0))));
+    call
+}
+
+/// Creates the LaTeX representation of the argument list of a function call.
+fn render_args(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let args : Vec<String> = tree.successors.iter().map(|s| render(s, context)).collect();
+    parenthesize(&args.join(", "))
+}
+
+/// Creates the LaTeX representation of a number. The exponent of the scientific notation is rendered as a power of ten
+/// and numbers of an arbitrary radix are rendered with the radix as subscript (e.g. "0r36:z1" as "\mathrm{z1}_{36}").
+fn render_number(value: & str, number_type: NumberType) -> String {
+    let mut repr = match split_radix_literal(value) {
+        Some((radix, digits)) if value.starts_with("0r") => format!("\\mathrm{{{0}}}_{{{1}}}", digits, radix),
+        Some(_) => value.to_string(),
+        None => match value.find('E') {
+            Some(idx) => format!("{0} \\cdot 10^{{{1}}}", &value[..idx], &value[idx + 1..]),
+            None => value.to_string()
+        }
+    };
+    if number_type == NumberType::Complex {
+        repr.push('i');
+    }
+    repr
+}
+
+/// Creates the LaTeX representation of the name of a constant, function or function argument.
+/// Names with more than one character are set upright.
+fn render_identifier(name: & str) -> String {
+    if name.chars().count() == 1 {
+        escape(name)
+    }
+    else {
+        format!("\\mathrm{{{0}}}", escape(name))
+    }
+}
+
+/// Escapes the characters of the specified name that have a special meaning in LaTeX.
+fn escape(name: & str) -> String {
+    name.replace("_", "\\_")
+}
+
+/// Puts the specified LaTeX string into scalable parentheses.
+fn parenthesize(s: & str) -> String {
+    format!("\\left({0}\\right)", s)
+}
+
+/// Checks whether the specified tree node is an unary operation.
+fn is_unary(tree: & TreeNode<Token>) -> bool {
+    tree.content.get_type() == TokenType::Operation && tree.successors.len() == 1
+}
+
+/// Checks whether the specified tree node is a call of a built-in function that is rendered as a superscript.
+fn is_power_function(tree: & TreeNode<Token>, context: & MathContext) -> bool {
+    match context.get_function_type(tree.content.get_value()) {
+        Some(FunctionType::Pow) | Some(FunctionType::Exp) => tree.content.get_type() == TokenType::Function,
+        _ => false
+    }
+}
@@ -7,27 +7,93 @@ extern crate serde_json;
 extern crate serde;
 extern crate num;
 
+pub mod prelude;
 pub mod math_context;
 pub mod math_result;
+pub mod matrix;
+pub mod unit;
+
+// The tokens and the expression trees are internal types, which are public for the integration with the user interface
+// and may change without notice. Use the prelude instead.
+#[doc(hidden)]
 pub mod token;
+#[doc(hidden)]
 pub mod tree;
 
 mod result_error;
 mod parser;
 mod evaluator;
 mod error_templates;
+mod deparser;
+mod latex;
+mod sympy;
+mod differentiator;
+mod simplifier;
+mod char_set;
 mod f64formatter;
+mod random;
 
 #[cfg(test)]
 mod test;
 
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use parser::{Parser, ParseError};
-use token::Token;
-use math_context::MathContext;
+use parser::tokenizer::Tokenizer;
+use token::{Token, TokenType, SymbolicTokenType, NumberType};
+use math_context::{MathContext, OperationType, FunctionType};
 use tree::TreeNode;
 use evaluator::{Evaluator, EvaluationError};
 use math_result::MathResult;
 use result_error::ResultError;
+use error_templates::ExpectedErrorTemplate;
+use random::Random;
+
+pub use error_templates::create_location_string;
+
+/// The number of sample points of an identity check (the first half is real, the second half is complex).
+pub const IDENTITY_SAMPLES : usize = 100;
+
+/// The relative tolerance of the comparison of both sides of an identity.
+const IDENTITY_TOLERANCE : f64 = 1e-9;
+
+/// The maximum number of counterexamples of an identity check that are kept.
+const MAX_COUNTEREXAMPLES : usize = 5;
+
+/// The seed of the pseudo random sample points of an identity check (fixed, so that the checks are reproducible).
+const IDENTITY_SEED : u64 = 0x7465_726D_635F_6964;
+
+/// The real parts and the imaginary parts of the sample points are taken from the interval [-SAMPLE_RANGE, SAMPLE_RANGE).
+const SAMPLE_RANGE : f64 = 2.0;
+
+/// The result of the numerical check of an identity.
+pub struct IdentityCheck {
+    /// The variables of the identity (the unknown constants in order of appearance).
+    pub variables: Vec<String>,
+    /// The number of sample points at which both sides could be evaluated.
+    pub samples: usize,
+    /// The number of sample points at which the identity does not hold.
+    pub failures: usize,
+    /// The first sample points at which the identity does not hold (values of the variables, left side and right side).
+    pub counterexamples: Vec<(Vec<MathResult>, MathResult, MathResult)>
+}
+
+/// The values of an expression in a single variable at equidistant sample points (e.g. for plotting).
+pub struct FunctionSamples {
+    /// The variable of the expression (the unknown constant) or None if the expression is constant.
+    pub variable: Option<String>,
+    /// The sample points and the values of the expression. A value is None if the expression can not be evaluated
+    /// or is not a finite real number at the sample point (e.g. "ln(x)" for x <= 0).
+    pub points: Vec<(f64, Option<f64>)>
+}
+
+/// The durations of the parsing and the evaluation of an input (e.g. for benchmarks).
+pub struct EvaluationStats {
+    /// The duration of the parsing.
+    pub parse_time: Duration,
+    /// The duration of the evaluation (zero if the input could not be parsed).
+    pub eval_time: Duration
+}
 
 /// Creates an expression tree from the specified input string.
 fn parse(s: & str, context: & MathContext) -> Result<TreeNode<Token>, ParseError> {
@@ -60,8 +126,616 @@ fn evaluate(tree: & TreeNode<Token>, context: & mut MathContext, s: & str) -> Re
 /// }
 /// ```
 pub fn get_result(s: & str, context: & mut MathContext) -> Result<Option<MathResult>, ResultError> {
+    let input = insert_ans(s, context);
+    let s = input.as_str();
     match parse(s.clone(), context) {
         Ok(ref x) => Ok(evaluate(x, context, s)?),
         Err(err) => Err(ResultError::from(err))
     }
 }
+
+/// Computes the result of the specified input string like get_result and measures the durations of the parsing and
+/// the evaluation.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::math_result::MathResult;
+/// use termc_model::get_result_with_stats;
+///
+/// let mut context = MathContext::new();
+/// let (result, stats) = get_result_with_stats("2^10", &mut context);
+/// assert!(result.ok().unwrap() == Some(MathResult::from(1024.0)));
+/// assert!(stats.parse_time.as_secs() == 0);
+/// ```
+pub fn get_result_with_stats(s: & str, context: & mut MathContext) -> (Result<Option<MathResult>, ResultError>, EvaluationStats) {
+    let input = insert_ans(s, context);
+    let s = input.as_str();
+    let start = Instant::now();
+    let tree = parse(s, context);
+    let parse_time = start.elapsed();
+
+    match tree {
+        Ok(ref x) => {
+            let start = Instant::now();
+            let result = evaluate(x, context, s).map_err(ResultError::from);
+            (result, EvaluationStats {parse_time: parse_time, eval_time: start.elapsed()})
+        },
+        Err(err) => (Err(ResultError::from(err)), EvaluationStats {parse_time: parse_time, eval_time: Duration::new(0, 0)})
+    }
+}
+
+/// Inserts the previous result "ans" before the specified input if the auto-ans mode of the context is enabled and the input
+/// starts with a binary operation (e.g. "* 2" is completed to "ans * 2"). As "+" and "-" are unary operations as well, they
+/// only continue the previous result if they are followed by whitespace (e.g. "- 2", whereas "-2" stays negative).
+/// The input is returned unchanged if there is no previous result or if the completed input can not be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::{get_result, insert_ans};
+///
+/// let mut context = MathContext::new();
+/// context.set_auto_ans(true);
+/// get_result("5", &mut context).unwrap();
+/// assert!(insert_ans("+ 5", &context) == "ans + 5");
+/// assert!(insert_ans("^2", &context) == "ans ^2");
+/// assert!(insert_ans("-5", &context) == "-5");
+/// assert!(insert_ans("= 2", &context) == "= 2");
+/// ```
+pub fn insert_ans(s: & str, context: & MathContext) -> String {
+    if !context.get_auto_ans() || context.get_constant_value("ans").is_none() {
+        return s.to_string();
+    }
+
+    let input = s.trim_start();
+    let continues_result = match Tokenizer::new(context, input).peek() {
+        Some(Ok(ref t)) if t.get_type() == TokenType::Operation => match context.get_operation_type(t.get_value()) {
+            Some(OperationType::Assign) | Some(OperationType::BitNot) | None => false,
+            Some(_) if context.is_unary_operation(t.get_value()) =>
+                input.chars().nth(t.get_end_pos() + 1).map_or(false, char::is_whitespace),
+            Some(_) => true
+        },
+        _ => false
+    };
+
+    let completed = format!("ans {0}", input);
+    if continues_result && parse(&completed, context).is_ok() {
+        completed
+    }
+    else {
+        s.to_string()
+    }
+}
+
+/// Splits the specified input string into its statements, which are separated by ";" outside of parentheses and brackets
+/// (e.g. "a = 2; m = [1; 2]" is split into "a = 2" and "m = [1; 2]"). The statements are trimmed and empty statements
+/// are skipped, so that the input may end with ";".
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::split_statements;
+///
+/// assert!(split_statements("a = 2; b = [1, 2; 3, 4];") == vec!["a = 2", "b = [1, 2; 3, 4]"]);
+/// assert!(split_statements("1 + 2") == vec!["1 + 2"]);
+/// ```
+pub fn split_statements(s: & str) -> Vec<& str> {
+    let mut statements = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in s.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ';' if depth == 0 => {
+                statements.push(s[start..idx].trim());
+                start = idx + 1;
+            },
+            _ => ()
+        }
+    }
+    statements.push(s[start..].trim());
+    statements.into_iter().filter(|statement| !statement.is_empty()).collect()
+}
+
+/// Computes the results of the statements of the specified input string, which are separated by ";"
+/// (e.g. "a = 2; b = 3; a*b"). The statements are evaluated in order, so that each statement may use the definitions of
+/// the previous statements. The evaluation stops at the first error, but the definitions of the previous statements are kept.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::math_result::MathResult;
+/// use termc_model::get_statement_results;
+///
+/// let mut context = MathContext::new();
+/// let results = get_statement_results("a = 2; b = 3; a*b", &mut context).unwrap();
+/// assert!(results == vec![None, None, Some(MathResult::from(6.0))]);
+/// ```
+pub fn get_statement_results(s: & str, context: & mut MathContext) -> Result<Vec<Option<MathResult>>, ResultError> {
+    let mut results = Vec::new();
+    for statement in split_statements(s) {
+        results.push(get_result(statement, context)?);
+    }
+    Ok(results)
+}
+
+/// Normalizes the specified input string, so that inputs that only differ in whitespace or redundant parentheses have the
+/// same normalized form (e.g. "1+1" and "(1) + 1" are both normalized to "1 + 1"). The statements of the input are
+/// normalized separately. Statements that can not be parsed (e.g. commands) are only trimmed.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::normalize_input;
+///
+/// let context = MathContext::new();
+/// assert!(normalize_input(" f(x)=x^2+1 ;f( 2 ) ", &context) == "f(x) = x^2 + 1; f(2)");
+/// assert!(normalize_input("format  hex", &context) == "format  hex");
+/// ```
+pub fn normalize_input(s: & str, context: & MathContext) -> String {
+    let statements : Vec<String> = split_statements(s).iter().map(|statement| match parse(statement, context) {
+        Ok(tree) => deparser::deparse(&tree, context),
+        Err(_) => statement.to_string()
+    }).collect();
+    statements.join("; ")
+}
+
+/// Creates the LaTeX representation of the specified input string.
+/// If the input string is the name of a user defined function, the definition of that function is rendered.
+/// Otherwise, the input string is parsed (but not evaluated) and the resulting expression is rendered.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::{get_result, get_latex};
+///
+/// fn main() {
+///     let mut context = MathContext::new();
+///     get_result("f(x) = sqrt(x)/2", &mut context).unwrap();
+///     assert!(get_latex("f", &context).unwrap() == "f\\left(x\\right) = \\frac{\\sqrt{x}}{2}");
+///     assert!(get_latex("2^(1+3)", &context).unwrap() == "2^{1 + 3}");
+/// }
+/// ```
+pub fn get_latex(s: & str, context: & MathContext) -> Result<String, ResultError> {
+    match context.get_user_function_latex(s.trim()) {
+        Some(latex) => Ok(latex),
+        None => Ok(latex::render(&parse(s, context)?, context))
+    }
+}
+
+/// Creates the SymPy-compatible Python representation of the specified input string.
+/// If the input string is the name of a user defined function, the definition of that function is rendered as Lambda.
+/// Otherwise, the input string is parsed (but not evaluated) and the resulting expression is rendered.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::{get_result, get_sympy};
+///
+/// fn main() {
+///     let mut context = MathContext::new();
+///     get_result("f(x) = ln(x)^2 % 3", &mut context).unwrap();
+///     assert!(get_sympy("f", &context).unwrap() == "f = Lambda(x, Mod(log(x)**2, 3))");
+///     assert!(get_sympy("e^(2i*pi)", &context).unwrap() == "E**(2*I*pi)");
+/// }
+/// ```
+pub fn get_sympy(s: & str, context: & MathContext) -> Result<String, ResultError> {
+    match context.get_user_function_sympy(s.trim()) {
+        Some(sympy) => Ok(sympy),
+        None => Ok(sympy::render(&parse(s, context)?, context))
+    }
+}
+
+/// Converts simple SymPy-style input into termc syntax (best effort), e.g. "2**E" becomes "2^e".
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::from_sympy;
+///
+/// assert!(from_sympy("log(x)**2 + 3E2*I") == "ln(x)^2 + 3E2*i");
+/// ```
+pub fn from_sympy(s: & str) -> String {
+    sympy::import(s)
+}
+
+/// Computes the derivative of the specified input string with respect to the specified variable.
+/// If the input string is the name of a user defined function, the definition of that function is differentiated.
+/// Otherwise, the input string is parsed (but not evaluated) and the resulting expression is differentiated.
+/// The simplified derivative is returned as expression string.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::{get_result, get_derivative};
+///
+/// fn main() {
+///     let mut context = MathContext::new();
+///     assert!(get_derivative("sin(x)*x^2", "x", &context).unwrap() == "cos(x)*x^2 + 2*sin(x)*x");
+///     get_result("f(x) = x^3", &mut context).unwrap();
+///     assert!(get_derivative("f", "x", &context).unwrap() == "3*x^2");
+/// }
+/// ```
+pub fn get_derivative(s: & str, var: & str, context: & MathContext) -> Result<String, ResultError> {
+    let var = var.trim();
+    if var.is_empty() || !var.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(ResultError::from(EvaluationError::from(ExpectedErrorTemplate::new(var, "variable name",
+            Some(format!("\"{0}\"", var)), 0))));
+    }
+
+    let derivative = match context.get_user_function_tree(s.trim()) {
+        Some(t) => differentiator::differentiate(t, var, context, s)?,
+        None => differentiator::differentiate(&parse(s, context)?, var, context, s)?
+    };
+    Ok(deparser::deparse(&derivative, context))
+}
+
+/// Simplifies the specified expression by algebraic rewrites without evaluating its symbols and returns the canonical
+/// representation of the simplified expression: operations on numbers are folded, neutral and absorbing elements are
+/// removed and like terms are combined.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::get_simplified;
+///
+/// fn main() {
+///     let context = MathContext::new();
+///     assert!(get_simplified("2*x + 0 + y*1 - x + 3*4", &context).unwrap() == "x + y + 12");
+///     assert!(get_simplified("(a - a)*sin(b) + b^1", &context).unwrap() == "b");
+/// }
+/// ```
+pub fn get_simplified(s: & str, context: & MathContext) -> Result<String, ResultError> {
+    let tree = parse(s, context)?;
+    Ok(deparser::deparse(&simplifier::simplify(&tree, context), context))
+}
+
+/// Replaces the user constants and the previous results (e.g. "ans") in the definition of the specified user function by
+/// their current values, so that later changes of the constants do not change the function. Parameters, bound variables
+/// (e.g. "k" of "sum(k^2, k, 1, n)") and temporary definitions that shadow a constant are kept, as well as the constants of
+/// other user functions that are called by the function. Returns the names of the replaced constants.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::{get_result, freeze_user_function};
+///
+/// fn main() {
+///     let mut context = MathContext::new();
+///     get_result("a = 2", &mut context).unwrap();
+///     get_result("f(x) = x + a", &mut context).unwrap();
+///     assert!(freeze_user_function("f", &mut context).unwrap() == vec!["a"]);
+///     get_result("a = 5", &mut context).unwrap();
+///     assert!(get_result("f(1)", &mut context).unwrap().unwrap().value.re == 3.0);
+/// }
+/// ```
+pub fn freeze_user_function(name: & str, context: & mut MathContext) -> Result<Vec<String>, ResultError> {
+    let (tree, vars) = match context.get_user_function_body(name) {
+        Some(x) => x,
+        None => return Err(ResultError::from(EvaluationError::from(ExpectedErrorTemplate::new(name, "user defined function",
+            Some(format!("\"{0}\"", name)), 0))))
+    };
+
+    let mut tree = tree.as_ref().clone();
+    let mut bound = vars.clone();
+    let mut frozen = Vec::new();
+    freeze_constants(& mut tree, & mut bound, context, & mut frozen)?;
+    if !frozen.is_empty() {
+        let input = format!("{0}({1}) = {2}", name, vars.join(", "), deparser::deparse(&tree, context));
+        context.add_user_function(name, tree, vars, input);
+    }
+    frozen.sort();
+    frozen.dedup();
+    Ok(frozen)
+}
+
+/// Replaces the user constants and the previous results of the specified tree that are not bound (e.g. by a parameter)
+/// by the expression trees of their current values and adds their names to the frozen names.
+fn freeze_constants(tree: & mut TreeNode<Token>, bound: & mut Vec<String>, context: & MathContext, frozen: & mut Vec<String>)
+                    -> Result<(), ResultError> {
+    let name = tree.content.get_value().to_string();
+    let is_variable = match tree.content.get_type() {
+        TokenType::UserConstant => true,
+        TokenType::Constant => MathContext::parse_result_name(&name).is_some(),
+        _ => false
+    };
+    if is_variable {
+        if bound.contains(&name) {
+            return Ok(());
+        }
+        if let Some(value) = context.get_constant_value(&name) {
+            // the value is inserted as the expression tree of its representation (e.g. "3+4i" or "[1, 2]")
+            let repr = format!("{0}", value);
+            let value_tree = parse(&repr, context)?;
+            if has_symbols(&value_tree) {
+                return Err(ResultError::from(EvaluationError::from(format!(
+                    "Error: The value {0} of the constant \"{1}\" can not be inserted into a function.", repr, name))));
+            }
+            *tree = value_tree;
+            frozen.push(name);
+        }
+        return Ok(());
+    }
+
+    let function_type = match tree.content.get_type() {
+        TokenType::Function => context.get_function_type(&name),
+        _ => None
+    };
+    let has_bound_variable = match function_type {
+        Some(FunctionType::Solve) => tree.successors.len() == 3,
+        Some(FunctionType::Sum) | Some(FunctionType::Prod) => context.is_series(tree),
+        _ => false
+    };
+
+    if has_bound_variable {
+        // the bound variable of a series or a root finding is only bound in the first argument
+        bound.push(tree.successors[1].content.get_value().to_string());
+        let result = freeze_constants(& mut tree.successors[0], bound, context, frozen);
+        bound.pop();
+        result?;
+        for s in tree.successors.iter_mut().skip(2) {
+            freeze_constants(s, bound, context, frozen)?;
+        }
+    }
+    else if function_type == Some(FunctionType::Old) {
+        // the first argument of "old" is the name of a constant and not its value
+        freeze_constants(& mut tree.successors[1], bound, context, frozen)?;
+    }
+    else if tree.content.get_type() == TokenType::Keyword {
+        // the temporary definitions of a scoped expression are bound in the later definitions and in the body
+        let n = bound.len();
+        let last = tree.successors.len() - 1;
+        let mut result = Ok(());
+        for (i, s) in tree.successors.iter_mut().enumerate() {
+            result = if i < last && s.successors.len() == 2 {
+                let definition = freeze_constants(& mut s.successors[1], bound, context, frozen);
+                bound.push(s.successors[0].content.get_value().to_string());
+                definition
+            }
+            else {
+                freeze_constants(s, bound, context, frozen)
+            };
+            if result.is_err() {
+                break;
+            }
+        }
+        bound.truncate(n);
+        result?;
+    }
+    else {
+        for s in tree.successors.iter_mut() {
+            freeze_constants(s, bound, context, frozen)?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether the specified tree contains symbols (unknown constants or functions).
+fn has_symbols(tree: & TreeNode<Token>) -> bool {
+    match tree.content.get_type() {
+        TokenType::Symbol(_) => true,
+        _ => tree.successors.iter().any(|s| has_symbols(s))
+    }
+}
+
+/// Repeatedly evaluates the assignment "name = expr", starting with "name = start", until the distance between two
+/// successive values is at most the specified tolerance or the maximum number of iterations is reached.
+/// Returns the last value, the number of performed iterations and whether the iteration converged
+/// (always false if no tolerance is specified).
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::iterate;
+///
+/// fn main() {
+///     let mut context = MathContext::new();
+///     let (value, n, converged) = iterate("x", "x/2 + 1/x", "1", 50, Some(1e-12), &mut context).unwrap();
+///     assert!((value.value.re - 2.0f64.sqrt()).abs() < 1e-12 && n < 10 && converged);
+///     let (value, n, converged) = iterate("y", "y + 1", "0", 5, None, &mut context).unwrap();
+///     assert!(value.value.re == 5.0 && n == 5 && !converged);
+/// }
+/// ```
+pub fn iterate(name: & str, expr: & str, start: & str, max_iterations: usize, tolerance: Option<f64>,
+               context: & mut MathContext) -> Result<(MathResult, usize, bool), ResultError> {
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(ResultError::from(EvaluationError::from(ExpectedErrorTemplate::new(name, "constant name",
+            Some(format!("\"{0}\"", name)), 0))));
+    }
+
+    get_result(&format!("{0} = {1}", name, start), context)?;
+    let assignment = format!("{0} = {1}", name, expr);
+    // the start value is defined now, so the assignment is parsed only once
+    let tree = parse(&assignment, context)?;
+
+    let mut previous = context.get_constant_value(name).unwrap();
+    for i in 0..max_iterations {
+        evaluate(&tree, context, &assignment)?;
+        let current = context.get_constant_value(name).unwrap();
+        if let Some(tol) = tolerance {
+            if distance(&previous, &current) <= tol {
+                return Ok((current, i + 1, true));
+            }
+        }
+        previous = current;
+    }
+    Ok((previous, max_iterations, false))
+}
+
+/// Computes the distance between two successive values of an iteration.
+/// For matrices of the same dimensions, the maximum distance of the elements is taken.
+fn distance(a: & MathResult, b: & MathResult) -> f64 {
+    match (&a.matrix, &b.matrix) {
+        (&Some(ref m), &Some(ref n)) if m.rows == n.rows && m.cols == n.cols => {
+            m.elements.iter().zip(n.elements.iter()).map(|(x, y)| distance(x, y)).fold(0.0, f64::max)
+        },
+        (&None, &None) => (a.value - b.value).norm(),
+        _ => ::std::f64::INFINITY
+    }
+}
+
+/// Checks numerically whether the specified equation "lhs == rhs" holds for all values of its variables
+/// (the unknown constants of the equation). Both sides are evaluated at IDENTITY_SAMPLES pseudo random sample points,
+/// half of them real and half of them complex, and compared with a relative tolerance.
+/// Sample points at which a side can not be evaluated or is not finite are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::check_identity;
+///
+/// fn main() {
+///     let mut context = MathContext::new();
+///     let check = check_identity("sin(x)^2 + cos(x)^2 == 1", &mut context).unwrap();
+///     assert!(check.variables == vec!["x"] && check.samples == 100 && check.failures == 0);
+///     let check = check_identity("sqrt(x^2) == x", &mut context).unwrap();
+///     assert!(check.failures > 0 && check.counterexamples.len() == 5);
+/// }
+/// ```
+pub fn check_identity(s: & str, context: & mut MathContext) -> Result<IdentityCheck, ResultError> {
+    let tree = parse(s, context)?;
+    let is_equation = tree.content.get_type() == TokenType::Operation && tree.successors.len() == 2
+        && context.get_operation_type(tree.content.get_value()) == Some(OperationType::Equal);
+    if !is_equation {
+        return Err(ResultError::from(EvaluationError::from(ExpectedErrorTemplate::new(s, "equation (e.g. \"lhs == rhs\")",
+            Some(format!("expression \"{0}\"", tree.content)), tree.content.get_end_pos()))));
+    }
+
+    let mut variables = Vec::new();
+    collect_variables(&tree, context, &mut variables);
+    // without variables, a single evaluation suffices
+    let n_samples = if variables.is_empty() { 1 } else { IDENTITY_SAMPLES };
+
+    let mut check = IdentityCheck {variables: variables, samples: 0, failures: 0, counterexamples: Vec::new()};
+    let mut first_error = None;
+    let mut random = Random::new(IDENTITY_SEED);
+    let mut e = Evaluator::new(context);
+    for i in 0..n_samples {
+        let point : Vec<MathResult> = check.variables.iter().map(|_| {
+            let re = random.uniform(-SAMPLE_RANGE, SAMPLE_RANGE);
+            if 2 * i < n_samples { MathResult::from(re) } else { MathResult::from((re, random.uniform(-SAMPLE_RANGE, SAMPLE_RANGE))) }
+        }).collect();
+        let values : HashMap<String, MathResult> = check.variables.iter().cloned().zip(point.iter().cloned()).collect();
+
+        let sides = e.evaluate_with(tree.successors[0].as_ref(), values.clone(), s)
+            .and_then(|lhs| e.evaluate_with(tree.successors[1].as_ref(), values, s).map(|rhs| (lhs, rhs)));
+        let (lhs, rhs) = match sides {
+            Ok(x) => x,
+            Err(err) => {
+                if first_error.is_none() {
+                    first_error = Some(err);
+                }
+                continue;
+            }
+        };
+        let (lhs_mag, rhs_mag) = (magnitude(&lhs), magnitude(&rhs));
+        if !lhs_mag.is_finite() || !rhs_mag.is_finite() {
+            continue;
+        }
+
+        check.samples += 1;
+        if distance(&lhs, &rhs) > IDENTITY_TOLERANCE * lhs_mag.max(rhs_mag).max(1.0) {
+            check.failures += 1;
+            if check.counterexamples.len() < MAX_COUNTEREXAMPLES {
+                check.counterexamples.push((point, lhs, rhs));
+            }
+        }
+    }
+
+    match first_error {
+        Some(err) if check.samples == 0 => Err(ResultError::from(err)),
+        _ => Ok(check)
+    }
+}
+
+/// Collects the names of the unknown constants of the specified tree in order of appearance.
+/// Names of functions (e.g. the function argument of "integrate") are no variables.
+fn collect_variables(tree: & TreeNode<Token>, context: & MathContext, variables: & mut Vec<String>) {
+    let name = tree.content.get_value();
+    if tree.content.get_type() == TokenType::Symbol(SymbolicTokenType::UnknownConstant)
+        && context.get_function_type(name).is_none() && !variables.iter().any(|v| v == name) {
+        variables.push(name.to_string());
+    }
+    for t in tree.successors.iter() {
+        collect_variables(t, context, variables);
+    }
+}
+
+/// Computes the magnitude of a value (for matrices, the sum of the moduli of the elements).
+fn magnitude(x: & MathResult) -> f64 {
+    match x.matrix {
+        Some(ref m) => m.elements.iter().map(magnitude).sum(),
+        None => x.value.norm()
+    }
+}
+
+/// Evaluates the specified expression in a single variable (its unknown constant) at the specified number of equidistant
+/// sample points from the lower to the upper bound (both included). The bounds are expressions that must evaluate to
+/// finite real numbers (e.g. "-pi"). Sample points at which the expression can not be evaluated are kept without a value.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::math_context::MathContext;
+/// use termc_model::sample_function;
+///
+/// fn main() {
+///     let mut context = MathContext::new();
+///     let samples = sample_function("x^2", "-1", "1", 5, &mut context).unwrap();
+///     assert!(samples.variable == Some(String::from("x")));
+///     assert!(samples.points[0] == (-1.0, Some(1.0)) && samples.points[2] == (0.0, Some(0.0)));
+///     let samples = sample_function("ln(x)", "-1", "1", 3, &mut context).unwrap();
+///     assert!(samples.points[0].1.is_none() && samples.points[2].1 == Some(0.0));
+/// }
+/// ```
+pub fn sample_function(s: & str, from: & str, to: & str, samples: usize, context: & mut MathContext) -> Result<FunctionSamples, ResultError> {
+    let tree = parse(s, context)?;
+    let mut variables = Vec::new();
+    collect_variables(&tree, context, &mut variables);
+    if variables.len() > 1 {
+        return Err(ResultError::from(EvaluationError::from(ExpectedErrorTemplate::new(s, "expression in a single variable",
+            Some(format!("variables \"{0}\"", variables.join("\", \""))), tree.content.get_end_pos()))));
+    }
+    let a = evaluate_bound(from, context)?;
+    let b = evaluate_bound(to, context)?;
+
+    let variable = variables.pop();
+    let n = samples.max(2);
+    let mut e = Evaluator::new(context);
+    let points = (0..n).map(|i| {
+        let x = a + (b - a) * i as f64 / (n - 1) as f64;
+        let values : HashMap<String, MathResult> = variable.iter().map(|v| (v.clone(), MathResult::from(x))).collect();
+        let y = match e.evaluate_with(&tree, values, s) {
+            Ok(ref y) if y.result_type == NumberType::Real && !y.is_matrix() && y.value.re.is_finite() => Some(y.value.re),
+            _ => None
+        };
+        (x, y)
+    }).collect();
+    Ok(FunctionSamples {variable: variable, points: points})
+}
+
+/// Evaluates the specified bound of a sampling interval, which must be a finite real number.
+/// Unlike get_result, the evaluation does not change the previous results.
+fn evaluate_bound(s: & str, context: & mut MathContext) -> Result<f64, ResultError> {
+    let tree = parse(s, context)?;
+    let x = Evaluator::new(context).evaluate_with(&tree, HashMap::new(), s)?;
+    if x.result_type != NumberType::Real || x.is_matrix() || !x.value.re.is_finite() {
+        return Err(ResultError::from(EvaluationError::from(ExpectedErrorTemplate::new(s, "finite real bound",
+            Some(format!("{0}", x)), tree.content.get_end_pos()))));
+    }
+    Ok(x.value.re)
+}
@@ -1,10 +1,20 @@
 use std::f64;
-use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::collections::{HashMap, HashSet, VecDeque};
 use num::complex::Complex;
+use num::{BigInt, Zero, pow};
+use num::traits::ToPrimitive;
 use token::{Token, TokenType, SymbolicTokenType};
 use token::NumberType;
-use math_result::MathResult;
+use math_result::{MathResult, BigRational, Matrix};
 use tree::TreeNode;
+use deparser;
+use latex;
+use sympy;
+use char_set::CharSet;
+use error_templates::create_location_string;
 
 /// Defines the types of supported operations.
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
@@ -13,9 +23,23 @@ pub enum OperationType {
     Sub,
     Mul,
     Div,
+    IntDiv,
     Pow,
     Mod,
-    Assign
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    Assign,
+    Factorial,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    BitNot
 }
 
 /// Defines the types of supported built-in functions.
@@ -32,6 +56,10 @@ pub enum FunctionType {
     Coth,
     Sqrt,
     Ln,
+    Log,
+    Log2,
+    Log10,
+    Gamma,
     Pow,
     Root,
     ArcCos,
@@ -44,9 +72,223 @@ pub enum FunctionType {
     ArcCoth,
     Im,
     Re,
+    Abs,
+    Sign,
+    Conj,
+    Arg,
+    Polar,
+    Floor,
+    Ceil,
+    Trunc,
+    Frac,
+    Round,
+    DivMod,
+    Gcd,
+    Lcm,
+    IsPrime,
+    Factor,
+    Ncr,
+    Npr,
+    Binomial,
+    Det,
+    Transpose,
+    Inv,
+    Old,
+    Out,
+    Sum,
+    Prod,
+    Min,
+    Max,
+    Mean,
+    Median,
+    StdDev,
+    Var,
+    Integrate,
+    Solve,
+    If,
+    Convert,
     UserFunction
 }
 
+impl FunctionType {
+
+    /// Returns the category of the function type (e.g. "trigonometric" for the sine).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::FunctionType;
+    ///
+    /// assert!(FunctionType::Tan.get_category() == "trigonometric");
+    /// assert!(FunctionType::ArcSinh.get_category() == "inverse hyperbolic");
+    /// ```
+    pub fn get_category(& self) -> & 'static str {
+        match *self {
+            FunctionType::Cos | FunctionType::Sin | FunctionType::Tan | FunctionType::Cot => "trigonometric",
+            FunctionType::ArcCos | FunctionType::ArcSin | FunctionType::ArcTan | FunctionType::ArcCot => "inverse trigonometric",
+            FunctionType::Cosh | FunctionType::Sinh | FunctionType::Tanh | FunctionType::Coth => "hyperbolic",
+            FunctionType::ArcCosh | FunctionType::ArcSinh | FunctionType::ArcTanh | FunctionType::ArcCoth => "inverse hyperbolic",
+            FunctionType::Exp | FunctionType::Ln | FunctionType::Log | FunctionType::Log2 | FunctionType::Log10 => "exponential and logarithmic",
+            FunctionType::Sqrt | FunctionType::Pow | FunctionType::Root => "power and root",
+            FunctionType::Gamma => "special",
+            FunctionType::Gcd | FunctionType::Lcm | FunctionType::IsPrime | FunctionType::Factor => "number theory",
+            FunctionType::Ncr | FunctionType::Npr | FunctionType::Binomial => "combinatorics",
+            FunctionType::Im | FunctionType::Re | FunctionType::Abs | FunctionType::Sign | FunctionType::Conj | FunctionType::Arg
+            | FunctionType::Polar => "complex number",
+            FunctionType::Floor | FunctionType::Ceil | FunctionType::Trunc | FunctionType::Frac | FunctionType::Round
+            | FunctionType::DivMod => "rounding",
+            FunctionType::Det | FunctionType::Transpose | FunctionType::Inv => "matrix",
+            FunctionType::Old | FunctionType::Out => "history",
+            FunctionType::Sum | FunctionType::Prod | FunctionType::Integrate | FunctionType::Solve => "numerical",
+            FunctionType::Min | FunctionType::Max | FunctionType::Mean | FunctionType::Median | FunctionType::StdDev
+            | FunctionType::Var => "statistical",
+            FunctionType::If => "conditional",
+            FunctionType::Convert => "unit",
+            FunctionType::UserFunction => "user defined"
+        }
+    }
+}
+
+/// Defines how complex numbers are compared by the comparison operations.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ComparisonMode {
+    /// Comparing complex numbers is an error.
+    Strict,
+    /// Complex numbers are compared by their modulus.
+    Modulus
+}
+
+impl Default for ComparisonMode {
+    fn default() -> ComparisonMode {
+        ComparisonMode::Strict
+    }
+}
+
+/// Defines whether real numbers are evaluated as floating point numbers or as exact rational numbers.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PrecisionMode {
+    /// Numbers are 64 bit floating point numbers.
+    Float,
+    /// Real numbers are arbitrary-precision rational numbers as long as the operations are exact (+, -, *, /, //, %
+    /// and integer powers). All other operations and functions evaluate floating point numbers.
+    Exact
+}
+
+impl Default for PrecisionMode {
+    fn default() -> PrecisionMode {
+        PrecisionMode::Float
+    }
+}
+
+/// Defines the unit of the angles that are taken by the trigonometric functions and returned by the inverse
+/// trigonometric functions.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum AngleMode {
+    /// Angles are measured in radians.
+    Rad,
+    /// Angles are measured in degrees (a full circle has 360 degrees).
+    Deg,
+    /// Angles are measured in gradians (a full circle has 400 gradians).
+    Grad
+}
+
+impl AngleMode {
+    /// Returns the number of units of a half circle (e.g. 180 for degrees).
+    pub fn get_half_circle(& self) -> f64 {
+        match *self {
+            AngleMode::Rad => f64::consts::PI,
+            AngleMode::Deg => 180.0,
+            AngleMode::Grad => 200.0
+        }
+    }
+}
+
+impl Default for AngleMode {
+    fn default() -> AngleMode {
+        AngleMode::Rad
+    }
+}
+
+/// The maximum absolute value of an integer exponent for which powers are computed exactly.
+const MAX_EXACT_EXPONENT : i64 = 100_000;
+
+/// The largest argument of the factorial that is computed exactly in the exact precision mode.
+const MAX_EXACT_FACTORIAL : u64 = 10_000;
+
+/// The largest number of factors of a binomial coefficient or permutation count that are multiplied exactly (in the
+/// exact precision mode, up to MAX_EXACT_FACTORIAL factors are multiplied exactly). Larger binomial coefficients and
+/// permutation counts are approximated with the logarithm of the gamma function.
+const MAX_EXACT_COMBINATORICS : u64 = 100;
+
+/// The largest argument of the gamma function whose result is finite.
+const MAX_GAMMA_ARG : f64 = 171.62;
+
+/// The coefficients of the Lanczos approximation of the gamma function (g = 7, n = 9).
+const LANCZOS_COEFFICIENTS : [f64; 9] = [0.99999999999980993, 676.5203681218851, -1259.1392167224028, 771.32342877765313,
+    -176.61502916214059, 12.507343278686905, -0.13857109526572012, 9.9843695780195716e-6, 1.5056327351493116e-7];
+
+/// The maximum number of previous values that are kept for each user constant.
+pub const MAX_CONSTANT_HISTORY : usize = 16;
+
+/// The maximum number of previous results that are kept (available as "ans1" to "ans16").
+pub const MAX_RESULT_HISTORY : usize = 16;
+
+/// Describes the operation or function call that first produced a NaN or infinite value from finite operands during the
+/// last evaluation (see MathContext::set_non_finite_tracking).
+#[derive(Clone, PartialEq, Debug)]
+pub struct NonFiniteOrigin {
+    /// The produced value (e.g. "NaN" or "inf").
+    pub value: String,
+    /// The symbol of the operation or the name of the function (e.g. "/" or "exp").
+    pub name: String,
+    /// Whether the origin is a function call (otherwise, it is an operation).
+    pub is_function: bool,
+    /// The operands of the operation or the arguments of the function call.
+    pub operands: Vec<String>,
+    /// The input that contains the operation (e.g. the definition of a user function).
+    pub input: String,
+    /// The position of the operation or the function name in the input.
+    pub pos: usize
+}
+
+impl fmt::Display for NonFiniteOrigin {
+
+    /// Formats the origin like a located error message, e.g. "The value inf was first produced by the function "exp"
+    /// with the argument 1000." followed by the input with a marker at the function name.
+    fn fmt(& self, f: & mut fmt::Formatter) -> fmt::Result {
+        let (kind, noun) = if self.is_function { ("function", "argument") } else { ("operation", "operand") };
+        let operands = match self.operands.split_last() {
+            Some((last, init)) if !init.is_empty() => format!("{0}s {1} and {2}", noun, init.join(", "), last),
+            Some((last, _)) => format!("{0} {1}", noun, last),
+            None => format!("no {0}s", noun)
+        };
+        write!(f, "The value {0} was first produced by the {1} \"{2}\" with the {3}.\n{4}", self.value, kind, self.name, operands,
+               create_location_string(self.input.as_str(), self.pos))
+    }
+}
+
+/// Defines the settings of a MathContext, which are not part of its serialization (e.g. they are kept if a
+/// serialized context is loaded).
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ContextSettings {
+    /// The comparison mode for complex numbers.
+    pub comparison_mode: ComparisonMode,
+    /// The precision mode for real numbers.
+    pub precision_mode: PrecisionMode,
+    /// Whether the constant subtrees of user function definitions are evaluated when the functions are defined.
+    pub optimize: bool,
+    /// Whether the built-in functions return infinity or NaN for arguments outside of their domain instead of an error.
+    pub permissive: bool,
+    /// Whether the commands that access the file system (e.g. save and load) are disabled, e.g. for untrusted inputs.
+    pub sandbox: bool,
+    /// Whether the inputs that start with a binary operation continue the previous result (e.g. "* 2" is "ans * 2").
+    pub auto_ans: bool,
+    /// Whether assignments inside of expressions are allowed and yield the assigned value (e.g. "(x = 5) + 2" is 7).
+    pub assignment_values: bool,
+    /// Whether the origin of the first NaN or infinite value of an evaluation is recorded.
+    pub non_finite_tracking: bool
+}
+
 /// Defines the mathematical context.
 #[derive(Serialize, Deserialize)]
 pub struct MathContext {
@@ -56,22 +298,43 @@ pub struct MathContext {
 
     /// Set of symbols representing numbers.
     #[serde(skip_serializing, skip_deserializing)]
-    number_symbols: HashSet<char>,
+    number_symbols: CharSet,
 
     /// Set of symbols representing words.
     #[serde(skip_serializing, skip_deserializing)]
-    literals : HashSet<char>,
+    literals : CharSet,
+
+    /// Set of symbols with which operations start.
+    #[serde(skip_serializing, skip_deserializing)]
+    operation_symbols : CharSet,
 
     /// Set of functions (function type and number of arguments).
     #[serde(skip_serializing, skip_deserializing)]
     functions: HashMap<String, (FunctionType, u32)>,
 
     /// Set of user defined functions (the function expression tree and it's variables).
-    user_functions: HashMap<String, (TreeNode<Token>, Vec<String>)>,
+    /// The trees are shared with the evaluator, so that function calls do not copy them.
+    user_functions: HashMap<String, (Rc<TreeNode<Token>>, Vec<String>)>,
 
     /// The user inputs that define user functions.
     user_function_inputs: HashMap<String, String>,
 
+    /// The canonical (pretty-printed) forms of the user inputs that define user functions.
+    #[serde(skip_serializing, skip_deserializing)]
+    user_function_canonical_inputs: HashMap<String, String>,
+
+    /// The dependency index: the user constants and user functions that are referenced by each user function.
+    #[serde(skip_serializing, skip_deserializing)]
+    user_function_dependencies: HashMap<String, HashSet<String>>,
+
+    /// The user constants and user functions that have been used in an evaluation in this session.
+    #[serde(skip_serializing, skip_deserializing)]
+    used_user_definitions: HashSet<String>,
+
+    /// The user constants and user functions that have been defined in this session (and not loaded from a file).
+    #[serde(skip_serializing, skip_deserializing)]
+    session_definitions: HashSet<String>,
+
     /// Map of built-in constants (constant representation and value).
     #[serde(skip_serializing, skip_deserializing)]
     constants : HashMap<String, MathResult>,
@@ -79,9 +342,38 @@ pub struct MathContext {
     /// Map of user defined constants (constant representation and value).
     user_constants: HashMap<String, MathResult>,
 
+    /// The previous values of the user defined constants in this session (the most recent value first).
+    #[serde(skip_serializing, skip_deserializing)]
+    user_constant_history: HashMap<String, VecDeque<MathResult>>,
+
+    /// The numbered evaluations of this session (input and result; the first evaluation has the number 1).
+    #[serde(skip_serializing, skip_deserializing)]
+    transcript: Vec<(String, Option<MathResult>)>,
+
+    /// The warnings of the last evaluations that have not been reported yet (e.g. unused function parameters).
+    #[serde(skip_serializing, skip_deserializing)]
+    warnings: Vec<String>,
+
+    /// The last numerical results of this session (the most recent result first).
+    #[serde(skip_serializing, skip_deserializing)]
+    results: VecDeque<MathResult>,
+
     /// Set of punctuation symbols.
     #[serde(skip_serializing, skip_deserializing)]
-    punctuation : HashSet<char>
+    punctuation : CharSet,
+
+    /// The settings of the evaluation, which are not part of the serialization.
+    #[serde(skip_serializing, skip_deserializing)]
+    settings: ContextSettings,
+
+    /// The origin of the first NaN or infinite value of the last evaluation (None if there is none or it is not tracked).
+    #[serde(skip_serializing, skip_deserializing)]
+    non_finite_origin: Option<NonFiniteOrigin>,
+
+    /// The unit of angles of the trigonometric functions.
+    /// Serializations of older versions do not contain the angle mode, so radians are assumed.
+    #[serde(default)]
+    angle_mode: AngleMode
 }
 
 impl<'a> MathContext {
@@ -99,23 +391,28 @@ impl<'a> MathContext {
 
         let (number_symbols, literals, operations, functions, constants,
             punctuation) = MathContext::get_init_values();
+        let operation_symbols = MathContext::get_operation_symbols(& operations);
         MathContext {
-            operations: operations, number_symbols: number_symbols, literals: literals,
+            operations: operations, number_symbols: number_symbols, literals: literals, operation_symbols: operation_symbols,
             functions: functions, user_functions: HashMap::new(), user_function_inputs: HashMap::new(),
-            constants: constants, user_constants: HashMap::new(), punctuation: punctuation
+            user_function_canonical_inputs: HashMap::new(), user_function_dependencies: HashMap::new(),
+            used_user_definitions: HashSet::new(), session_definitions: HashSet::new(), constants: constants, user_constants: HashMap::new(),
+            user_constant_history: HashMap::new(), transcript: Vec::new(), warnings: Vec::new(),
+            results: VecDeque::new(), punctuation: punctuation, settings: ContextSettings::default(),
+            non_finite_origin: None, angle_mode: AngleMode::Rad
         }
     }
 
-    fn get_init_values() -> (HashSet<char>, HashSet<char>, HashMap<String, (OperationType, u32)>,
+    fn get_init_values() -> (CharSet, CharSet, HashMap<String, (OperationType, u32)>,
                         HashMap<String, (FunctionType, u32)>, HashMap<String, MathResult>,
-                        HashSet<char>) {
+                        CharSet) {
 
-        let number_symbols: HashSet<char> = vec!['0', '1', '2', '3', '4', '5', '6', '7', '8', '9']
+        let number_symbols: CharSet = vec!['0', '1', '2', '3', '4', '5', '6', '7', '8', '9']
             .into_iter().collect();
 
         // all literal symbols with which function names or constant names can start with
         // e.g. "pi" or "c0", but now allowed is starting with a number like "0c"
-        let literals: HashSet<char> = vec!['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k',
+        let literals: CharSet = vec!['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k',
         'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'A', 'B', 'C',
         'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U',
         'V', 'W', 'X', 'Y', 'Z', '_'].into_iter().collect();
@@ -123,12 +420,33 @@ impl<'a> MathContext {
         // define the operation types associated with their string representation
         let mut operations: HashMap<String, (OperationType, u32)> = HashMap::new();
         operations.insert(String::from("="), (OperationType::Assign, 1));
-        operations.insert(String::from("+"), (OperationType::Add, 2));
-        operations.insert(String::from("-"), (OperationType::Sub, 2));
-        operations.insert(String::from("*"), (OperationType::Mul, 3));
-        operations.insert(String::from("/"), (OperationType::Div, 3));
-        operations.insert(String::from("%"), (OperationType::Mod, 3));
-        operations.insert(String::from("^"), (OperationType::Pow, 4));
+        // compound assignments (e.g. "x += 1") are expanded to assignments by the parser (e.g. "x = x + 1")
+        operations.insert(String::from("+="), (OperationType::Assign, 1));
+        operations.insert(String::from("-="), (OperationType::Assign, 1));
+        operations.insert(String::from("*="), (OperationType::Assign, 1));
+        operations.insert(String::from("/="), (OperationType::Assign, 1));
+        operations.insert(String::from("<"), (OperationType::Less, 2));
+        operations.insert(String::from(">"), (OperationType::Greater, 2));
+        operations.insert(String::from("<="), (OperationType::LessEqual, 2));
+        operations.insert(String::from(">="), (OperationType::GreaterEqual, 2));
+        operations.insert(String::from("=="), (OperationType::Equal, 2));
+        operations.insert(String::from("!="), (OperationType::NotEqual, 2));
+        // the bitwise operations bind weaker than the arithmetic operations (like in C or Python)
+        operations.insert(String::from("|"), (OperationType::BitOr, 3));
+        operations.insert(String::from("xor"), (OperationType::BitXor, 4));
+        operations.insert(String::from("&"), (OperationType::BitAnd, 5));
+        operations.insert(String::from("<<"), (OperationType::ShiftLeft, 6));
+        operations.insert(String::from(">>"), (OperationType::ShiftRight, 6));
+        operations.insert(String::from("+"), (OperationType::Add, 7));
+        operations.insert(String::from("-"), (OperationType::Sub, 7));
+        operations.insert(String::from("*"), (OperationType::Mul, 8));
+        operations.insert(String::from("/"), (OperationType::Div, 8));
+        operations.insert(String::from("%"), (OperationType::Mod, 8));
+        operations.insert(String::from("//"), (OperationType::IntDiv, 8));
+        operations.insert(String::from("^"), (OperationType::Pow, 9));
+        operations.insert(String::from("**"), (OperationType::Pow, 9)); // alias for "^"
+        operations.insert(String::from("!"), (OperationType::Factorial, 10)); // postfix operation
+        operations.insert(String::from("~"), (OperationType::BitNot, 10)); // prefix operation
 
         // defines functions types with associated with their string representation
         let mut functions: HashMap<String, (FunctionType, u32)> = HashMap::new();
@@ -162,11 +480,54 @@ impl<'a> MathContext {
         functions.insert(String::from("exp"), (FunctionType::Exp, 1));
         functions.insert(String::from("sqrt"), (FunctionType::Sqrt, 1));
         functions.insert(String::from("ln"), (FunctionType::Ln, 1));
+        functions.insert(String::from("log2"), (FunctionType::Log2, 1));
+        functions.insert(String::from("log10"), (FunctionType::Log10, 1));
+        functions.insert(String::from("gamma"), (FunctionType::Gamma, 1));
         functions.insert(String::from("im"), (FunctionType::Im, 1));
         functions.insert(String::from("re"), (FunctionType::Re, 1));
+        functions.insert(String::from("abs"), (FunctionType::Abs, 1));
+        functions.insert(String::from("sign"), (FunctionType::Sign, 1));
+        functions.insert(String::from("conj"), (FunctionType::Conj, 1));
+        functions.insert(String::from("arg"), (FunctionType::Arg, 1));
+        functions.insert(String::from("floor"), (FunctionType::Floor, 1));
+        functions.insert(String::from("ceil"), (FunctionType::Ceil, 1));
+        functions.insert(String::from("trunc"), (FunctionType::Trunc, 1));
+        functions.insert(String::from("frac"), (FunctionType::Frac, 1));
+        functions.insert(String::from("det"), (FunctionType::Det, 1));
+        functions.insert(String::from("transpose"), (FunctionType::Transpose, 1));
+        functions.insert(String::from("inv"), (FunctionType::Inv, 1));
+        functions.insert(String::from("Out"), (FunctionType::Out, 1));
 
         functions.insert(String::from("pow"), (FunctionType::Pow, 2));
         functions.insert(String::from("root"), (FunctionType::Root, 2));
+        functions.insert(String::from("round"), (FunctionType::Round, 2));
+        functions.insert(String::from("divmod"), (FunctionType::DivMod, 2));
+        functions.insert(String::from("gcd"), (FunctionType::Gcd, 2));
+        functions.insert(String::from("lcm"), (FunctionType::Lcm, 2));
+        functions.insert(String::from("isprime"), (FunctionType::IsPrime, 1));
+        functions.insert(String::from("factor"), (FunctionType::Factor, 1));
+        functions.insert(String::from("ncr"), (FunctionType::Ncr, 2));
+        functions.insert(String::from("npr"), (FunctionType::Npr, 2));
+        functions.insert(String::from("binomial"), (FunctionType::Binomial, 2));
+        functions.insert(String::from("log"), (FunctionType::Log, 2));
+        functions.insert(String::from("old"), (FunctionType::Old, 2));
+        functions.insert(String::from("convert"), (FunctionType::Convert, 2));
+        functions.insert(String::from("polar"), (FunctionType::Polar, 2));
+
+        functions.insert(String::from("integrate"), (FunctionType::Integrate, 3));
+        functions.insert(String::from("solve"), (FunctionType::Solve, 3));
+        functions.insert(String::from("if"), (FunctionType::If, 3));
+
+        functions.insert(String::from("prod"), (FunctionType::Prod, 4));
+
+        // the statistics functions accept any number of arguments (0 means any)
+        functions.insert(String::from("sum"), (FunctionType::Sum, 0));
+        functions.insert(String::from("min"), (FunctionType::Min, 0));
+        functions.insert(String::from("max"), (FunctionType::Max, 0));
+        functions.insert(String::from("mean"), (FunctionType::Mean, 0));
+        functions.insert(String::from("median"), (FunctionType::Median, 0));
+        functions.insert(String::from("stddev"), (FunctionType::StdDev, 0));
+        functions.insert(String::from("var"), (FunctionType::Var, 0));
 
         // defines constants
         let mut constants: HashMap<String, MathResult> = HashMap::new();
@@ -174,10 +535,13 @@ impl<'a> MathContext {
         constants.insert(String::from("e"), MathResult::from(f64::consts::E));
         constants.insert(String::from("i"), MathResult::from(Complex::i()));  // the imaginary unit
 
-        let mut punctuation: HashSet<char> = HashSet::new();
+        let mut punctuation: CharSet = CharSet::new();
         punctuation.insert('(');
         punctuation.insert(')');
         punctuation.insert(',');
+        punctuation.insert('[');
+        punctuation.insert(']');
+        punctuation.insert(';');
 
         (number_symbols, literals, operations, functions, constants, punctuation)
     }
@@ -188,10 +552,28 @@ impl<'a> MathContext {
 
         self.number_symbols = number_symbols;
         self.literals = literals;
+        self.operation_symbols = MathContext::get_operation_symbols(& operations);
         self.operations = operations;
         self.functions = functions;
         self.constants = constants;
         self.punctuation = punctuation;
+
+        // the canonical inputs are not serialized, so they have to be rebuilt from the function trees
+        // the same applies to the dependency index
+        let mut canonical_inputs = HashMap::new();
+        let mut dependencies = HashMap::new();
+        for (repr, &(ref t, ref vars)) in &self.user_functions {
+            canonical_inputs.insert(repr.clone(), self.build_canonical_input(repr, t, vars));
+            dependencies.insert(repr.clone(), MathContext::collect_dependencies(repr, t));
+        }
+        self.user_function_canonical_inputs = canonical_inputs;
+        self.user_function_dependencies = dependencies;
+    }
+
+    /// Returns the set of symbols with which the specified operations start.
+    fn get_operation_symbols(operations: & HashMap<String, (OperationType, u32)>) -> CharSet {
+        // operations that are words (e.g. "xor") are read like constant names
+        operations.keys().filter_map(|op| op.chars().next()).filter(|c| !c.is_alphabetic()).collect()
     }
 
     /// Checks whether the specified string is an operation.
@@ -209,6 +591,60 @@ impl<'a> MathContext {
         self.operations.contains_key(s)
     }
 
+    /// Checks whether the specified operation is right associative (e.g. "a = b = 3" is "a = (b = 3)").
+    /// All other binary operations are left associative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let context = MathContext::new();
+    /// assert!(context.is_right_associative("="));
+    /// assert!(!context.is_right_associative("-"));
+    /// ```
+    pub fn is_right_associative(& self, s: & str) -> bool {
+        self.get_operation_type(s) == Some(OperationType::Assign)
+    }
+
+    /// Returns the binary operation of the specified compound assignment (e.g. "+" for "+=").
+    /// Returns None if the specified string is not a compound assignment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let context = MathContext::new();
+    /// assert!(context.get_compound_operation("*=") == Some("*"));
+    /// assert!(context.get_compound_operation("=") == None);
+    /// assert!(context.get_compound_operation("<=") == None);
+    /// ```
+    pub fn get_compound_operation<'b>(& self, s: &'b str) -> Option<&'b str> {
+        if self.get_operation_type(s) != Some(OperationType::Assign) || !s.ends_with('=') {
+            return None;
+        }
+        let op = &s[..s.len() - 1];
+        if self.is_operation(op) { Some(op) } else { None }
+    }
+
+    /// Returns the longest operation with which the specified string starts.
+    /// Returns None if the specified string does not start with an operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let context = MathContext::new();
+    /// assert!(context.match_operation("**2") == Some("**"));
+    /// assert!(context.match_operation("*-2") == Some("*"));
+    /// assert!(context.match_operation("2*3") == None);
+    /// ```
+    pub fn match_operation(& self, s: & str) -> Option<& str> {
+        self.operations.keys().filter(|op| s.starts_with(op.as_str())).max_by_key(|op| op.len()).map(|op| op.as_str())
+    }
+
     /// Checks whether the specified string is an unary operation.
     /// An unary operation is an operation that may take only one operand, e.g. "-3", where the
     /// "-" has only one operand "3".
@@ -227,7 +663,7 @@ impl<'a> MathContext {
     pub fn is_unary_operation(&self, s: & str) -> bool {
         match self.get_operation_type(s) {
             Some(x) => {
-                if x == OperationType::Add || x == OperationType::Sub {
+                if x == OperationType::Add || x == OperationType::Sub || x == OperationType::BitNot {
                     true
                 }
                 else {
@@ -238,6 +674,22 @@ impl<'a> MathContext {
         }
     }
 
+    /// Checks whether the specified string is a postfix operation.
+    /// A postfix operation follows its only operand, e.g. "5!".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let context = MathContext::new();
+    /// assert!(context.is_postfix_operation("!"));
+    /// assert!(!context.is_postfix_operation("-"));
+    /// ```
+    pub fn is_postfix_operation(&self, s: & str) -> bool {
+        self.get_operation_type(s) == Some(OperationType::Factorial)
+    }
+
     /// Checks whether the specified string is a function.
     ///
     /// # Examples
@@ -298,6 +750,21 @@ impl<'a> MathContext {
         self.number_symbols.contains(c)
     }
 
+    /// Checks whether the specified character is the first symbol of an operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let context = MathContext::new();
+    /// let is_op_symbol = context.is_operation_symbol(& '*');
+    /// assert!(is_op_symbol == true);
+    /// ```
+    pub fn is_operation_symbol(& self, c: & char) -> bool {
+        self.operation_symbols.contains(c)
+    }
+
     /// Checks whether the specified character is a literal symbol.
     ///
     /// # Examples
@@ -340,7 +807,30 @@ impl<'a> MathContext {
     /// assert!(is_built_in_const == true);
     /// ```
     pub fn is_built_in_constant(& self, s: & str) -> bool {
-        self.constants.contains_key(s)
+        self.constants.contains_key(s) || MathContext::parse_result_name(s).is_some()
+    }
+
+    /// Parses the number n of a previous result name "ans<n>" (e.g. "ans2" is the result before the most recent one).
+    /// Returns None if the specified string is not a previous result name or n is not between 1 and MAX_RESULT_HISTORY.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// assert!(MathContext::parse_result_name("ans2") == Some(2));
+    /// assert!(MathContext::parse_result_name("ans") == None);
+    /// assert!(MathContext::parse_result_name("ans02") == None);
+    /// assert!(MathContext::parse_result_name("ans17") == None);
+    /// ```
+    pub fn parse_result_name(s: & str) -> Option<usize> {
+        if !s.starts_with("ans") || s[3..].starts_with('0') {
+            return None;
+        }
+        match s[3..].parse::<usize>() {
+            Ok(n) if n >= 1 && n <= MAX_RESULT_HISTORY && s[3..].chars().all(|c| c.is_ascii_digit()) => Some(n),
+            _ => None
+        }
     }
 
     /// Checks whether the specified string is a user defined constant.
@@ -427,7 +917,10 @@ impl<'a> MathContext {
         match self.constants.get(s) {
             Some(x) => Some(x.clone()),
             None => {
-                self.user_constants.get(s).cloned()
+                match MathContext::parse_result_name(s) {
+                    Some(n) => self.get_previous_result(n),
+                    None => self.user_constants.get(s).cloned()
+                }
             }
         }
     }
@@ -459,7 +952,7 @@ impl<'a> MathContext {
     ///
     /// let context = MathContext::new();
     /// let op_prec = context.get_operation_precedence("+");
-    /// assert!(op_prec == Some(2 as u32));
+    /// assert!(op_prec == Some(7 as u32));
     /// ```
     pub fn get_operation_precedence(& self, s: & str) -> Option<u32> {
         match self.operations.get(s) {
@@ -491,7 +984,7 @@ impl<'a> MathContext {
         }
     }
 
-    /// Returns the number of arguments for the specified function
+    /// Returns the number of arguments for the specified function (0 for functions with any number of arguments, e.g. "mean").
     ///
     /// # Examples
     ///
@@ -501,6 +994,7 @@ impl<'a> MathContext {
     /// let context = MathContext::new();
     /// let n_args = context.get_function_arg_num("pow");
     /// assert!(n_args == Some(2));
+    /// assert!(context.get_function_arg_num("mean") == Some(0));
     /// ```
     pub fn get_function_arg_num(& self, s: & str) -> Option<u32> {
         match self.functions.get(s) {
@@ -514,6 +1008,68 @@ impl<'a> MathContext {
         }
     }
 
+    /// Checks whether the specified function call is a series with a bound variable (e.g. "sum(1/k^2, k, 1, 10)").
+    /// Products are always series, whereas a sum is only a series if it has four arguments and the second argument is
+    /// a name (e.g. "k"). Otherwise, the sum adds its arguments (e.g. "sum(1, 2, 3, 4)").
+    pub fn is_series(& self, tree: & TreeNode<Token>) -> bool {
+        match self.get_function_type(tree.content.get_value()) {
+            Some(FunctionType::Prod) => true,
+            Some(FunctionType::Sum) => tree.successors.len() == 4 && match tree.successors[1].content.get_type() {
+                TokenType::Symbol(SymbolicTokenType::UnknownConstant) | TokenType::UserConstant | TokenType::Constant => true,
+                _ => false
+            },
+            _ => false
+        }
+    }
+
+    /// Returns a short description of the specified symbol: the kind of the symbol (built-in or user defined constant,
+    /// function or operation) followed by its value, its number of arguments or its definition.
+    /// User definitions are described as defined this session or loaded from a file.
+    /// Returns None if the symbol is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let mut context = MathContext::new();
+    /// context.add_user_constant("c", MathResult::from(4.1));
+    ///
+    /// assert!(context.get_symbol_description("tan").unwrap() == "built-in function, 1 argument, trigonometric");
+    /// assert!(context.get_symbol_description("c").unwrap() == "user constant = 4.1 (Real), defined this session");
+    /// assert!(context.get_symbol_description("q").is_none());
+    /// ```
+    pub fn get_symbol_description(& self, s: & str) -> Option<String> {
+        let describe_args = |n: u32| match n {
+            0 => String::from("any number of arguments"),
+            1 => String::from("1 argument"),
+            _ => format!("{0} arguments", n)
+        };
+        let describe_origin = |repr: & str| if self.session_definitions.contains(repr) { "defined this session" } else { "loaded from a file" };
+
+        if s == "ans" || MathContext::parse_result_name(s).is_some() {
+            return Some(match self.get_constant_value(s) {
+                Some(x) => format!("previous result = {0} ({1:?})", x, x.result_type),
+                None => String::from("previous result (not available)")
+            });
+        }
+        if let Some(x) = self.user_constants.get(s) {
+            return Some(format!("user constant = {0} ({1:?}), {2}", x, x.result_type, describe_origin(s)));
+        }
+        if let Some(&(_, ref vars)) = self.user_functions.get(s) {
+            let definition = self.get_user_function_canonical_input(s).unwrap_or_default();
+            return Some(format!("user function, {0}, {1}, {2}", describe_args(vars.len() as u32), definition, describe_origin(s)));
+        }
+        if let Some(x) = self.constants.get(s) {
+            return Some(format!("built-in constant = {0} ({1:?})", x, x.result_type));
+        }
+        if let Some(&(ref t, n)) = self.functions.get(s) {
+            return Some(format!("built-in function, {0}, {1}", describe_args(n), t.get_category()));
+        }
+        self.operations.get(s).map(|&(_, precedence)| format!("built-in operation, precedence {0}", precedence))
+    }
+
     /// Implements the mathematical "+" operation.
     ///
     /// # Examples
@@ -527,6 +1083,9 @@ impl<'a> MathContext {
     /// assert!(MathContext::operation_add(& lhs, & rhs).value.re - 9.0_f64 < 10e-10_f64);
     /// ```
     pub fn operation_add(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        if let Some((l, r)) = MathContext::get_exact_operands(lhs, rhs) {
+            return MathResult::from(l + r);
+        }
         let t = MathContext::get_result_type(& vec![lhs, rhs]);
         MathResult::new(t, lhs.value + rhs.value)
     }
@@ -544,10 +1103,31 @@ impl<'a> MathContext {
     /// assert!(MathContext::operation_sub(& lhs, & rhs).value.re - 1.0_f64 < 10e-10_f64);
     /// ```
     pub fn operation_sub(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        if let Some((l, r)) = MathContext::get_exact_operands(lhs, rhs) {
+            return MathResult::from(l - r);
+        }
         let t = MathContext::get_result_type(& vec![lhs, rhs]);
         MathResult::new(t, lhs.value - rhs.value)
     }
 
+    /// Implements the mathematical negation (unary "-" operation).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(5.0_f64);
+    /// assert!(MathContext::operation_neg(& arg).value.re == -5.0_f64);
+    /// ```
+    pub fn operation_neg(arg: & MathResult) -> MathResult {
+        match arg.exact {
+            Some(ref r) => MathResult::from(-r),
+            None => MathContext::operation_sub(& MathResult::from(0.0), arg)
+        }
+    }
+
     /// Implements the mathematical "*" operation.
     ///
     /// # Examples
@@ -561,6 +1141,9 @@ impl<'a> MathContext {
     /// assert!(MathContext::operation_mul(& lhs, & rhs).value.re - 20.0_f64 < 10e-10_f64);
     /// ```
     pub fn operation_mul(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        if let Some((l, r)) = MathContext::get_exact_operands(lhs, rhs) {
+            return MathResult::from(l * r);
+        }
         let t = MathContext::get_result_type(& vec![lhs, rhs]);
         MathResult::new(t, lhs.value * rhs.value)
     }
@@ -578,10 +1161,43 @@ impl<'a> MathContext {
     /// assert!(MathContext::operation_div(& lhs, & rhs).value.re - 5.0_f64/4.0_f64 < 10e-10_f64);
     /// ```
     pub fn operation_div(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        if let Some((l, r)) = MathContext::get_exact_operands(lhs, rhs) {
+            // the division by zero is left to the floating point arithmetic (infinity or NaN)
+            if !r.is_zero() {
+                return MathResult::from(l / r);
+            }
+        }
         let t = MathContext::get_result_type(& vec![lhs, rhs]);
+        if t == NumberType::Real {
+            // real operands are divided as reals, so that a zero divisor results in +-infinity or NaN
+            // (the complex division would result in NaN for both parts)
+            return MathResult::from(lhs.value.re / rhs.value.re);
+        }
         MathResult::new(t, lhs.value / rhs.value)
     }
 
+    /// Implements the mathematical "//" operation (floor division) for real numbers.
+    /// The quotient is rounded towards negative infinity. The imaginary parts of the operands are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let lhs = MathResult::from(-7.0_f64);
+    /// let rhs = MathResult::from(2.0_f64);
+    /// assert!(MathContext::operation_int_div(& lhs, & rhs).value.re == -4.0_f64);
+    /// ```
+    pub fn operation_int_div(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        if let Some((l, r)) = MathContext::get_exact_operands(lhs, rhs) {
+            if !r.is_zero() {
+                return MathResult::from((l / r).floor());
+            }
+        }
+        MathResult::from((lhs.value.re / rhs.value.re).floor())
+    }
+
     /// Implements the mathematical "/" operation.
     ///
     /// # Examples
@@ -595,6 +1211,11 @@ impl<'a> MathContext {
     /// assert!(MathContext::operation_mod(& lhs, & rhs).value.re - 2.0 < 10e-10_f64);
     /// ```
     pub fn operation_mod(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        if let Some((l, r)) = MathContext::get_exact_operands(lhs, rhs) {
+            if l.is_integer() && r.is_integer() && !r.is_zero() {
+                return MathResult::from(BigRational::from_integer(l.numer() % r.numer()));
+            }
+        }
         let t = MathContext::get_result_type(& vec![lhs, rhs]);
 
         // check if the input was no float
@@ -608,22 +1229,20 @@ impl<'a> MathContext {
                 NumberType::Complex => return MathResult::from(f64::NAN),
                 NumberType::Real => lhs.value.re as i64
             };
-            let rhs_i = match lhs.result_type {
+            let rhs_i = match rhs.result_type {
                 NumberType::Complex => return MathResult::from(f64::NAN),
                 NumberType::Real => rhs.value.re as i64
             };
+            if rhs_i == 0 {
+                return MathResult::from(f64::NAN); // the remainder of a division by zero is undefined
+            }
 
             MathResult::new(t, Complex::from((lhs_i % rhs_i) as f64))
         }
     }
 
-    /// Checks whether the specified float has decimal_places.
-    fn has_decimal_places(f: f64) -> bool {
-        let i = f as i64;
-        f.abs() - (i.abs() as f64) > 0.0_f64
-    }
-
-    /// Implements the mathematical "^" operation.
+    /// Implements the bitwise "&" operation for integers in the 64 bit two's complement representation.
+    /// The result is NaN if an operand is not an integer (see MathResult::to_i64).
     ///
     /// # Examples
     ///
@@ -631,36 +1250,15 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let lhs = MathResult::from(5.0_f64);
-    /// let rhs = MathResult::from(4.0_f64);
-    /// assert!(MathContext::operation_pow(& lhs, & rhs).value.re - 625.0_f64 < 10e-10_f64);
+    /// let lhs = MathResult::from(12.0_f64);
+    /// let rhs = MathResult::from(10.0_f64);
+    /// assert!(MathContext::operation_bit_and(& lhs, & rhs).value.re == 8.0_f64);
     /// ```
-    pub fn operation_pow(lhs: & MathResult, rhs: & MathResult) -> MathResult {
-        let t = MathContext::get_result_type(& vec![lhs, rhs]);
-        match lhs.result_type {
-            NumberType::Real => {
-                match rhs.result_type {
-                    NumberType::Real => {
-                        // ordinary pow, e.g. "a^b"
-                        MathResult::new(t, Complex::from(lhs.value.re.powf(rhs.value.re)))
-                    },
-
-                    NumberType::Complex => {
-                        // exponent is complex, e.g. "a^(b+ci)" = "exp(ln(a) * (b+ci))"
-                        MathResult::new(t, (rhs.value * lhs.value.re.ln()).exp())
-                    }
-                }
-            },
-
-            NumberType::Complex =>  {
-                // base is complex, e.g. "(a+bi)^c" = "exp(ln(a+bi) * c)" or
-                // base and exponent are complex, e.g. "(a+bi)^(c+di)" = "exp(ln(a+bi) * (c+di))"
-                MathResult::new(t, (lhs.value.ln() * rhs.value).exp())
-            }
-        }
+    pub fn operation_bit_and(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        MathContext::apply_bitwise(lhs, rhs, |l, r| Some(l & r))
     }
 
-    /// Implements the mathematical root operation.
+    /// Implements the bitwise "|" operation for integers in the 64 bit two's complement representation.
     ///
     /// # Examples
     ///
@@ -668,15 +1266,15 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(8.0_f64);
-    /// let root = MathResult::from(3.0_f64);
-    /// assert!(MathContext::operation_root(& arg, & root).value.re - 2.0_f64 < 10e-10_f64);
+    /// let lhs = MathResult::from(12.0_f64);
+    /// let rhs = MathResult::from(10.0_f64);
+    /// assert!(MathContext::operation_bit_or(& lhs, & rhs).value.re == 14.0_f64);
     /// ```
-    pub fn operation_root(arg: & MathResult, root: & MathResult) -> MathResult {
-        MathContext::operation_pow(arg, &MathResult::new(root.result_type.clone(), 1.0 / root.value))
+    pub fn operation_bit_or(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        MathContext::apply_bitwise(lhs, rhs, |l, r| Some(l | r))
     }
 
-    /// Implements the mathematical cosine function.
+    /// Implements the bitwise "xor" operation for integers in the 64 bit two's complement representation.
     ///
     /// # Examples
     ///
@@ -684,90 +1282,1047 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(0.0_f64);
-    /// assert!(MathContext::function_cos(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let lhs = MathResult::from(12.0_f64);
+    /// let rhs = MathResult::from(10.0_f64);
+    /// assert!(MathContext::operation_bit_xor(& lhs, & rhs).value.re == 6.0_f64);
     /// ```
-    pub fn function_cos(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.cos())
+    pub fn operation_bit_xor(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        MathContext::apply_bitwise(lhs, rhs, |l, r| Some(l ^ r))
     }
 
-    /// Implements the mathematical sine function.
+    /// Implements the "<<" operation (left shift of a 64 bit integer, the bits shifted out are discarded).
+    /// The result is NaN if the shift count is not between 0 and 63.
     ///
     /// # Examples
     ///
     /// ```
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
-    /// use std::f64;
     ///
-    /// let arg = MathResult::from(f64::consts::FRAC_PI_2);
-    /// assert!(MathContext::function_sin(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let lhs = MathResult::from(3.0_f64);
+    /// let rhs = MathResult::from(4.0_f64);
+    /// assert!(MathContext::operation_shift_left(& lhs, & rhs).value.re == 48.0_f64);
     /// ```
-    pub fn function_sin(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.sin())
+    pub fn operation_shift_left(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        MathContext::apply_bitwise(lhs, rhs, |l, r| if r >= 0 && r < 64 { Some(l.wrapping_shl(r as u32)) } else { None })
     }
 
-    /// Implements the mathematical tangent function.
+    /// Implements the ">>" operation (arithmetic right shift of a 64 bit integer, which keeps the sign).
+    /// The result is NaN if the shift count is not between 0 and 63.
     ///
     /// # Examples
     ///
     /// ```
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
-    /// use std::f64;
     ///
-    /// let arg = MathResult::from(f64::consts::FRAC_PI_4);
-    /// assert!(MathContext::function_tan(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let lhs = MathResult::from(-48.0_f64);
+    /// let rhs = MathResult::from(4.0_f64);
+    /// assert!(MathContext::operation_shift_right(& lhs, & rhs).value.re == -3.0_f64);
     /// ```
-    pub fn function_tan(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.tan())
+    pub fn operation_shift_right(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        MathContext::apply_bitwise(lhs, rhs, |l, r| if r >= 0 && r < 64 { Some(l >> r) } else { None })
     }
 
-    /// Implements the mathematical cotangent function.
+    /// Implements the prefix "~" operation (bitwise complement of a 64 bit integer, e.g. "~5" is -6).
     ///
     /// # Examples
     ///
     /// ```
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
-    /// use std::f64;
     ///
-    /// let arg = MathResult::from(f64::consts::FRAC_PI_4);
-    /// assert!(MathContext::function_cot(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let arg = MathResult::from(5.0_f64);
+    /// assert!(MathContext::operation_bit_not(& arg).value.re == -6.0_f64);
     /// ```
-    pub fn function_cot(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.cos() / arg.value.sin())
+    pub fn operation_bit_not(arg: & MathResult) -> MathResult {
+        MathContext::apply_bitwise(arg, & MathResult::from(0.0_f64), |l, _| Some(!l))
     }
 
-    /// Implements the mathematical inverse cosine function.
+    /// Applies the specified function to the 64 bit integer values of the operands of a bitwise operation.
+    /// The result is exact if the left operand is exact and NaN if an operand is not an integer or the function returns None.
+    fn apply_bitwise<F>(lhs: & MathResult, rhs: & MathResult, f: F) -> MathResult where F: Fn(i64, i64) -> Option<i64> {
+        match (lhs.to_i64(), rhs.to_i64()) {
+            (Some(l), Some(r)) => match f(l, r) {
+                Some(x) if lhs.is_exact() => MathResult::from(BigRational::from_integer(BigInt::from(x))),
+                Some(x) => MathResult::from(x as f64),
+                None => MathResult::from(f64::NAN)
+            },
+            _ => MathResult::from(f64::NAN)
+        }
+    }
+
+    /// Returns the comparison mode for complex numbers.
     ///
     /// # Examples
     ///
     /// ```
-    /// use termc_model::math_context::MathContext;
-    /// use termc_model::math_result::MathResult;
+    /// use termc_model::math_context::{MathContext, ComparisonMode};
     ///
-    /// let arg = MathResult::from(1.0_f64.cos());
-    /// assert!(MathContext::function_arccos(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let context = MathContext::new();
+    /// assert!(context.get_comparison_mode() == ComparisonMode::Strict);
+    /// ```
+    pub fn get_comparison_mode(& self) -> ComparisonMode {
+        self.settings.comparison_mode.clone()
+    }
+
+    /// Sets the comparison mode for complex numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::{MathContext, ComparisonMode};
+    ///
+    /// let mut context = MathContext::new();
+    /// assert!(get_result("3i > 2", &mut context).is_err());
+    ///
+    /// context.set_comparison_mode(ComparisonMode::Modulus);
+    /// let result = get_result("3i > 2", &mut context).unwrap().unwrap();
+    /// assert!(result.value.re == 1.0);
+    /// ```
+    pub fn set_comparison_mode(& mut self, mode: ComparisonMode) {
+        self.settings.comparison_mode = mode;
+    }
+
+    /// Returns the precision mode for real numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::{MathContext, PrecisionMode};
+    ///
+    /// let context = MathContext::new();
+    /// assert!(context.get_precision_mode() == PrecisionMode::Float);
+    /// ```
+    pub fn get_precision_mode(& self) -> PrecisionMode {
+        self.settings.precision_mode.clone()
+    }
+
+    /// Sets the precision mode for real numbers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::{MathContext, PrecisionMode};
+    ///
+    /// let mut context = MathContext::new();
+    /// context.set_precision_mode(PrecisionMode::Exact);
+    /// let result = get_result("1/3 * 3", &mut context).unwrap().unwrap();
+    /// assert!(result.to_string() == "1");
+    /// ```
+    pub fn set_precision_mode(& mut self, mode: PrecisionMode) {
+        self.settings.precision_mode = mode;
+    }
+
+    /// Returns whether the constant subtrees of user function definitions are folded.
+    pub fn get_optimize(& self) -> bool {
+        self.settings.optimize
+    }
+
+    /// Sets whether the constant subtrees of user function definitions (e.g. "2*pi" in "f(x) = 2*pi*x") are evaluated
+    /// when the functions are defined, so that they are not evaluated again in each call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// context.set_optimize(true);
+    /// get_result("f(x) = x * 2^10", &mut context).unwrap();
+    /// assert!(context.get_user_function_canonical_input("f") == Some(String::from("f(x) = x*1024")));
+    /// ```
+    pub fn set_optimize(& mut self, optimize: bool) {
+        self.settings.optimize = optimize;
+    }
+
+    /// Returns whether the permissive mode is enabled.
+    pub fn get_permissive(& self) -> bool {
+        self.settings.permissive
+    }
+
+    /// Sets whether the permissive mode is enabled. By default, arguments outside of the domain of a built-in function
+    /// or operation (e.g. a zero divisor) are reported as errors. In the permissive mode, the results of the floating
+    /// point arithmetic (infinity or NaN) are returned instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// assert!(get_result("1/0", &mut context).is_err());
+    /// context.set_permissive(true);
+    /// let result = get_result("1/0", &mut context).unwrap().unwrap();
+    /// assert!(result.value.re.is_infinite());
+    /// ```
+    pub fn set_permissive(& mut self, permissive: bool) {
+        self.settings.permissive = permissive;
+    }
+
+    /// Returns whether the sandbox mode is enabled.
+    pub fn get_sandbox(& self) -> bool {
+        self.settings.sandbox
+    }
+
+    /// Sets whether the sandbox mode is enabled. In the sandbox mode, the commands that access the file system
+    /// (e.g. save, load and run) are rejected, so that untrusted inputs can be evaluated safely.
+    /// The sandbox mode can not be changed by the inputs themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// context.set_sandbox(true);
+    /// assert!(context.get_sandbox());
+    /// ```
+    pub fn set_sandbox(& mut self, sandbox: bool) {
+        self.settings.sandbox = sandbox;
+    }
+
+    /// Returns whether the inputs that start with a binary operation continue the previous result.
+    pub fn get_auto_ans(& self) -> bool {
+        self.settings.auto_ans
+    }
+
+    /// Sets whether the inputs that start with a binary operation continue the previous result "ans"
+    /// (e.g. "* 2" is evaluated as "ans * 2").
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let mut context = MathContext::new();
+    /// context.set_auto_ans(true);
+    /// get_result("3", &mut context).unwrap();
+    /// assert!(get_result("* 2", &mut context).unwrap() == Some(MathResult::from(6.0)));
+    /// ```
+    pub fn set_auto_ans(& mut self, auto_ans: bool) {
+        self.settings.auto_ans = auto_ans;
+    }
+
+    /// Returns whether assignments inside of expressions yield the assigned value.
+    pub fn get_assignment_values(& self) -> bool {
+        self.settings.assignment_values
+    }
+
+    /// Sets whether assignments inside of expressions are allowed and yield the assigned value (e.g. "(x = 5) + 2" is 7).
+    /// Chained assignments (e.g. "a = b = 3") are supported regardless of this setting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let mut context = MathContext::new();
+    /// assert!(get_result("(x = 5) + 2", &mut context).is_err());
+    /// context.set_assignment_values(true);
+    /// assert!(get_result("(x = 5) + 2", &mut context).unwrap() == Some(MathResult::from(7.0)));
+    /// ```
+    pub fn set_assignment_values(& mut self, assignment_values: bool) {
+        self.settings.assignment_values = assignment_values;
+    }
+
+    /// Returns whether the origins of NaN and infinite values are tracked.
+    pub fn get_non_finite_tracking(& self) -> bool {
+        self.settings.non_finite_tracking
+    }
+
+    /// Sets whether the origins of NaN and infinite values are tracked. If the tracking is enabled, the operation or
+    /// function call that first produces a NaN or infinite value from finite operands during an evaluation is recorded
+    /// (see MathContext::get_non_finite_origin).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// context.set_non_finite_tracking(true);
+    /// get_result("exp(1000) - exp(1000)", &mut context).unwrap();
+    /// let origin = context.get_non_finite_origin().unwrap();
+    /// assert!(origin.name == "exp" && origin.value == "inf" && origin.operands == vec!["1000"]);
+    /// ```
+    pub fn set_non_finite_tracking(& mut self, tracking: bool) {
+        self.settings.non_finite_tracking = tracking;
+        if !tracking {
+            self.non_finite_origin = None;
+        }
+    }
+
+    /// Returns the settings of the context, which are not part of the serialization.
+    pub fn get_settings(& self) -> ContextSettings {
+        self.settings.clone()
+    }
+
+    /// Sets all settings of the context at once (e.g. to keep the settings when a serialized context is loaded).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::{MathContext, PrecisionMode};
+    ///
+    /// let mut context = MathContext::new();
+    /// context.set_precision_mode(PrecisionMode::Exact);
+    /// context.set_optimize(true);
+    /// let settings = context.get_settings();
+    ///
+    /// let mut other = MathContext::new();
+    /// other.set_settings(settings);
+    /// assert!(other.get_precision_mode() == PrecisionMode::Exact && other.get_optimize());
+    /// ```
+    pub fn set_settings(& mut self, settings: ContextSettings) {
+        let tracking = settings.non_finite_tracking;
+        self.settings = settings;
+        self.set_non_finite_tracking(tracking);
+    }
+
+    /// Returns the origin of the first NaN or infinite value of the last evaluation. Returns None if no operation or
+    /// function call produced a NaN or infinite value from finite operands or if the tracking is disabled.
+    pub fn get_non_finite_origin(& self) -> Option<& NonFiniteOrigin> {
+        self.non_finite_origin.as_ref()
+    }
+
+    /// Sets the origin of the first NaN or infinite value of the current evaluation (None at the start of an evaluation).
+    pub fn set_non_finite_origin(& mut self, origin: Option<NonFiniteOrigin>) {
+        self.non_finite_origin = origin;
+    }
+
+    /// Returns the unit of angles of the trigonometric functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::{MathContext, AngleMode};
+    ///
+    /// let context = MathContext::new();
+    /// assert!(context.get_angle_mode() == AngleMode::Rad);
+    /// ```
+    pub fn get_angle_mode(& self) -> AngleMode {
+        self.angle_mode.clone()
+    }
+
+    /// Sets the unit of angles of the trigonometric functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::{MathContext, AngleMode};
+    ///
+    /// let mut context = MathContext::new();
+    /// context.set_angle_mode(AngleMode::Deg);
+    /// let result = get_result("sin(90)", &mut context).unwrap().unwrap();
+    /// assert!(result.value.re == 1.0);
+    /// ```
+    pub fn set_angle_mode(& mut self, mode: AngleMode) {
+        self.angle_mode = mode;
+    }
+
+    /// Returns the exact rational numbers of the specified operands if both operands are exact.
+    fn get_exact_operands<'b>(lhs: &'b MathResult, rhs: &'b MathResult) -> Option<(&'b BigRational, &'b BigRational)> {
+        match (lhs.exact.as_ref(), rhs.exact.as_ref()) {
+            (Some(l), Some(r)) => Some((l, r)),
+            _ => None
+        }
+    }
+
+    /// Computes the exact power of an exact base and an exact integer exponent.
+    /// Returns None if the power can not be computed exactly (or the exponent is too large).
+    fn exact_pow(lhs: & MathResult, rhs: & MathResult) -> Option<MathResult> {
+        let (base, exponent) = MathContext::get_exact_operands(lhs, rhs)?;
+        if !exponent.is_integer() {
+            return None;
+        }
+        let n = exponent.numer().to_i64()?;
+        if n.abs() > MAX_EXACT_EXPONENT || (n < 0 && base.is_zero()) {
+            return None;
+        }
+        let numer : BigInt = pow(base.numer().clone(), n.abs() as usize);
+        let denom : BigInt = pow(base.denom().clone(), n.abs() as usize);
+        let x = BigRational::new(numer, denom);
+        Some(MathResult::from(if n < 0 { x.recip() } else { x }))
+    }
+
+    /// Converts the specified integer into a MathResult, which is exact if specified.
+    fn from_integer(x: BigInt, exact: bool) -> MathResult {
+        if exact {
+            MathResult::from(BigRational::from_integer(x))
+        }
+        else {
+            // integers beyond the range of floating point numbers are infinite
+            MathResult::from(x.to_f64().unwrap_or(if x < BigInt::zero() { f64::NEG_INFINITY } else { f64::INFINITY }))
+        }
+    }
+
+    /// Returns the greatest common divisor of the specified numbers (Euclidean algorithm).
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 { a } else { MathContext::gcd(b, a % b) }
+    }
+
+    /// Returns (a * b) mod m without overflow.
+    fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+        ((a as u128 * b as u128) % m as u128) as u64
+    }
+
+    /// Returns (base ^ exponent) mod m by binary exponentiation.
+    fn pow_mod(base: u64, exponent: u64, m: u64) -> u64 {
+        let (mut base, mut exponent, mut result) = (base % m, exponent, 1);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = MathContext::mul_mod(result, base, m);
+            }
+            base = MathContext::mul_mod(base, base, m);
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// Checks whether the specified number is a prime number by the Miller-Rabin test.
+    /// The test is deterministic for all 64 bit numbers with the first 12 primes as witnesses.
+    fn is_prime(n: u64) -> bool {
+        const WITNESSES : [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+        if n < 2 {
+            return false;
+        }
+        for &p in WITNESSES.iter() {
+            if n % p == 0 {
+                return n == p;
+            }
+        }
+
+        // n - 1 = d * 2^s with an odd d
+        let (mut d, mut s) = (n - 1, 0);
+        while d % 2 == 0 {
+            d /= 2;
+            s += 1;
+        }
+        'witness: for &a in WITNESSES.iter() {
+            let mut x = MathContext::pow_mod(a, d, n);
+            if x == 1 || x == n - 1 {
+                continue;
+            }
+            for _ in 1..s {
+                x = MathContext::mul_mod(x, x, n);
+                if x == n - 1 {
+                    continue 'witness;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    /// Adds the prime factors of the specified number to the specified factors (in no particular order).
+    /// Composite numbers are split by Pollard's rho algorithm.
+    fn factorize(n: u64, factors: & mut Vec<u64>) {
+        if n == 1 {
+            return;
+        }
+        if MathContext::is_prime(n) {
+            factors.push(n);
+            return;
+        }
+        let d = if n % 2 == 0 { 2 } else { MathContext::pollard_rho(n) };
+        MathContext::factorize(d, factors);
+        MathContext::factorize(n / d, factors);
+    }
+
+    /// Returns a non-trivial divisor of the specified odd composite number (Pollard's rho algorithm).
+    fn pollard_rho(n: u64) -> u64 {
+        let mut c = 1;
+        loop {
+            // the sequence x -> x^2 + c (mod n) is tried with increasing c until it yields a divisor other than n
+            let f = move |x: u64| ((MathContext::mul_mod(x, x, n) as u128 + c as u128) % n as u128) as u64;
+            let (mut x, mut y, mut d) = (2, 2, 1);
+            while d == 1 {
+                x = f(x);
+                y = f(f(y));
+                d = MathContext::gcd(if x > y { x - y } else { y - x }, n);
+            }
+            if d != n {
+                return d;
+            }
+            c += 1;
+        }
+    }
+
+    /// Checks whether the specified float has decimal_places.
+    fn has_decimal_places(f: f64) -> bool {
+        let i = f as i64;
+        f.abs() - (i.abs() as f64) > 0.0_f64
+    }
+
+    /// Implements the mathematical "^" operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let lhs = MathResult::from(5.0_f64);
+    /// let rhs = MathResult::from(4.0_f64);
+    /// assert!(MathContext::operation_pow(& lhs, & rhs).value.re - 625.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn operation_pow(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        if let Some(x) = MathContext::exact_pow(lhs, rhs) {
+            return x;
+        }
+        let t = MathContext::get_result_type(& vec![lhs, rhs]);
+        match lhs.result_type {
+            NumberType::Real => {
+                match rhs.result_type {
+                    NumberType::Real => {
+                        // ordinary pow, e.g. "a^b"
+                        MathResult::new(t, Complex::from(lhs.value.re.powf(rhs.value.re)))
+                    },
+
+                    NumberType::Complex => {
+                        // exponent is complex, e.g. "a^(b+ci)" = "exp(ln(a) * (b+ci))"
+                        MathResult::new(t, (rhs.value * lhs.value.re.ln()).exp())
+                    }
+                }
+            },
+
+            NumberType::Complex =>  {
+                // base is complex, e.g. "(a+bi)^c" = "exp(ln(a+bi) * c)" or
+                // base and exponent are complex, e.g. "(a+bi)^(c+di)" = "exp(ln(a+bi) * (c+di))"
+                MathResult::new(t, (lhs.value.ln() * rhs.value).exp())
+            }
+        }
+    }
+
+    /// Implements the mathematical factorial operation ("!"). Non-integer and complex numbers use the gamma function.
+    /// In the exact precision mode, the factorial of exact integers is computed exactly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(5.0_f64);
+    /// assert!(MathContext::operation_factorial(& arg).value.re == 120.0_f64);
+    /// ```
+    pub fn operation_factorial(arg: & MathResult) -> MathResult {
+        if let Some(ref r) = arg.exact {
+            if r.is_integer() {
+                if let Some(n) = r.numer().to_u64() {
+                    if n <= MAX_EXACT_FACTORIAL {
+                        let product = (2..n + 1).fold(BigInt::from(1), |acc, k| acc * BigInt::from(k));
+                        return MathResult::from(BigRational::from_integer(product));
+                    }
+                }
+            }
+        }
+        MathContext::function_gamma(& MathContext::operation_add(arg, & MathResult::from(1.0_f64)))
+    }
+
+    /// Implements the mathematical root operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(8.0_f64);
+    /// let root = MathResult::from(3.0_f64);
+    /// assert!(MathContext::operation_root(& arg, & root).value.re - 2.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn operation_root(arg: & MathResult, root: & MathResult) -> MathResult {
+        MathContext::operation_pow(arg, &MathResult::new(root.result_type.clone(), 1.0 / root.value))
+    }
+
+    /// Implements the mathematical cosine function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(0.0_f64);
+    /// assert!(MathContext::function_cos(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_cos(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.cos())
+    }
+
+    /// Implements the mathematical sine function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    /// use std::f64;
+    ///
+    /// let arg = MathResult::from(f64::consts::FRAC_PI_2);
+    /// assert!(MathContext::function_sin(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_sin(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.sin())
+    }
+
+    /// Implements the mathematical tangent function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    /// use std::f64;
+    ///
+    /// let arg = MathResult::from(f64::consts::FRAC_PI_4);
+    /// assert!(MathContext::function_tan(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_tan(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.tan())
+    }
+
+    /// Implements the mathematical cotangent function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    /// use std::f64;
+    ///
+    /// let arg = MathResult::from(f64::consts::FRAC_PI_4);
+    /// assert!(MathContext::function_cot(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_cot(arg: & MathResult) -> MathResult {
+        MathContext::operation_div(& MathResult::new(arg.result_type.clone(), arg.value.cos()),
+                                    & MathResult::new(arg.result_type.clone(), arg.value.sin()))
+    }
+
+    /// Implements the mathematical inverse cosine function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1.0_f64.cos());
+    /// assert!(MathContext::function_arccos(& arg).value.re - 1.0_f64 < 10e-10_f64);
     /// ```
     pub fn function_arccos(arg: & MathResult) -> MathResult {
         let t : NumberType = match arg.result_type {
             NumberType::Real => {
-                if !(arg.value.re <= 1.0_f64 && arg.value.re >= -1.0_f64) {
-                    NumberType::Complex
+                if !(arg.value.re <= 1.0_f64 && arg.value.re >= -1.0_f64) {
+                    NumberType::Complex
+                }
+                else {
+                    NumberType::Real
+                }
+            },
+
+            NumberType::Complex => NumberType::Complex
+        };
+
+        MathResult::new(t, arg.value.acos())
+    }
+
+    /// Implements the mathematical inverse sine function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1.0_f64.sin());
+    /// assert!(MathContext::function_arcsin(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_arcsin(arg: & MathResult) -> MathResult {
+        let t : NumberType = match arg.result_type {
+            NumberType::Real => {
+                if !(arg.value.re <= 1.0_f64 && arg.value.re >= -1.0_f64) {
+                    NumberType::Complex
+                }
+                else {
+                    NumberType::Real
+                }
+            },
+
+            NumberType::Complex => NumberType::Complex
+        };
+
+        MathResult::new(t, arg.value.asin())
+    }
+
+    /// Implements the mathematical inverse tangent function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1.0_f64.tan());
+    /// assert!(MathContext::function_arctan(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_arctan(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.atan())
+    }
+
+    /// Implements the mathematical inverse cotangent function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1.0_f64.cos() / 1.0_f64.sin());
+    /// assert!(MathContext::function_arccot(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_arccot(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), f64::consts::FRAC_PI_2 - arg.value.atan())
+    }
+
+    /// Implements the mathematical hyperbolic cosine function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(0.0_f64);
+    /// assert!(MathContext::function_cosh(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_cosh(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.cosh())
+    }
+
+    /// Implements the mathematical hyperbolic sine function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(0.5_f64.sinh());
+    /// assert!(MathContext::function_arctan(& arg).value.re - 0.5_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_sinh(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.sinh())
+    }
+
+    /// Implements the mathematical hyperbolic tangent function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(0.7_f64.tanh());
+    /// assert!(MathContext::function_arctanh(& arg).value.re - 0.7_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_tanh(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.tanh())
+    }
+
+    /// Implements the mathematical hyperbolic cotangent function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1.0_f64.cosh() / 1.0_f64.sinh());
+    /// assert!(MathContext::function_arccoth(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_coth(arg: & MathResult) -> MathResult {
+        MathContext::operation_div(& MathResult::new(arg.result_type.clone(), arg.value.cosh()),
+                                    & MathResult::new(arg.result_type.clone(), arg.value.sinh()))
+    }
+
+    /// Implements the mathematical inverse hyperbolic cosine function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1.0_f64.cosh());
+    /// assert!(MathContext::function_arccosh(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_arccosh(arg: & MathResult) -> MathResult {
+        let t : NumberType = match arg.result_type {
+            NumberType::Real => {
+                if !(arg.value.re >= 1.0_f64) {
+                    NumberType::Complex
+                }
+                else {
+                    NumberType::Real
+                }
+            },
+
+            NumberType::Complex => NumberType::Complex
+        };
+
+        MathResult::new(t, arg.value.acosh())
+    }
+
+    /// Implements the mathematical inverse hyperbolic sine function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1.0_f64.sinh());
+    /// assert!(MathContext::function_arcsinh(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_arcsinh(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.asinh())
+    }
+
+    /// Implements the mathematical inverse hyperbolic tangent function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1.0_f64.tanh());
+    /// assert!(MathContext::function_arctanh(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_arctanh(arg: & MathResult) -> MathResult {
+        let t : NumberType = match arg.result_type {
+            NumberType::Real => {
+                if !(arg.value.re > -1.0_f64 && arg.value.re < 1.0_f64) {
+                    NumberType::Complex
+                }
+                else {
+                    NumberType::Real
+                }
+            },
+
+            NumberType::Complex => NumberType::Complex
+        };
+
+        MathResult::new(t, arg.value.atanh())
+    }
+
+    /// Implements the mathematical inverse hyperbolic cotangent function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(0.5_f64.tanh());
+    /// assert!(MathContext::function_arccoth(& arg).value.re - 0.549306144_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_arccoth(arg: & MathResult) -> MathResult {
+        let t : NumberType = match arg.result_type {
+            NumberType::Real => {
+                if !(arg.value.re > 1.0_f64 || arg.value.re < -1.0_f64) {
+                    NumberType::Complex
+                }
+                else {
+                    NumberType::Real
+                }
+            },
+
+            NumberType::Complex => NumberType::Complex
+        };
+
+        let temp = MathResult::new(NumberType::Complex, -Complex::<f64>::i() * arg.value);
+        MathResult::new(t, 1.0_f64 / Complex::i() * MathContext::function_arccot(& temp).value)
+    }
+
+    /// Implements the mathematical exponential function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    /// use std::f64;
+    ///
+    /// let arg = MathResult::from(2.0_f64);
+    /// assert!(MathContext::function_exp(& arg).value.re - f64::consts::E * f64::consts::E < 10e-10_f64);
+    /// ```
+    pub fn function_exp(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), arg.value.exp())
+    }
+
+    /// Implements the mathematical logarithmus naturalis function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(5.0_f64.exp());
+    /// assert!(MathContext::function_ln(& arg).value.re - 5.0_f64 < 10e-10_f64);
+    /// ```
+    pub fn function_ln(arg: & MathResult) -> MathResult {
+        let t : NumberType = match arg.result_type {
+            NumberType::Real => {
+                if arg.value.re < 0.0_f64 {
+                    NumberType::Complex
+                }
+                else {
+                    NumberType::Real
+                }
+            },
+
+            NumberType::Complex => NumberType::Complex
+        };
+
+        MathResult::new(t, arg.value.ln())
+    }
+
+    /// Implements the mathematical logarithm function with the specified base.
+    /// The logarithms of positive real numbers to the bases 2 and 10 are computed directly, so that
+    /// e.g. "log(1000, 10)" is exactly 3.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(81.0_f64);
+    /// let base = MathResult::from(3.0_f64);
+    /// assert!((MathContext::function_log(& arg, & base).value.re - 4.0_f64).abs() < 10e-10_f64);
+    /// ```
+    pub fn function_log(arg: & MathResult, base: & MathResult) -> MathResult {
+        if arg.result_type == NumberType::Real && base.result_type == NumberType::Real && arg.value.re > 0.0_f64 {
+            if base.value.re == 2.0_f64 {
+                return MathResult::from(arg.value.re.log2());
+            }
+            else if base.value.re == 10.0_f64 {
+                return MathResult::from(arg.value.re.log10());
+            }
+        }
+        MathContext::operation_div(& MathContext::function_ln(arg), & MathContext::function_ln(base))
+    }
+
+    /// Implements the mathematical binary logarithm function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1024.0_f64);
+    /// assert!(MathContext::function_log2(& arg).value.re == 10.0_f64);
+    /// ```
+    pub fn function_log2(arg: & MathResult) -> MathResult {
+        MathContext::function_log(arg, & MathResult::from(2.0_f64))
+    }
+
+    /// Implements the mathematical decimal logarithm function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(1000.0_f64);
+    /// assert!(MathContext::function_log10(& arg).value.re == 3.0_f64);
+    /// ```
+    pub fn function_log10(arg: & MathResult) -> MathResult {
+        MathContext::function_log(arg, & MathResult::from(10.0_f64))
+    }
+
+    /// Implements the gamma function. Positive integers are computed as a product, all other numbers with the
+    /// Lanczos approximation (and the reflection formula for real parts less than 0.5).
+    /// The poles of the gamma function (zero and the negative integers) result in NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(0.5_f64);
+    /// assert!((MathContext::function_gamma(& arg).value.re - 3.14159265358979_f64.sqrt()).abs() < 10e-10_f64);
+    /// ```
+    pub fn function_gamma(arg: & MathResult) -> MathResult {
+        match arg.result_type {
+            NumberType::Real => {
+                let x = arg.value.re;
+                if x.fract() == 0.0_f64 && x <= 0.0_f64 {
+                    MathResult::from(f64::NAN)
+                }
+                else if x > MAX_GAMMA_ARG {
+                    MathResult::from(f64::INFINITY)
+                }
+                else if x.fract() == 0.0_f64 {
+                    MathResult::from((2..x as u64).fold(1.0_f64, |acc, k| acc * k as f64))
                 }
                 else {
-                    NumberType::Real
+                    MathResult::from(MathContext::lanczos_gamma(Complex::from(x)).re)
                 }
             },
+            NumberType::Complex => MathResult::new(NumberType::Complex, MathContext::lanczos_gamma(arg.value))
+        }
+    }
 
-            NumberType::Complex => NumberType::Complex
-        };
+    /// Approximates the natural logarithm of the gamma function for real numbers not less than 0.5 with the Lanczos
+    /// approximation. Unlike the gamma function itself, the result does not overflow for large arguments.
+    fn ln_gamma(x: f64) -> f64 {
+        let z = x - 1.0_f64;
+        let mut sum = LANCZOS_COEFFICIENTS[0];
+        for (i, c) in LANCZOS_COEFFICIENTS.iter().enumerate().skip(1) {
+            sum += *c / (z + i as f64);
+        }
+        let t = z + 7.5_f64;
+        0.5_f64 * (2.0_f64 * f64::consts::PI).ln() + (z + 0.5_f64) * t.ln() - t + sum.ln()
+    }
 
-        MathResult::new(t, arg.value.acos())
+    /// Approximates the gamma function with the Lanczos approximation.
+    fn lanczos_gamma(z: Complex<f64>) -> Complex<f64> {
+        let pi = Complex::from(f64::consts::PI);
+        if z.re < 0.5_f64 {
+            // reflection formula: gamma(z) * gamma(1-z) = pi/sin(pi*z)
+            pi / ((pi * z).sin() * MathContext::lanczos_gamma(Complex::from(1.0_f64) - z))
+        }
+        else {
+            let z = z - 1.0_f64;
+            let mut x = Complex::from(LANCZOS_COEFFICIENTS[0]);
+            for (i, c) in LANCZOS_COEFFICIENTS.iter().enumerate().skip(1) {
+                x = x + *c / (z + i as f64);
+            }
+            let t = z + 7.5_f64;
+            (2.0_f64 * f64::consts::PI).sqrt() * (t.ln() * (z + 0.5_f64)).exp() * (-t).exp() * x
+        }
     }
 
-    /// Implements the mathematical inverse sine function.
+    /// Implements the mathematical square root function.
     ///
     /// # Examples
     ///
@@ -775,13 +2330,13 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(1.0_f64.sin());
-    /// assert!(MathContext::function_arcsin(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let arg = MathResult::from(25.0_f64);
+    /// assert!(MathContext::function_sqrt(& arg).value.re - 5.0_f64 < 10e-10_f64);
     /// ```
-    pub fn function_arcsin(arg: & MathResult) -> MathResult {
+    pub fn function_sqrt(arg: & MathResult) -> MathResult {
         let t : NumberType = match arg.result_type {
             NumberType::Real => {
-                if !(arg.value.re <= 1.0_f64 && arg.value.re >= -1.0_f64) {
+                if arg.value.re < 0.0_f64 {
                     NumberType::Complex
                 }
                 else {
@@ -792,10 +2347,10 @@ impl<'a> MathContext {
             NumberType::Complex => NumberType::Complex
         };
 
-        MathResult::new(t, arg.value.asin())
+        MathResult::new(t, arg.value.sqrt())
     }
 
-    /// Implements the mathematical inverse tangent function.
+    /// Implements the mathematical imaginary-part function.
     ///
     /// # Examples
     ///
@@ -803,14 +2358,15 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(1.0_f64.tan());
-    /// assert!(MathContext::function_arctan(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let arg = MathResult::from((25.7, 89.224));
+    /// assert!(MathContext::function_im(& arg).value.im - 89.224_f64 < 10e-10_f64);
+    /// assert!(MathContext::function_im(& arg).value.re - 0.0_f64 < 10e-10_f64);
     /// ```
-    pub fn function_arctan(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.atan())
+    pub fn function_im(arg: & MathResult) -> MathResult {
+        MathResult::new(NumberType::Complex, Complex::new(0.0_f64, arg.value.im))
     }
 
-    /// Implements the mathematical inverse cotangent function.
+    /// Implements the mathematical imaginary-part function.
     ///
     /// # Examples
     ///
@@ -818,14 +2374,15 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(1.0_f64.cos() / 1.0_f64.sin());
-    /// assert!(MathContext::function_arccot(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let arg = MathResult::from((25.7, 89.224));
+    /// assert!(MathContext::function_re(& arg).value.im - 0.0_f64 < 10e-10_f64);
+    /// assert!(MathContext::function_re(& arg).value.re - 25.7_f64 < 10e-10_f64);
     /// ```
-    pub fn function_arccot(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), f64::consts::FRAC_PI_2 - arg.value.atan())
+    pub fn function_re(arg: & MathResult) -> MathResult {
+        MathResult::new(NumberType::Real, Complex::new(arg.value.re, 0.0_f64))
     }
 
-    /// Implements the mathematical hyperbolic cosine function.
+    /// Implements the absolute value function. The result of complex numbers is their modulus.
     ///
     /// # Examples
     ///
@@ -833,14 +2390,15 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(0.0_f64);
-    /// assert!(MathContext::function_cosh(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// assert!(MathContext::function_abs(& MathResult::from(-2.5)) == MathResult::from(2.5));
+    /// assert!(MathContext::function_abs(& MathResult::from((3.0, -4.0))) == MathResult::from(5.0));
     /// ```
-    pub fn function_cosh(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.cosh())
+    pub fn function_abs(arg: & MathResult) -> MathResult {
+        MathResult::from(arg.value.norm())
     }
 
-    /// Implements the mathematical hyperbolic sine function.
+    /// Implements the sign function. The result of complex numbers is the number divided by its modulus,
+    /// the sign of zero is zero.
     ///
     /// # Examples
     ///
@@ -848,14 +2406,18 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(0.5_f64.sinh());
-    /// assert!(MathContext::function_arctan(& arg).value.re - 0.5_f64 < 10e-10_f64);
+    /// assert!(MathContext::function_sign(& MathResult::from(-2.5)) == MathResult::from(-1.0));
+    /// assert!(MathContext::function_sign(& MathResult::from((3.0, -4.0))) == MathResult::from((0.6, -0.8)));
     /// ```
-    pub fn function_sinh(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.sinh())
+    pub fn function_sign(arg: & MathResult) -> MathResult {
+        let modulus = arg.value.norm();
+        if modulus == 0.0 {
+            return MathResult::from(0.0);
+        }
+        MathResult::new(arg.result_type.clone(), arg.value / modulus)
     }
 
-    /// Implements the mathematical hyperbolic tangent function.
+    /// Implements the complex conjugate.
     ///
     /// # Examples
     ///
@@ -863,14 +2425,16 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(0.7_f64.tanh());
-    /// assert!(MathContext::function_arctanh(& arg).value.re - 0.7_f64 < 10e-10_f64);
+    /// assert!(MathContext::function_conj(& MathResult::from((3.0, -4.0))) == MathResult::from((3.0, 4.0)));
+    /// assert!(MathContext::function_conj(& MathResult::from(2.5)) == MathResult::from(2.5));
     /// ```
-    pub fn function_tanh(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.tanh())
+    pub fn function_conj(arg: & MathResult) -> MathResult {
+        let mut x = arg.clone();
+        x.value = arg.value.conj();
+        x
     }
 
-    /// Implements the mathematical hyperbolic cotangent function.
+    /// Implements the argument (the phase) of a complex number in radians in the interval (-pi, pi].
     ///
     /// # Examples
     ///
@@ -878,14 +2442,16 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(1.0_f64.cosh() / 1.0_f64.sinh());
-    /// assert!(MathContext::function_arccoth(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// assert!(MathContext::function_arg(& MathResult::from((0.0, 2.0))).value.re - 1.5707963267948966 < 10e-10);
+    /// assert!(MathContext::function_arg(& MathResult::from(-1.0)).value.re - 3.141592653589793 < 10e-10);
     /// ```
-    pub fn function_coth(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.cosh() / arg.value.sinh())
+    pub fn function_arg(arg: & MathResult) -> MathResult {
+        MathResult::from(arg.value.arg())
     }
 
-    /// Implements the mathematical inverse hyperbolic cosine function.
+    /// Implements the construction of a complex number from its modulus r and its argument theta (in radians).
+    /// The modulus and the argument must be real, otherwise the result is NaN. Components that only differ from zero by
+    /// rounding errors are zero (e.g. the real part of "polar(2, pi/2)").
     ///
     /// # Examples
     ///
@@ -893,27 +2459,49 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(1.0_f64.cosh());
-    /// assert!(MathContext::function_arccosh(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let z = MathContext::function_polar(& MathResult::from(2.0), & MathResult::from(1.5707963267948966));
+    /// assert!(z == MathResult::from((0.0, 2.0)));
     /// ```
-    pub fn function_arccosh(arg: & MathResult) -> MathResult {
-        let t : NumberType = match arg.result_type {
-            NumberType::Real => {
-                if !(arg.value.re >= 1.0_f64) {
-                    NumberType::Complex
-                }
-                else {
-                    NumberType::Real
-                }
-            },
+    pub fn function_polar(r: & MathResult, theta: & MathResult) -> MathResult {
+        if r.result_type == NumberType::Complex || theta.result_type == NumberType::Complex {
+            return MathResult::from(f64::NAN);
+        }
+        let z = Complex::from_polar(& r.value.re, & theta.value.re);
+        let round = |x: f64| if x.abs() <= 2.0 * f64::EPSILON * r.value.re.abs() { 0.0 } else { x };
+        MathResult::from((round(z.re), round(z.im)))
+    }
 
-            NumberType::Complex => NumberType::Complex
-        };
+    /// Implements the floor function. Complex numbers are rounded componentwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from((2.7, -1.2));
+    /// assert!(MathContext::function_floor(& arg) == MathResult::from((2.0, -2.0)));
+    /// ```
+    pub fn function_floor(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), Complex::new(arg.value.re.floor(), arg.value.im.floor()))
+    }
 
-        MathResult::new(t, arg.value.acosh())
+    /// Implements the ceiling function. Complex numbers are rounded componentwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from((2.2, -1.7));
+    /// assert!(MathContext::function_ceil(& arg) == MathResult::from((3.0, -1.0)));
+    /// ```
+    pub fn function_ceil(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), Complex::new(arg.value.re.ceil(), arg.value.im.ceil()))
     }
 
-    /// Implements the mathematical inverse hyperbolic sine function.
+    /// Implements the truncation function (rounding towards zero). Complex numbers are truncated componentwise.
     ///
     /// # Examples
     ///
@@ -921,14 +2509,15 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(1.0_f64.sinh());
-    /// assert!(MathContext::function_arcsinh(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let arg = MathResult::from((2.7, -1.7));
+    /// assert!(MathContext::function_trunc(& arg) == MathResult::from((2.0, -1.0)));
     /// ```
-    pub fn function_arcsinh(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.asinh())
+    pub fn function_trunc(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), Complex::new(arg.value.re.trunc(), arg.value.im.trunc()))
     }
 
-    /// Implements the mathematical inverse hyperbolic tangent function.
+    /// Implements the fractional part function. The result has the same sign as the argument.
+    /// Complex numbers are processed componentwise.
     ///
     /// # Examples
     ///
@@ -936,27 +2525,63 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(1.0_f64.tanh());
-    /// assert!(MathContext::function_arctanh(& arg).value.re - 1.0_f64 < 10e-10_f64);
+    /// let arg = MathResult::from(-2.75);
+    /// assert!(MathContext::function_frac(& arg).value.re + 0.75 < 10e-10_f64);
     /// ```
-    pub fn function_arctanh(arg: & MathResult) -> MathResult {
-        let t : NumberType = match arg.result_type {
-            NumberType::Real => {
-                if !(arg.value.re > -1.0_f64 && arg.value.re < 1.0_f64) {
-                    NumberType::Complex
-                }
-                else {
-                    NumberType::Real
-                }
-            },
+    pub fn function_frac(arg: & MathResult) -> MathResult {
+        MathResult::new(arg.result_type.clone(), Complex::new(arg.value.re.fract(), arg.value.im.fract()))
+    }
 
-            NumberType::Complex => NumberType::Complex
-        };
+    /// Implements the rounding function that rounds the argument to the specified number of decimal places.
+    /// Halfway cases are rounded away from zero and complex numbers are rounded componentwise.
+    /// The number of decimal places must be a real integer (it may be negative), otherwise the result is NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let arg = MathResult::from(3.14159);
+    /// let digits = MathResult::from(2.0);
+    /// assert!(MathContext::function_round(& arg, & digits).value.re - 3.14 < 10e-10_f64);
+    /// ```
+    pub fn function_round(arg: & MathResult, digits: & MathResult) -> MathResult {
+        if digits.result_type == NumberType::Complex || MathContext::has_decimal_places(digits.value.re) {
+            return MathResult::from(f64::NAN);
+        }
 
-        MathResult::new(t, arg.value.atanh())
+        let factor = 10.0_f64.powi(digits.value.re as i32);
+        MathResult::new(arg.result_type.clone(), Complex::new((arg.value.re * factor).round() / factor,
+                                                              (arg.value.im * factor).round() / factor))
     }
 
-    /// Implements the mathematical inverse hyperbolic cotangent function.
+    /// Implements the divmod function, which returns the quotient of the floor division ("//") and the remainder as vector.
+    /// Unlike the remainder of "%", the remainder has the sign of the divisor, so that the dividend is quotient * divisor + remainder.
+    /// The imaginary parts of the arguments are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::{MathResult, Matrix};
+    ///
+    /// let lhs = MathResult::from(-7.0_f64);
+    /// let rhs = MathResult::from(2.0_f64);
+    /// let expected = Matrix::new(1, 2, vec![MathResult::from(-4.0_f64), MathResult::from(1.0_f64)]);
+    /// assert!(MathContext::function_divmod(& lhs, & rhs) == MathResult::from(expected));
+    /// ```
+    pub fn function_divmod(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        // exact numbers are real, otherwise only the real parts are used
+        let real = |x: & MathResult| if x.is_exact() { x.clone() } else { MathContext::function_re(x) };
+        let (lhs, rhs) = (real(lhs), real(rhs));
+        let quotient = MathContext::operation_int_div(& lhs, & rhs);
+        let remainder = MathContext::operation_sub(& lhs, & MathContext::operation_mul(& quotient, & rhs));
+        MathResult::from(Matrix::new(1, 2, vec![quotient, remainder]))
+    }
+
+    /// Implements the greatest common divisor of two integers (the result is not negative and "gcd(0, 0)" is 0).
+    /// The result is NaN if an argument is not an integer (see MathResult::to_i64).
     ///
     /// # Examples
     ///
@@ -964,44 +2589,173 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(0.5_f64.tanh());
-    /// assert!(MathContext::function_arccoth(& arg).value.re - 0.549306144_f64 < 10e-10_f64);
+    /// let lhs = MathResult::from(-12.0_f64);
+    /// let rhs = MathResult::from(18.0_f64);
+    /// assert!(MathContext::function_gcd(& lhs, & rhs).value.re == 6.0_f64);
     /// ```
-    pub fn function_arccoth(arg: & MathResult) -> MathResult {
-        let t : NumberType = match arg.result_type {
-            NumberType::Real => {
-                if !(arg.value.re > 1.0_f64 || arg.value.re < -1.0_f64) {
-                    NumberType::Complex
-                }
-                else {
-                    NumberType::Real
-                }
+    pub fn function_gcd(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        match (lhs.to_i64(), rhs.to_i64()) {
+            (Some(a), Some(b)) => {
+                let gcd = MathContext::gcd(a.wrapping_abs() as u64, b.wrapping_abs() as u64);
+                MathContext::from_integer(BigInt::from(gcd), lhs.is_exact() && rhs.is_exact())
             },
+            _ => MathResult::from(f64::NAN)
+        }
+    }
 
-            NumberType::Complex => NumberType::Complex
+    /// Implements the least common multiple of two integers (the result is not negative and 0 if an argument is 0).
+    /// The result is NaN if an argument is not an integer (see MathResult::to_i64).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let lhs = MathResult::from(4.0_f64);
+    /// let rhs = MathResult::from(6.0_f64);
+    /// assert!(MathContext::function_lcm(& lhs, & rhs).value.re == 12.0_f64);
+    /// ```
+    pub fn function_lcm(lhs: & MathResult, rhs: & MathResult) -> MathResult {
+        match (lhs.to_i64(), rhs.to_i64()) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (a.wrapping_abs() as u64, b.wrapping_abs() as u64);
+                let lcm = if a == 0 || b == 0 { BigInt::zero() } else { BigInt::from(a / MathContext::gcd(a, b)) * BigInt::from(b) };
+                MathContext::from_integer(lcm, lhs.is_exact() && rhs.is_exact())
+            },
+            _ => MathResult::from(f64::NAN)
+        }
+    }
+
+    /// Checks whether the argument is a prime number (the result is 1 or 0).
+    /// The result is NaN if the argument is not an integer (see MathResult::to_i64).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// assert!(MathContext::function_isprime(& MathResult::from(97.0_f64)).value.re == 1.0_f64);
+    /// assert!(MathContext::function_isprime(& MathResult::from(91.0_f64)).value.re == 0.0_f64);
+    /// ```
+    pub fn function_isprime(arg: & MathResult) -> MathResult {
+        match arg.to_i64() {
+            Some(n) => MathResult::from(if n > 1 && MathContext::is_prime(n as u64) { 1.0_f64 } else { 0.0_f64 }),
+            None => MathResult::from(f64::NAN)
+        }
+    }
+
+    /// Implements the prime factorization of an integer greater than 1, which is returned as vector of the prime factors in
+    /// ascending order (e.g. [2, 2, 3] for 12). The result is NaN if the argument is not an integer greater than 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::{MathResult, Matrix};
+    ///
+    /// let factors = vec![MathResult::from(2.0_f64), MathResult::from(2.0_f64), MathResult::from(3.0_f64)];
+    /// assert!(MathContext::function_factor(& MathResult::from(12.0_f64)) == MathResult::from(Matrix::new(1, 3, factors)));
+    /// ```
+    pub fn function_factor(arg: & MathResult) -> MathResult {
+        let n = match arg.to_i64() {
+            Some(n) if n > 1 => n as u64,
+            _ => return MathResult::from(f64::NAN)
         };
+        let mut factors = Vec::new();
+        MathContext::factorize(n, & mut factors);
+        factors.sort();
+        let factors : Vec<MathResult> = factors.into_iter().map(|p| MathContext::from_integer(BigInt::from(p), arg.is_exact())).collect();
+        MathResult::from(Matrix::new(1, factors.len(), factors))
+    }
 
-        let temp = MathResult::new(NumberType::Complex, -Complex::<f64>::i() * arg.value);
-        MathResult::new(t, 1.0_f64 / Complex::i() * MathContext::function_arccot(& temp).value)
+    /// Implements the binomial coefficient "n choose k" of two non-negative integers (0 if k is greater than n).
+    /// Small coefficients are multiplied exactly (without overflow), larger coefficients are approximated with the
+    /// logarithm of the gamma function (see MAX_EXACT_COMBINATORICS). The result is NaN if an argument is not a non-negative
+    /// integer (see MathResult::to_i64).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let n = MathResult::from(5.0_f64);
+    /// let k = MathResult::from(2.0_f64);
+    /// assert!(MathContext::function_ncr(& n, & k).value.re == 10.0_f64);
+    /// ```
+    pub fn function_ncr(n: & MathResult, k: & MathResult) -> MathResult {
+        let (n, k, exact) = match MathContext::combinatorics_args(n, k) {
+            Some(x) => x,
+            None => return MathResult::from(f64::NAN)
+        };
+        if k > n {
+            return MathContext::from_integer(BigInt::zero(), exact);
+        }
+
+        // "n choose k" equals "n choose n-k", thus the smaller one needs fewer factors
+        let k = if n - k < k { n - k } else { k };
+        if MathContext::is_exact_combinatorics(k, exact) {
+            // each partial product is a binomial coefficient itself and thus, the division is exact
+            let x = (1..k + 1).fold(BigInt::from(1), |acc, i| acc * BigInt::from(n - k + i) / BigInt::from(i));
+            MathContext::from_integer(x, exact)
+        }
+        else {
+            let (n, k) = (n as f64, k as f64);
+            MathResult::from((MathContext::ln_gamma(n + 1.0_f64) - MathContext::ln_gamma(k + 1.0_f64)
+                - MathContext::ln_gamma(n - k + 1.0_f64)).exp())
+        }
     }
 
-    /// Implements the mathematical exponential function.
+    /// Implements the number of k-permutations of n ("n! / (n-k)!") of two non-negative integers (0 if k is greater
+    /// than n). Small counts are multiplied exactly (without overflow), larger counts are approximated with the
+    /// logarithm of the gamma function (see MAX_EXACT_COMBINATORICS). The result is NaN if an argument is not a non-negative
+    /// integer (see MathResult::to_i64).
     ///
     /// # Examples
     ///
     /// ```
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
-    /// use std::f64;
     ///
-    /// let arg = MathResult::from(2.0_f64);
-    /// assert!(MathContext::function_exp(& arg).value.re - f64::consts::E * f64::consts::E < 10e-10_f64);
+    /// let n = MathResult::from(5.0_f64);
+    /// let k = MathResult::from(2.0_f64);
+    /// assert!(MathContext::function_npr(& n, & k).value.re == 20.0_f64);
     /// ```
-    pub fn function_exp(arg: & MathResult) -> MathResult {
-        MathResult::new(arg.result_type.clone(), arg.value.exp())
+    pub fn function_npr(n: & MathResult, k: & MathResult) -> MathResult {
+        let (n, k, exact) = match MathContext::combinatorics_args(n, k) {
+            Some(x) => x,
+            None => return MathResult::from(f64::NAN)
+        };
+        if k > n {
+            MathContext::from_integer(BigInt::zero(), exact)
+        }
+        else if MathContext::is_exact_combinatorics(k, exact) {
+            let x = (n - k + 1..n + 1).fold(BigInt::from(1), |acc, i| acc * BigInt::from(i));
+            MathContext::from_integer(x, exact)
+        }
+        else {
+            let (n, k) = (n as f64, k as f64);
+            MathResult::from((MathContext::ln_gamma(n + 1.0_f64) - MathContext::ln_gamma(n - k + 1.0_f64)).exp())
+        }
     }
 
-    /// Implements the mathematical logarithmus naturalis function.
+    /// Checks whether the specified number of factors of a combinatorics function are multiplied exactly.
+    fn is_exact_combinatorics(factors: u64, exact: bool) -> bool {
+        factors <= MAX_EXACT_COMBINATORICS || (exact && factors <= MAX_EXACT_FACTORIAL)
+    }
+
+    /// Returns the arguments of a combinatorics function as non-negative integers and whether both are exact.
+    /// Returns None if an argument is not a non-negative integer.
+    fn combinatorics_args(n: & MathResult, k: & MathResult) -> Option<(u64, u64, bool)> {
+        match (n.to_i64(), k.to_i64()) {
+            (Some(a), Some(b)) if a >= 0 && b >= 0 => Some((a as u64, b as u64, n.is_exact() && k.is_exact())),
+            _ => None
+        }
+    }
+
+    /// Implements the sum of any number of arguments.
     ///
     /// # Examples
     ///
@@ -1009,27 +2763,51 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(5.0_f64.exp());
-    /// assert!(MathContext::function_ln(& arg).value.re - 5.0_f64 < 10e-10_f64);
+    /// let args = vec![MathResult::from(1.5), MathResult::from((2.0, 1.0))];
+    /// assert!(MathContext::function_sum(& args) == MathResult::from((3.5, 1.0)));
     /// ```
-    pub fn function_ln(arg: & MathResult) -> MathResult {
-        let t : NumberType = match arg.result_type {
-            NumberType::Real => {
-                if arg.value.re < 0.0_f64 {
-                    NumberType::Complex
-                }
-                else {
-                    NumberType::Real
-                }
-            },
+    pub fn function_sum(args: & Vec<MathResult>) -> MathResult {
+        let (first, rest) = args.split_first().unwrap();
+        rest.iter().fold(first.clone(), |sum, x| MathContext::operation_add(& sum, x))
+    }
 
-            NumberType::Complex => NumberType::Complex
-        };
+    /// Implements the minimum of any number of real arguments. The result is NaN if an argument is complex.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let args = vec![MathResult::from(3.0), MathResult::from(-1.0), MathResult::from(2.0)];
+    /// assert!(MathContext::function_min(& args) == MathResult::from(-1.0));
+    /// ```
+    pub fn function_min(args: & Vec<MathResult>) -> MathResult {
+        match MathContext::sort_real_args(args) {
+            Some(sorted) => sorted[0].clone(),
+            None => MathResult::from(f64::NAN)
+        }
+    }
 
-        MathResult::new(t, arg.value.ln())
+    /// Implements the maximum of any number of real arguments. The result is NaN if an argument is complex.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let args = vec![MathResult::from(3.0), MathResult::from(-1.0), MathResult::from(2.0)];
+    /// assert!(MathContext::function_max(& args) == MathResult::from(3.0));
+    /// ```
+    pub fn function_max(args: & Vec<MathResult>) -> MathResult {
+        match MathContext::sort_real_args(args) {
+            Some(sorted) => sorted[sorted.len() - 1].clone(),
+            None => MathResult::from(f64::NAN)
+        }
     }
 
-    /// Implements the mathematical square root function.
+    /// Implements the arithmetic mean of any number of arguments. The mean of exact numbers is exact.
     ///
     /// # Examples
     ///
@@ -1037,27 +2815,44 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from(25.0_f64);
-    /// assert!(MathContext::function_sqrt(& arg).value.re - 5.0_f64 < 10e-10_f64);
+    /// let args = vec![MathResult::from(1.0), MathResult::from(2.0), MathResult::from(3.0), MathResult::from(4.0)];
+    /// assert!(MathContext::function_mean(& args) == MathResult::from(2.5));
     /// ```
-    pub fn function_sqrt(arg: & MathResult) -> MathResult {
-        let t : NumberType = match arg.result_type {
-            NumberType::Real => {
-                if arg.value.re < 0.0_f64 {
-                    NumberType::Complex
-                }
-                else {
-                    NumberType::Real
-                }
-            },
+    pub fn function_mean(args: & Vec<MathResult>) -> MathResult {
+        let sum = MathContext::function_sum(args);
+        let n = MathContext::create_count(args.len(), sum.exact.is_some());
+        MathContext::operation_div(& sum, & n)
+    }
 
-            NumberType::Complex => NumberType::Complex
+    /// Implements the median of any number of real arguments. The median of an even number of arguments is the mean
+    /// of the two middle values. The result is NaN if an argument is complex.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let args = vec![MathResult::from(4.0), MathResult::from(1.0), MathResult::from(3.0), MathResult::from(2.0)];
+    /// assert!(MathContext::function_median(& args) == MathResult::from(2.5));
+    /// ```
+    pub fn function_median(args: & Vec<MathResult>) -> MathResult {
+        let sorted = match MathContext::sort_real_args(args) {
+            Some(sorted) => sorted,
+            None => return MathResult::from(f64::NAN)
         };
-
-        MathResult::new(t, arg.value.sqrt())
+        let mid = sorted.len() / 2;
+        if sorted.len() % 2 == 1 {
+            sorted[mid].clone()
+        }
+        else {
+            MathContext::function_mean(& vec![sorted[mid - 1].clone(), sorted[mid].clone()])
+        }
     }
 
-    /// Implements the mathematical imaginary-part function.
+    /// Implements the sample variance of at least two real arguments (the sum of the squared deviations from the mean
+    /// divided by the number of arguments minus one). The result is NaN if an argument is complex or there are less
+    /// than two arguments.
     ///
     /// # Examples
     ///
@@ -1065,15 +2860,24 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from((25.7, 89.224));
-    /// assert!(MathContext::function_im(& arg).value.im - 89.224_f64 < 10e-10_f64);
-    /// assert!(MathContext::function_im(& arg).value.re - 0.0_f64 < 10e-10_f64);
+    /// let args = vec![MathResult::from(2.0), MathResult::from(4.0), MathResult::from(6.0)];
+    /// assert!(MathContext::function_var(& args) == MathResult::from(4.0));
     /// ```
-    pub fn function_im(arg: & MathResult) -> MathResult {
-        MathResult::new(NumberType::Complex, Complex::new(0.0_f64, arg.value.im))
+    pub fn function_var(args: & Vec<MathResult>) -> MathResult {
+        if args.len() < 2 || MathContext::sort_real_args(args).is_none() {
+            return MathResult::from(f64::NAN);
+        }
+        let mean = MathContext::function_mean(args);
+        let squares : Vec<MathResult> = args.iter().map(|x| {
+            let d = MathContext::operation_sub(x, & mean);
+            MathContext::operation_mul(& d, & d)
+        }).collect();
+        let sum = MathContext::function_sum(& squares);
+        let n = MathContext::create_count(args.len() - 1, sum.exact.is_some());
+        MathContext::operation_div(& sum, & n)
     }
 
-    /// Implements the mathematical imaginary-part function.
+    /// Implements the sample standard deviation of at least two real arguments (the square root of the sample variance).
     ///
     /// # Examples
     ///
@@ -1081,12 +2885,31 @@ impl<'a> MathContext {
     /// use termc_model::math_context::MathContext;
     /// use termc_model::math_result::MathResult;
     ///
-    /// let arg = MathResult::from((25.7, 89.224));
-    /// assert!(MathContext::function_re(& arg).value.im - 0.0_f64 < 10e-10_f64);
-    /// assert!(MathContext::function_re(& arg).value.re - 25.7_f64 < 10e-10_f64);
+    /// let args = vec![MathResult::from(2.0), MathResult::from(4.0), MathResult::from(6.0)];
+    /// assert!((MathContext::function_stddev(& args).value.re - 2.0).abs() < 10e-10_f64);
     /// ```
-    pub fn function_re(arg: & MathResult) -> MathResult {
-        MathResult::new(NumberType::Real, Complex::new(arg.value.re, 0.0_f64))
+    pub fn function_stddev(args: & Vec<MathResult>) -> MathResult {
+        MathContext::function_sqrt(& MathContext::function_var(args))
+    }
+
+    /// Returns the specified arguments sorted by their values or None if an argument is complex.
+    fn sort_real_args(args: & Vec<MathResult>) -> Option<Vec<MathResult>> {
+        if args.iter().any(|x| x.result_type == NumberType::Complex) {
+            return None;
+        }
+        let mut sorted = args.clone();
+        sorted.sort_by(|a, b| a.value.re.partial_cmp(& b.value.re).unwrap_or(Ordering::Equal));
+        Some(sorted)
+    }
+
+    /// Creates the number of arguments of a statistics function, which is exact if the arguments are exact.
+    fn create_count(n: usize, exact: bool) -> MathResult {
+        if exact {
+            MathResult::from(BigRational::from_integer(BigInt::from(n as i64)))
+        }
+        else {
+            MathResult::from(n as f64)
+        }
     }
 
     /// Returns the result type for a mathematical expression with the given operands.
@@ -1103,6 +2926,7 @@ impl<'a> MathContext {
     }
 
     /// Adds the specified user constant to the mathematical context.
+    /// If the constant is already defined, its previous value is kept in the history of the constant and its usage is reset.
     ///
     /// # Examples
     ///
@@ -1126,7 +2950,155 @@ impl<'a> MathContext {
     /// }
     /// ```
     pub fn add_user_constant<S>(& mut self, repr: S, value: MathResult) where S: Into<String> {
-        self.user_constants.insert(repr.into(), value);
+        let repr_string = repr.into();
+        self.used_user_definitions.remove(& repr_string);
+        self.session_definitions.insert(repr_string.clone());
+        if let Some(previous) = self.user_constants.insert(repr_string.clone(), value) {
+            let history = self.user_constant_history.entry(repr_string).or_insert_with(VecDeque::new);
+            history.push_front(previous);
+            history.truncate(MAX_CONSTANT_HISTORY);
+        }
+    }
+
+    /// Gets the k-th previous value of the specified user constant (k = 1 is the value before the current one).
+    /// Returns None if the constant has less than k previous values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let mut context = MathContext::new();
+    /// context.add_user_constant("x", MathResult::from(1.0));
+    /// context.add_user_constant("x", MathResult::from(2.0));
+    /// context.add_user_constant("x", MathResult::from(3.0));
+    ///
+    /// assert!(context.get_previous_constant_value("x", 2) == Some(MathResult::from(1.0)));
+    /// assert!(context.get_previous_constant_value("x", 3).is_none());
+    /// ```
+    pub fn get_previous_constant_value(& self, repr: & str, k: usize) -> Option<MathResult> {
+        if k == 0 {
+            return None;
+        }
+        self.user_constant_history.get(repr).and_then(|h| h.get(k - 1)).cloned()
+    }
+
+    /// Gets the number of previous values of the specified user constant.
+    pub fn get_previous_constant_value_count(& self, repr: & str) -> usize {
+        self.user_constant_history.get(repr).map_or(0, |h| h.len())
+    }
+
+    /// Adds an evaluation (input and result) to the transcript of this session and returns its number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let mut context = MathContext::new();
+    /// assert!(context.add_transcript_entry("x = 2", None) == 1);
+    /// assert!(context.add_transcript_entry("x + 1", Some(MathResult::from(3.0))) == 2);
+    ///
+    /// assert!(context.get_transcript_input(1) == Some(String::from("x = 2")));
+    /// assert!(context.get_output(2) == Some(MathResult::from(3.0)));
+    /// assert!(context.get_output(1).is_none());
+    /// ```
+    pub fn add_transcript_entry<S>(& mut self, input: S, output: Option<MathResult>) -> usize where S: Into<String> {
+        self.transcript.push((input.into(), output));
+        self.transcript.len()
+    }
+
+    /// Gets the input of the evaluation with the specified number.
+    pub fn get_transcript_input(& self, n: usize) -> Option<String> {
+        if n == 0 {
+            return None;
+        }
+        self.transcript.get(n - 1).map(|e| e.0.clone())
+    }
+
+    /// Gets the result of the evaluation with the specified number.
+    /// Returns None if there is no such evaluation or if it had no result (e.g. an assignment).
+    pub fn get_output(& self, n: usize) -> Option<MathResult> {
+        if n == 0 {
+            return None;
+        }
+        self.transcript.get(n - 1).and_then(|e| e.1.clone())
+    }
+
+    /// Gets the number of evaluations in the transcript of this session.
+    pub fn get_transcript_len(& self) -> usize {
+        self.transcript.len()
+    }
+
+    /// Moves the transcript and the previous results of the specified context into this context
+    /// (e.g. after loading a serialized context).
+    pub fn take_transcript(& mut self, other: & mut MathContext) {
+        self.transcript = ::std::mem::replace(& mut other.transcript, Vec::new());
+        self.results = ::std::mem::replace(& mut other.results, VecDeque::new());
+    }
+
+    /// Adds a warning of an evaluation, which is reported after the evaluation.
+    /// A warning that has not been reported yet is not added again (e.g. for a literal in a sum).
+    pub fn add_warning<S>(& mut self, warning: S) where S: Into<String> {
+        let warning = warning.into();
+        if !self.warnings.contains(& warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Removes and returns the warnings that have not been reported yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// get_result("f(x, y) = x + 1", &mut context).unwrap();
+    /// assert!(context.take_warnings() == vec!["Warning: The parameter \"y\" of the function \"f\" is not used."]);
+    /// assert!(context.take_warnings().is_empty());
+    /// ```
+    pub fn take_warnings(& mut self) -> Vec<String> {
+        ::std::mem::replace(& mut self.warnings, Vec::new())
+    }
+
+    /// Adds a numerical result of an evaluation to the previous results ("ans1", "ans2", ...).
+    /// Only the last MAX_RESULT_HISTORY results are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let mut context = MathContext::new();
+    /// context.add_result(MathResult::from(1.0));
+    /// context.add_result(MathResult::from(2.0));
+    ///
+    /// assert!(context.get_previous_result(1) == Some(MathResult::from(2.0)));
+    /// assert!(context.get_constant_value("ans2") == Some(MathResult::from(1.0)));
+    /// assert!(context.get_previous_result(3).is_none());
+    /// ```
+    pub fn add_result(& mut self, result: MathResult) {
+        self.results.push_front(result);
+        self.results.truncate(MAX_RESULT_HISTORY);
+    }
+
+    /// Gets the n-th previous result (n = 1 is the most recent result).
+    /// Returns None if there are less than n previous results.
+    pub fn get_previous_result(& self, n: usize) -> Option<MathResult> {
+        if n == 0 {
+            return None;
+        }
+        self.results.get(n - 1).cloned()
+    }
+
+    /// Gets the number of previous results.
+    pub fn get_previous_result_count(& self) -> usize {
+        self.results.len()
     }
 
     /// Adds the specified user constant to the mathematical context.
@@ -1159,6 +3131,9 @@ impl<'a> MathContext {
     pub fn remove_user_constant<S>(& mut self, repr: S) where S: Into<String> {
         let repr_string = repr.into();
         self.user_constants.remove(& repr_string);
+        self.user_constant_history.remove(& repr_string);
+        self.used_user_definitions.remove(& repr_string);
+        self.session_definitions.remove(& repr_string);
     }
 
     /// Adds the specified user function to the mathematical context.
@@ -1193,8 +3168,35 @@ impl<'a> MathContext {
     pub fn add_user_function<S1, S2>(& mut self, repr: S1, t: TreeNode<Token>, vars: Vec<String>,
                                      input: S2) where S1: Into<String>, S2: Into<String> {
         let repr_string : String = repr.into();
-        self.user_functions.insert(repr_string.clone(), (t, vars));
-        self.user_function_inputs.insert(repr_string, input.into());
+        let canonical = self.build_canonical_input(& repr_string, & t, & vars);
+        let dependencies = MathContext::collect_dependencies(& repr_string, & t);
+        self.user_functions.insert(repr_string.clone(), (Rc::new(t), vars));
+        self.user_function_inputs.insert(repr_string.clone(), input.into());
+        self.user_function_canonical_inputs.insert(repr_string.clone(), canonical);
+        self.user_function_dependencies.insert(repr_string.clone(), dependencies);
+        self.session_definitions.insert(repr_string);
+    }
+
+    /// Returns the names of the user constants and user functions that are referenced in the specified expression tree
+    /// of the specified user function. Recursive calls of the function itself are no dependencies.
+    fn collect_dependencies(repr: & str, t: & TreeNode<Token>) -> HashSet<String> {
+        let mut dependencies = HashSet::new();
+        let mut stack = vec![t];
+        while let Some(node) = stack.pop() {
+            match node.content.get_type() {
+                TokenType::UserConstant | TokenType::UserFunction if node.content.get_value() != repr => {
+                    dependencies.insert(node.content.get_value().to_string());
+                },
+                _ => ()
+            }
+            stack.extend(node.successors.iter().map(|s| s.as_ref()));
+        }
+        dependencies
+    }
+
+    /// Builds the canonical definition string of the specified user function, e.g. "f(x, y) = x^2 + y".
+    fn build_canonical_input(& self, repr: & str, t: & TreeNode<Token>, vars: & Vec<String>) -> String {
+        format!("{0}({1}) = {2}", repr, vars.join(", "), deparser::deparse(t, self))
     }
 
     /// Removes the specified user function to the mathematical context.
@@ -1234,6 +3236,10 @@ impl<'a> MathContext {
         let repr_string: String = repr.into();
         self.user_functions.remove(& repr_string);
         self.user_function_inputs.remove(& repr_string);
+        self.user_function_canonical_inputs.remove(& repr_string);
+        self.user_function_dependencies.remove(& repr_string);
+        self.used_user_definitions.remove(& repr_string);
+        self.session_definitions.remove(& repr_string);
     }
 
     /// Substitutes the arguments of the specified user function with the specified tokens.
@@ -1278,7 +3284,7 @@ impl<'a> MathContext {
             return None;
         }
         let f_entry = f_entry.unwrap();
-        let mut f_tree = f_entry.0.clone();
+        let mut f_tree = f_entry.0.as_ref().clone();
         let f_args = &f_entry.1;
         if f_args.len() != args.len() {
             return None;
@@ -1363,6 +3369,144 @@ impl<'a> MathContext {
         self.user_function_inputs.get(repr).cloned()
     }
 
+    /// Gets the canonical form of the user input that defined the specified user function.
+    /// In contrast to the original user input, the canonical form does not depend on the whitespace
+    /// and the redundant parentheses that have been typed by the user.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// get_result("f( x,y )=((x^2))+  y", &mut context).unwrap();
+    ///
+    /// let f_input = context.get_user_function_input("f").unwrap();
+    /// assert!(f_input == "f( x,y )=((x^2))+  y");
+    /// let f_canonical = context.get_user_function_canonical_input("f").unwrap();
+    /// assert!(f_canonical == "f(x, y) = x^2 + y");
+    /// ```
+    pub fn get_user_function_canonical_input(& self, repr: & str) -> Option<String> {
+        self.user_function_canonical_inputs.get(repr).cloned()
+    }
+
+    /// Marks the specified user constant or user function as used in this session.
+    pub fn mark_user_definition_used(& mut self, repr: & str) {
+        if !self.used_user_definitions.contains(repr) {
+            self.used_user_definitions.insert(repr.to_string());
+        }
+    }
+
+    /// Returns the sorted names of all user constants and user functions that are neither referenced by any
+    /// user function nor have been used in this session. The constant "ans" is never reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::get_result;
+    ///
+    /// fn main() {
+    ///     let mut context = MathContext::new();
+    ///     get_result("a = 1", &mut context).unwrap();
+    ///     get_result("b = 2", &mut context).unwrap();
+    ///     get_result("c = 3", &mut context).unwrap();
+    ///     get_result("f(x) = x*a", &mut context).unwrap();
+    ///     get_result("b+1", &mut context).unwrap();
+    ///     assert!(context.get_unused_user_definitions() == vec!["c", "f"]);
+    /// }
+    /// ```
+    pub fn get_unused_user_definitions(& self) -> Vec<String> {
+        let referenced : HashSet<& String> = self.user_function_dependencies.values().flat_map(|d| d.iter()).collect();
+        let mut unused : Vec<String> = self.user_constants.keys().chain(self.user_functions.keys())
+            .filter(|repr| repr.as_str() != "ans" && !referenced.contains(repr) && !self.used_user_definitions.contains(repr.as_str()))
+            .cloned().collect();
+        unused.sort();
+        unused
+    }
+
+    /// Returns the LaTeX representation of the definition of the specified user function.
+    /// Returns None if the specified user function does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::get_result;
+    ///
+    /// fn main() {
+    ///     let mut context = MathContext::new();
+    ///     get_result("g(x, y) = x^2*pi", &mut context).unwrap();
+    ///     assert!(context.get_user_function_latex("g").unwrap() == "g\\left(x, y\\right) = x^{2} \\cdot \\pi");
+    ///     assert!(context.get_user_function_latex("h").is_none());
+    /// }
+    /// ```
+    pub fn get_user_function_latex(& self, repr: & str) -> Option<String> {
+        self.user_functions.get(repr).map(|&(ref t, ref vars)| latex::render_definition(repr, vars, t, self))
+    }
+
+    /// Returns the SymPy representation of the definition of the specified user function.
+    /// Returns None if the specified user function does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::get_result;
+    ///
+    /// fn main() {
+    ///     let mut context = MathContext::new();
+    ///     get_result("g(x, y) = x^2*pi", &mut context).unwrap();
+    ///     assert!(context.get_user_function_sympy("g").unwrap() == "g = Lambda((x, y), x**2*pi)");
+    ///     assert!(context.get_user_function_sympy("h").is_none());
+    /// }
+    /// ```
+    pub fn get_user_function_sympy(& self, repr: & str) -> Option<String> {
+        self.user_functions.get(repr).map(|&(ref t, ref vars)| sympy::render_definition(repr, vars, t, self))
+    }
+
+    /// Returns the expression tree of the definition of the specified user function.
+    /// Returns None if the specified user function does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::get_result;
+    ///
+    /// fn main() {
+    ///     let mut context = MathContext::new();
+    ///     get_result("f(x) = x^2", &mut context).unwrap();
+    ///     assert!(context.get_user_function_tree("f").unwrap().content.get_value() == "^");
+    ///     assert!(context.get_user_function_tree("g").is_none());
+    /// }
+    /// ```
+    pub fn get_user_function_tree(& self, repr: & str) -> Option<& TreeNode<Token>> {
+        self.user_functions.get(repr).map(|&(ref t, _)| t.as_ref())
+    }
+
+    /// Returns the shared expression tree and the argument names of the specified user function.
+    /// Returns None if the specified user function does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::get_result;
+    ///
+    /// fn main() {
+    ///     let mut context = MathContext::new();
+    ///     get_result("f(x, y) = x*y", &mut context).unwrap();
+    ///     let (tree, args) = context.get_user_function_body("f").unwrap();
+    ///     assert!(tree.content.get_value() == "*");
+    ///     assert!(args == vec!["x", "y"]);
+    /// }
+    /// ```
+    pub fn get_user_function_body(& self, repr: & str) -> Option<(Rc<TreeNode<Token>>, Vec<String>)> {
+        self.user_functions.get(repr).map(|&(ref t, ref vars)| (t.clone(), vars.clone()))
+    }
+
     /// Gets all user defined constants.
     ///
     /// # Examples
@@ -1388,7 +3532,111 @@ impl<'a> MathContext {
         self.user_constants.clone()
     }
 
-    /// Gets all user defined function definitions.
+    /// Gets the names of all user defined constants in alphabetical order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_context::MathContext;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let mut context = MathContext::new();
+    /// context.add_user_constant("c", MathResult::from(4.1));
+    /// context.add_user_constant("a", MathResult::from(2.0));
+    /// context.add_user_constant("b", MathResult::from(3.0));
+    ///
+    /// assert!(context.get_user_constant_names() == vec!["a", "b", "c"]);
+    /// ```
+    pub fn get_user_constant_names(&self) -> Vec<String> {
+        let mut names : Vec<String> = self.user_constants.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Gets the names of all user defined functions in alphabetical order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// get_result("g(x) = x", &mut context).unwrap();
+    /// get_result("f(x) = 2*x", &mut context).unwrap();
+    ///
+    /// assert!(context.get_user_function_names() == vec!["f", "g"]);
+    /// ```
+    pub fn get_user_function_names(&self) -> Vec<String> {
+        let mut names : Vec<String> = self.user_functions.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Gets the names of all built-in and user defined functions and constants in alphabetical order
+    /// (e.g. for the completion of user inputs).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// get_result("sigma = 2", &mut context).unwrap();
+    /// get_result("square(x) = x^2", &mut context).unwrap();
+    ///
+    /// let names = context.get_symbol_names();
+    /// assert!(names.contains(&String::from("pi")) && names.contains(&String::from("sin")));
+    /// assert!(names.contains(&String::from("sigma")) && names.contains(&String::from("square")));
+    ///
+    /// let mut sorted = names.clone();
+    /// sorted.sort();
+    /// assert!(names == sorted);
+    /// ```
+    pub fn get_symbol_names(&self) -> Vec<String> {
+        let mut names : Vec<String> = self.functions.keys().chain(self.constants.keys()).chain(self.user_functions.keys())
+            .chain(self.user_constants.keys()).cloned().collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Gets the call signatures of all built-in and user defined functions, ordered by the function names.
+    /// The arguments of user functions are named like in their definition. The arguments of built-in functions are
+    /// named by their position (e.g. "pow(x, y)"), as only their number is known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::get_result;
+    /// use termc_model::math_context::MathContext;
+    ///
+    /// let mut context = MathContext::new();
+    /// get_result("area(w, h) = w*h", &mut context).unwrap();
+    ///
+    /// let signatures = context.get_function_signatures();
+    /// assert!(signatures.contains(&String::from("area(w, h)")) && signatures.contains(&String::from("pow(x, y)")));
+    /// assert!(signatures.contains(&String::from("mean(x1, x2, ...)")));
+    /// ```
+    pub fn get_function_signatures(&self) -> Vec<String> {
+        let describe_args = |n: u32| match n {
+            0 => String::from("x1, x2, ..."),
+            1 => String::from("x"),
+            2 => String::from("x, y"),
+            3 => String::from("x, y, z"),
+            _ => (1..n + 1).map(|i| format!("x{0}", i)).collect::<Vec<String>>().join(", ")
+        };
+
+        let mut signatures : Vec<String> = self.functions.iter().map(|(name, &(ref t, n))| match *t {
+            FunctionType::Prod => format!("{0}(f, k, first, last)", name),
+            _ => format!("{0}({1})", name, describe_args(n))
+        }).chain(self.user_functions.iter().map(|(name, &(_, ref vars))| format!("{0}({1})", name, vars.join(", ")))).collect();
+        signatures.sort();
+        signatures
+    }
+
+    /// Gets all user defined function definitions in their canonical form, ordered by the function names.
     ///
     /// # Examples
     ///
@@ -1405,13 +3653,10 @@ impl<'a> MathContext {
     /// fn main() {
     ///     let mut context = MathContext::new();
     ///
-    ///     let mut input = "f(x) = x";
-    ///     let mut f = Token::new(TokenType::Symbol(SymbolicTokenType::UnknownFunction), String::from("f"), 0);
-    ///     let mut f_node: TreeNode<Token> = TreeNode::new(f);
-    ///     let mut x = Token::new(TokenType::Symbol(SymbolicTokenType::UnknownConstant), String::from("x"), 2);
+    ///     let mut input = "f(x) =   x";
+    ///     let mut x = Token::new(TokenType::Symbol(SymbolicTokenType::UnknownConstant), String::from("x"), 9);
     ///     let mut x_node: TreeNode<Token> = TreeNode::new(x);
-    ///     f_node.successors.push(Box::new(x_node));
-    ///     context.add_user_function("f", f_node, vec![String::from("x")], input);
+    ///     context.add_user_function("f", x_node, vec![String::from("x")], input);
     ///
     ///     let user_functions = context.get_user_function_definitions();
     ///     assert!(user_functions.len() == 1);
@@ -1420,8 +3665,10 @@ impl<'a> MathContext {
     /// ```
     pub fn get_user_function_definitions(&self) -> Vec<String> {
         let mut result = Vec::new();
-        for (_, input) in &self.user_function_inputs {
-            result.push(input.clone())
+        for name in self.get_user_function_names() {
+            if let Some(input) = self.user_function_canonical_inputs.get(& name) {
+                result.push(input.clone())
+            }
         }
         result
     }
@@ -1,18 +1,35 @@
 use std::fmt;
-use f64formatter::F64Formatter;
+use f64formatter::{F64Formatter, format_radix};
 use serde::{Serialize, Serializer, Deserialize, Deserializer};
 use serde::ser::{SerializeStruct};
 use serde::de;
 
 pub use token::NumberType;
 pub use num::complex::Complex;
+pub use matrix::Matrix;
+pub use num::BigRational;
+pub use unit::{Unit, Dimension};
+use num::{BigInt, Zero};
+use num::traits::ToPrimitive;
+use std::str::FromStr;
+use std::cmp;
+
+/// The maximum relative error of a fraction that is formatted instead of the decimal number.
+const FRACTION_EPSILON : f64 = 1e-12;
 
 /// Defines the result of a mathematical expression.
-/// The result can be a real or a complex number and thus, be only numerical.
+/// The result can be a real or a complex number or a matrix of such numbers and thus, be only numerical.
+/// For matrices, the value is zero and the result type is complex if any element is complex.
+/// Results of the exact precision mode additionally hold the exact rational number, while the value holds
+/// its nearest floating point approximation.
+/// Quantities with a unit (e.g. "3 km") hold the value in SI base units (e.g. 3000) and the unit that is used for the output.
 #[derive(Clone, PartialEq)]
 pub struct MathResult {
     pub result_type: NumberType,
-    pub value: Complex<f64>
+    pub value: Complex<f64>,
+    pub matrix: Option<Matrix>,
+    pub exact: Option<BigRational>,
+    pub unit: Option<Unit>
 }
 
 impl Serialize for MathResult {
@@ -21,10 +38,22 @@ impl Serialize for MathResult {
     fn serialize<S>(&self, serializer: S) -> Result<(S::Ok), S::Error> where
         S: Serializer
     {
-        let mut struc = serializer.serialize_struct("MathResult", 3)?;
+        // the matrix, exact and unit fields are omitted if not present, so serialized numbers stay compatible with older versions
+        let n_fields = 3 + self.matrix.iter().count() + self.exact.iter().count() + self.unit.iter().count();
+        let mut struc = serializer.serialize_struct("MathResult", n_fields)?;
         struc.serialize_field("result_type", &self.result_type)?;
         struc.serialize_field("re", &self.value.re)?;
         struc.serialize_field("im", &self.value.im)?;
+        if let Some(ref m) = self.matrix {
+            struc.serialize_field("matrix", m)?;
+        }
+        if let Some(ref r) = self.exact {
+            // the exact rational number is serialized as string (e.g. "1/3")
+            struc.serialize_field("exact", &r.to_string())?;
+        }
+        if let Some(ref u) = self.unit {
+            struc.serialize_field("unit", u)?;
+        }
         struc.end()
     }
 }
@@ -37,7 +66,7 @@ impl Deserialize for MathResult
         D: Deserializer,
     {
 
-        enum Field {ResultType, Re, Im};
+        enum Field {ResultType, Re, Im, Matrix, Exact, Unit};
 
         impl Deserialize for Field
         {
@@ -51,7 +80,7 @@ impl Deserialize for MathResult
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("`result_type (NumberType)`, `re (f64)` or `im (f64)`")
+                        formatter.write_str("`result_type (NumberType)`, `re (f64)`, `im (f64)`, `matrix (Matrix)`, `exact (String)` or `unit (Unit)`")
                     }
 
                     fn visit_str<E>(self, value: &str) -> Result<Field, E>
@@ -61,6 +90,9 @@ impl Deserialize for MathResult
                             "result_type" => Ok(Field::ResultType),
                             "re" => Ok(Field::Re),
                             "im" => Ok(Field::Im),
+                            "matrix" => Ok(Field::Matrix),
+                            "exact" => Ok(Field::Exact),
+                            "unit" => Ok(Field::Unit),
                             _ => Err(de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -85,6 +117,9 @@ impl Deserialize for MathResult
                 let mut result_type = None;
                 let mut re = None;
                 let mut im = None;
+                let mut matrix = None;
+                let mut exact : Option<String> = None;
+                let mut unit = None;
                 while let Some(key) = visitor.visit_key()? {
                     match key {
                         Field::ResultType => {
@@ -105,6 +140,24 @@ impl Deserialize for MathResult
                             }
                             im = Some(visitor.visit_value()?);
                         }
+                        Field::Matrix => {
+                            if matrix.is_some() {
+                                return Err(de::Error::duplicate_field("matrix"));
+                            }
+                            matrix = Some(visitor.visit_value()?);
+                        }
+                        Field::Exact => {
+                            if exact.is_some() {
+                                return Err(de::Error::duplicate_field("exact"));
+                            }
+                            exact = Some(visitor.visit_value()?);
+                        }
+                        Field::Unit => {
+                            if unit.is_some() {
+                                return Err(de::Error::duplicate_field("unit"));
+                            }
+                            unit = Some(visitor.visit_value()?);
+                        }
                     }
                 }
                 let result_type = match result_type {
@@ -119,11 +172,15 @@ impl Deserialize for MathResult
                     Some(im) => im,
                     None => return Err(de::Error::missing_field("im")),
                 };
-                Ok(MathResult {result_type: result_type, value: Complex::new(re, im)})
+                let exact = match exact {
+                    Some(e) => Some(BigRational::from_str(&e).map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&e), &"a rational number"))?),
+                    None => None
+                };
+                Ok(MathResult {result_type: result_type, value: Complex::new(re, im), matrix: matrix, exact: exact, unit: unit})
             }
         }
 
-        const FIELDS: &'static [&'static str] = &["result_type", "re", "im"];
+        const FIELDS: &'static [&'static str] = &["result_type", "re", "im", "matrix", "exact", "unit"];
         deserializer.deserialize_struct("MathResult", FIELDS, MathResultVisitor)
     }
 }
@@ -148,15 +205,149 @@ impl MathResult {
     /// }
     /// ```
     pub fn new(t: NumberType, val: Complex<f64>) -> MathResult {
-        MathResult {result_type: t, value: val}
+        MathResult {result_type: t, value: val, matrix: None, exact: None, unit: None}
+    }
+
+    /// Checks whether the result is a matrix (or a vector).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_result::{MathResult, Matrix};
+    ///
+    /// assert!(MathResult::from(Matrix::identity(2)).is_matrix());
+    /// assert!(!MathResult::from(2.0).is_matrix());
+    /// ```
+    pub fn is_matrix(& self) -> bool {
+        self.matrix.is_some()
+    }
+
+    /// Checks whether the result holds an exact rational number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate num;
+    /// extern crate termc_model;
+    ///
+    /// use num::BigInt;
+    /// use termc_model::math_result::{MathResult, BigRational};
+    ///
+    /// fn main() {
+    ///     assert!(MathResult::from(BigRational::new(BigInt::from(1), BigInt::from(3))).is_exact());
+    ///     assert!(!MathResult::from(2.0).is_exact());
+    /// }
+    /// ```
+    pub fn is_exact(& self) -> bool {
+        self.exact.is_some()
+    }
+
+    /// Checks whether the result holds an exact integer.
+    pub fn is_exact_integer(& self) -> bool {
+        self.exact.as_ref().map_or(false, |r| r.is_integer())
+    }
+
+    /// Returns the result as a 64 bit integer, if it is a real integer in the range of i64 (the operand of the bitwise operations).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// assert!(MathResult::from(-12.0).to_i64() == Some(-12));
+    /// assert!(MathResult::from(2.5).to_i64() == None);
+    /// assert!(MathResult::from(1e30).to_i64() == None);
+    /// ```
+    pub fn to_i64(& self) -> Option<i64> {
+        if self.result_type != NumberType::Real || self.is_matrix() || self.unit.is_some() {
+            return None;
+        }
+        match self.exact {
+            Some(ref r) if r.is_integer() => r.numer().to_i64(),
+            Some(_) => None,
+            None => {
+                let x = self.value.re;
+                // 2^63 is the first value that does not fit into an i64
+                if x.fract() == 0.0 && x >= -9223372036854775808.0 && x < 9223372036854775808.0 { Some(x as i64) } else { None }
+            }
+        }
+    }
+
+    /// Returns the result with the specified unit. The exact value is discarded, as quantities are not exact.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_result::{MathResult, Unit};
+    ///
+    /// let length = MathResult::from(1500.0).with_unit(Unit::parse("km"));
+    /// assert!(format!("{0}", length) == "1.5 km");
+    /// ```
+    pub fn with_unit(mut self, unit: Option<Unit>) -> MathResult {
+        if unit.is_some() {
+            self.exact = None;
+        }
+        self.unit = unit;
+        self
+    }
+
+    /// Returns the physical dimension of the result (dimensionless if the result has no unit).
+    pub fn get_dimension(& self) -> Dimension {
+        self.unit.as_ref().map_or(Dimension::default(), |u| u.dimension)
+    }
+
+    /// Returns the value of a result with a unit in this unit (e.g. 1.5 for 1500 m in km) and the name of the unit.
+    fn split_unit(& self) -> Option<(MathResult, & str)> {
+        self.unit.as_ref().map(|u| (MathResult::new(self.result_type.clone(), self.value / u.scale), u.name.as_str()))
     }
 }
 
+/// Returns the nearest floating point number of the specified rational number.
+/// Numerator and denominator are shifted, so that they can be represented as floating point numbers.
+fn rational_to_f64(r: & BigRational) -> f64 {
+    let shift = cmp::max(r.numer().bits(), r.denom().bits()).saturating_sub(1000);
+    let numer : BigInt = r.numer() >> shift;
+    let denom : BigInt = r.denom() >> shift;
+    if denom.is_zero() {
+        return if r.numer() > & BigInt::zero() { f64::INFINITY } else { f64::NEG_INFINITY };
+    }
+    numer.to_f64().unwrap_or(0.0) / denom.to_f64().unwrap_or(1.0)
+}
+
+/// Formats the specified matrix row by row (e.g. "[1, 2; 3, 4]") with the specified element formatter.
+fn fmt_matrix<F>(f: & mut fmt::Formatter, m: & Matrix, fmt_element: F) -> fmt::Result
+    where F: Fn(& MathResult, & mut fmt::Formatter) -> fmt::Result {
+
+    write!(f, "[")?;
+    for r in 0..m.rows {
+        if r > 0 {
+            write!(f, "; ")?;
+        }
+        for c in 0..m.cols {
+            if c > 0 {
+                write!(f, ", ")?;
+            }
+            fmt_element(m.get(r, c), f)?;
+        }
+    }
+    write!(f, "]")
+}
+
 impl fmt::Display for MathResult {
 
     /// Returns the formatted error message.
     fn fmt(& self, f: & mut fmt::Formatter) -> fmt::Result {
 
+        if let Some((x, unit)) = self.split_unit() {
+            return write!(f, "{0} {1}", x, unit);
+        }
+        if let Some(ref m) = self.matrix {
+            return fmt_matrix(f, m, |x, f| fmt::Display::fmt(x, f));
+        }
+        if let Some(ref r) = self.exact {
+            return write!(f, "{0}", r);
+        }
+
         match self.result_type {
             NumberType::Real => write!(f, "{0}", self.value.re),
             NumberType::Complex => write!(f, "{0}", self.value)
@@ -170,16 +361,35 @@ macro_rules! fmt_impl {
     // obj: the MathResult instance to be formatted
     // fmt_type: the formatting type (e.g. 'b' (binary), 'o' (octal) or 'x' (hexadecimal))
 
+        if let Some((x, unit)) = $obj.split_unit() {
+            return write!($f, concat!("{0:", $fmt_type, "} {1}"), x, unit);
+        }
+        if let Some(ref m) = $obj.matrix {
+            return fmt_matrix($f, m, |x, f| write!(f, concat!("{0:", $fmt_type, "}"), x));
+        }
+
+        if let Some(ref r) = $obj.exact {
+            // exact integers are formatted without loss of precision
+            if r.is_integer() {
+                return write!($f, concat!("{0:#", $fmt_type, "}"), r.numer())
+            }
+        }
+
         if $obj.value.is_nan() || $obj.value.is_infinite() {
             // prevent output like "0xNaN" for hex format, which should be just "NaN"
             return write!($f, "{0}", $obj.value)
         }
 
-        match $obj.result_type {
-            NumberType::Real => write!($f, concat!("{0:#" ,$fmt_type, "}"), F64Formatter($obj.value.re)),
-            NumberType::Complex => {
+        // the precision limits the number of digits after the radix point
+        match ($obj.result_type.clone(), $f.precision()) {
+            (NumberType::Real, None) => write!($f, concat!("{0:#", $fmt_type, "}"), F64Formatter($obj.value.re)),
+            (NumberType::Real, Some(prec)) => write!($f, concat!("{0:#.1$", $fmt_type, "}"), F64Formatter($obj.value.re), prec),
+            (NumberType::Complex, prec) => {
                 let tmp : Complex<F64Formatter> = Complex::new(F64Formatter($obj.value.re), F64Formatter($obj.value.im));
-                write!($f, concat!("{0:#", $fmt_type, "}"), tmp)
+                match prec {
+                    None => write!($f, concat!("{0:#", $fmt_type, "}"), tmp),
+                    Some(prec) => write!($f, concat!("{0:#.1$", $fmt_type, "}"), tmp, prec)
+                }
             }
         }
     }}
@@ -220,8 +430,16 @@ impl fmt::Octal for MathResult {
 impl fmt::LowerExp for MathResult {
     /// Implements the formatted lower exponential output for MathResult.
     fn fmt(& self, f: & mut fmt::Formatter) -> fmt::Result {
+        if let Some((x, unit)) = self.split_unit() {
+            return write!(f, "{0:e} {1}", x, unit);
+        }
+        if let Some(ref m) = self.matrix {
+            return fmt_matrix(f, m, |x, f| write!(f, "{0:e}", x));
+        }
         match self.result_type {
             NumberType::Real => write!(f, "{0:#e}", self.value.re),
+            // complex numbers with NaN or infinite parts are formatted like in the decimal format (e.g. "1+NaNi")
+            NumberType::Complex if self.value.is_nan() || self.value.is_infinite() => write!(f, "{0}", self.value),
             NumberType::Complex => write!(f, "{0:#e}", self.value)
         }
     }
@@ -230,8 +448,16 @@ impl fmt::LowerExp for MathResult {
 impl fmt::UpperExp for MathResult {
     /// Implements the formatted upper exponential output for MathResult.
     fn fmt(& self, f: & mut fmt::Formatter) -> fmt::Result {
+        if let Some((x, unit)) = self.split_unit() {
+            return write!(f, "{0:E} {1}", x, unit);
+        }
+        if let Some(ref m) = self.matrix {
+            return fmt_matrix(f, m, |x, f| write!(f, "{0:E}", x));
+        }
         match self.result_type {
             NumberType::Real => write!(f, "{0:#E}", self.value.re),
+            // complex numbers with NaN or infinite parts are formatted like in the decimal format (e.g. "1+NaNi")
+            NumberType::Complex if self.value.is_nan() || self.value.is_infinite() => write!(f, "{0}", self.value),
             NumberType::Complex => write!(f, "{0:#E}", self.value)
         }
     }
@@ -244,39 +470,250 @@ pub trait FormatIEEE754 {
     fn ieee754_fmt(&self) -> String;
 }
 
+/// Formats the bits of the specified number in IEEE754 representation.
+/// NaN and infinity are formatted by name, as their bits are not meaningful to the user.
+fn fmt_ieee754(x: f64) -> String {
+    if x.is_nan() || x.is_infinite() {
+        format!("{0}", x)
+    }
+    else {
+        format!("{0:#b}", x.to_bits())
+    }
+}
+
 impl FormatIEEE754 for MathResult {
     /// Implements the formatted IEEE754 output for MathResult.
+    /// The real part and the imaginary part of complex numbers are labeled, as their bits are formatted separately.
     /// NOTE: This only works on machines which use the IEEE754 format internally for floating point number representation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::f64;
+    /// use termc_model::math_result::{MathResult, FormatIEEE754};
+    ///
+    /// assert!(MathResult::from(-2.0).ieee754_fmt() == format!("0b11{0}", "0".repeat(62)));
+    /// assert!(MathResult::from((f64::NAN, -2.0)).ieee754_fmt() == format!("re: NaN + im: 0b11{0}·i", "0".repeat(62)));
+    /// ```
     fn ieee754_fmt(&self) -> String {
+        if let Some((x, unit)) = self.split_unit() {
+            return format!("{0} {1}", x.ieee754_fmt(), unit);
+        }
+        if let Some(ref m) = self.matrix {
+            let rows : Vec<String> = (0..m.rows).map(|r| {
+                let row : Vec<String> = (0..m.cols).map(|c| m.get(r, c).ieee754_fmt()).collect();
+                row.join(", ")
+            }).collect();
+            return format!("[{0}]", rows.join("; "));
+        }
         match self.result_type {
+            NumberType::Real => fmt_ieee754(self.value.re),
+            NumberType::Complex => format!("re: {0} + im: {1}·i", fmt_ieee754(self.value.re), fmt_ieee754(self.value.im))
+        }
+    }
+}
 
-            NumberType::Real => {
-                if self.value.re.is_nan() || self.value.re.is_infinite() {
-                    // prevent output like "0xNaN" for hex format, which should be just "NaN"
-                    format!("{0}", self.value.re)
-                }
-                else {
-                    format!("{0:#b}", self.value.re.to_bits())
-                }
-            },
+/// The trait to format a number as fraction.
+pub trait FormatFraction {
+    /// Formats a number as the nearest fraction whose denominator does not exceed the specified maximum denominator.
+    /// Numbers that are not close enough to such a fraction are formatted as decimal numbers.
+    /// Example: decimal 0.75 is "3/4"
+    fn fraction_fmt(&self, max_denominator: u64) -> String;
+}
+
+impl FormatFraction for MathResult {
+    /// Implements the formatted fraction output for MathResult.
+    /// Complex numbers are formatted as fractions componentwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_result::{MathResult, FormatFraction};
+    ///
+    /// assert!(MathResult::from(0.1 + 0.2).fraction_fmt(1000) == "3/10");
+    /// assert!(MathResult::from((-1.5, 0.25)).fraction_fmt(1000) == "-3/2+1/4i");
+    /// assert!(MathResult::from(3.14159).fraction_fmt(100) == "3.14159");
+    /// ```
+    fn fraction_fmt(&self, max_denominator: u64) -> String {
+        if let Some((x, unit)) = self.split_unit() {
+            return format!("{0} {1}", x.fraction_fmt(max_denominator), unit);
+        }
+        if let Some(ref m) = self.matrix {
+            let rows : Vec<String> = (0..m.rows).map(|r| {
+                let row : Vec<String> = (0..m.cols).map(|c| m.get(r, c).fraction_fmt(max_denominator)).collect();
+                row.join(", ")
+            }).collect();
+            return format!("[{0}]", rows.join("; "));
+        }
+        if let Some(ref r) = self.exact {
+            if r.denom().bits() <= 64 && r.denom().to_u64().map_or(false, |d| d <= max_denominator) {
+                return format!("{0}", r);
+            }
+        }
+
+        match self.result_type {
+            NumberType::Real => fmt_fraction(self.value.re, max_denominator),
             NumberType::Complex => {
-                if self.value.is_nan() || self.value.is_infinite() {
-                    // prevent output like "0xNaN+0xNaNi" for hex format, which should be just "NaN+NaNi"
-                    format!("{0}", self.value)
+                let im = fmt_fraction(self.value.im, max_denominator);
+                let sign = if im.starts_with('-') { "" } else { "+" };
+                format!("{0}{1}{2}i", fmt_fraction(self.value.re, max_denominator), sign, im)
+            }
+        }
+    }
+}
+
+/// The trait to format a number in an arbitrary radix.
+pub trait FormatRadix {
+    /// Formats a number in the specified radix (from 2 to 36) like a number literal of an arbitrary radix.
+    /// Example: decimal 1297 in radix 36 is "0r36:101"
+    fn radix_fmt(&self, radix: u32) -> String;
+}
+
+impl FormatRadix for MathResult {
+    /// Implements the formatted output in an arbitrary radix for MathResult.
+    /// Complex numbers are formatted componentwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_result::{MathResult, FormatRadix};
+    ///
+    /// assert!(MathResult::from(1297.0).radix_fmt(36) == "0r36:101");
+    /// assert!(MathResult::from(-0.5).radix_fmt(3) == "-0r3:0.1111111111…");
+    /// assert!(MathResult::from((2.0, -1.0)).radix_fmt(5) == "0r5:2-0r5:1i");
+    /// ```
+    fn radix_fmt(&self, radix: u32) -> String {
+        if let Some((x, unit)) = self.split_unit() {
+            return format!("{0} {1}", x.radix_fmt(radix), unit);
+        }
+        if let Some(ref m) = self.matrix {
+            let rows : Vec<String> = (0..m.rows).map(|r| {
+                let row : Vec<String> = (0..m.cols).map(|c| m.get(r, c).radix_fmt(radix)).collect();
+                row.join(", ")
+            }).collect();
+            return format!("[{0}]", rows.join("; "));
+        }
+        if let Some(ref r) = self.exact {
+            // exact integers are formatted without loss of precision
+            if r.is_integer() {
+                let digits = r.numer().to_str_radix(radix);
+                return if digits.starts_with('-') {
+                    format!("-0r{0}:{1}", radix, &digits[1..])
                 }
                 else {
-                    format!("{0:#b}", Complex::new(self.value.re.to_bits(), 
-                                                   self.value.im.to_bits()))
-                }
+                    format!("0r{0}:{1}", radix, digits)
+                };
+            }
+        }
+
+        match self.result_type {
+            NumberType::Real => format_radix(self.value.re, radix),
+            NumberType::Complex => {
+                let im = format_radix(self.value.im, radix);
+                let sign = if im.starts_with('-') { "" } else { "+" };
+                format!("{0}{1}{2}i", format_radix(self.value.re, radix), sign, im)
             }
         }
     }
 }
 
+/// The trait to format a number in polar form.
+pub trait FormatPolar {
+    /// Formats a number by its modulus and its argument (in radians), so that it can be read again.
+    /// Example: decimal 2i is "2*exp(1.5707963267948966i)"
+    fn polar_fmt(&self) -> String;
+}
+
+impl FormatPolar for MathResult {
+    /// Implements the formatted polar output for MathResult.
+    /// Non-negative real numbers are formatted as decimal numbers, as their argument is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::math_result::{MathResult, FormatPolar};
+    ///
+    /// assert!(MathResult::from((0.0, 2.0)).polar_fmt() == "2*exp(1.5707963267948966i)");
+    /// assert!(MathResult::from(-1.0).polar_fmt() == "1*exp(3.141592653589793i)");
+    /// assert!(MathResult::from(2.5).polar_fmt() == "2.5");
+    /// ```
+    fn polar_fmt(&self) -> String {
+        if let Some((x, unit)) = self.split_unit() {
+            return format!("{0} {1}", x.polar_fmt(), unit);
+        }
+        if let Some(ref m) = self.matrix {
+            let rows : Vec<String> = (0..m.rows).map(|r| {
+                let row : Vec<String> = (0..m.cols).map(|c| m.get(r, c).polar_fmt()).collect();
+                row.join(", ")
+            }).collect();
+            return format!("[{0}]", rows.join("; "));
+        }
+        if self.result_type == NumberType::Real && !(self.value.re < 0.0) {
+            return format!("{0}", self);
+        }
+
+        format!("{0}*exp({1}i)", self.value.norm(), self.value.arg())
+    }
+}
+
+/// Formats the specified number as fraction (e.g. "-3/4") if it is close enough to a fraction whose denominator
+/// does not exceed the specified maximum denominator. Otherwise, the number is formatted as decimal number.
+fn fmt_fraction(x: f64, max_denominator: u64) -> String {
+    match approximate_fraction(x, max_denominator) {
+        Some((p, q)) if q == 1.0 => format!("{0}", p),
+        Some((p, q)) => format!("{0}/{1}", p, q),
+        None => format!("{0}", x)
+    }
+}
+
+/// Approximates the specified number by the nearest fraction whose denominator does not exceed the specified maximum
+/// denominator (numerator and denominator). The fraction is the best approximation that is found by the continued
+/// fraction expansion of the number. Returns None if the relative error of the fraction exceeds FRACTION_EPSILON.
+fn approximate_fraction(x: f64, max_denominator: u64) -> Option<(f64, f64)> {
+    if !x.is_finite() || max_denominator == 0 {
+        return None;
+    }
+
+    let max_q = max_denominator as f64;
+    let y = x.abs();
+    // the convergents p0/q0 and p1/q1 of the continued fraction
+    let (mut p0, mut q0, mut p1, mut q1) = (0.0_f64, 1.0_f64, 1.0_f64, 0.0_f64);
+    let mut r = y;
+    loop {
+        let a = r.floor();
+        let (p2, q2) = (a * p1 + p0, a * q1 + q0);
+        if q2 > max_q {
+            // the best semiconvergent with a denominator within the bound may be closer than the last convergent
+            let k = ((max_q - q0) / q1).floor();
+            let (ps, qs) = (k * p1 + p0, k * q1 + q0);
+            if (y - ps / qs).abs() < (y - p1 / q1).abs() {
+                p1 = ps;
+                q1 = qs;
+            }
+            break;
+        }
+        p0 = p1;
+        q0 = q1;
+        p1 = p2;
+        q1 = q2;
+
+        let frac = r - a;
+        if frac == 0.0 || p1 / q1 == y {
+            break;
+        }
+        r = 1.0 / frac;
+    }
+
+    if (y - p1 / q1).abs() > FRACTION_EPSILON * y.max(1.0) {
+        return None;
+    }
+    Some((if x < 0.0 { -p1 } else { p1 }, q1))
+}
+
 impl From<Complex<f64>> for MathResult {
     /// Converts a complex number into a MathResult.
     fn from(cmplx: Complex<f64>) -> Self {
-        MathResult {result_type: NumberType::Complex, value: Complex::from(cmplx)}
+        MathResult {result_type: NumberType::Complex, value: Complex::from(cmplx), matrix: None, exact: None, unit: None}
     }
 }
 
@@ -284,10 +721,10 @@ impl<'a> From<&'a Complex<f64>> for MathResult {
     /// Converts a complex number reference into a MathResult.
     fn from(cmplx: &'a Complex<f64>) -> Self {
         if cmplx.im == 0.0_f64 {
-            MathResult {result_type: NumberType::Real, value: Complex::from(cmplx.re)}
+            MathResult {result_type: NumberType::Real, value: Complex::from(cmplx.re), matrix: None, exact: None, unit: None}
         }
         else {
-            MathResult {result_type: NumberType::Complex, value: Complex::from(cmplx.clone())}
+            MathResult {result_type: NumberType::Complex, value: Complex::from(cmplx.clone()), matrix: None, exact: None, unit: None}
         }
     }
 }
@@ -296,10 +733,10 @@ impl From<(f64, f64)> for MathResult {
     /// Converts a tuple of two floats into a MathResult (complex type).
     fn from(tpl: (f64, f64)) -> Self {
         if tpl.1 == 0.0_f64 {
-            MathResult {result_type: NumberType::Real, value: Complex::from(tpl.0)}
+            MathResult {result_type: NumberType::Real, value: Complex::from(tpl.0), matrix: None, exact: None, unit: None}
         }
         else {
-            MathResult {result_type: NumberType::Complex, value: Complex::new(tpl.0, tpl.1)}
+            MathResult {result_type: NumberType::Complex, value: Complex::new(tpl.0, tpl.1), matrix: None, exact: None, unit: None}
         }
     }
 }
@@ -308,10 +745,10 @@ impl<'a> From<&'a (f64, f64)> for MathResult {
     /// Converts a tuple reference of two floats into a MathResult (complex type).
     fn from(tpl: &'a (f64, f64)) -> Self {
         if tpl.1 == 0.0_f64 {
-            MathResult {result_type: NumberType::Real, value: Complex::from(tpl.0)}
+            MathResult {result_type: NumberType::Real, value: Complex::from(tpl.0), matrix: None, exact: None, unit: None}
         }
         else {
-            MathResult {result_type: NumberType::Complex, value: Complex::new(tpl.0, tpl.1)}
+            MathResult {result_type: NumberType::Complex, value: Complex::new(tpl.0, tpl.1), matrix: None, exact: None, unit: None}
         }
     }
 }
@@ -319,13 +756,29 @@ impl<'a> From<&'a (f64, f64)> for MathResult {
 impl From<f64> for MathResult {
     /// Converts a real number into a MathResult.
     fn from(real: f64) -> Self {
-        MathResult {result_type: NumberType::Real, value: Complex::from(real)}
+        MathResult {result_type: NumberType::Real, value: Complex::from(real), matrix: None, exact: None, unit: None}
     }
 }
 
 impl<'a> From<&'a f64> for MathResult {
     /// Converts a real number reference into a MathResult.
     fn from(real: & f64) -> Self {
-        MathResult {result_type: NumberType::Real, value: Complex::from(real.clone())}
+        MathResult {result_type: NumberType::Real, value: Complex::from(real.clone()), matrix: None, exact: None, unit: None}
+    }
+}
+
+impl From<BigRational> for MathResult {
+    /// Converts an exact rational number into a MathResult (real type).
+    fn from(r: BigRational) -> Self {
+        MathResult {result_type: NumberType::Real, value: Complex::from(rational_to_f64(& r)), matrix: None, exact: Some(r), unit: None}
+    }
+}
+
+impl From<Matrix> for MathResult {
+    /// Converts a matrix into a MathResult. Complex elements without imaginary part are reduced to real numbers.
+    fn from(m: Matrix) -> Self {
+        let m = m.map(|x| if x.result_type == NumberType::Complex && x.value.im == 0.0_f64 { MathResult::from(x.value.re) } else { x.clone() });
+        let t = if m.elements.iter().any(|x| x.result_type == NumberType::Complex) { NumberType::Complex } else { NumberType::Real };
+        MathResult {result_type: t, value: Complex::from(0.0), matrix: Some(m), exact: None, unit: None}
     }
 }
@@ -0,0 +1,298 @@
+use num::complex::Complex;
+use math_result::MathResult;
+use math_context::MathContext;
+
+/// Defines a matrix of real or complex numbers.
+/// Vectors are matrices with a single row (e.g. "[1, 2, 3]") or a single column (e.g. "[1; 2; 3]").
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Matrix {
+    /// The number of rows.
+    pub rows: usize,
+    /// The number of columns.
+    pub cols: usize,
+    /// The elements of the matrix in row-major order.
+    pub elements: Vec<MathResult>
+}
+
+impl Matrix {
+    /// Creates a new matrix with the specified dimensions and elements (in row-major order).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::matrix::Matrix;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let m = Matrix::new(1, 2, vec![MathResult::from(1.0), MathResult::from(2.0)]);
+    /// assert!(m.get(0, 1) == &MathResult::from(2.0));
+    /// ```
+    pub fn new(rows: usize, cols: usize, elements: Vec<MathResult>) -> Matrix {
+        assert!(rows * cols == elements.len());
+        Matrix {rows: rows, cols: cols, elements: elements}
+    }
+
+    /// Creates the identity matrix with the specified number of rows and columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::matrix::Matrix;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let m = Matrix::identity(2);
+    /// assert!(m.get(1, 1) == &MathResult::from(1.0) && m.get(0, 1) == &MathResult::from(0.0));
+    /// ```
+    pub fn identity(n: usize) -> Matrix {
+        let elements = (0..n * n).map(|i| MathResult::from(if i / n == i % n { 1.0 } else { 0.0 })).collect();
+        Matrix::new(n, n, elements)
+    }
+
+    /// Returns the element in the specified row and column.
+    pub fn get(& self, row: usize, col: usize) -> & MathResult {
+        & self.elements[row * self.cols + col]
+    }
+
+    /// Checks whether the matrix has as many rows as columns.
+    pub fn is_square(& self) -> bool {
+        self.rows == self.cols
+    }
+
+    /// Applies the specified function to every element of the matrix.
+    pub fn map<F>(& self, f: F) -> Matrix where F: Fn(& MathResult) -> MathResult {
+        Matrix::new(self.rows, self.cols, self.elements.iter().map(f).collect())
+    }
+
+    /// Adds the specified matrix element-wise.
+    /// Returns None if the dimensions of the matrices differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::matrix::Matrix;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let m = Matrix::identity(2);
+    /// assert!(m.add(& m).unwrap().get(0, 0) == &MathResult::from(2.0));
+    /// assert!(m.add(& Matrix::identity(3)).is_none());
+    /// ```
+    pub fn add(& self, other: & Matrix) -> Option<Matrix> {
+        self.zip_with(other, MathContext::operation_add)
+    }
+
+    /// Subtracts the specified matrix element-wise.
+    /// Returns None if the dimensions of the matrices differ.
+    pub fn sub(& self, other: & Matrix) -> Option<Matrix> {
+        self.zip_with(other, MathContext::operation_sub)
+    }
+
+    /// Combines the elements of two matrices with the same dimensions.
+    fn zip_with<F>(& self, other: & Matrix, f: F) -> Option<Matrix> where F: Fn(& MathResult, & MathResult) -> MathResult {
+        if self.rows != other.rows || self.cols != other.cols {
+            return None;
+        }
+        let elements = self.elements.iter().zip(other.elements.iter()).map(|(a, b)| f(a, b)).collect();
+        Some(Matrix::new(self.rows, self.cols, elements))
+    }
+
+    /// Computes the matrix product.
+    /// Returns None if the number of columns of this matrix differs from the number of rows of the specified matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::matrix::Matrix;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let a = Matrix::new(1, 2, vec![MathResult::from(1.0), MathResult::from(2.0)]);
+    /// let b = Matrix::new(2, 1, vec![MathResult::from(3.0), MathResult::from(4.0)]);
+    /// assert!(a.mul(& b).unwrap().elements == vec![MathResult::from(11.0)]);
+    /// assert!(a.mul(& a).is_none());
+    /// ```
+    pub fn mul(& self, other: & Matrix) -> Option<Matrix> {
+        if self.cols != other.rows {
+            return None;
+        }
+        let mut elements = Vec::with_capacity(self.rows * other.cols);
+        for r in 0..self.rows {
+            for c in 0..other.cols {
+                // the sum starts with the first product, so that exact elements stay exact
+                let mut sum = MathContext::operation_mul(self.get(r, 0), other.get(0, c));
+                for k in 1..self.cols {
+                    sum = MathContext::operation_add(& sum, & MathContext::operation_mul(self.get(r, k), other.get(k, c)));
+                }
+                elements.push(sum);
+            }
+        }
+        Some(Matrix::new(self.rows, other.cols, elements))
+    }
+
+    /// Raises a square matrix to the specified integer power. Negative exponents use the inverse matrix.
+    /// Returns None if the matrix is not square or if it is singular and the exponent is negative.
+    pub fn pow(& self, exponent: i64) -> Option<Matrix> {
+        if !self.is_square() {
+            return None;
+        }
+        let mut base = if exponent < 0 { self.inv()? } else { self.clone() };
+        let mut n = exponent.abs();
+        let mut result : Option<Matrix> = None;
+
+        // exponentiation by squaring
+        while n > 0 {
+            if n % 2 == 1 {
+                result = Some(match result {
+                    Some(r) => r.mul(& base)?,
+                    None => base.clone()
+                });
+            }
+            n /= 2;
+            if n > 0 {
+                base = base.mul(& base)?;
+            }
+        }
+        Some(result.unwrap_or(Matrix::identity(self.rows)))
+    }
+
+    /// Returns the transposed matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::matrix::Matrix;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let m = Matrix::new(1, 2, vec![MathResult::from(1.0), MathResult::from(2.0)]);
+    /// assert!(m.transpose().rows == 2 && m.transpose().cols == 1);
+    /// ```
+    pub fn transpose(& self) -> Matrix {
+        let mut elements = Vec::with_capacity(self.elements.len());
+        for c in 0..self.cols {
+            for r in 0..self.rows {
+                elements.push(self.get(r, c).clone());
+            }
+        }
+        Matrix::new(self.cols, self.rows, elements)
+    }
+
+    /// Computes the determinant of a square matrix (Gaussian elimination with partial pivoting).
+    /// Returns None if the matrix is not square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::matrix::Matrix;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let m = Matrix::new(2, 2, vec![MathResult::from(1.0), MathResult::from(2.0), MathResult::from(3.0), MathResult::from(4.0)]);
+    /// assert!((m.det().unwrap().value.re + 2.0).abs() < 1e-10);
+    /// ```
+    pub fn det(& self) -> Option<MathResult> {
+        if !self.is_square() {
+            return None;
+        }
+        let n = self.rows;
+        let mut a = self.to_complex();
+        let mut det = Complex::new(1.0, 0.0);
+
+        for col in 0..n {
+            let pivot = Matrix::find_pivot(& a, n, col);
+            if a[pivot * n + col].norm() == 0.0 {
+                return Some(MathResult::from(0.0));
+            }
+            if pivot != col {
+                Matrix::swap_rows(& mut a, n, pivot, col);
+                det = -det;
+            }
+            det = det * a[col * n + col];
+            for r in col + 1..n {
+                let factor = a[r * n + col] / a[col * n + col];
+                for c in col..n {
+                    let x = a[col * n + c];
+                    a[r * n + c] = a[r * n + c] - factor * x;
+                }
+            }
+        }
+        Some(MathResult::from(& det))
+    }
+
+    /// Computes the inverse of a square matrix (Gauss-Jordan elimination with partial pivoting).
+    /// Returns None if the matrix is not square or singular.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::matrix::Matrix;
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// let m = Matrix::new(2, 2, vec![MathResult::from(2.0), MathResult::from(0.0), MathResult::from(0.0), MathResult::from(4.0)]);
+    /// assert!(m.inv().unwrap().get(1, 1) == &MathResult::from(0.25));
+    /// assert!(Matrix::new(1, 1, vec![MathResult::from(0.0)]).inv().is_none());
+    /// ```
+    pub fn inv(& self) -> Option<Matrix> {
+        if !self.is_square() {
+            return None;
+        }
+        let n = self.rows;
+        let width = 2 * n;
+
+        // augmented matrix [A | I]
+        let a = self.to_complex();
+        let mut aug : Vec<Complex<f64>> = Vec::with_capacity(n * width);
+        for r in 0..n {
+            aug.extend_from_slice(& a[r * n..(r + 1) * n]);
+            aug.extend((0..n).map(|c| Complex::new(if r == c { 1.0 } else { 0.0 }, 0.0)));
+        }
+
+        for col in 0..n {
+            let pivot = Matrix::find_pivot(& aug, width, col);
+            if aug[pivot * width + col].norm() == 0.0 {
+                return None;
+            }
+            Matrix::swap_rows(& mut aug, width, pivot, col);
+
+            let p = aug[col * width + col];
+            for c in 0..width {
+                aug[col * width + c] = aug[col * width + c] / p;
+            }
+            for r in 0..n {
+                if r == col {
+                    continue;
+                }
+                let factor = aug[r * width + col];
+                for c in 0..width {
+                    let x = aug[col * width + c];
+                    aug[r * width + c] = aug[r * width + c] - factor * x;
+                }
+            }
+        }
+
+        let mut elements = Vec::with_capacity(n * n);
+        for r in 0..n {
+            elements.extend(aug[r * width + n..(r + 1) * width].iter().map(|x| MathResult::from(x)));
+        }
+        Some(Matrix::new(n, n, elements))
+    }
+
+    /// Returns the elements as complex numbers.
+    fn to_complex(& self) -> Vec<Complex<f64>> {
+        self.elements.iter().map(|x| x.value).collect()
+    }
+
+    /// Returns the row (starting at the specified column index) with the largest absolute value in that column.
+    fn find_pivot(a: & Vec<Complex<f64>>, width: usize, col: usize) -> usize {
+        let rows = a.len() / width;
+        let mut pivot = col;
+        for r in col + 1..rows {
+            if a[r * width + col].norm() > a[pivot * width + col].norm() {
+                pivot = r;
+            }
+        }
+        pivot
+    }
+
+    /// Swaps two rows of a matrix stored in row-major order.
+    fn swap_rows(a: & mut Vec<Complex<f64>>, width: usize, r1: usize, r2: usize) {
+        for c in 0..width {
+            a.swap(r1 * width + c, r2 * width + c);
+        }
+    }
+}
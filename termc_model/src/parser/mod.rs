@@ -28,7 +28,7 @@ impl fmt::Display for ParseError {
         match *self {
             ParseError::ExpectedError(ref e) => write!(f, "{0}", e),
             ParseError::InputError(ref e) => write!(f, "{0}", e),
-            ParseError::IncompleteInputError => write!(f, "{0}", self.description())
+            ParseError::IncompleteInputError => write!(f, "Error: {0}", self.description())
         }
     }
 }
@@ -70,6 +70,9 @@ impl Error for ParseError {
     }
 }
 
+/// The keyword that introduces a scoped expression with temporary definitions.
+pub const SCOPE_KEYWORD : &'static str = "with";
+
 /// Defines the Parser.
 pub struct Parser<'a> {
     /// The mathematical environment.
@@ -142,8 +145,43 @@ impl<'a> Parser<'a> {
     /// (1) a whole expression in parenthesis
     /// (2) an operand (number, constant or function call)
     /// (3) an unary operation (that is not further processed)
+    /// Postfix operations (e.g. "5!") that follow an expression in parenthesis or an operand are parsed as well.
     fn parse_element(& mut self) -> Result<TreeNode<Token>, ParseError> {
 
+        let elem = self.parse_prefixed_element()?;
+        if elem.content.get_type() == TokenType::Operation && elem.successors.len() == 0 {
+            // an unprocessed unary operation has no operand yet
+            return Ok(elem);
+        }
+        self.parse_postfix(elem)
+    }
+
+    /// Parses the specified element followed by any number of postfix operations (e.g. "3!!" is "(3!)!").
+    fn parse_postfix(& mut self, elem: TreeNode<Token>) -> Result<TreeNode<Token>, ParseError> {
+
+        let mut elem = elem;
+        loop {
+            let is_postfix = match self.tokenizer.peek() {
+                Some(Ok(t)) => t.get_type() == TokenType::Operation && self.context.is_postfix_operation(t.get_value()),
+                _ => false
+            };
+            if !is_postfix {
+                return Ok(elem);
+            }
+
+            let t = match self.tokenizer.next() {
+                Some(res) => res?,
+                None => return Err(ParseError::IncompleteInputError)
+            };
+            let mut wrap = TreeNode::new(t);
+            wrap.successors.push(Box::new(elem));
+            elem = wrap;
+        }
+    }
+
+    /// Parses an element of the expression without the postfix operations that follow it.
+    fn parse_prefixed_element(& mut self) -> Result<TreeNode<Token>, ParseError> {
+
         if self.is_punc("(") {
             // expression in parenthesis
             match self.tokenizer.next() {
@@ -154,6 +192,9 @@ impl<'a> Parser<'a> {
             self.skip_punc(")")?;
             return Ok(exp);
         }
+        else if self.is_punc("[") {
+            return self.parse_matrix();
+        }
         else {
             let t = match self.tokenizer.next() {
                 Some(res) => res?,
@@ -162,9 +203,13 @@ impl<'a> Parser<'a> {
             let token_type = t.get_type();
 
             match token_type {
-                TokenType::Number(_) | TokenType::Constant | TokenType::UserConstant | TokenType::Symbol(SymbolicTokenType::UnknownConstant) => {
+                TokenType::Number(_) | TokenType::Constant | TokenType::UserConstant | TokenType::Unit | TokenType::Symbol(SymbolicTokenType::UnknownConstant) => {
                     Ok(TreeNode::new(t))
                 },
+                TokenType::Symbol(SymbolicTokenType::UnknownFunction) if t.get_value() == SCOPE_KEYWORD => {
+                    // return the complete parsed scoped expression
+                    self.parse_scope(t)
+                },
                 TokenType::Function | TokenType::UserFunction | TokenType::Symbol(SymbolicTokenType::UnknownFunction) => {
                     // return the complete parsed function call subtree
                     self.parse_function(t)
@@ -211,6 +256,54 @@ impl<'a> Parser<'a> {
         Ok(ret)
     }
 
+    /// Parses a matrix (e.g. "[1, 2; 3, 4]"), where the elements of a row are separated by "," and the rows by ";".
+    /// The resulting tree has a "[" node for the matrix with a "[" node for each row that has the elements as successors.
+    fn parse_matrix(& mut self) -> Result<TreeNode<Token>, ParseError> {
+
+        let open = match self.tokenizer.next() {
+            Some(res) => res?,
+            None => return Err(ParseError::IncompleteInputError)
+        };
+        let mut matrix = TreeNode::new(open.clone());
+
+        loop {
+            let mut row = TreeNode::new(open.clone());
+            loop {
+                let elem = self.parse_expression()?;
+                row.successors.push(Box::new(elem));
+                if !self.is_punc(",") {
+                    break;
+                }
+                self.skip_punc(",")?;
+            }
+            matrix.successors.push(Box::new(row));
+            if !self.is_punc(";") {
+                break;
+            }
+            self.skip_punc(";")?;
+        }
+
+        self.skip_punc("]")?;
+        Ok(matrix)
+    }
+
+    /// Parses a scoped expression with temporary definitions (e.g. "with(a=2, b=3) a*b").
+    /// The resulting tree has the definitions as successors followed by the body expression.
+    fn parse_scope(& mut self, t: Token) -> Result<TreeNode<Token>, ParseError> {
+
+        self.skip_punc("(")?;
+        let definitions = self.parse_function_arg_list()?;
+        self.skip_punc(")")?;
+        let body = self.parse_expression()?;
+
+        let mut ret = TreeNode::new(Token::new(TokenType::Keyword, t.get_value().to_string(), t.get_end_pos()));
+        for definition in definitions.into_iter() {
+            ret.successors.push(Box::new(definition));
+        }
+        ret.successors.push(Box::new(body));
+        Ok(ret)
+    }
+
     /// Parses the argument list of a function call.
     fn parse_function_arg_list(& mut self) -> Result<Vec<TreeNode<Token>>, ParseError> {
 
@@ -317,6 +410,8 @@ impl<'a> Parser<'a> {
         };
         if t.get_type() == TokenType::Operation {
             let his_prec = self.context.get_operation_precedence(t.get_value()).unwrap();
+            // the right operand of a right associative operation (e.g. "a = b = 3") may contain operations of the same precedence
+            let right_prec = if self.context.is_right_associative(t.get_value()) { his_prec - 1 } else { his_prec };
             if his_prec > my_prec {
                 let t = match self.tokenizer.next() {
                     Some(res) => res?,
@@ -336,7 +431,7 @@ impl<'a> Parser<'a> {
                         // the unary expression is the right operand of the binary operation "t"
                         let unary = self.recursive_parse_unary(elem)?;
                         if !self.tokenizer.eof() {
-                            let right = self.recursive_parse_binary(unary, his_prec)?;
+                            let right = self.recursive_parse_binary(unary, right_prec)?;
                             wrap.successors.push(Box::new(right));
                         }
                         else {
@@ -352,7 +447,7 @@ impl<'a> Parser<'a> {
                     // "elem" must be an operand or an parsed unary expression.
                     // Check for further operations with higher precedence than "t".
                     if !self.tokenizer.eof() {
-                        let right = self.recursive_parse_binary(elem, his_prec)?;
+                        let right = self.recursive_parse_binary(elem, right_prec)?;
                         wrap.successors.push(Box::new(right));
                     }
                     else {
@@ -360,6 +455,10 @@ impl<'a> Parser<'a> {
                     }
                 }
 
+                if self.context.get_compound_operation(wrap.content.get_value()).is_some() {
+                    wrap = self.expand_compound_assignment(wrap)?;
+                }
+
                 // Check for further binary operations.
                 let ret: TreeNode<Token>;
                 if !self.tokenizer.eof() {
@@ -376,6 +475,32 @@ impl<'a> Parser<'a> {
         Ok(left)
     }
 
+    /// Expands the specified compound assignment to the equivalent assignment (e.g. "x += 1" to "x = x + 1").
+    /// The target must be an existing user defined constant, as its current value is used.
+    fn expand_compound_assignment(& self, tree: TreeNode<Token>) -> Result<TreeNode<Token>, ParseError> {
+        let mut tree = tree;
+        let rhs = tree.successors.pop().unwrap(); // the compound assignment is a binary operation
+        let lhs = tree.successors.pop().unwrap();
+        if lhs.content.get_type() != TokenType::UserConstant {
+            let found = match lhs.content.get_type() {
+                TokenType::Symbol(SymbolicTokenType::UnknownConstant) => format!("undefined constant \"{0}\"", lhs.content),
+                _ => format!("expression \"{0}\"", lhs.content)
+            };
+            return Err(ParseError::from(ExpectedErrorTemplate::new(self.tokenizer.get_input(), format!(
+                "user defined constant as target of \"{0}\"", tree.content), Some(found), lhs.content.get_end_pos())));
+        }
+
+        let pos = tree.content.get_end_pos();
+        let op = self.context.get_compound_operation(tree.content.get_value()).unwrap().to_string();
+        let mut operation = TreeNode::new(Token::new(TokenType::Operation, op, pos));
+        operation.successors.push(lhs.clone());
+        operation.successors.push(rhs);
+        let mut assignment = TreeNode::new(Token::new(TokenType::Operation, String::from("="), pos));
+        assignment.successors.push(lhs);
+        assignment.successors.push(Box::new(operation));
+        Ok(assignment)
+    }
+
     /// Parses an unary expression.
     fn recursive_parse_unary(& mut self, left: TreeNode<Token>) -> Result<TreeNode<Token>, ParseError> {
 
@@ -398,15 +523,14 @@ impl<'a> Parser<'a> {
             }
         }
         else if t_type == TokenType::Number(NumberType::Real) || t_type == TokenType::Number(NumberType::Complex) ||
-            t_type == TokenType::Constant || t_type == TokenType::Function || t_type == TokenType::UserConstant || t.successors.len() > 0 {
+            t_type == TokenType::Constant || t_type == TokenType::Function || t_type == TokenType::UserConstant || t_type == TokenType::Unit ||
+            t_type == TokenType::Symbol(SymbolicTokenType::UnknownConstant) || t.successors.len() > 0 {
 
+            // unknown constants are accepted like in binary expressions (e.g. function parameters as in "f(x) = -x"),
+            // the evaluator reports them if they remain undefined
             m_left.successors.push(Box::new(t));
             Ok(m_left)
         }
-        else if t_type == TokenType::Symbol(SymbolicTokenType::UnknownConstant) {
-            Err(ParseError::from(ExpectedErrorTemplate::new(self.tokenizer.get_input(), "unary operation or operand",
-                                                            Some(format!("undefined constant \"{0}\"", t.content)), t.content.get_end_pos())))
-        }
         else if t_type == TokenType::Symbol(SymbolicTokenType::UnknownFunction) {
             Err(ParseError::from(ExpectedErrorTemplate::new(self.tokenizer.get_input(), "unary operation or operand",
                                                             Some(format!("undefined function \"{0}\"", t.content)), t.content.get_end_pos())))
@@ -1,34 +1,40 @@
 
-use std::str::Chars;
+use std::str::CharIndices;
 use std::iter::Peekable;
 
 /// The input stream operates on an input string and provides character-wise access.
+/// The stream keeps track of both the character index and the byte offset of the next character,
+/// so that parts of the input string can be sliced without copying (byte offsets) while error messages
+/// can still mark positions of multi-byte characters correctly (character indices).
 pub struct InputStream<'a> {
     /// The input string.
     input: &'a str,
-    /// The position of the next character in the input string.
+    /// The position (character index) of the next character in the input string.
     pos: usize,
+    /// The byte offset of the next character in the input string.
+    byte_pos: usize,
     /// The iterator over the input string.
-    iterator: Peekable<Chars<'a>>
+    iterator: Peekable<CharIndices<'a>>
 }
 
 impl<'a> InputStream<'a> {
 
     /// Generates a new InputStream instance.
     pub fn new(input: &'a str) -> InputStream<'a> {
-        InputStream{input: input, pos: 0, iterator: input.chars().peekable()}
+        InputStream{input: input, pos: 0, byte_pos: 0, iterator: input.char_indices().peekable()}
     }
 
     /// Returns the character of the next position of the stream without discarding it from the stream.
     pub fn peek(& mut self) -> Option<char> {
-        self.iterator.peek().map(|x| *x)
+        self.iterator.peek().map(|x| x.1)
     }
 
     /// Returns the character of the next position of the stream and advances the stream position.
     pub fn next(& mut self) -> Option<char> {
         match self.iterator.next() {
-            Some(x) => {
+            Some((i, x)) => {
                 self.pos += 1;
+                self.byte_pos = i + x.len_utf8();
                 Some(x)
             },
             None => None
@@ -40,11 +46,26 @@ impl<'a> InputStream<'a> {
         self.iterator.peek().is_none()
     }
 
-    /// Returns the current position of the input string.
+    /// Returns the current position (character index) of the input string.
     pub fn get_pos(& self) -> usize {
         self.pos
     }
 
+    /// Returns the current byte offset of the input string.
+    pub fn get_byte_pos(& self) -> usize {
+        self.byte_pos
+    }
+
+    /// Returns the part of the input string between the specified byte offsets without copying it.
+    pub fn slice(& self, start: usize, end: usize) -> &'a str {
+        &self.input[start..end]
+    }
+
+    /// Returns the part of the input string that has not been read yet without copying it.
+    pub fn remaining(& self) -> &'a str {
+        &self.input[self.byte_pos..]
+    }
+
     /// Returns the input string.
     pub fn get_input(& self) -> & str {
         & self.input
@@ -4,10 +4,12 @@ pub mod input_stream;
 
 use std::fmt;
 use std::error::Error;
+use std::collections::VecDeque;
 use error_templates::create_location_string;
 use token::{Token, TokenType, SymbolicTokenType, NumberType};
 use parser::tokenizer::input_stream::InputStream;
 use math_context::MathContext;
+use unit;
 
 /// Defines the error if an invalid / unknown token has been read.
 #[derive(Clone, Debug)]
@@ -61,14 +63,16 @@ pub struct Tokenizer<'a> {
     /// The character input stream.
     input_stream: InputStream<'a>,
     /// The current token.
-    token: Option<Result<Token, TokenError>>
+    token: Option<Result<Token, TokenError>>,
+    /// The tokens that have already been read from the input stream, but not yet returned (e.g. the tokens of a number with a unit).
+    pending: VecDeque<Result<Token, TokenError>>
 }
 
 impl<'a> Tokenizer<'a> {
 
     /// Creates a new Tokenizer instance.
     pub fn new(context: &'a MathContext, input: &'a str) -> Tokenizer<'a> {
-        let mut t = Tokenizer{context: context, input_stream: InputStream::new(input), token: None};
+        let mut t = Tokenizer{context: context, input_stream: InputStream::new(input), token: None, pending: VecDeque::new()};
         t.token = t.read_dispatcher();
         t
     }
@@ -106,6 +110,9 @@ impl<'a> Tokenizer<'a> {
 
     /// Calls the correct reading method regarding the current token.
     fn read_dispatcher(& mut self) -> Option<Result<Token, TokenError>> {
+        if let Some(t) = self.pending.pop_front() {
+            return Some(t);
+        }
         self.ignore_while(Tokenizer::is_whitespace);
         let peeked_char = match self.input_stream.peek() {
            Some(c) => c,
@@ -116,10 +123,11 @@ impl<'a> Tokenizer<'a> {
             Some(Ok(self.read_char_sequence()))
         }
         else if self.context.is_number_symbol(& peeked_char) || peeked_char == '.' {
-            Some(Ok(self.read_number()))
+            let number = self.read_number();
+            Some(Ok(self.read_unit(number)))
         }
-        else if self.context.is_operation(& peeked_char.to_string()) {
-            Some(Ok(self.read_operation()))
+        else if self.context.is_operation_symbol(& peeked_char) {
+            Some(self.read_operation())
         }
         else if self.context.is_punctuation_symbol(& peeked_char) {
             Some(Ok(self.read_punctuation()))
@@ -145,7 +153,7 @@ impl<'a> Tokenizer<'a> {
     /// Reads a number token from the input stream.
     fn read_number(& mut self) -> Token {
 
-        let mut value = String::new();
+        let start = self.input_stream.get_byte_pos();
         let mut is_first_digit = true;
         let mut last_was_e = false;
         let mut formatting_zero = false;
@@ -162,44 +170,49 @@ impl<'a> Tokenizer<'a> {
                     formatting_zero = false;
                 }
                 last_was_e = false;
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
             }
             else if peeked_char == '.' && is_first_digit {
                 formatting_zero = false;
                 last_was_e = false;
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
             }
             else if peeked_char == '.' {
                 formatting_zero = false;
                 last_was_e = false;
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
             }
             else if peeked_char == 'i' && !is_first_digit {
                 num_type = NumberType::Complex;
-                self.input_stream.next().unwrap();
                 break;
             }
             else if peeked_char == 'E' {
                 formatting_zero = false;
                 last_was_e = true;
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
             }
             else if (peeked_char == '+' || peeked_char == '-') && last_was_e {
                 formatting_zero = false;
                 last_was_e = false;
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
+            }
+            else if peeked_char == 'r' && formatting_zero {
+                // numbers of an arbitrary radix (e.g. "0r36:z1"), whose digits may be any letters
+                self.input_stream.next();
+                self.read_radix_digits();
+                break;
             }
             else if (peeked_char == 'x' || peeked_char == 'o' || peeked_char == 'b') && formatting_zero  {
                 // formatting characters for hexadecimal, octal and binary numbers
                 formatting_zero = false;
                 last_was_e = false;
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
             }
             else if peeked_char == 'a' || peeked_char == 'b' || peeked_char == 'c' || peeked_char == 'd' || peeked_char == 'e' || peeked_char == 'f' {
                 // digits of hexadecimal numbers (note: the 'b' is tested for in the previous else-if branch)
                 formatting_zero = false;
                 last_was_e = false;
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
             }
             else if self.context.is_literal_symbol(&peeked_char) {
                 // We are adding the literal symbols to the value string although they are no number symbols, so the parsing of the number will fail.
@@ -210,7 +223,7 @@ impl<'a> Tokenizer<'a> {
                 // 5h
                 //  ^~~~ Found: Invalid literal symbol(s).
 
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
             }
             else {
                 break;
@@ -219,37 +232,91 @@ impl<'a> Tokenizer<'a> {
             is_first_digit = false;
         }
 
+        let mut value = self.input_stream.slice(start, self.input_stream.get_byte_pos()).to_string();
+        if value.contains('_') && Tokenizer::is_digit_grouping(&value) {
+            // the digit separators (e.g. in "1_000_000") are not part of the value
+            value = value.replace('_', "");
+        }
+        if num_type == NumberType::Complex {
+            // the imaginary unit "i" is not part of the value
+            self.input_stream.next();
+        }
+
         Token::new(TokenType::Number(num_type), value, self.get_pos())
     }
 
-    /// Reads a constant or a function token from the input stream.
+    /// Checks whether each "_" of the specified number literal separates two digits (e.g. "1_000.5" or "0xff_ff").
+    /// Otherwise, the "_" is left in the value, so that the parsing of the number fails.
+    fn is_digit_grouping(value: & str) -> bool {
+        let radix = if value.starts_with("0x") { 16 } else { 10 };
+        let chars : Vec<char> = value.chars().collect();
+        chars.iter().enumerate().all(|(i, &c)| c != '_' ||
+            (i > 0 && i + 1 < chars.len() && chars[i - 1].is_digit(radix) && chars[i + 1].is_digit(radix)))
+    }
+
+    /// Reads the radix and the digits of a number of an arbitrary radix after the prefix "0r" (e.g. "36:z1.i" of "0r36:z1.i").
+    /// The radix is a decimal number that is separated from the digits by ":". The digits are validated by the evaluator.
+    fn read_radix_digits(& mut self) {
+        while self.input_stream.peek().map_or(false, |c| c.is_digit(10)) {
+            self.input_stream.next();
+        }
+        if self.input_stream.peek() == Some(':') {
+            self.input_stream.next();
+            while self.input_stream.peek().map_or(false, |c| c.is_alphanumeric() || c == '.') {
+                self.input_stream.next();
+            }
+        }
+    }
+
+    /// Reads the unit that follows the specified number token after whitespace (e.g. "km/h" in "36 km/h"), if any.
+    /// A number with a unit is tokenized like the product of the number and the unit in parentheses (e.g. "(36*km/h)"),
+    /// so that e.g. "3 m / 2 s" is the quotient of two quantities. Returns the next token to be returned.
+    fn read_unit(& mut self, number: Token) -> Token {
+        let remaining = self.input_stream.remaining();
+        let unit_start = remaining.find(|c: char| !c.is_whitespace()).unwrap_or(remaining.len());
+        let unit_len = unit::match_unit(& remaining[unit_start..]);
+        if unit_start == 0 || unit_len == 0 {
+            return number;
+        }
+
+        let unit = remaining[unit_start..unit_start + unit_len].to_string();
+        for _ in remaining[..unit_start + unit_len].chars() {
+            self.input_stream.next();
+        }
+        let pos = number.get_end_pos();
+        self.pending.push_back(Ok(number));
+        self.pending.push_back(Ok(Token::new(TokenType::Operation, String::from("*"), pos)));
+        self.pending.push_back(Ok(Token::new(TokenType::Unit, unit, self.get_pos())));
+        self.pending.push_back(Ok(Token::new(TokenType::Punctuation, String::from(")"), self.get_pos())));
+        Token::new(TokenType::Punctuation, String::from("("), pos)
+    }
+
+    /// Reads a constant, a function or a word operation token from the input stream.
     fn read_char_sequence(& mut self) -> Token {
 
-        let mut value = String::new();
+        let start = self.input_stream.get_byte_pos();
 
         while !self.input_stream.eof() {
 
             let peeked_char = self.input_stream.peek().unwrap();
             if self.context.is_literal_symbol(& peeked_char) || self.context.is_number_symbol(& peeked_char) {
-                value.push(self.input_stream.next().unwrap());
+                self.input_stream.next();
             }
             else {
                 break;
             }
         }
 
+        let value = self.input_stream.slice(start, self.input_stream.get_byte_pos()).to_string();
+
         let token : Token;
-        let mut next_is_paren = false;
-        if !self.input_stream.eof() {
-            let peeked_char = self.input_stream.peek().unwrap();
-            if peeked_char == '(' {
-                next_is_paren = true;
-            }
-            else {
-                next_is_paren = false;
-            }
+        // whitespace between a function name and the open parenthesis is allowed (e.g. "sin (x)")
+        let next_is_paren = self.input_stream.remaining().trim_start().starts_with('(');
+        if self.context.is_operation(& value) {
+            // an operation that is a word (e.g. "xor")
+            token = Token::new(TokenType::Operation, value, self.get_pos());
         }
-        if self.context.is_built_in_constant(& value) && !next_is_paren {
+        else if self.context.is_built_in_constant(& value) && !next_is_paren {
             token = Token::new(TokenType::Constant, value, self.get_pos());
         }
         else if self.context.is_user_constant(& value) && !next_is_paren {
@@ -288,15 +355,24 @@ impl<'a> Tokenizer<'a> {
     }
 
     /// Reads an operation token from the input stream.
-    fn read_operation(& mut self) -> Token {
-
-        let mut value = String::new();
+    /// The longest operation of the mathematical context that matches the input is read (e.g. "**" instead of "*").
+    /// Returns an error if the input only starts like an operation (e.g. "!" without "=").
+    fn read_operation(& mut self) -> Result<Token, TokenError> {
+
+        let value = match self.context.match_operation(self.input_stream.remaining()) {
+            Some(op) => op.to_string(),
+            None => {
+                let c = self.input_stream.peek().unwrap_or(' ');
+                return Err(TokenError::new(c.to_string(), create_location_string(
+                    self.input_stream.get_input(), self.input_stream.get_pos())));
+            }
+        };
 
-        if !self.input_stream.eof() {
-            value.push(self.input_stream.next().unwrap());
+        for _ in value.chars() {
+            self.input_stream.next();
         }
 
-        Token::new(TokenType::Operation, value, self.get_pos())
+        Ok(Token::new(TokenType::Operation, value, self.get_pos()))
     }
 
     /// Reads a punctuation token from the input stream.
@@ -0,0 +1,21 @@
+//! The prelude of termc_model, which re-exports the types and functions that are needed to evaluate mathematical
+//! expressions, so that they can be imported at once without knowing the internal module paths.
+//!
+//! # Examples
+//!
+//! ```
+//! use termc_model::prelude::*;
+//!
+//! let mut context = MathContext::new();
+//! let result = get_result("2*3 + 1", &mut context).unwrap().unwrap();
+//! assert!(result == MathResult::from(7.0));
+//! assert!(result.result_type == NumberType::Real);
+//! ```
+
+pub use math_context::MathContext;
+pub use math_result::MathResult;
+pub use token::NumberType;
+pub use result_error::ResultError;
+pub use parser::ParseError;
+pub use evaluator::EvaluationError;
+pub use super::{get_result, get_statement_results, get_result_with_stats, EvaluationStats};
@@ -0,0 +1,29 @@
+/// Defines a small pseudo random number generator (xorshift64*).
+/// The generator is seeded explicitly, so that the generated sequences are reproducible.
+#[derive(Clone, Debug)]
+pub struct Random {
+    /// The state of the generator (never zero).
+    state: u64
+}
+
+impl Random {
+
+    /// Creates a new Random instance with the specified seed.
+    pub fn new(seed: u64) -> Random {
+        Random {state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed }}
+    }
+
+    /// Generates the next pseudo random 64 bit number.
+    pub fn next_u64(& mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Generates a pseudo random number that is uniformly distributed in the interval [a, b).
+    pub fn uniform(& mut self, a: f64, b: f64) -> f64 {
+        let x = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64; // 53 random bits in [0, 1)
+        a + x * (b - a)
+    }
+}
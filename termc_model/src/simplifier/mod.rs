@@ -0,0 +1,248 @@
+use token::{Token, TokenType, NumberType};
+use math_context::{MathContext, OperationType};
+use tree::TreeNode;
+use deparser;
+use deparser::is_binary;
+
+/// The maximum number of rewrites of a single simplification (guards against rules that undo each other).
+const MAX_REWRITES : usize = 10000;
+
+/// The maximum number of significant digits of a folded number. Results that need more digits (e.g. "0.1 + 0.2") are
+/// rounding artifacts of the floating point arithmetic and are therefore not folded.
+const MAX_FOLDED_DIGITS : usize = 15;
+
+/// A rewrite rule: returns the rewritten tree if the rule applies to the root of the specified tree, otherwise None.
+type Rule = fn(& TreeNode<Token>, & MathContext) -> Option<TreeNode<Token>>;
+
+/// The rewrite rules in the order in which they are tried.
+const RULES : [Rule; 3] = [fold_constants, remove_neutral_elements, combine_like_terms];
+
+/// Simplifies the specified expression tree by algebraic rewrites: operations on numbers are folded (e.g. "2*3" becomes "6"),
+/// neutral and absorbing elements are removed (e.g. "x + 0" and "x*1" become "x", "x*0" becomes "0") and like terms are
+/// combined (e.g. "2*x + y + 3*x" becomes "5*x + y"). Symbols (e.g. unknown constants or user constants) are never evaluated.
+pub fn simplify(tree: & TreeNode<Token>, context: & MathContext) -> TreeNode<Token> {
+    let mut rewrites = 0;
+    rewrite(tree, context, & mut rewrites)
+}
+
+/// Applies the rewrite rules bottom-up: first to the successors, then to the root until no rule applies anymore.
+fn rewrite(tree: & TreeNode<Token>, context: & MathContext, rewrites: & mut usize) -> TreeNode<Token> {
+    let mut node = TreeNode::new(tree.content.clone());
+    node.successors = tree.successors.iter().map(|s| Box::new(rewrite(s, context, rewrites))).collect();
+
+    while *rewrites < MAX_REWRITES {
+        match RULES.iter().filter_map(|rule| rule(& node, context)).next() {
+            Some(t) => {
+                *rewrites += 1;
+                // the rewritten tree may contain new subtrees that can be simplified
+                node = rewrite(& t, context, rewrites);
+            },
+            None => break
+        }
+    }
+    node
+}
+
+/// Folds a binary operation of two numbers (e.g. "2*3" becomes "6"). Quotients and powers are only folded if the result
+/// is an integer (e.g. "1/3" is kept).
+fn fold_constants(tree: & TreeNode<Token>, context: & MathContext) -> Option<TreeNode<Token>> {
+    if !is_binary(tree) {
+        return None;
+    }
+    let a = get_number(tree.successors[0].as_ref())?;
+    let b = get_number(tree.successors[1].as_ref())?;
+    let x = match get_operation_type(tree, context)? {
+        OperationType::Add => a + b,
+        OperationType::Sub => a - b,
+        OperationType::Mul => a * b,
+        OperationType::Div if b != 0.0 && (a / b).fract() == 0.0 => a / b,
+        OperationType::Pow if (a.powf(b)).fract() == 0.0 => a.powf(b),
+        _ => return None
+    };
+    if is_exact(x) { Some(number(x)) } else { None }
+}
+
+/// Removes neutral and absorbing elements and double negations (e.g. "x + 0", "x*1", "x^1" and "--x" become "x",
+/// "x*0" becomes "0" and "x^0" becomes "1").
+fn remove_neutral_elements(tree: & TreeNode<Token>, context: & MathContext) -> Option<TreeNode<Token>> {
+    if tree.content.get_type() != TokenType::Operation {
+        return None;
+    }
+    if tree.successors.len() == 1 {
+        let x = tree.successors[0].as_ref();
+        return match get_operation_type(tree, context)? {
+            OperationType::Add => Some(x.clone()),
+            OperationType::Sub if get_number(x) == Some(0.0) => Some(number(0.0)),
+            OperationType::Sub => get_negated(x).cloned(),
+            _ => None
+        };
+    }
+    if !is_binary(tree) || is_quantity(tree) {
+        return None;
+    }
+
+    let (lhs, rhs) = (tree.successors[0].as_ref(), tree.successors[1].as_ref());
+    let (a, b) = (get_number(lhs), get_number(rhs));
+    match get_operation_type(tree, context)? {
+        OperationType::Add if a == Some(0.0) => Some(rhs.clone()),
+        OperationType::Add if b == Some(0.0) => Some(lhs.clone()),
+        OperationType::Add => get_negated(rhs).map(|x| operation("-", lhs.clone(), x.clone())),
+        OperationType::Sub if b == Some(0.0) => Some(lhs.clone()),
+        OperationType::Sub if a == Some(0.0) => Some(negate(rhs.clone())),
+        OperationType::Sub => get_negated(rhs).map(|x| operation("+", lhs.clone(), x.clone())),
+        OperationType::Mul if a == Some(0.0) || b == Some(0.0) => Some(number(0.0)),
+        OperationType::Mul if a == Some(1.0) => Some(rhs.clone()),
+        OperationType::Mul if b == Some(1.0) => Some(lhs.clone()),
+        OperationType::Mul if a == Some(-1.0) => Some(negate(rhs.clone())),
+        OperationType::Mul if b == Some(-1.0) => Some(negate(lhs.clone())),
+        OperationType::Div if b == Some(1.0) => Some(lhs.clone()),
+        OperationType::Div if a == Some(0.0) && b.map_or(true, |b| b != 0.0) => Some(number(0.0)),
+        OperationType::Pow if b == Some(0.0) || a == Some(1.0) => Some(number(1.0)),
+        OperationType::Pow if b == Some(1.0) => Some(lhs.clone()),
+        _ => None
+    }
+}
+
+/// Combines the like terms of a sum (e.g. "2*x + y - x + 3" becomes "x + y + 3"). Terms are alike if they only differ in
+/// their numeric coefficient. The combined terms keep the order of their first occurrence, the numbers are moved to the end.
+fn combine_like_terms(tree: & TreeNode<Token>, context: & MathContext) -> Option<TreeNode<Token>> {
+    match get_operation_type(tree, context) {
+        Some(OperationType::Add) | Some(OperationType::Sub) if is_binary(tree) => (),
+        _ => return None
+    }
+
+    let mut terms = Vec::new();
+    collect_terms(tree, 1.0, context, & mut terms);
+
+    // group the terms by their canonical representation (the numbers have the key "")
+    let mut groups : Vec<(String, f64, Option<TreeNode<Token>>)> = Vec::new();
+    for (c, term) in terms.iter().cloned() {
+        let key = term.as_ref().map_or(String::new(), |t| deparser::deparse(t, context));
+        match groups.iter().position(|g| g.0 == key) {
+            Some(i) => groups[i].1 += c,
+            None => groups.push((key, c, term))
+        }
+    }
+    if groups.len() == terms.len() || groups.iter().any(|g| !is_exact(g.1)) {
+        return None;
+    }
+
+    // the numbers are the last term
+    if let Some(i) = groups.iter().position(|g| g.2.is_none()) {
+        let numbers = groups.remove(i);
+        groups.push(numbers);
+    }
+
+    let mut sum : Option<TreeNode<Token>> = None;
+    for (_, c, term) in groups.into_iter().filter(|g| g.1 != 0.0) {
+        let magnitude = match term {
+            Some(t) => if c.abs() == 1.0 { t } else { operation("*", number(c.abs()), t) },
+            None => number(c.abs())
+        };
+        sum = Some(match sum {
+            None if c < 0.0 => negate(magnitude),
+            None => magnitude,
+            Some(s) => operation(if c < 0.0 { "-" } else { "+" }, s, magnitude)
+        });
+    }
+    Some(sum.unwrap_or(number(0.0)))
+}
+
+/// Collects the terms of the specified sum with their numeric coefficients (e.g. (2, x) for "2*x" and (-5, None) for "-5").
+fn collect_terms(tree: & TreeNode<Token>, sign: f64, context: & MathContext, terms: & mut Vec<(f64, Option<TreeNode<Token>>)>) {
+    if tree.content.get_type() == TokenType::Operation {
+        match (get_operation_type(tree, context), tree.successors.len()) {
+            (Some(OperationType::Add), 2) => {
+                collect_terms(tree.successors[0].as_ref(), sign, context, terms);
+                collect_terms(tree.successors[1].as_ref(), sign, context, terms);
+                return;
+            },
+            (Some(OperationType::Sub), 2) => {
+                collect_terms(tree.successors[0].as_ref(), sign, context, terms);
+                collect_terms(tree.successors[1].as_ref(), -sign, context, terms);
+                return;
+            },
+            (Some(OperationType::Sub), 1) => {
+                collect_terms(tree.successors[0].as_ref(), -sign, context, terms);
+                return;
+            },
+            _ => ()
+        }
+    }
+
+    if let Some(x) = get_number(tree) {
+        terms.push((sign * x, None));
+    }
+    else if is_binary(tree) && !is_quantity(tree) && get_operation_type(tree, context) == Some(OperationType::Mul) {
+        let (lhs, rhs) = (tree.successors[0].as_ref(), tree.successors[1].as_ref());
+        match (get_number(lhs), get_number(rhs)) {
+            (Some(c), _) => terms.push((sign * c, Some(rhs.clone()))),
+            (_, Some(c)) => terms.push((sign * c, Some(lhs.clone()))),
+            _ => terms.push((sign, Some(tree.clone())))
+        }
+    }
+    else {
+        terms.push((sign, Some(tree.clone())));
+    }
+}
+
+/// Checks whether the specified number is finite and has at most MAX_FOLDED_DIGITS significant digits.
+fn is_exact(x: f64) -> bool {
+    let repr = format!("{0}", x.abs());
+    let digits = repr.replace(".", "");
+    x.is_finite() && digits.trim_start_matches('0').trim_end_matches('0').len() <= MAX_FOLDED_DIGITS
+}
+
+/// Creates a tree node of the specified real number. Negative numbers are created as unary operations.
+fn number(x: f64) -> TreeNode<Token> {
+    if x < 0.0 {
+        negate(number(-x))
+    }
+    else {
+        // adding 0 turns -0 into 0
+        TreeNode::new(Token::new(TokenType::Number(NumberType::Real), format!("{0}", x + 0.0), 0))
+    }
+}
+
+/// Creates the negation of the specified tree.
+fn negate(t: TreeNode<Token>) -> TreeNode<Token> {
+    let mut node = TreeNode::new(Token::new(TokenType::Operation, String::from("-"), 0));
+    node.successors = vec![Box::new(t)];
+    node
+}
+
+/// Creates the specified binary operation.
+fn operation(op: & str, lhs: TreeNode<Token>, rhs: TreeNode<Token>) -> TreeNode<Token> {
+    let mut node = TreeNode::new(Token::new(TokenType::Operation, op.to_string(), 0));
+    node.successors = vec![Box::new(lhs), Box::new(rhs)];
+    node
+}
+
+/// Returns the value of the specified tree node if it is a real number (or a negated real number).
+fn get_number(t: & TreeNode<Token>) -> Option<f64> {
+    match t.content.get_type() {
+        TokenType::Number(NumberType::Real) => t.content.get_value().parse::<f64>().ok(),
+        TokenType::Operation if t.content.get_value() == "-" && t.successors.len() == 1 => get_number(t.successors[0].as_ref()).map(|x| -x),
+        _ => None
+    }
+}
+
+/// Returns the operand of the specified tree node if it is a negation.
+fn get_negated(t: & TreeNode<Token>) -> Option<& TreeNode<Token>> {
+    if t.content.get_type() == TokenType::Operation && t.content.get_value() == "-" && t.successors.len() == 1 {
+        Some(t.successors[0].as_ref())
+    }
+    else {
+        None
+    }
+}
+
+/// Returns the type of the operation of the specified tree node (None if the node is not an operation).
+fn get_operation_type(t: & TreeNode<Token>, context: & MathContext) -> Option<OperationType> {
+    if t.content.get_type() == TokenType::Operation { context.get_operation_type(t.content.get_value()) } else { None }
+}
+
+/// Checks whether the specified tree node is a number with a unit (e.g. "3 km"), which is kept as it is.
+fn is_quantity(t: & TreeNode<Token>) -> bool {
+    is_binary(t) && t.successors[1].content.get_type() == TokenType::Unit
+}
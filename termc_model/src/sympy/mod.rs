@@ -0,0 +1,265 @@
+use token::{Token, TokenType, NumberType, SymbolicTokenType, split_radix_literal};
+use math_context::{MathContext, OperationType, FunctionType};
+use tree::TreeNode;
+use deparser::{is_binary, get_precedence};
+
+/// Creates the SymPy-compatible Python representation of the specified expression tree.
+/// Powers are rendered with "**", the modulo operation with Mod() and the built-in constants as pi, E and I.
+pub fn render(tree: & TreeNode<Token>, context: & MathContext) -> String {
+
+    match tree.content.get_type() {
+        TokenType::Operation => {
+            if tree.successors.len() == 1 {
+                render_unary(tree, context)
+            }
+            else if tree.successors.len() == 2 {
+                render_binary(tree, context)
+            }
+            else {
+                tree.content.get_value().to_string()
+            }
+        },
+
+        TokenType::Function => render_function(tree, context),
+
+        TokenType::UserFunction | TokenType::Symbol(SymbolicTokenType::UnknownFunction) => {
+            format!("{0}({1})", tree.content, render_args(tree, context))
+        },
+
+        TokenType::Number(number_type) => render_number(tree.content.get_value(), number_type),
+
+        TokenType::Punctuation if tree.content.get_value() == "[" => {
+            let rows : Vec<String> = tree.successors.iter().map(|row| format!("[{0}]", render_args(row, context))).collect();
+            format!("Matrix([{0}])", rows.join(", "))
+        },
+
+        TokenType::Constant => {
+            match tree.content.get_value() {
+                "e" => String::from("E"),
+                "i" => String::from("I"),
+                c => c.to_string()
+            }
+        },
+
+        // the units are symbols (e.g. "m/s**2")
+        TokenType::Unit => tree.content.get_value().replace("^", "**"),
+
+        _ => tree.content.get_value().to_string()
+    }
+}
+
+/// Creates the SymPy representation of the definition of the specified user function, e.g. "f = Lambda(x, x**2)".
+pub fn render_definition(repr: & str, vars: & Vec<String>, tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let vars_repr = if vars.len() == 1 { vars[0].clone() } else { format!("({0})", vars.join(", ")) };
+    format!("{0} = Lambda({1}, {2})", repr, vars_repr, render(tree, context))
+}
+
+/// Converts simple SymPy-style input into termc syntax (best effort): "**" becomes "^", the SymPy constants E and I
+/// become e and i and the functions log and ceiling become ln and ceil. Numbers (e.g. "2E3") are not modified.
+pub fn import(s: & str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '*' && chars.peek() == Some(&'*') {
+            chars.next();
+            result.push('^');
+        }
+        else if c.is_alphabetic() || c == '_' {
+            let mut ident = c.to_string();
+            while let Some(&n) = chars.peek() {
+                if !(n.is_alphanumeric() || n == '_') {
+                    break;
+                }
+                ident.push(n);
+                chars.next();
+            }
+            result.push_str(match ident.as_str() {
+                "E" => "e",
+                "I" => "i",
+                "log" => "ln",
+                "ceiling" => "ceil",
+                "Abs" => "abs",
+                _ => &ident
+            });
+        }
+        else if c.is_digit(10) {
+            // copy numbers including their exponent or base prefix unmodified
+            result.push(c);
+            while let Some(&n) = chars.peek() {
+                if !(n.is_alphanumeric() || n == '.') {
+                    break;
+                }
+                result.push(n);
+                chars.next();
+            }
+        }
+        else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Creates the SymPy representation of an unary operation.
+fn render_unary(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let operand = tree.successors[0].as_ref();
+    let operand_repr = render(operand, context);
+
+    if context.is_postfix_operation(tree.content.get_value()) {
+        format!("factorial({0})", operand_repr)
+    }
+    else if is_binary(operand) {
+        format!("{0}({1})", tree.content, operand_repr)
+    }
+    else {
+        format!("{0}{1}", tree.content, operand_repr)
+    }
+}
+
+/// Creates the SymPy representation of a binary operation.
+fn render_binary(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let lhs = tree.successors[0].as_ref();
+    let rhs = tree.successors[1].as_ref();
+
+    match context.get_operation_type(tree.content.get_value()) {
+        Some(OperationType::Pow) => render_power(lhs, rhs, context),
+        Some(OperationType::Mod) => format!("Mod({0}, {1})", render(lhs, context), render(rhs, context)),
+        _ => {
+            let prec = get_precedence(tree, context);
+
+            // All binary operations are left associative, so the right operand needs parentheses if its
+            // precedence is equal to the precedence of the current operation.
+            // Python chains comparisons (e.g. "1 < 2 < 3"), so nested comparisons are always put into parentheses.
+            let is_comparison = prec == context.get_operation_precedence("<").unwrap_or(0);
+            let mut lhs_repr = render(lhs, context);
+            if is_binary(lhs) && (get_precedence(lhs, context) < prec || (is_comparison && get_precedence(lhs, context) == prec)) {
+                lhs_repr = format!("({0})", lhs_repr);
+            }
+            let mut rhs_repr = render(rhs, context);
+            let is_product = prec >= context.get_operation_precedence("*").unwrap_or(0);
+            if (is_binary(rhs) && get_precedence(rhs, context) <= prec) || (is_product && is_complex_number(rhs)) {
+                rhs_repr = format!("({0})", rhs_repr);
+            }
+
+            // Python writes the bitwise exclusive or as "^"
+            let op = match context.get_operation_type(tree.content.get_value()) {
+                Some(OperationType::BitXor) => "^",
+                _ => tree.content.get_value()
+            };
+
+            if prec < context.get_operation_precedence("*").unwrap_or(0) {
+                format!("{0} {1} {2}", lhs_repr, op, rhs_repr)
+            }
+            else {
+                format!("{0}{1}{2}", lhs_repr, op, rhs_repr)
+            }
+        }
+    }
+}
+
+/// Creates the SymPy representation of the specified power.
+/// In Python, "**" binds stronger than an unary minus and is right associative, so operations in the base
+/// and binary operations in the exponent are put into parentheses.
+fn render_power(base: & TreeNode<Token>, exponent: & TreeNode<Token>, context: & MathContext) -> String {
+    let mut base_repr = render(base, context);
+    if base.content.get_type() == TokenType::Operation || is_complex_number(base) {
+        base_repr = format!("({0})", base_repr);
+    }
+    let mut exponent_repr = render(exponent, context);
+    if is_binary(exponent) || is_complex_number(exponent) {
+        exponent_repr = format!("({0})", exponent_repr);
+    }
+    format!("{0}**{1}", base_repr, exponent_repr)
+}
+
+/// Creates the SymPy representation of a built-in function call.
+fn render_function(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let name = match context.get_function_type(tree.content.get_value()) {
+        Some(FunctionType::Pow) => return render_power(tree.successors[0].as_ref(), tree.successors[1].as_ref(), context),
+        Some(FunctionType::Trunc) => {
+            let arg = render(tree.successors[0].as_ref(), context);
+            return format!("(sign({0})*floor(Abs({0})))", arg);
+        },
+        Some(FunctionType::Sum) | Some(FunctionType::Prod) if context.is_series(tree) => {
+            let arg = |i: usize| render(tree.successors[i].as_ref(), context);
+            let name = if tree.content.get_value() == "sum" { "summation" } else { "product" };
+            return format!("{0}({1}, ({2}, {3}, {4}))", name, arg(0), arg(1), arg(2), arg(3));
+        },
+        Some(FunctionType::Integrate) => {
+            let integrand = render(& apply_to_variable(tree.successors[0].as_ref(), context), context);
+            let arg = |i: usize| render(tree.successors[i].as_ref(), context);
+            return format!("integrate({0}, (x, {1}, {2}))", integrand, arg(1), arg(2));
+        },
+        Some(FunctionType::If) => {
+            let arg = |i: usize| render(tree.successors[i].as_ref(), context);
+            return format!("Piecewise(({1}, {0}), ({2}, True))", arg(0), arg(1), arg(2));
+        },
+        Some(FunctionType::Inv) => return format!("({0})**(-1)", render(tree.successors[0].as_ref(), context)),
+        Some(FunctionType::Sum) => "Add",
+        Some(FunctionType::Conj) => "conjugate",
+        Some(FunctionType::Polar) => {
+            let arg = |i: usize| render(tree.successors[i].as_ref(), context);
+            return format!("({0})*exp(I*({1}))", arg(0), arg(1));
+        },
+        Some(FunctionType::Mean) => return format!("Add({0})/{1}", render_args(tree, context), tree.successors.len()),
+        Some(FunctionType::Min) => "Min",
+        Some(FunctionType::Max) => "Max",
+        Some(FunctionType::Ln) | Some(FunctionType::Log) => "log",
+        Some(FunctionType::Log2) => return format!("log({0}, 2)", render_args(tree, context)),
+        Some(FunctionType::Log10) => return format!("log({0}, 10)", render_args(tree, context)),
+        Some(FunctionType::Abs) => "Abs",
+        Some(FunctionType::Solve) => "nsolve",
+        Some(FunctionType::Convert) => "convert_to",
+        Some(FunctionType::Ceil) => "ceiling",
+        Some(FunctionType::ArcCos) => "acos",
+        Some(FunctionType::ArcSin) => "asin",
+        Some(FunctionType::ArcTan) => "atan",
+        Some(FunctionType::ArcCot) => "acot",
+        Some(FunctionType::ArcCosh) => "acosh",
+        Some(FunctionType::ArcSinh) => "asinh",
+        Some(FunctionType::ArcTanh) => "atanh",
+        Some(FunctionType::ArcCoth) => "acoth",
+        _ => tree.content.get_value()
+    };
+
+    format!("{0}({1})", name, render_args(tree, context))
+}
+
+/// Creates the call of the specified function name with the variable "x" (e.g. the integrand of an integral).
+fn apply_to_variable(f: & TreeNode<Token>, context: & MathContext) -> TreeNode<Token> {
+    let name = f.content.get_value().to_string();
+    let f_type = if context.is_built_in_function(&name) { TokenType::Function } else { TokenType::UserFunction };
+    let mut call = TreeNode::new(Token::new(f_type, name, f.content.get_end_pos()));
+    call.successors.push(Box::new(TreeNode::new(Token::new(TokenType::Symbol(SymbolicTokenType::UnknownConstant), String::from("x"), 0))));
+    call
+}
+
+/// Creates the SymPy representation of the argument list of a function call.
+fn render_args(tree: & TreeNode<Token>, context: & MathContext) -> String {
+    let args : Vec<String> = tree.successors.iter().map(|s| render(s, context)).collect();
+    args.join(", ")
+}
+
+/// Creates the SymPy representation of a number. Imaginary numbers are multiplied with the imaginary unit I.
+/// Numbers of an arbitrary radix, which Python does not support, are converted with int (e.g. "0r36:z1" is "int('z1', 36)").
+fn render_number(value: & str, number_type: NumberType) -> String {
+    let repr = match split_radix_literal(value) {
+        Some((radix, digits)) if value.starts_with("0r") => match digits.find('.') {
+            Some(idx) => format!("Rational(int('{0}{1}', {2}), {2}**{3})", &digits[..idx], &digits[idx + 1..], radix,
+                                 digits.len() - idx - 1),
+            None => format!("int('{0}', {1})", digits, radix)
+        },
+        Some(_) => value.to_string(),
+        None => value.replace("E", "e")
+    };
+    match number_type {
+        NumberType::Complex => format!("{0}*I", repr),
+        NumberType::Real => repr
+    }
+}
+
+/// Checks whether the specified tree node is an imaginary number (rendered as a product).
+fn is_complex_number(tree: & TreeNode<Token>) -> bool {
+    tree.content.get_type() == TokenType::Number(NumberType::Complex)
+}
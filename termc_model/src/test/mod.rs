@@ -1,10 +1,15 @@
 use std::f64;
+use std::time::Instant;
 use serde_json;
-use super::get_result;
-use math_context::MathContext;
+use super::{get_result, get_statement_results, split_statements, normalize_input, get_latex, get_sympy, from_sympy, get_derivative, get_simplified, iterate, check_identity,
+            sample_function, freeze_user_function, IDENTITY_SAMPLES};
+use math_context::{MathContext, ComparisonMode, PrecisionMode, AngleMode};
 use token::{NumberType, TokenType, SymbolicTokenType, Token};
 use tree::TreeNode;
-use math_result::MathResult;
+use parser::tokenizer::Tokenizer;
+use parser::tokenizer::input_stream::InputStream;
+use char_set::CharSet;
+use math_result::{MathResult, FormatFraction, FormatRadix, FormatIEEE754, FormatPolar};
 
 static TEST_BOUND : f64 = 10e-10;
 
@@ -622,6 +627,17 @@ fn tst_get_result() {
     assert!(result.result_type == NumberType::Real);
     assert!(result.value.re - 87.0 < TEST_BOUND);
 
+    // test log functions
+    let result = get_result("log(81, 3) + log2(1024) - log10(0.001)", & mut context).unwrap().unwrap();
+    assert!(result.result_type == NumberType::Real);
+    assert!((result.value.re - 17.0).abs() < TEST_BOUND);
+    let result = get_result("log(1000, 10)", & mut context).unwrap().unwrap();
+    assert!(result.value.re == 3.0);
+    let result = get_result("log10(-1)", & mut context).unwrap().unwrap();
+    assert!(result.result_type == NumberType::Complex);
+    assert!((result.value.im - f64::consts::PI / f64::consts::LN_10).abs() < TEST_BOUND);
+    assert!(get_result("log(8)", & mut context).is_err());
+
     // test pow function
     let result = get_result("pow(5, 2)", & mut context);
     assert!(result.is_ok());
@@ -823,6 +839,17 @@ fn tst_get_result() {
     let msg = format!("{}", result.err().unwrap());
     assert!(msg == "Error: Expected built-in or user defined function.\n3-cis(pi/2)+sin(0)\n    ^~~~ Found: unknown function \"cis(...)\"");
 
+    // test call of a constant (the multiplication is suggested for a single argument)
+    let result = get_result("pi(2)", & mut context);
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Expected built-in or user defined function.\npi(2)\n ^~~~ Found: constant \"pi\" that is not callable (did you mean \"pi*2\"?)");
+    let result = get_result("3 + e(1 + x)", & mut context);
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Expected built-in or user defined function.\n3 + e(1 + x)\n    ^~~~ Found: constant \"e\" that is not callable (did you mean \"e*(1 + x)\"?)");
+    let result = get_result("pi(1, 2)", & mut context);
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Expected built-in or user defined function.\npi(1, 2)\n ^~~~ Found: constant \"pi\" that is not callable");
+
     // test unknown constant
     let result = get_result("5*3+cos(py)-7^1", & mut context);
     assert!(result.is_err());
@@ -844,11 +871,11 @@ fn tst_get_result() {
 
 
     // test unexpected token
-    let result = get_result("5+|", & mut context);
+    let result = get_result("5+$", & mut context);
     assert!(result.is_err());
     let msg = format!("{}", result.err().unwrap());
     println!("Error-msg: {}", msg);
-    assert!(msg == "Error: Unknown token found: \"|\".\n5+|\n  ^~~~");
+    assert!(msg == "Error: Unknown token found: \"$\".\n5+$\n  ^~~~");
 
 
     // test expectation of ")" in argument list
@@ -883,11 +910,13 @@ fn tst_get_result() {
     let msg = format!("{}", result.err().unwrap());
     assert!(msg == "Error: Expected new constant name or function name.\npi = 5\n ^~~~ Found: built-in expression \"pi\"");
 
-    // test expectation error for recursive user function definition
+    // test expectation error for endless recursive user function calls
     let result = get_result("z(x) = z(x) + 2", & mut context);
+    assert!(result.is_ok());
+    let result = get_result("z(1)", & mut context);
     assert!(result.is_err());
     let msg = format!("{}", result.err().unwrap());
-    assert!(msg == "Error: Expected non-symbolic expression.\nz(x) = z(x) + 2\n       ^~~~ Found: symbolic expression \"z\"");
+    assert!(msg == "Error: Maximum depth of 64 nested user function calls exceeded in \"z\".");
     // reset context
     let mut context = MathContext::new();
 
@@ -899,6 +928,15 @@ fn tst_get_result() {
     // reset context
     let mut context = MathContext::new();
 
+    // test definition of functions with assignments in the function body
+    let result = get_result("f(x) = (x = 2) + x", & mut context);
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Expected expression.\nf(x) = (x = 2) + x\n          ^~~~ Found: assignment to parameter \"x\" in function definition");
+    let result = get_result("f(x) = x * (c = 2)", & mut context);
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Expected expression.\nf(x) = x * (c = 2)\n              ^~~~ Found: assignment in function definition");
+    assert!(!context.is_user_function("f") && !context.is_user_constant("c"));
+
     // test definition of user function with equal arguments
     let result = get_result("h(x, y, x) = x^2+y", & mut context);
     assert!(result.is_err());
@@ -1053,3 +1091,1647 @@ fn tst_deserialization() {
     let f_input = f_input.unwrap();
     assert!(f_input == "f(x) = x^2");
 }
+
+#[test]
+fn tst_canonical_input() {
+    let mut context = MathContext::new();
+
+    // test removal of whitespace and redundant parentheses
+    let result = get_result("f(x,  y)=(x*(y))+((2))", & mut context);
+    assert!(result.is_ok());
+    assert!(context.get_user_function_input("f").unwrap() == "f(x,  y)=(x*(y))+((2))");
+    assert!(context.get_user_function_canonical_input("f").unwrap() == "f(x, y) = x*y + 2");
+
+    // test preservation of parentheses that are required by the operation precedence
+    let result = get_result("g(a, b) = (a+b)*(a-(b-1))^2", & mut context);
+    assert!(result.is_ok());
+    assert!(context.get_user_function_canonical_input("g").unwrap() == "g(a, b) = (a + b)*(a - (b - 1))^2");
+
+    // test unary operations, complex numbers and function calls
+    let result = get_result("h(x)=-(x+1)*sin( 2*x )+3i", & mut context);
+    assert!(result.is_ok());
+    assert!(context.get_user_function_canonical_input("h").unwrap() == "h(x) = -(x + 1)*sin(2*x) + 3i");
+
+    // test whitespace between function names and the open parenthesis
+    let result = get_result("k (x) = sin (x) + max\t(x, 2)", & mut context);
+    assert!(result.is_ok());
+    assert!(context.get_user_function_canonical_input("k").unwrap() == "k(x) = sin(x) + max(x, 2)");
+    assert!(get_result("k (0)", & mut context).unwrap().unwrap().value.re == 2.0);
+
+    // test that the canonical form is restored after deserialization
+    let s = serde_json::to_string(& context).unwrap();
+    let mut m : MathContext = serde_json::from_str(& s).unwrap();
+    m.initialize();
+    assert!(m.get_user_function_canonical_input("g").unwrap() == "g(a, b) = (a + b)*(a - (b - 1))^2");
+}
+
+#[test]
+fn tst_number_like_constant_names() {
+    let mut context = MathContext::new();
+
+    // test rejection of names that look like an exponent
+    let result = get_result("E12 = 5", & mut context);
+    assert!(result.is_err());
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Expected new constant name or function name.\nE12 = 5\n  ^~~~ Found: constant name \"E12\" that resembles a number literal");
+    assert!(get_result("E = 5", & mut context).is_err());
+    assert!(!context.is_user_constant("E12"));
+
+    // test that names like radix literals without the leading zero are accepted
+    for name in vec!["x1", "x2", "b0", "b1", "o7", "b101", "x1f"] {
+        assert!(get_result(& format!("{0} = 2", name), & mut context).is_ok());
+        assert!(context.get_constant_value(name).unwrap().value.re == 2.0);
+    }
+
+    // test that ordinary names are still accepted
+    assert!(get_result("b = 2", & mut context).is_ok());
+    assert!(get_result("x = 3", & mut context).is_ok());
+    assert!(get_result("c0 = 4", & mut context).is_ok());
+    assert!(get_result("b102 = 5", & mut context).is_ok());
+    assert!(get_result("Ex = 6", & mut context).is_ok());
+    let result = get_result("0b11+b", & mut context);
+    assert!(result.ok().unwrap().unwrap().value.re - 5.0 < TEST_BOUND);
+
+    // test that function parameters are not affected
+    assert!(get_result("f(x1) = x1^2", & mut context).is_ok());
+}
+
+#[test]
+fn tst_comparison() {
+    let mut context = MathContext::new();
+
+    // test comparison of real numbers
+    let result = get_result("3 < 4", & mut context).ok().unwrap().unwrap();
+    assert!(result.result_type == NumberType::Real);
+    assert!(result.value.re == 1.0);
+    let result = get_result("3 > 4", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 0.0);
+
+    // test precedence of comparisons
+    let result = get_result("1 + 2 > 2*1", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 1.0);
+
+    // test comparison of complex numbers with a vanishing imaginary part
+    let result = get_result("2+3i-3i < 5", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 1.0);
+
+    // test error for the comparison of complex numbers
+    let result = get_result("2 < 1+i", & mut context);
+    assert!(result.is_err());
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Expected real operands.\n2 < 1+i\n  ^~~~ Found: comparison \"<\" of complex numbers");
+
+    // test comparison of complex numbers by their modulus
+    context.set_comparison_mode(ComparisonMode::Modulus);
+    let result = get_result("3+4i > 4.9", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 1.0);
+    let result = get_result("3+4i > -5", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 0.0);
+}
+
+#[test]
+fn tst_rounding_functions() {
+    let mut context = MathContext::new();
+
+    // test floor, ceil, trunc and frac functions
+    let result = get_result("floor(-2.5)", & mut context).ok().unwrap().unwrap();
+    assert!(result.result_type == NumberType::Real);
+    assert!(result.value.re == -3.0);
+    let result = get_result("ceil(-2.5)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == -2.0);
+    let result = get_result("trunc(-2.5)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == -2.0);
+    let result = get_result("frac(-2.5)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == -0.5);
+
+    // test componentwise rounding of complex numbers
+    let result = get_result("floor(1.5-0.5i)", & mut context).ok().unwrap().unwrap();
+    assert!(result.result_type == NumberType::Complex);
+    assert!(result.value.re == 1.0);
+    assert!(result.value.im == -1.0);
+    let result = get_result("trunc(1.5+0.5i)", & mut context).ok().unwrap().unwrap();
+    assert!(result.result_type == NumberType::Real);
+    assert!(result.value.re == 1.0);
+
+    // test abs and sign functions with real and complex arguments
+    let result = get_result("abs(-2.5)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 2.5);
+    let result = get_result("abs(3+4i)", & mut context).ok().unwrap().unwrap();
+    assert!(result.result_type == NumberType::Real);
+    assert!(result.value.re == 5.0);
+    let result = get_result("sign(-2.5) + sign(0) + 2*sign(7)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 1.0);
+    let result = get_result("sign(3-4i)", & mut context).ok().unwrap().unwrap();
+    assert!(result.result_type == NumberType::Complex);
+    assert!((result.value.re - 0.6).abs() < TEST_BOUND);
+    assert!((result.value.im + 0.8).abs() < TEST_BOUND);
+
+    // test the complex helpers conj, arg and polar
+    assert!(get_result("conj(3+4i)", & mut context).unwrap().unwrap() == MathResult::from((3.0, -4.0)));
+    assert!(get_result("conj(2)", & mut context).unwrap().unwrap() == MathResult::from(2.0));
+    assert!((get_result("arg(-2i)", & mut context).unwrap().unwrap().value.re + f64::consts::FRAC_PI_2).abs() < TEST_BOUND);
+    assert!(get_result("polar(2, pi/2)", & mut context).unwrap().unwrap() == MathResult::from((0.0, 2.0)));
+    let result = get_result("polar(abs(1-i), arg(1-i))", & mut context).unwrap().unwrap();
+    assert!((result.value.re - 1.0).abs() < TEST_BOUND && (result.value.im + 1.0).abs() < TEST_BOUND);
+    assert!(get_result("polar(i, 1)", & mut context).is_err());
+    assert!(MathResult::from((0.0, -3.0)).polar_fmt() == "3*exp(-1.5707963267948966i)");
+    assert!(get_latex("conj(z) + arg(z)", & context).unwrap() == "\\overline{z} + \\arg\\left(z\\right)");
+
+    assert!(get_latex("abs(x) + sign(x)", & context).unwrap() == "\\left| x \\right| + \\operatorname{sgn}\\left(x\\right)");
+    assert!(get_sympy("abs(x) + sign(x)", & context).unwrap() == "Abs(x) + sign(x)");
+
+    // test round function with positive, zero and negative number of decimal places
+    let result = get_result("round(pi, 3)", & mut context).ok().unwrap().unwrap();
+    assert!((result.value.re - 3.142).abs() < TEST_BOUND);
+    let result = get_result("round(2.5, 0)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 3.0);
+    let result = get_result("round(1234.5, -2)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 1200.0);
+    let result = get_result("round(1.25+2.75i, 1)", & mut context).ok().unwrap().unwrap();
+    assert!((result.value.re - 1.3).abs() < TEST_BOUND);
+    assert!((result.value.im - 2.8).abs() < TEST_BOUND);
+
+    // test round function with invalid number of decimal places
+    let result = get_result("round(pi, 1.5)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re.is_nan());
+
+    // test round function with the default number of decimal places
+    assert!(get_result("round(pi)", & mut context).ok().unwrap().unwrap().value.re == 3.0);
+    assert!(get_result("round(-2.5)", & mut context).ok().unwrap().unwrap().value.re == -3.0);
+    let result = get_result("round(1.5-0.4i)", & mut context).ok().unwrap().unwrap();
+    assert!(result.value.re == 2.0 && result.value.im == 0.0);
+
+    // test argument number error for round function
+    let result = get_result("round(pi, 1, 2)", & mut context);
+    assert!(result.is_err());
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Expected 2 argument(s).\nround(pi, 1, 2)\n    ^~~~ Found: 3 argument(s)");
+}
+
+#[test]
+fn tst_char_set() {
+    let mut set : CharSet = vec!['a', '0', '\u{7f}', 'π'].into_iter().collect();
+    set.insert('\u{2212}');
+
+    // test ASCII characters (stored in the bitmap)
+    assert!(set.contains(& 'a'));
+    assert!(set.contains(& '0'));
+    assert!(set.contains(& '\u{7f}'));
+    assert!(!set.contains(& 'b'));
+    assert!(!set.contains(& '\u{0}'));
+
+    // test non-ASCII characters (stored in the fallback set)
+    assert!(set.contains(& 'π'));
+    assert!(set.contains(& '\u{2212}'));
+    assert!(!set.contains(& 'λ'));
+}
+
+#[test]
+fn tst_input_stream() {
+    let mut stream = InputStream::new("π+10");
+
+    // test tracking of character indices and byte offsets for multi-byte characters
+    assert!(stream.next() == Some('π'));
+    assert!(stream.get_pos() == 1);
+    assert!(stream.get_byte_pos() == 2);
+    assert!(stream.next() == Some('+'));
+    assert!(stream.get_pos() == 2);
+    assert!(stream.get_byte_pos() == 3);
+
+    // test slicing of the input string
+    let start = stream.get_byte_pos();
+    stream.next();
+    stream.next();
+    assert!(stream.eof());
+    assert!(stream.slice(start, stream.get_byte_pos()) == "10");
+    assert!(stream.slice(0, 2) == "π");
+
+    // test marking of an unknown multi-byte token
+    let mut context = MathContext::new();
+    let result = get_result("2 − 1", & mut context);
+    assert!(result.is_err());
+    let msg = format!("{}", result.err().unwrap());
+    assert!(msg == "Error: Unknown token found: \"−\".\n2 − 1\n  ^~~~");
+}
+
+/// Benchmarks the tokenization of very long inputs.
+/// Run with "cargo test --release -- --ignored --nocapture".
+#[test]
+#[ignore]
+fn bench_tokenizer() {
+    let context = MathContext::new();
+    let inputs = vec![("numbers", vec!["1234567890.5"; 100000].join("+")),
+                      ("literals", vec!["abcdefghij_klmnopqrstuvwxyz"; 100000].join("*")),
+                      ("functions", vec!["cos(sin(2.5), pi)"; 100000].join("-"))];
+
+    for (name, input) in inputs {
+        let start = Instant::now();
+        let mut tokenizer = Tokenizer::new(& context, & input);
+        let mut count = 0;
+        while let Some(t) = tokenizer.next() {
+            assert!(t.is_ok());
+            count += 1;
+        }
+        let elapsed = start.elapsed();
+        println!("{0}: {1} tokens from {2} characters in {3}.{4:06}s", name, count, input.len(), elapsed.as_secs(), elapsed.subsec_nanos() / 1000);
+    }
+}
+
+#[test]
+fn tst_latex() {
+    let mut context = MathContext::new();
+
+    // fractions, roots and superscripts
+    assert!(get_latex("1/(2+x)", &context).unwrap() == "\\frac{1}{2 + x}");
+    assert!(get_latex("sqrt(2) + root(8, 3)", &context).unwrap() == "\\sqrt{2} + \\sqrt[3]{8}");
+    assert!(get_latex("log(x, 3) + log2(x)", &context).unwrap() == "\\log_{3}\\left(x\\right) + \\log_{2}\\left(x\\right)");
+    assert!(get_latex("(1+2)^(3*4)", &context).unwrap() == "\\left(1 + 2\\right)^{3 \\cdot 4}");
+    assert!(get_latex("pow(2, 3)^2", &context).unwrap() == "\\left(2^{3}\\right)^{2}");
+    assert!(get_latex("exp(2*pi)", &context).unwrap() == "e^{2 \\cdot \\pi}");
+
+    // parentheses are only inserted where required
+    assert!(get_latex("(1-2)-(3-4)*5", &context).unwrap() == "1 - 2 - \\left(3 - 4\\right) \\cdot 5");
+    assert!(get_latex("2*-3", &context).unwrap() == "2 \\cdot \\left(-3\\right)");
+
+    // functions, identifiers and numbers
+    assert!(get_latex("floor(sin(x_1))", &context).unwrap() == "\\left\\lfloor \\sin\\left(\\mathrm{x\\_1}\\right) \\right\\rfloor");
+    assert!(get_latex("round(2.5E-3, 2) + 3i", &context).unwrap() == "\\operatorname{round}\\left(2.5 \\cdot 10^{-3}, 2\\right) + 3i");
+
+    // user functions are rendered with their definition
+    get_result("f(x, y) = x^2 % y", &mut context).unwrap();
+    assert!(get_latex("f", &context).unwrap() == "f\\left(x, y\\right) = x^{2} \\bmod y");
+    assert!(get_latex("f(1, 2)", &context).unwrap() == "f\\left(1, 2\\right)");
+
+    // parse errors are reported
+    assert!(get_latex("sin(", &context).is_err());
+}
+
+#[test]
+fn tst_sympy() {
+    let mut context = MathContext::new();
+
+    // export of operations, functions, constants and numbers
+    assert!(get_sympy("2^3^2 - (1-2)", &context).unwrap() == "(2**3)**2 - (1 - 2)");
+    assert!(get_sympy("-2^2 + pow(x, 1/2)", &context).unwrap() == "(-2)**2 + x**(1/2)");
+    assert!(get_sympy("7 % 3 + ln(e) * acos(i)", &context).unwrap() == "Mod(7, 3) + log(E)*acos(I)");
+    assert!(get_sympy("log(x, 3) + log10(x)", &context).unwrap() == "log(x, 3) + log(x, 10)");
+    assert!(get_sympy("1/2i + ceil(1.5E-3)", &context).unwrap() == "1/(2*I) + ceiling(1.5e-3)");
+    assert!(get_sympy("trunc(x)^2", &context).unwrap() == "(sign(x)*floor(Abs(x)))**2");
+
+    // user functions are exported as Lambda
+    get_result("f(x) = x^2 + 1", &mut context).unwrap();
+    assert!(get_sympy("f", &context).unwrap() == "f = Lambda(x, x**2 + 1)");
+    assert!(get_sympy("f(2)*3", &context).unwrap() == "f(2)*3");
+    assert!(get_sympy("sin(", &context).is_err());
+
+    // import of SymPy-style input
+    assert!(from_sympy("2**10") == "2^10");
+    assert!(from_sympy("E**(I*pi) + log(x_E)") == "e^(i*pi) + ln(x_E)");
+    assert!(from_sympy("2E3*ceiling(0xE)") == "2E3*ceil(0xE)");
+    let result = get_result(&from_sympy("2**10 + log(E)"), &mut context).unwrap().unwrap();
+    assert!((result.value.re - 1025.0).abs() < TEST_BOUND);
+}
+
+#[test]
+fn tst_pow_alias() {
+    let mut context = MathContext::new();
+
+    // "**" is tokenized as one operation
+    let mut tokenizer = Tokenizer::new(&context, "2**3*4");
+    assert!(tokenizer.next().unwrap().unwrap().get_value() == "2");
+    assert!(tokenizer.next().unwrap().unwrap().get_value() == "**");
+    assert!(tokenizer.next().unwrap().unwrap().get_value() == "3");
+    assert!(tokenizer.next().unwrap().unwrap().get_value() == "*");
+
+    // "**" has the same semantics as "^", "*" is unaffected
+    let result = get_result("2**10", & mut context).unwrap().unwrap();
+    assert!((result.value.re - 1024.0).abs() < TEST_BOUND);
+    let result = get_result("2**3^2 - 2*3**2", & mut context).unwrap().unwrap();
+    assert!((result.value.re - 46.0).abs() < TEST_BOUND);
+    let result = get_result("2**-1 + 2*-3", & mut context).unwrap().unwrap();
+    assert!((result.value.re + 5.5).abs() < TEST_BOUND);
+    assert!(get_result("2***3", & mut context).is_err());
+
+    // the canonical form uses "^"
+    get_result("f(x) = x ** 2", & mut context).unwrap();
+    assert!(context.get_user_function_canonical_input("f").unwrap() == "f(x) = x^2");
+}
+
+#[test]
+fn tst_multi_char_operations() {
+    let mut context = MathContext::new();
+
+    // the longest matching operation is read
+    let mut tokenizer = Tokenizer::new(&context, "1<=2==1!=0>-1");
+    let values : Vec<String> = (0..9).map(|_| tokenizer.next().unwrap().unwrap().get_value().to_string()).collect();
+    assert!(values == vec!["1", "<=", "2", "==", "1", "!=", "0", ">", "-"]);
+
+    // "!" is a postfix operation, so it can not be used as binary operation
+    let result = get_result("2 ! 3", & mut context);
+    assert!(result.is_err());
+    assert!(result.err().unwrap().to_string() == "Error: Expected end of input.\n2 ! 3\n     ^~~~");
+
+    // comparison operations
+    let cases = vec![("2<=2", 1.0), ("3<=2", 0.0), ("2>=3", 0.0), ("3>=3", 1.0), ("1+i==1+i", 1.0), ("1+i==1", 0.0),
+                     ("2!=2", 0.0), ("2i!=2", 1.0), ("1+1==2 == 1", 1.0)];
+    for (input, expected) in cases {
+        let result = get_result(input, & mut context).unwrap().unwrap();
+        assert!((result.value.re - expected).abs() < TEST_BOUND);
+    }
+
+    // complex operands of ordering comparisons follow the comparison mode
+    assert!(get_result("3+4i>=5", & mut context).is_err());
+    context.set_comparison_mode(ComparisonMode::Modulus);
+    let result = get_result("3+4i>=5", & mut context).unwrap().unwrap();
+    assert!((result.value.re - 1.0).abs() < TEST_BOUND);
+
+    // rendering
+    assert!(get_latex("1 <= 2 != 3 >= 4", & context).unwrap() == "1 \\leq 2 \\neq 3 \\geq 4");
+    assert!(get_sympy("1 <= 2 == 3", & context).unwrap() == "(1 <= 2) == 3");
+}
+
+#[test]
+fn tst_int_div() {
+    let mut context = MathContext::new();
+
+    // floor division rounds towards negative infinity
+    let cases = vec![("7//2", 3.0), ("-7//2", -4.0), ("7//-2", -4.0), ("-7//-2", 3.0), ("6//3", 2.0), ("-6//3", -2.0),
+                     ("7.5//2.5", 3.0), ("-0.5//1", -1.0), ("2+7//2*2", 8.0), ("17//5%3", 0.0)];
+    for (input, expected) in cases {
+        let result = get_result(input, & mut context).unwrap().unwrap();
+        assert!((result.value.re - expected).abs() < TEST_BOUND);
+    }
+
+    // "//" has the same precedence as "/"
+    assert!(context.get_operation_precedence("//") == context.get_operation_precedence("/"));
+
+    // complex operands are an error
+    let result = get_result("(3+4i)//2", & mut context);
+    assert!(result.is_err());
+    assert!(result.err().unwrap().to_string() == "Error: Expected real operands.\n(3+4i)//2\n       ^~~~ Found: integer division \"//\" of complex numbers");
+
+    // the canonical form, LaTeX and SymPy representation
+    get_result("f(x) = (x+1)//2", & mut context).unwrap();
+    assert!(context.get_user_function_canonical_input("f").unwrap() == "f(x) = (x + 1)//2");
+    assert!(get_latex("7//2", & context).unwrap() == "\\left\\lfloor \\frac{7}{2} \\right\\rfloor");
+    assert!(get_sympy("-7//2", & context).unwrap() == "-7//2");
+}
+
+#[test]
+fn tst_divmod() {
+    let mut context = MathContext::new();
+
+    // the quotient is floored and the remainder has the sign of the divisor
+    let cases = vec![("divmod(7, 2)", 3.0, 1.0), ("divmod(-7, 2)", -4.0, 1.0), ("divmod(7, -2)", -4.0, -1.0),
+                     ("divmod(-7, -2)", 3.0, -1.0), ("divmod(7.5, 2)", 3.0, 1.5)];
+    for (input, quotient, remainder) in cases {
+        let m = get_result(input, & mut context).unwrap().unwrap().matrix.unwrap();
+        assert!(m.get(0, 0).value.re == quotient && m.get(0, 1).value.re == remainder);
+    }
+
+    let result = get_result("divmod(7, 0)", & mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected non-zero divisor.\ndivmod(7, 0)\n          ^~~~ Found: divisor 0");
+    assert!(get_sympy("divmod(7, 2)", & context).unwrap() == "divmod(7, 2)");
+}
+
+#[test]
+fn tst_number_theory() {
+    let mut context = MathContext::new();
+
+    let cases = vec![("gcd(-12, 18)", 6.0), ("gcd(0, 5)", 5.0), ("gcd(0, 0)", 0.0), ("lcm(4, 6)", 12.0), ("lcm(-3, 5)", 15.0),
+                     ("lcm(0, 7)", 0.0), ("isprime(97)", 1.0), ("isprime(91)", 0.0), ("isprime(1)", 0.0), ("isprime(-7)", 0.0),
+                     ("isprime(1000000007)", 1.0)];
+    for (input, expected) in cases {
+        let result = get_result(input, & mut context).unwrap().unwrap();
+        assert!(result.value.re == expected);
+    }
+
+    // the prime factors are returned as vector in ascending order
+    let cases = vec![("factor(12)", vec![2.0, 2.0, 3.0]), ("factor(97)", vec![97.0]),
+                     ("factor(600851475143)", vec![71.0, 839.0, 1471.0, 6857.0])];
+    for (input, expected) in cases {
+        let m = get_result(input, & mut context).unwrap().unwrap().matrix.unwrap();
+        assert!(m.rows == 1 && m.cols == expected.len());
+        for (i, p) in expected.iter().enumerate() {
+            assert!(m.get(0, i).value.re == *p);
+        }
+    }
+
+    // the arguments must be integers and factor requires an integer greater than 1
+    let result = get_result("gcd(2.5, 4)", & mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected 64 bit integer argument.\ngcd(2.5, 4)\n      ^~~~ Found: argument 2.5 of function \"gcd\"");
+    let result = get_result("factor(1)", & mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected integer greater than 1.\nfactor(1)\n       ^~~~ Found: argument 1");
+    assert!(get_result("isprime(2i)", & mut context).is_err());
+    assert!(get_result("lcm(3 m, 2)", & mut context).is_err());
+}
+
+#[test]
+fn tst_combinatorics() {
+    let mut context = MathContext::new();
+
+    let cases = vec![("ncr(5, 2)", 10.0), ("ncr(5, 0)", 1.0), ("ncr(5, 5)", 1.0), ("ncr(2, 5)", 0.0), ("binomial(10, 3)", 120.0),
+                     ("npr(5, 2)", 20.0), ("npr(5, 0)", 1.0), ("npr(2, 5)", 0.0), ("ncr(50, 25)", 126410606437752.0)];
+    for (input, expected) in cases {
+        let result = get_result(input, & mut context).unwrap().unwrap();
+        assert!(result.value.re == expected);
+    }
+
+    // large arguments do not overflow (too large results are infinite)
+    let result = get_result("ncr(1000, 500)", & mut context).unwrap().unwrap();
+    assert!((result.value.re / 2.7028824094543656e299 - 1.0).abs() < 10e-10);
+    let result = get_result("npr(300, 120)", & mut context).unwrap().unwrap();
+    assert!(result.value.re.is_finite() && result.value.re > 1e280);
+    assert!(get_result("ncr(5000, 2500)", & mut context).unwrap().unwrap().value.re == f64::INFINITY);
+
+    // the arguments must be non-negative integers
+    let result = get_result("ncr(-5, 2)", & mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected non-negative argument.\nncr(-5, 2)\n    ^~~~ Found: argument -5");
+    let result = get_result("npr(5, 1.5)", & mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected 64 bit integer argument.\nnpr(5, 1.5)\n         ^~~~ Found: argument 1.5 of function \"npr\"");
+}
+
+#[test]
+fn tst_bitwise_operations() {
+    let mut context = MathContext::new();
+
+    // the bitwise operations bind weaker than the arithmetic operations and stronger than the comparisons
+    let cases = vec![("12 & 10", 8.0), ("12 | 10", 14.0), ("12 xor 10", 6.0), ("3 << 4", 48.0), ("-48 >> 4", -3.0),
+                     ("~5", -6.0), ("~~5", 5.0), ("~2 + 1", -2.0), ("1 + 2 << 3", 24.0), ("6 & 3 | 8", 10.0),
+                     ("1 | 6 xor 3", 5.0), ("5 & 1 == 1", 1.0), ("0xff & ~0x0f", 240.0), ("1 << 63", -9223372036854775808.0)];
+    for (input, expected) in cases {
+        let result = get_result(input, & mut context).unwrap().unwrap();
+        assert!(result.value.re == expected);
+    }
+
+    // the operands must be integers and the shift count must be between 0 and 63
+    let result = get_result("2.5 & 1", & mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected 64 bit integer operand.\n2.5 & 1\n  ^~~~ Found: operand 2.5 of operation \"&\"");
+    let result = get_result("1 << 64", & mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected shift count between 0 and 63.\n1 << 64\n      ^~~~ Found: shift count 64");
+    assert!(get_result("3i | 1", & mut context).is_err());
+    assert!(get_result("~[1, 2]", & mut context).is_err());
+    assert!(get_result("3 m & 1", & mut context).is_err());
+
+    // "xor" is an operation and thus, not a valid constant name
+    assert!(get_result("xor = 3", & mut context).is_err());
+
+    // the canonical form, LaTeX and SymPy representation
+    get_result("f(x) = ~x & 255 xor 1 << 4", & mut context).unwrap();
+    assert!(context.get_user_function_canonical_input("f").unwrap() == "f(x) = ~x & 255 xor 1 << 4");
+    get_result("g(x) = (x | 1) & 3", & mut context).unwrap();
+    assert!(context.get_user_function_canonical_input("g").unwrap() == "g(x) = (x | 1) & 3");
+    assert!(get_latex("~5 xor 3 << 1", & context).unwrap() == "\\lnot 5 \\oplus 3 \\ll 1");
+    assert!(get_sympy("5 xor 3", & context).unwrap() == "5 ^ 3");
+}
+
+#[test]
+fn tst_unused_definitions() {
+    let mut context = MathContext::new();
+
+    get_result("a = 1", & mut context).unwrap();
+    get_result("b = 2", & mut context).unwrap();
+    get_result("c = 3", & mut context).unwrap();
+    get_result("f(x) = x*a", & mut context).unwrap();
+    get_result("g(x) = f(x) + 1", & mut context).unwrap();
+    assert!(context.get_unused_user_definitions() == vec!["b", "c", "g"]);
+
+    // using a definition in an evaluation marks it as used ("ans" is never reported)
+    get_result("g(b)", & mut context).unwrap();
+    assert!(context.get_unused_user_definitions() == vec!["c"]);
+
+    // redefining a definition resets its usage
+    get_result("b = 5", & mut context).unwrap();
+    assert!(context.get_unused_user_definitions() == vec!["b", "c"]);
+
+    // the dependency index is updated when a function is removed
+    context.remove_user_function("g");
+    context.remove_user_function("f");
+    assert!(context.get_unused_user_definitions() == vec!["b", "c"]);
+    get_result("f(x) = x", & mut context).unwrap();
+    assert!(context.get_unused_user_definitions() == vec!["b", "c", "f"]); // "a" has been used in the evaluation of "g(b)"
+
+    // the dependency index is rebuilt after deserialization, the usage is not serialized
+    get_result("h(x) = x*c", & mut context).unwrap();
+    let s = serde_json::to_string(& context).unwrap();
+    let mut m : MathContext = serde_json::from_str(& s).unwrap();
+    m.initialize();
+    assert!(m.get_unused_user_definitions() == vec!["a", "b", "f", "h"]);
+}
+
+#[test]
+fn tst_diff() {
+    let mut context = MathContext::new();
+
+    // sum, product, quotient and chain rule
+    assert!(get_derivative("sin(x)*x^2", "x", &context).unwrap() == "cos(x)*x^2 + 2*sin(x)*x");
+    assert!(get_derivative("x/(1+x)", "x", &context).unwrap() == "(1 + x - x)/(1 + x)^2");
+    assert!(get_derivative("ln(cos(x))", "x", &context).unwrap() == "-(sin(x)/cos(x))");
+    assert!(get_derivative("e^x + 2^x + x^x", "x", &context).unwrap() == "e^x + 2^x*ln(2) + x^x*(ln(x) + x/x)");
+    assert!(get_derivative("sqrt(x) - root(x, 3)", "x", &context).unwrap() == "1/(2*sqrt(x)) - x^(1/3 - 1)/3");
+    assert!(get_derivative("log2(x) + log(3, x)", "x", &context).unwrap() == "1/(x*ln(2)) - ln(3)/x/ln(x)^2");
+
+    // other symbols are treated as constants
+    get_result("a = 2", &mut context).unwrap();
+    assert!(get_derivative("a*x - y", "x", &context).unwrap() == "a");
+    assert!(get_derivative("x*y", "y", &context).unwrap() == "x");
+
+    // user functions are substituted
+    get_result("f(x) = x^3", &mut context).unwrap();
+    assert!(get_derivative("f", "x", &context).unwrap() == "3*x^2");
+    assert!(get_derivative("f(2*x)", "x", &context).unwrap() == "6*(2*x)^2");
+
+    // errors
+    let err = get_derivative("x%2", "x", &context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected differentiable expression.\nx%2\n ^~~~ Found: expression \"%\"");
+    assert!(get_derivative("floor(x)", "x", &context).is_err());
+    assert!(get_derivative("x^2", "1 x", &context).is_err());
+}
+
+#[test]
+fn tst_simplify() {
+    let mut context = MathContext::new();
+
+    // constant folding (only if the result is exact)
+    assert!(get_simplified("2*3 + 4", &context).unwrap() == "10");
+    assert!(get_simplified("6/3 + 1/3", &context).unwrap() == "2 + 1/3");
+    assert!(get_simplified("0.1 + 0.2", &context).unwrap() == "0.1 + 0.2");
+
+    // neutral and absorbing elements
+    assert!(get_simplified("x*1 + 0*y", &context).unwrap() == "x");
+    assert!(get_simplified("x^0 + y^1", &context).unwrap() == "1 + y");
+    assert!(get_simplified("sin(x/1) - 0", &context).unwrap() == "sin(x)");
+    assert!(get_simplified("x - -3", &context).unwrap() == "x + 3");
+
+    // like terms
+    assert!(get_simplified("2*x + 3*x", &context).unwrap() == "5*x");
+    assert!(get_simplified("x*2 + y + x", &context).unwrap() == "3*x + y");
+    assert!(get_simplified("2*x - 5*x", &context).unwrap() == "-(3*x)");
+    assert!(get_simplified("x - x", &context).unwrap() == "0");
+    assert!(get_simplified("4 + x + 3 + sin(x) - x", &context).unwrap() == "sin(x) + 7");
+
+    // symbols are not evaluated
+    get_result("a = 2", &mut context).unwrap();
+    assert!(get_simplified("a + a", &context).unwrap() == "2*a");
+
+    assert!(get_simplified("2*(x", &context).is_err());
+}
+
+#[test]
+fn tst_non_finite_origin() {
+    let mut context = MathContext::new();
+
+    // the origins are only tracked if the tracking is enabled
+    get_result("exp(1000) - exp(1000)", &mut context).unwrap();
+    assert!(context.get_non_finite_origin().is_none());
+
+    context.set_non_finite_tracking(true);
+    let result = get_result("2^2000*0", &mut context).unwrap().unwrap();
+    assert!(result.value.re.is_nan());
+    assert!(context.get_non_finite_origin().unwrap().to_string() ==
+        "The value inf was first produced by the operation \"^\" with the operands 2 and 2000.\n2^2000*0\n ^~~~");
+
+    // the first origin is kept, operations on non-finite operands are no origins
+    get_result("1 + exp(1000) - exp(800)", &mut context).unwrap();
+    let origin = context.get_non_finite_origin().unwrap().clone();
+    assert!(origin.name == "exp" && origin.is_function && origin.operands == vec!["1000"] && origin.value == "inf");
+    assert!(origin.to_string() == "The value inf was first produced by the function \"exp\" with the argument 1000.\n\
+        1 + exp(1000) - exp(800)\n      ^~~~");
+
+    // each evaluation starts without an origin
+    get_result("1 + 1", &mut context).unwrap();
+    assert!(context.get_non_finite_origin().is_none());
+
+    get_result("2^2000", &mut context).unwrap();
+    context.set_non_finite_tracking(false);
+    assert!(context.get_non_finite_origin().is_none());
+}
+
+#[test]
+fn tst_scoped_definitions() {
+    let mut context = MathContext::new();
+
+    let result = get_result("with(a=2, b=3) a*b + 1", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 7.0).abs() < TEST_BOUND);
+    assert!(!context.is_user_constant("a") && !context.is_user_constant("b"));
+
+    // later definitions see earlier ones, inner scopes shadow outer scopes and user constants
+    get_result("c = 10", &mut context).unwrap();
+    let result = get_result("with(c=2, d=c^2) d + with(c=1) c", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 5.0).abs() < TEST_BOUND);
+    let result = get_result("c", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 10.0).abs() < TEST_BOUND);
+
+    // temporary definitions are passed to user functions
+    get_result("f(x) = x^2", &mut context).unwrap();
+    let result = get_result("with(y=3) f(y)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 9.0).abs() < TEST_BOUND);
+
+    // errors do not leak definitions
+    assert!(get_result("with(z=2) z + q", &mut context).is_err());
+    assert!(!context.is_user_constant("z"));
+    assert!(get_result("with(pi=3) pi", &mut context).is_err());
+    assert!(get_result("with(2) 1", &mut context).is_err());
+    assert!(get_result("with(a=2) b = a", &mut context).is_err());
+    assert!(!context.is_user_constant("b"));
+}
+
+#[test]
+fn tst_user_function_scopes() {
+    let mut context = MathContext::new();
+
+    // the arguments are bound by name, so they do not interfere with the constants of the caller
+    get_result("x = 5", &mut context).unwrap();
+    get_result("f(x, y) = x - y", &mut context).unwrap();
+    let result = get_result("f(1, x)", &mut context).unwrap().unwrap();
+    assert!((result.value.re + 4.0).abs() < TEST_BOUND);
+    let result = get_result("with(y=10) f(y, 1)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 9.0).abs() < TEST_BOUND);
+
+    // temporary definitions of the caller are not visible in the function body
+    get_result("c = 1", &mut context).unwrap();
+    get_result("g(x) = x + c", &mut context).unwrap();
+    let result = get_result("with(c=100) g(c)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 101.0).abs() < TEST_BOUND);
+
+    // recursive definitions are allowed, but the call depth is limited
+    get_result("h(n) = h(n - 1)", &mut context).unwrap();
+    assert!(context.get_unused_user_definitions().contains(&String::from("h")));
+    let err = get_result("h(3)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Maximum depth of 64 nested user function calls exceeded in \"h\".");
+    assert!(get_result("k(n) = k(n, 1)", &mut context).is_ok());
+    assert!(get_result("k(1)", &mut context).is_err());
+}
+
+#[test]
+fn tst_matrix() {
+    let mut context = MathContext::new();
+
+    // construction, arithmetic and display
+    let result = get_result("[1, 2; 3, 4]", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "[1, 2; 3, 4]");
+    let result = get_result("[1, 2; 3, 4] * [5; 6] - [1; 1]", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "[16; 38]");
+    let result = get_result("2 * [1, i] / 2 + -[1, 1]", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "[0, -1+1i]");
+    let result = get_result("[1, 1; 0, 1]^3", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "[1, 3; 0, 1]");
+    let result = get_result("[1, 2] == [1, 1+1]", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 1.0);
+
+    // matrix functions
+    let result = get_result("det([1, 2; 3, 4])", &mut context).unwrap().unwrap();
+    assert!((result.value.re + 2.0).abs() < TEST_BOUND);
+    let result = get_result("transpose([1, 2, 3])", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "[1; 2; 3]");
+    let result = get_result("inv([2, 0; 0, 4]) * [2, 0; 0, 4]", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "[1, 0; 0, 1]");
+
+    // matrices can be stored in constants, passed to user functions and serialized
+    get_result("A = [1, 2; 3, 4]", &mut context).unwrap();
+    get_result("f(x) = x*x", &mut context).unwrap();
+    let result = get_result("f(A)", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "[7, 10; 15, 22]");
+    let s = serde_json::to_string(& context).unwrap();
+    let mut m : MathContext = serde_json::from_str(& s).unwrap();
+    m.initialize();
+    let result = get_result("A", &mut m).unwrap().unwrap();
+    assert!(format!("{}", result) == "[1, 2; 3, 4]");
+
+    // errors
+    let err = get_result("[1, 2] + [1, 2, 3]", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected matrices with matching dimensions.\n[1, 2] + [1, 2, 3]\n       ^~~~ Found: operation \"+\" with operands of type 1x2 and 1x3");
+    let err = get_result("[1, 2; 3]", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected 2 element(s) in row 2.\n[1, 2; 3]\n       ^~~~ Found: 1 element(s)");
+    assert!(get_result("sin([1, 2])", &mut context).is_err());
+    assert!(get_result("det([1, 2])", &mut context).is_err());
+    assert!(get_result("inv([1, 2; 2, 4])", &mut context).is_err());
+    assert!(get_result("det(2)", &mut context).is_err());
+    assert!(get_result("[1, 2", &mut context).is_err());
+}
+
+#[test]
+fn tst_precision_mode() {
+    let mut context = MathContext::new();
+    context.set_precision_mode(PrecisionMode::Exact);
+
+    // exact arithmetic
+    let result = get_result("1/3 * 3", &mut context).unwrap().unwrap();
+    assert!(result.is_exact() && format!("{}", result) == "1");
+    let result = get_result("2^200", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "1606938044258990275541962092341162602522202993782792835301376");
+    let result = get_result("0.1 + 0.2 == 0.3", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 1.0);
+    let result = get_result("0 - (2/3)^-2 + 7 // 2 + 0x1F % 4", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "15/4");
+    let result = get_result("[1/2, 1; 0, 1]^2", &mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "[1/4, 3/2; 0, 1]");
+    let result = get_result("1/3 < 0.3334", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 1.0);
+
+    // inexact operations and complex numbers fall back to floating point numbers
+    let result = get_result("sqrt(4) + 1/2", &mut context).unwrap().unwrap();
+    assert!(!result.is_exact() && result.value.re == 2.5);
+    let result = get_result("1/2 + i", &mut context).unwrap().unwrap();
+    assert!(!result.is_exact() && result.value.re == 0.5 && result.value.im == 1.0);
+
+    // exact constants can be serialized
+    get_result("c = 1/3", &mut context).unwrap();
+    let s = serde_json::to_string(& context).unwrap();
+    let mut m : MathContext = serde_json::from_str(& s).unwrap();
+    m.initialize();
+    let result = get_result("c", &mut m).unwrap().unwrap();
+    assert!(result.is_exact() && format!("{}", result) == "1/3");
+
+    // float mode
+    context.set_precision_mode(PrecisionMode::Float);
+    let result = get_result("1/4", &mut context).unwrap().unwrap();
+    assert!(!result.is_exact() && format!("{}", result) == "0.25");
+}
+
+#[test]
+fn tst_previous_values() {
+    let mut context = MathContext::new();
+
+    // constants may refer to their current value when they are reassigned
+    get_result("x = 2", &mut context).unwrap();
+    get_result("x = x/2 + 1/x", &mut context).unwrap();
+    get_result("x = x/2 + 1/x", &mut context).unwrap();
+    let result = get_result("x", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 17.0 / 12.0).abs() < TEST_BOUND);
+    let result = get_result("old(x, 1) + old(x, 2)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 3.5).abs() < TEST_BOUND);
+
+    // the history is bounded
+    for _ in 0..20 {
+        get_result("x = x + 1", &mut context).unwrap();
+    }
+    assert!(context.get_previous_constant_value_count("x") == 16);
+    let result = get_result("x - old(x, 16)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 16.0).abs() < TEST_BOUND);
+
+    // errors
+    let err = get_result("old(x, 17)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected 17 previous value(s) of \"x\".\nold(x, 17)\n  ^~~~ Found: 16 previous value(s)");
+    let err = get_result("old(x, 1.5)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected positive integer.\nold(x, 1.5)\n         ^~~~ Found: 1.5");
+    let err = get_result("old(pi, 1)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected user defined constant.\nold(pi, 1)\n     ^~~~ Found: expression \"pi\"");
+
+    // removing a constant removes its history
+    context.remove_user_constant("x");
+    get_result("x = 1", &mut context).unwrap();
+    assert!(get_result("old(x, 1)", &mut context).is_err());
+}
+
+#[test]
+fn tst_factorial_gamma() {
+    let mut context = MathContext::new();
+
+    // the factorial is a postfix operation that binds stronger than all other operations
+    let cases = vec![("5!", 120.0), ("0!", 1.0), ("-3!", -6.0), ("2^3!", 64.0), ("3!^2", 36.0), ("3!!", 720.0),
+                     ("(1+2)! + 1", 7.0), ("3! == 6", 1.0)];
+    for (input, expected) in cases {
+        let result = get_result(input, & mut context).unwrap().unwrap();
+        assert!(result.value.re == expected);
+    }
+    let result = get_result("0.5!", & mut context).unwrap().unwrap();
+    assert!((result.value.re - f64::consts::PI.sqrt() / 2.0).abs() < TEST_BOUND);
+    let err = get_result("(-1)!", & mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected operand other than a negative integer.\n(-1)!\n ^~~~ Found: operand -1");
+    let result = get_result("171!", & mut context).unwrap().unwrap();
+    assert!(result.value.re.is_infinite());
+
+    // the gamma function supports real and complex arguments
+    let result = get_result("gamma(0.5)^2", & mut context).unwrap().unwrap();
+    assert!((result.value.re - f64::consts::PI).abs() < TEST_BOUND);
+    let result = get_result("gamma(-1.5)", & mut context).unwrap().unwrap();
+    assert!((result.value.re - 4.0 * f64::consts::PI.sqrt() / 3.0).abs() < TEST_BOUND);
+    let result = get_result("gamma(1+i)", & mut context).unwrap().unwrap();
+    assert!(result.result_type == NumberType::Complex);
+    assert!((result.value.re - 0.4980156681183560).abs() < TEST_BOUND && (result.value.im + 0.1549498283018106).abs() < TEST_BOUND);
+
+    // exact factorials
+    context.set_precision_mode(PrecisionMode::Exact);
+    let result = get_result("25!", & mut context).unwrap().unwrap();
+    assert!(format!("{}", result) == "15511210043330985984000000");
+
+    // rendering
+    assert!(get_result("f(x) = (x+1)! * -x!", & mut context).is_ok());
+    assert!(context.get_user_function_canonical_input("f").unwrap() == "f(x) = (x + 1)!*-x!");
+    assert!(get_latex("(x+1)! + gamma(x)", & context).unwrap() == "\\left(x + 1\\right)! + \\Gamma\\left(x\\right)");
+    assert!(get_sympy("(x+1)! + gamma(x)", & context).unwrap() == "factorial(x + 1) + gamma(x)");
+
+    // errors
+    let err = get_result("!3", & mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected unary operation.\n!3\n^~~~ Found: non-unary operation \"!\"");
+    let err = get_result("[1, 2]!", & mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected scalar operand.\n[1, 2]!\n      ^~~~ Found: matrix operand of operation \"!\"");
+}
+
+#[test]
+fn tst_iterate() {
+    let mut context = MathContext::new();
+
+    // fixed point of cos(x)
+    let (value, n, converged) = iterate("x", "cos(x)", "1", 1000, Some(1e-12), & mut context).unwrap();
+    assert!(converged && n < 100);
+    assert!((value.value.re - 0.7390851332151607).abs() < 1e-10);
+    assert!(context.get_constant_value("x").unwrap() == value);
+
+    // fixed number of iterations
+    let (value, n, converged) = iterate("y", "2*y", "1", 10, None, & mut context).unwrap();
+    assert!(value.value.re == 1024.0 && n == 10 && !converged);
+    let (_, n, converged) = iterate("y", "y + 1", "0", 3, Some(0.5), & mut context).unwrap();
+    assert!(n == 3 && !converged);
+
+    // matrices
+    let (value, _, converged) = iterate("m", "[0.5, 0; 0, 0.25]*m", "[1, 2; 3, 4]", 1000, Some(1e-12), & mut context).unwrap();
+    assert!(converged && value.matrix.unwrap().elements.iter().all(|e| e.value.re.abs() < 1e-10));
+
+    // errors
+    let err = iterate("pi", "cos(pi)", "1", 10, None, & mut context).err().unwrap();
+    assert!(err.to_string().starts_with("Error: Expected"));
+    let err = iterate("x y", "x", "1", 10, None, & mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected constant name.\nx y\n^~~~ Found: \"x y\"");
+    assert!(iterate("z", "z + q", "1", 10, None, & mut context).is_err());
+}
+
+#[test]
+fn tst_freeze_user_function() {
+    let mut context = MathContext::new();
+
+    // later changes of the constants do not change the frozen function
+    get_result("a = 2", & mut context).unwrap();
+    get_result("f(x) = x + a", & mut context).unwrap();
+    assert!(freeze_user_function("f", & mut context).unwrap() == vec!["a"]);
+    get_result("a = 5", & mut context).unwrap();
+    assert!(get_result("f(1)", & mut context).unwrap().unwrap().value.re == 3.0);
+    assert!(context.get_user_function_canonical_input("f").unwrap() == "f(x) = x + 2");
+
+    // parameters and bound variables shadow the constants
+    get_result("k = 10", & mut context).unwrap();
+    get_result("c = 3+4i", & mut context).unwrap();
+    get_result("g(a, n) = a*sum(k, k, 1, n) + k*c", & mut context).unwrap();
+    assert!(freeze_user_function("g", & mut context).unwrap() == vec!["c", "k"]);
+    assert!(context.get_user_function_canonical_input("g").unwrap() == "g(a, n) = a*sum(k, k, 1, n) + 10*(3 + 4i)");
+    get_result("h(x) = 2*x", & mut context).unwrap();
+    assert!(freeze_user_function("h", & mut context).unwrap().is_empty());
+
+    // only user defined functions can be frozen
+    assert!(freeze_user_function("sin", & mut context).is_err());
+    assert!(freeze_user_function("a", & mut context).is_err());
+}
+
+#[test]
+fn tst_outputs() {
+    let mut context = MathContext::new();
+
+    // only evaluations that are added to the transcript are numbered
+    let result = get_result("2 + 3", &mut context).unwrap();
+    assert!(context.add_transcript_entry("2 + 3", result) == 1);
+    let result = get_result("x = 4", &mut context).unwrap();
+    assert!(context.add_transcript_entry("x = 4", result) == 2);
+    let result = get_result("Out(1) * x", &mut context).unwrap();
+    assert!(context.add_transcript_entry("Out(1) * x", result) == 3);
+    let result = get_result("Out(3) - Out(1)", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 15.0);
+    assert!(context.get_transcript_input(3) == Some(String::from("Out(1) * x")));
+    assert!(context.get_transcript_len() == 3);
+
+    // errors
+    let err = get_result("Out(2)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected result of evaluation 2.\nOut(2)\n  ^~~~ Found: no result of evaluation 2");
+    let err = get_result("Out(4)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected result of evaluation 4.\nOut(4)\n  ^~~~ Found: 3 evaluation(s)");
+    let err = get_result("Out(0)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected positive integer.\nOut(0)\n    ^~~~ Found: 0");
+}
+
+#[test]
+fn tst_previous_results() {
+    let mut context = MathContext::new();
+
+    // only numerical results are kept, the most recent result is "ans1"
+    get_result("2 + 3", &mut context).unwrap();
+    get_result("x = 4", &mut context).unwrap();
+    get_result("ans1 * x", &mut context).unwrap();
+    let result = get_result("ans1 - ans2", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 15.0);
+    let result = get_result("ans1 + ans3", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 20.0);
+    assert!(context.get_previous_result_count() == 4);
+
+    // the previous results are bounded
+    for _ in 0..20 {
+        get_result("ans + 1", &mut context).unwrap();
+    }
+    assert!(context.get_previous_result_count() == 16);
+    let result = get_result("ans1 - ans16", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 15.0);
+
+    // errors
+    let mut context = MathContext::new();
+    get_result("7", &mut context).unwrap();
+    let err = get_result("ans3", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected 3 previous result(s).\nans3\n   ^~~~ Found: 1 previous result(s)");
+    let err = get_result("ans2 = 5", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected new constant name or function name.\nans2 = 5\n   ^~~~ Found: built-in expression \"ans2\"");
+    let err = get_result("ans17", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected built-in or user defined constant.\nans17\n    ^~~~ Found: unknown constant \"ans17\"");
+}
+
+#[test]
+fn tst_identity() {
+    let mut context = MathContext::new();
+
+    let check = check_identity("sin(x)^2 + cos(x)^2 == 1", &mut context).unwrap();
+    assert!(check.variables == vec!["x"] && check.samples == IDENTITY_SAMPLES && check.failures == 0);
+    let check = check_identity("exp(a + b) == exp(a) * exp(b)", &mut context).unwrap();
+    assert!(check.variables == vec!["a", "b"] && check.failures == 0);
+    let check = check_identity("1 + 1 == 2", &mut context).unwrap();
+    assert!(check.variables.is_empty() && check.samples == 1 && check.failures == 0);
+
+    // user constants are no variables
+    get_result("c = 3", &mut context).unwrap();
+    let check = check_identity("x*c == 3*x", &mut context).unwrap();
+    assert!(check.variables == vec!["x"] && check.failures == 0);
+
+    // counterexamples
+    let check = check_identity("sqrt(x^2) == x", &mut context).unwrap();
+    assert!(check.failures > 0 && check.failures < check.samples && check.counterexamples.len() == 5);
+    for &(ref point, ref lhs, ref rhs) in check.counterexamples.iter() {
+        assert!(point[0].value.re < 0.0 && (lhs.value + rhs.value).norm() < TEST_BOUND);
+    }
+
+    // errors
+    let err = check_identity("x + 1", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected equation (e.g. \"lhs == rhs\").\nx + 1\n  ^~~~ Found: expression \"+\"");
+    assert!(check_identity("foo(x) == 1", &mut context).is_err());
+}
+
+#[test]
+fn tst_conditional() {
+    let mut context = MathContext::new();
+
+    assert!(get_result("if(1 < 2, 3, 4)", &mut context).unwrap().unwrap().value.re == 3.0);
+    assert!(get_result("if(0, 3, 4)", &mut context).unwrap().unwrap().value.re == 4.0);
+    get_result("f(x) = if(x < 0, -x, x)", &mut context).unwrap();
+    assert!(get_result("f(-3) + f(2)", &mut context).unwrap().unwrap().value.re == 5.0);
+
+    // only the selected branch is evaluated
+    assert!(get_result("if(0 == 0, 5, Out(99))", &mut context).unwrap().unwrap().value.re == 5.0);
+    get_result("fact(n) = if(n <= 1, 1, n*fact(n - 1))", &mut context).unwrap();
+    assert!(get_result("fact(10)", &mut context).unwrap().unwrap().value.re == 3628800.0);
+
+    // conversions
+    assert!(get_latex("if(x < 0, 2*x, x^2)", &context).unwrap() ==
+        "\\begin{cases} 2 \\cdot x & \\text{if } x < 0 \\\\ x^{2} & \\text{otherwise} \\end{cases}");
+    assert!(get_sympy("if(x < 0, 2*x, x^2)", &context).unwrap() == "Piecewise((2*x, x < 0), (x**2, True))");
+    assert!(get_derivative("if(x < 0, x^3, x^2)", "x", &context).unwrap() == "if(x < 0, 3*x^2, 2*x)");
+
+    // errors
+    let err = get_result("if(i, 1, 2)", &mut context).err().unwrap();
+    assert!(err.to_string().starts_with("Error: Expected real condition.\nif(i, 1, 2)\n   ^~~~"));
+    assert!(get_result("if(1, 2)", &mut context).is_err());
+}
+
+#[test]
+fn tst_constant_folding() {
+    let mut context = MathContext::new();
+    let canonical = |context: &MathContext, f: &str| context.get_user_function_canonical_input(f).unwrap();
+
+    // the optimization is disabled by default
+    get_result("f(x) = x*(2 + 3)", &mut context).unwrap();
+    assert!(canonical(&context, "f") == "f(x) = x*(2 + 3)");
+
+    context.set_optimize(true);
+    get_result("f(x) = x*(2 + 3)^2", &mut context).unwrap();
+    assert!(canonical(&context, "f") == "f(x) = x*25");
+    assert!(get_result("f(2)", &mut context).unwrap().unwrap().value.re == 50.0);
+    get_result("g(x) = x - 2*3", &mut context).unwrap();
+    assert!(canonical(&context, "g") == "g(x) = x - 6");
+    get_result("h(x) = x + (1 - 3)", &mut context).unwrap();
+    assert!(get_result("h(1)", &mut context).unwrap().unwrap().value.re == -1.0);
+
+    // the trigonometric functions depend on the angle mode and are not folded
+    get_result("s(x) = sin(pi/2)*x", &mut context).unwrap();
+    assert!(canonical(&context, "s") == "s(x) = sin(1.5707963267948966)*x");
+    context.set_angle_mode(AngleMode::Deg);
+    assert!((get_result("s(1)", &mut context).unwrap().unwrap().value.re - 0.02741213359204429).abs() < TEST_BOUND);
+    context.set_angle_mode(AngleMode::Rad);
+
+    // exact values are not folded
+    context.set_precision_mode(PrecisionMode::Exact);
+    get_result("r(x) = x + 1/3", &mut context).unwrap();
+    assert!(canonical(&context, "r") == "r(x) = x + 1/3");
+    assert!(get_result("r(0) * 3", &mut context).unwrap().unwrap().to_string() == "1");
+}
+
+#[test]
+fn tst_unused_parameters() {
+    let mut context = MathContext::new();
+
+    get_result("f(x, y) = x + 1", &mut context).unwrap();
+    assert!(context.take_warnings() == vec!["Warning: The parameter \"y\" of the function \"f\" is not used."]);
+    // the warning is only given at the definition
+    get_result("f(1, 2)", &mut context).unwrap();
+    assert!(context.take_warnings().is_empty());
+
+    get_result("g(a, b, c) = 2", &mut context).unwrap();
+    assert!(context.take_warnings().len() == 3);
+    get_result("h(n, x) = sum(x^k, k, 0, n)", &mut context).unwrap();
+    assert!(context.take_warnings().is_empty());
+
+    // no warnings for failed definitions
+    assert!(get_result("u(x, y) = x + z", &mut context).is_err());
+    assert!(context.take_warnings().is_empty());
+}
+
+#[test]
+fn tst_warnings() {
+    let mut context = MathContext::new();
+
+    // parameters that shadow constants
+    get_result("c = 3", &mut context).unwrap();
+    get_result("f(c, y) = c*y", &mut context).unwrap();
+    assert!(context.take_warnings() == vec!["Warning: The parameter \"c\" of the function \"f\" shadows the constant \"c\"."]);
+    assert!(get_result("f(2, 5)", &mut context).unwrap().unwrap() == MathResult::from(10.0));
+
+    // integer literals that can not be represented exactly (each warning is only given once per evaluation)
+    assert!(get_result("9007199254740993 - 9007199254740993", &mut context).unwrap().unwrap() == MathResult::from(0.0));
+    assert!(context.take_warnings() == vec!["Warning: The number \"9007199254740993\" can not be represented exactly \
+                                             (precision lost, evaluated as 9007199254740992)."]);
+    get_result("9007199254740992 + 000123 + 0x20000000000001 + 1.1", &mut context).unwrap();
+    assert!(context.take_warnings().is_empty());
+
+    // exact literals do not lose precision
+    context.set_precision_mode(PrecisionMode::Exact);
+    get_result("9007199254740993", &mut context).unwrap();
+    assert!(context.take_warnings().is_empty());
+}
+
+#[test]
+fn tst_angle_mode() {
+    let mut context = MathContext::new();
+
+    // degrees
+    context.set_angle_mode(AngleMode::Deg);
+    let result = get_result("sin(90)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 1.0).abs() < TEST_BOUND);
+    let result = get_result("cos(60) + tan(45)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 1.5).abs() < TEST_BOUND);
+    let result = get_result("acos(0) + atan(1)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 135.0).abs() < TEST_BOUND);
+    let result = get_result("sinh(1)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 1.0_f64.sinh()).abs() < TEST_BOUND);
+
+    // gradians
+    context.set_angle_mode(AngleMode::Grad);
+    let result = get_result("sin(100) + asin(1)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 101.0).abs() < TEST_BOUND);
+
+    // derivatives respect the angle mode
+    context.set_angle_mode(AngleMode::Deg);
+    let derivative = get_derivative("sin(x)", "x", &context).unwrap();
+    get_result("x = 60", &mut context).unwrap();
+    let result = get_result(&derivative, &mut context).unwrap().unwrap();
+    assert!((result.value.re - f64::consts::PI / 360.0).abs() < TEST_BOUND);
+
+    // the angle mode is serialized and defaults to radians
+    let s = serde_json::to_string(& context).unwrap();
+    let m : MathContext = serde_json::from_str(& s).unwrap();
+    assert!(m.get_angle_mode() == AngleMode::Deg);
+    let m : MathContext = serde_json::from_str("{\"user_functions\": {}, \"user_function_inputs\": {}, \"user_constants\": {}}").unwrap();
+    assert!(m.get_angle_mode() == AngleMode::Rad);
+}
+
+#[test]
+fn tst_fraction_format() {
+    let mut context = MathContext::new();
+
+    let cases = vec![("0.1 + 0.2", "3/10"), ("1/3", "1/3"), ("-10/7", "-10/7"), ("4/2", "2"), ("0", "0"),
+                     ("1/2 - 0.75i", "1/2-3/4i"), ("[0.5, 1; 1/3, 2]", "[1/2, 1; 1/3, 2]"), ("pi", "3.141592653589793")];
+    for (input, expected) in cases {
+        let result = get_result(input, & mut context).unwrap().unwrap();
+        assert!(result.fraction_fmt(1000) == expected);
+    }
+
+    // the maximum denominator
+    let result = get_result("1/1024", & mut context).unwrap().unwrap();
+    assert!(result.fraction_fmt(1000) == "0.0009765625");
+    assert!(result.fraction_fmt(1024) == "1/1024");
+
+    // exact results are formatted exactly if the denominator is small enough
+    context.set_precision_mode(PrecisionMode::Exact);
+    let result = get_result("1/3 + 1/7", & mut context).unwrap().unwrap();
+    assert!(result.fraction_fmt(1000) == "10/21");
+}
+
+#[test]
+fn tst_radix_truncation() {
+    // exact fractional parts are formatted without marker
+    assert!(format!("{0:#b}", MathResult::from(0.75)) == "0b0.11");
+    assert!(format!("{0:#x}", MathResult::from(5.75)) == "0x5.c");
+
+    // truncated fractional parts are marked
+    assert!(format!("{0:#b}", MathResult::from(0.1)) == "0b0.0001100110…");
+    assert!(format!("{0:#x}", MathResult::from(0.1)) == "0x0.1999999999…");
+    assert!(format!("{0:#o}", MathResult::from((0.5, 0.1))) == "0o0.4+0o0.0631463146…i");
+
+    // the precision limits the number of digits
+    assert!(format!("{0:#.4b}", MathResult::from(0.1)) == "0b0.0001…");
+    assert!(format!("{0:#.2b}", MathResult::from(0.75)) == "0b0.11");
+    assert!(format!("{0:#.1b}", MathResult::from(0.75)) == "0b0.1…");
+    assert!(format!("{0:#.2o}", MathResult::from((0.5, 0.1))) == "0o0.4+0o0.06…i");
+}
+
+#[test]
+fn tst_radix_sign() {
+    let mut context = MathContext::new();
+
+    // real numbers
+    assert!(format!("{0:#x}", MathResult::from(-255.0)) == "-0xff");
+    assert!(format!("{0:#X}", MathResult::from(-255.0)) == "-0xFF");
+    assert!(format!("{0:#b}", MathResult::from(-5.75)) == "-0b101.11");
+    assert!(format!("{0:#o}", MathResult::from(-8.5)) == "-0o10.4");
+    assert!(format!("{0:#x}", MathResult::from(-0.0)) == "0x0");
+
+    // complex numbers
+    assert!(format!("{0:#x}", MathResult::from((-1.5, -2.0))) == "-0x1.8-0x2i");
+    assert!(format!("{0:#b}", MathResult::from((3.0, -1.0))) == "0b11-0b1i");
+    assert!(format!("{0:#o}", MathResult::from((-9.0, 8.0))) == "-0o11+0o10i");
+
+    // matrices are formatted elementwise, exact numbers without loss of precision
+    let result = get_result("[-1, 2; 3, -4]", &mut context).unwrap().unwrap();
+    assert!(format!("{0:#x}", result) == "[-0x1, 0x2; 0x3, -0x4]");
+    assert!(format!("{0:b}", result) == "[-0b1, 0b10; 0b11, -0b100]");
+    context.set_precision_mode(PrecisionMode::Exact);
+    let result = get_result("-255", &mut context).unwrap().unwrap();
+    assert!(format!("{0:#x}", result) == "-0xff");
+}
+
+#[test]
+fn tst_sum_prod() {
+    let mut context = MathContext::new();
+
+    let result = get_result("sum(k, k, 1, 100)", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 5050.0);
+    let result = get_result("prod(k, k, 1, 10)", &mut context).unwrap().unwrap();
+    assert!(result.value.re == 3628800.0);
+    let result = get_result("sum(1/k^2, k, 1, 1000)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 1.6439345666815615).abs() < 1e-12);
+
+    // empty ranges, nested series and complex terms
+    assert!(get_result("sum(k, k, 5, 1)", &mut context).unwrap().unwrap().value.re == 0.0);
+    assert!(get_result("prod(k, k, 5, 1)", &mut context).unwrap().unwrap().value.re == 1.0);
+    assert!(get_result("sum(sum(j*k, j, 1, k), k, 1, 3)", &mut context).unwrap().unwrap().value.re == 25.0);
+    assert!(get_result("sum(i^k, k, 0, 3)", &mut context).unwrap().unwrap().value.norm() < 1e-12);
+
+    // the bound variable shadows constants and is not defined afterwards
+    get_result("k = 100", &mut context).unwrap();
+    assert!(get_result("sum(k, k, 1, 3) + k", &mut context).unwrap().unwrap().value.re == 106.0);
+    assert!(get_result("sum(j, j, 1, 3) + j", &mut context).is_err());
+
+    // user functions may use series with their arguments as bounds
+    get_result("harmonic(n) = sum(1/k, k, 1, n)", &mut context).unwrap();
+    assert!((get_result("harmonic(4)", &mut context).unwrap().unwrap().value.re - 25.0/12.0).abs() < 1e-12);
+    context.set_precision_mode(PrecisionMode::Exact);
+    assert!(format!("{0}", get_result("harmonic(4)", &mut context).unwrap().unwrap()) == "25/12");
+    context.set_precision_mode(PrecisionMode::Float);
+
+    // errors
+    let msg = format!("{0}", get_result("sum(k, k, 1, 2.5)", &mut context).err().unwrap());
+    assert!(msg == "Error: Expected integer.\nsum(k, k, 1, 2.5)\n               ^~~~ Found: 2.5");
+    let msg = format!("{0}", get_result("sum(k, pi, 1, 2)", &mut context).err().unwrap());
+    assert!(msg == "Error: Expected new constant name or function name.\nsum(k, pi, 1, 2)\n        ^~~~ Found: built-in expression \"pi\"");
+    let msg = format!("{0}", get_result("sum(k, k, 1, 1E7)", &mut context).err().unwrap());
+    assert!(msg == "Error: Maximum number of 1000000 terms exceeded in \"sum\".");
+    assert!(get_result("prod(k, k, -1E18, 1E18)", &mut context).is_err());
+
+    // bounds beyond the maximum number of terms are accepted if the range is small enough
+    assert!(get_result("sum(k, k, 1E7, 1E7 + 1)", &mut context).unwrap().unwrap().value.re == 20000001.0);
+
+    // conversions
+    assert!(get_latex("sum(1/k^2, k, 1, n) + prod(k + 1, k, 1, 3)", &context).unwrap() ==
+        "\\sum_{k=1}^{n} \\frac{1}{k^{2}} + \\prod_{k=1}^{3} \\left(k + 1\\right)");
+    assert!(get_sympy("sum(1/k^2, k, 1, n)", &context).unwrap() == "summation(1/k**2, (k, 1, n))");
+}
+
+#[test]
+fn tst_integrate() {
+    let mut context = MathContext::new();
+
+    // built-in functions
+    let result = get_result("integrate(sin, 0, pi)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 2.0).abs() < 1e-9);
+    let result = get_result("integrate(exp, 0, 1)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - (f64::consts::E - 1.0)).abs() < 1e-9);
+
+    // user defined functions, reversed bounds and functions defined by integrals
+    get_result("f(x) = x^2", &mut context).unwrap();
+    let result = get_result("integrate(f, 0, 3)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 9.0).abs() < 1e-9);
+    let result = get_result("integrate(f, 3, 0)", &mut context).unwrap().unwrap();
+    assert!((result.value.re + 9.0).abs() < 1e-9);
+    get_result("F(t) = integrate(f, 0, t)", &mut context).unwrap();
+    let result = get_result("F(2)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 8.0 / 3.0).abs() < 1e-9);
+
+    // errors
+    let msg = format!("{0}", get_result("integrate(2, 0, 1)", &mut context).err().unwrap());
+    assert!(msg == "Error: Expected function with 1 argument.\nintegrate(2, 0, 1)\n          ^~~~ Found: expression \"2\"");
+    assert!(get_result("integrate(pow, 0, 1)", &mut context).is_err());
+    assert!(get_result("integrate(sin, 0, i)", &mut context).is_err());
+    let msg = format!("{0}", get_result("integrate(ln, 0, 1)", &mut context).err().unwrap());
+    assert!(msg.starts_with("Error: Expected finite integrand."));
+
+    // conversions
+    assert!(get_latex("integrate(sin, 0, pi)", &context).unwrap() == "\\int_{0}^{\\pi} \\sin\\left(x\\right) \\, dx");
+    assert!(get_sympy("integrate(ln, 1, e)", &context).unwrap() == "integrate(log(x), (x, 1, E))");
+}
+
+#[test]
+fn tst_solve() {
+    let mut context = MathContext::new();
+
+    let result = get_result("solve(x^2 - 2, x, 1)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 2.0_f64.sqrt()).abs() < 1e-12);
+    let result = get_result("solve(cos(t) - t, t, 0)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 0.7390851332151607).abs() < 1e-12);
+
+    // complex roots are found from complex initial guesses
+    let result = get_result("solve(z^2 + 1, z, 0.5 + i)", &mut context).unwrap().unwrap();
+    assert!(result.value.re.abs() < 1e-9 && (result.value.im - 1.0).abs() < 1e-9);
+
+    // the bound variable is not defined afterwards and user functions may use the solve function
+    assert!(get_result("x", &mut context).is_err());
+    get_result("cbrt(a) = solve(y^3 - a, y, 1)", &mut context).unwrap();
+    let result = get_result("cbrt(27)", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 3.0).abs() < 1e-9);
+
+    // errors
+    let msg = format!("{0}", get_result("solve(x^2 + 1, x, 1)", &mut context).err().unwrap());
+    assert!(msg == "Error: Expected convergent root search.\nsolve(x^2 + 1, x, 1)\n    ^~~~ Found: no root found from the initial guess 1 within 100 iterations");
+    assert!(get_result("solve(x^2, 2, 1)", &mut context).is_err());
+
+    assert!(get_sympy("solve(x^2 - 2, x, 1)", &context).unwrap() == "nsolve(x**2 - 2, x, 1)");
+}
+
+#[test]
+fn tst_units() {
+    let mut context = MathContext::new();
+    let output = |s: &str, context: &mut MathContext| format!("{0}", get_result(s, context).unwrap().unwrap());
+
+    assert!(output("3 m / 2 s", &mut context) == "1.5 m/s");
+    assert!(output("5 kg * 2 m/s^2", &mut context) == "10 N");
+    assert!(output("1 km + 200 m", &mut context) == "1.2 km");
+    assert!(output("2 km / 4 m", &mut context) == "500");
+    assert!(output("(3 m)^2", &mut context) == "9 m^2");
+    assert!(output("sqrt(16 m^2)", &mut context) == "4 m");
+    assert!(output("convert(36 km/h, m/s)", &mut context) == "10 m/s");
+    assert!(get_result("1 km > 200 m", &mut context).unwrap().unwrap().value.re == 1.0);
+
+    // quantities can be stored in user constants and passed to user functions
+    get_result("v = 72 km/h", &mut context).unwrap();
+    get_result("distance(t) = v*t", &mut context).unwrap();
+    assert!(output("convert(distance(30 min), km)", &mut context) == "36 km");
+
+    // units only follow numbers, so they do not shadow user constants of the same name
+    get_result("m = 2", &mut context).unwrap();
+    assert!(output("3*m", &mut context) == "6");
+    assert!(output("3 m", &mut context) == "3 m");
+
+    // serialization
+    let result = get_result("3 km", &mut context).unwrap().unwrap();
+    let deserialized : MathResult = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+    assert!(deserialized == result);
+
+    // conversions
+    get_result("f(x) = x*2 km", &mut context).unwrap();
+    assert!(context.get_user_function_canonical_input("f").unwrap() == "f(x) = x*(2 km)");
+    assert!(get_latex("3 m/s^2", &context).unwrap() == "3 \\, \\mathrm{m/s^{2}}");
+    assert!(get_sympy("3 m/s^2", &context).unwrap() == "3*m/s**2");
+
+    // errors
+    let err = get_result("3 m + 2 s", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected operands of the same dimension.\n3 m + 2 s\n    ^~~~ Found: dimensions \"m\" and \"s\"");
+    let err = get_result("sin(2 m)", &mut context).err().unwrap();
+    assert!(err.to_string().starts_with("Error: Expected dimensionless argument."));
+    let err = get_result("convert(3 m, s)", &mut context).err().unwrap();
+    assert!(err.to_string().starts_with("Error: Expected quantity of dimension \"s\"."));
+    assert!(get_result("sqrt(2 m)", &mut context).is_err());
+    assert!(get_result("[1 m, 2]", &mut context).is_err());
+}
+
+#[test]
+fn tst_domain_checks() {
+    let mut context = MathContext::new();
+
+    // arguments outside of the domain of a built-in function or operation are reported as errors
+    let err = get_result("1/0", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected non-zero divisor.\n1/0\n  ^~~~ Found: divisor 0");
+    let err = get_result("5 % 0", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected non-zero divisor.\n5 % 0\n    ^~~~ Found: divisor 0");
+    let err = get_result("root(8, 0)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected non-zero degree.\nroot(8, 0)\n        ^~~~ Found: degree 0");
+    let err = get_result("pow(0, -1)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected non-zero base of a negative exponent.\npow(0, -1)\n    ^~~~ Found: base 0 with the exponent -1");
+    let err = get_result("log(8, 1)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected base other than 0 and 1.\nlog(8, 1)\n       ^~~~ Found: base 1");
+    let err = get_result("gamma(-2)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected argument other than 0 and the negative integers.\ngamma(-2)\n      ^~~~ Found: argument -2");
+    let err = get_result("atanh(1)", &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected argument other than -1 and 1.\natanh(1)\n      ^~~~ Found: argument 1");
+    for input in vec!["0^-1", "7 // 0", "ln(0)", "log10(0)", "root(0, -2)", "2 m / 0", "gamma(0)", "(-3)!", "cot(0)", "coth(0)",
+                      "arccoth(-1)"] {
+        assert!(get_result(input, &mut context).is_err());
+    }
+
+    // arguments inside of the domain are not affected
+    assert!(get_result("0/1", &mut context).unwrap().unwrap().value.re == 0.0);
+    assert!(get_result("0^2", &mut context).unwrap().unwrap().value.re == 0.0);
+    assert!(get_result("ln(1)", &mut context).unwrap().unwrap().value.re == 0.0);
+    assert!(get_result("0!", &mut context).unwrap().unwrap().value.re == 1.0);
+    assert!(get_result("gamma(-0.5)", &mut context).unwrap().unwrap().value.re.is_finite());
+
+    // the permissive mode returns the results of the floating point arithmetic
+    context.set_permissive(true);
+    assert!(get_result("1/0", &mut context).unwrap().unwrap().value.re == f64::INFINITY);
+    assert!(get_result("-1/0", &mut context).unwrap().unwrap().value.re == f64::NEG_INFINITY);
+    assert!(get_result("0/0", &mut context).unwrap().unwrap().value.re.is_nan());
+    assert!(get_result("pow(0, -1)", &mut context).unwrap().unwrap().value.re.is_infinite());
+    assert!(get_result("ln(0)", &mut context).unwrap().unwrap().value.re.is_infinite());
+    assert!(get_result("5 % 0", &mut context).unwrap().unwrap().value.re.is_nan());
+    assert!(get_result("(-1)!", &mut context).unwrap().unwrap().value.re.is_nan());
+    assert!(get_result("cot(0)", &mut context).unwrap().unwrap().value.re.is_infinite());
+}
+
+#[test]
+fn tst_sample_function() {
+    let mut context = MathContext::new();
+
+    // the expression is evaluated at equidistant sample points including both bounds
+    let samples = sample_function("sin(x)", "-pi", "pi", 5, &mut context).unwrap();
+    assert!(samples.variable == Some(String::from("x")));
+    assert!(samples.points.len() == 5);
+    assert!(samples.points[0].0 == -f64::consts::PI && samples.points[4].0 == f64::consts::PI);
+    assert!(samples.points[2] == (0.0, Some(0.0)));
+    assert!((samples.points[3].1.unwrap() - 1.0).abs() < TEST_BOUND);
+
+    // sample points without a finite real value leave gaps
+    let samples = sample_function("1/t", "-1", "1", 3, &mut context).unwrap();
+    assert!(samples.points[0].1 == Some(-1.0) && samples.points[1].1.is_none() && samples.points[2].1 == Some(1.0));
+    let samples = sample_function("sqrt(x)", "-1", "1", 3, &mut context).unwrap();
+    assert!(samples.points[0].1.is_none());
+
+    // constant expressions have no variable
+    let samples = sample_function("2", "0", "1", 3, &mut context).unwrap();
+    assert!(samples.variable.is_none() && samples.points.iter().all(|p| p.1 == Some(2.0)));
+
+    // sampling does not change the previous results
+    get_result("7", &mut context).unwrap();
+    sample_function("x^2", "0", "3", 4, &mut context).unwrap();
+    assert!(get_result("ans", &mut context).unwrap().unwrap().value.re == 7.0);
+
+    // errors
+    let err = sample_function("x*y", "0", "1", 3, &mut context).err().unwrap();
+    assert!(err.to_string() == "Error: Expected expression in a single variable.\nx*y\n ^~~~ Found: variables \"x\", \"y\"");
+    let err = sample_function("x", "i", "1", 3, &mut context).err().unwrap();
+    assert!(err.to_string().starts_with("Error: Expected finite real bound."));
+    assert!(sample_function("x", "0", "1 +", 3, &mut context).is_err());
+}
+
+#[test]
+fn tst_radix_literals() {
+    let mut context = MathContext::new();
+
+    // numbers of an arbitrary radix from 2 to 36 (the digits are not case sensitive)
+    assert!(get_result("0r36:z1", &mut context).unwrap().unwrap().value.re == 1261.0);
+    assert!(get_result("0r36:Z1", &mut context).unwrap().unwrap().value.re == 1261.0);
+    assert!(get_result("0r2:101 + 0b101", &mut context).unwrap().unwrap().value.re == 10.0);
+    assert!(get_result("0r16:ff == 0xff", &mut context).unwrap().unwrap().value.re == 1.0);
+    let result = get_result("0r3:12.1", &mut context).unwrap().unwrap();
+    assert!((result.value.re - 16.0 / 3.0).abs() < TEST_BOUND);
+    context.set_precision_mode(PrecisionMode::Exact);
+    assert!(get_result("0r3:0.1", &mut context).unwrap().unwrap().to_string() == "1/3");
+    context.set_precision_mode(PrecisionMode::Float);
+
+    // output in an arbitrary radix, which can be entered again
+    let result = get_result("1261", &mut context).unwrap().unwrap();
+    assert!(result.radix_fmt(36) == "0r36:z1");
+    assert!(get_result(&result.radix_fmt(7), &mut context).unwrap().unwrap().value.re == 1261.0);
+    let result = get_result("[2, -0.5]", &mut context).unwrap().unwrap();
+    assert!(result.radix_fmt(2) == "[0r2:10, -0r2:0.1]");
+
+    // conversions
+    assert!(get_latex("0r36:z1 + 1", &context).unwrap() == "\\mathrm{z1}_{36} + 1");
+    assert!(get_sympy("0r36:z1", &context).unwrap() == "int('z1', 36)");
+    assert!(get_sympy("0r3:1.2", &context).unwrap() == "Rational(int('12', 3), 3**1)");
+
+    // errors
+    let err = get_result("0r37:1", &mut context).err().unwrap();
+    assert!(err.to_string().starts_with("Error: Expected literal number."));
+    assert!(get_result("0r8:9", &mut context).is_err());
+    assert!(get_result("0r36", &mut context).is_err());
+}
+
+#[test]
+fn tst_special_value_formats() {
+    let one = format!("0b{0}", "1".repeat(10) + &"0".repeat(52));
+
+    // the parts of complex numbers are labeled in the IEEE754 format
+    assert!(MathResult::from((1.0, 1.0)).ieee754_fmt() == format!("re: {0} + im: {0}·i", one));
+    assert!(MathResult::from((f64::NAN, 1.0)).ieee754_fmt() == format!("re: NaN + im: {0}·i", one));
+    assert!(MathResult::from((1.0, f64::NEG_INFINITY)).ieee754_fmt() == format!("re: {0} + im: -inf·i", one));
+    assert!(MathResult::from(f64::INFINITY).ieee754_fmt() == "inf");
+
+    // NaN and infinity are formatted by name in the exponential format
+    assert!(format!("{0:E}", MathResult::from(f64::NAN)) == "NaN");
+    assert!(format!("{0:E}", MathResult::from((f64::NAN, 1.0))) == "NaN+1i");
+    assert!(format!("{0:e}", MathResult::from((1.0, f64::INFINITY))) == "1+infi");
+}
+
+#[test]
+fn tst_symbol_description() {
+    let mut context = MathContext::new();
+    get_result("c = 4.1", &mut context).unwrap();
+    get_result("f(x, y) = x + 2*y", &mut context).unwrap();
+    get_result("c + 1", &mut context).unwrap();
+
+    assert!(context.get_symbol_description("tan").unwrap() == "built-in function, 1 argument, trigonometric");
+    assert!(context.get_symbol_description("asinh").unwrap() == "built-in function, 1 argument, inverse hyperbolic");
+    assert!(context.get_symbol_description("integrate").unwrap() == "built-in function, 3 arguments, numerical");
+    assert!(context.get_symbol_description("pi").unwrap() == "built-in constant = 3.141592653589793 (Real)");
+    assert!(context.get_symbol_description("//").unwrap() == "built-in operation, precedence 8");
+    assert!(context.get_symbol_description("c").unwrap() == "user constant = 4.1 (Real), defined this session");
+    assert!(context.get_symbol_description("f").unwrap() == "user function, 2 arguments, f(x, y) = x + 2*y, defined this session");
+    assert!(context.get_symbol_description("ans").unwrap() == "previous result = 5.1 (Real)");
+    assert!(context.get_symbol_description("ans2").unwrap() == "previous result (not available)");
+    assert!(context.get_symbol_description("q").is_none());
+
+    // the definitions of a deserialized context have been loaded from a file
+    let mut loaded : MathContext = serde_json::from_str(&serde_json::to_string(&context).unwrap()).unwrap();
+    loaded.initialize();
+    assert!(loaded.get_symbol_description("c").unwrap() == "user constant = 4.1 (Real), loaded from a file");
+    assert!(loaded.get_symbol_description("f").unwrap().ends_with(", loaded from a file"));
+}
+
+#[test]
+fn tst_statements() {
+    // ";" separates statements outside of parentheses and brackets
+    assert!(split_statements("a = 2;b = 3 ; a*b") == vec!["a = 2", "b = 3", "a*b"]);
+    assert!(split_statements("m = [1, 2; 3, 4]; det([1; 2]*[1, 2])") == vec!["m = [1, 2; 3, 4]", "det([1; 2]*[1, 2])"]);
+    assert!(split_statements(" ; 1;; 2 ;") == vec!["1", "2"]);
+    assert!(split_statements("").is_empty());
+
+    // each statement may use the definitions of the previous statements
+    let mut context = MathContext::new();
+    let results = get_statement_results("f(x) = x^2; c = f(3); c + 1; f(c)", &mut context).unwrap();
+    assert!(results == vec![None, None, Some(MathResult::from(10.0)), Some(MathResult::from(81.0))]);
+
+    // the evaluation stops at the first error
+    assert!(get_statement_results("d = 1; 1/; g = 2", &mut context).is_err());
+    assert!(context.is_user_constant("d") && !context.is_user_constant("g"));
+}
+
+#[test]
+fn tst_normalize_input() {
+    let context = MathContext::new();
+
+    // inputs that only differ in whitespace or redundant parentheses have the same normalized form
+    assert!(normalize_input("1+1", &context) == "1 + 1");
+    assert!(normalize_input("(1) + 1", &context) == "1 + 1");
+    assert!(normalize_input("sin( pi/2 )", &context) == normalize_input("sin((pi/2))", &context));
+    assert!(normalize_input("x=2;x*3", &context) == "x = 2; x*3");
+
+    // inputs that can not be parsed are only trimmed
+    assert!(normalize_input("  load \"a.json\" ", &context) == "load \"a.json\"");
+    assert!(normalize_input("1+", &context) == "1+");
+}
+
+#[test]
+fn tst_statistics() {
+    let mut context = MathContext::new();
+    let value = |s: & str, context: & mut MathContext| get_result(s, context).unwrap().unwrap().value.re;
+
+    assert!(value("mean(1, 2, 3, 4)", &mut context) == 2.5);
+    assert!(value("median(5, 1, 3)", &mut context) == 3.0);
+    assert!(value("median(4, 1, 3, 2)", &mut context) == 2.5);
+    assert!(value("min(3, -1, 2) + max(3, -1, 2)", &mut context) == 2.0);
+    assert!(value("var(2, 4, 6)", &mut context) == 4.0);
+    assert!((value("stddev(2, 4, 6)", &mut context) - 2.0).abs() < TEST_BOUND);
+    assert!(value("max(7)", &mut context) == 7.0);
+
+    // sum adds its arguments unless it is a series with a bound variable
+    assert!(value("sum(1, 2, 3)", &mut context) == 6.0);
+    assert!(value("sum(1, 2, 3, 4)", &mut context) == 10.0);
+    assert!(value("sum(k, k, 1, 4)", &mut context) == 10.0);
+    assert!(get_result("sum(1 + 2i, 3)", &mut context).unwrap().unwrap() == MathResult::from((4.0, 2.0)));
+
+    // the statistics functions can be used in user defined functions
+    get_result("spread(a, b, c) = max(a, b, c) - min(a, b, c)", &mut context).unwrap();
+    assert!(value("spread(4, -2, 1)", &mut context) == 6.0);
+
+    let msg = format!("{0}", get_result("mean()", &mut context).err().unwrap());
+    assert!(msg == "Error: Expected at least 1 argument(s).\nmean()\n   ^~~~ Found: 0 argument(s)");
+    let msg = format!("{0}", get_result("var(1)", &mut context).err().unwrap());
+    assert!(msg == "Error: Expected at least 2 argument(s).\nvar(1)\n  ^~~~ Found: 1 argument(s)");
+    let msg = format!("{0}", get_result("max(1, i)", &mut context).err().unwrap());
+    assert!(msg.starts_with("Error: Expected real argument.\nmax(1, i)\n"));
+    assert!(get_result("mean(1, i)", &mut context).unwrap().unwrap() == MathResult::from((0.5, 0.5)));
+}
+
+#[test]
+fn tst_digit_separators() {
+    let mut context = MathContext::new();
+
+    // "_" separates the digits of number literals
+    assert!(get_result("1_000_000 + 1", &mut context).unwrap().unwrap().value.re == 1000001.0);
+    assert!(get_result("1_234.5_6", &mut context).unwrap().unwrap().value.re == 1234.56);
+    assert!(get_result("0xff_ff", &mut context).unwrap().unwrap().value.re == 65535.0);
+    assert!(get_result("0b1010_1010", &mut context).unwrap().unwrap().value.re == 170.0);
+    assert!(get_result("2_000i", &mut context).unwrap().unwrap().value.im == 2000.0);
+
+    // a separator must be between two digits
+    assert!(get_result("1_", &mut context).is_err());
+    assert!(get_result("1__000", &mut context).is_err());
+    assert!(get_result("1_.5", &mut context).is_err());
+    assert!(get_result("0x_ff", &mut context).is_err());
+}
+
+#[test]
+fn tst_auto_ans() {
+    let mut context = MathContext::new();
+
+    // disabled by default
+    get_result("3", &mut context).unwrap();
+    assert!(get_result("* 2", &mut context).is_err());
+
+    context.set_auto_ans(true);
+    assert!(get_result("* 2", &mut context).unwrap().unwrap().value.re == 6.0);
+    assert!(get_result("^2", &mut context).unwrap().unwrap().value.re == 36.0);
+    assert!(get_result("// 5", &mut context).unwrap().unwrap().value.re == 7.0);
+    assert!(get_result("+ 3", &mut context).unwrap().unwrap().value.re == 10.0);
+    assert!(get_result("- 4", &mut context).unwrap().unwrap().value.re == 6.0);
+    assert!(get_result("!", &mut context).unwrap().unwrap().value.re == 720.0);
+
+    // signs, assignments and inputs that can not be completed are not changed
+    assert!(get_result("-4", &mut context).unwrap().unwrap().value.re == -4.0);
+    assert!(get_result("= 2", &mut context).is_err());
+    assert!(get_result("* )", &mut context).err().unwrap().to_string().contains("* )"));
+}
+
+#[test]
+fn tst_chained_assignment() {
+    let mut context = MathContext::new();
+
+    // "=" is right associative, so all constants of a chain get the value of the right hand side
+    assert!(get_result("a = b = c = 2 + 1", &mut context).unwrap().is_none());
+    for name in vec!["a", "b", "c"] {
+        assert!(context.get_constant_value(name).unwrap().value.re == 3.0);
+    }
+    get_result("x = 2", &mut context).unwrap();
+    get_result("x = y = x + 1", &mut context).unwrap();
+    assert!(context.get_constant_value("x").unwrap().value.re == 3.0);
+    assert!(context.get_constant_value("y").unwrap().value.re == 3.0);
+    assert!(get_result("a = f(t) = t", &mut context).is_err());
+    assert!(get_latex("a = b = 3", &context).unwrap() == "a = b = 3");
+
+    // assignments inside of expressions are an error by default and do not change the constant
+    let result = get_result("(x = 5) + 2", &mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected expression.\n(x = 5) + 2\n   ^~~~ Found: assignment inside of an \
+        expression (see \"set assignvalue\")");
+    assert!(context.get_constant_value("x").unwrap().value.re == 3.0);
+
+    // with the assignment values, they yield the assigned value, while assignment statements still have no result
+    context.set_assignment_values(true);
+    assert!(get_result("(x = 5) + 2", &mut context).unwrap().unwrap().value.re == 7.0);
+    assert!(context.get_constant_value("x").unwrap().value.re == 5.0);
+    assert!(get_result("sqrt(x = 16) + x", &mut context).unwrap().unwrap().value.re == 20.0);
+    assert!(get_result("w = 1", &mut context).unwrap().is_none());
+}
+
+#[test]
+fn tst_compound_assignment() {
+    let mut context = MathContext::new();
+
+    get_result("x = 10", &mut context).unwrap();
+    let cases = vec![("x += 5", 15.0), ("x -= 3", 12.0), ("x *= 2 + 1", 36.0), ("x /= 4", 9.0), ("x+=-1", 8.0)];
+    for (input, expected) in cases {
+        assert!(get_result(input, &mut context).unwrap().is_none());
+        assert!(context.get_constant_value("x").unwrap().value.re == expected);
+    }
+
+    // "/=" and "*=" are not confused with "//" and "**"
+    get_result("y = 7//2", &mut context).unwrap();
+    assert!(context.get_constant_value("y").unwrap().value.re == 3.0);
+    assert!(get_result("2**3", &mut context).unwrap().unwrap().value.re == 8.0);
+
+    // the target must be an existing user defined constant
+    let result = get_result("z += 1", &mut context);
+    assert!(result.err().unwrap().to_string() == "Error: Expected user defined constant as target of \"+=\".\nz += 1\n^~~~ Found: \
+        undefined constant \"z\"");
+    assert!(get_result("pi *= 2", &mut context).is_err());
+    assert!(get_result("2 -= 1", &mut context).is_err());
+}
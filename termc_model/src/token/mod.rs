@@ -25,7 +25,10 @@ pub enum TokenType {
     Operation,
     Punctuation,
     Symbol(SymbolicTokenType),
-    FunctionArg
+    FunctionArg,
+    Keyword,
+    /// A unit expression that follows a number literal (e.g. "m/s^2" in "9.81 m/s^2").
+    Unit
 }
 
 /// Defines the Token structure.
@@ -90,3 +93,38 @@ impl fmt::Display for Token {
         write!(f, "{}", self.get_value())
     }
 }
+
+/// Splits a non-decimal number literal into its radix and its digits (e.g. "0x1f" into 16 and "1f" or "0r36:z1" into
+/// 36 and "z1"). Returns None for decimal literals and for literals whose radix is not in the range from 2 to 36.
+///
+/// # Examples
+///
+/// ```
+/// use termc_model::token::split_radix_literal;
+///
+/// assert!(split_radix_literal("0b101") == Some((2, "101")));
+/// assert!(split_radix_literal("0r36:z1") == Some((36, "z1")));
+/// assert!(split_radix_literal("0r37:z1") == None);
+/// assert!(split_radix_literal("1.5") == None);
+/// ```
+pub fn split_radix_literal(value: & str) -> Option<(u32, & str)> {
+    if value.starts_with("0x") {
+        Some((16, & value[2..]))
+    }
+    else if value.starts_with("0o") {
+        Some((8, & value[2..]))
+    }
+    else if value.starts_with("0b") {
+        Some((2, & value[2..]))
+    }
+    else if value.starts_with("0r") {
+        let colon = value.find(':')?;
+        match value[2..colon].parse::<u32>() {
+            Ok(radix) if radix >= 2 && radix <= 36 => Some((radix, & value[colon + 1..])),
+            _ => None
+        }
+    }
+    else {
+        None
+    }
+}
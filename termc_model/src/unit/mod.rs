@@ -0,0 +1,261 @@
+use std::fmt;
+
+/// The number of SI base units.
+const N_BASE_UNITS : usize = 7;
+
+/// The names of the SI base units in the order of the exponents of a dimension.
+const BASE_UNITS : [&'static str; N_BASE_UNITS] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+/// The known units: the name, the scale (the value in SI base units) and the exponents of the SI base units.
+const UNITS : &'static [(&'static str, f64, [i32; N_BASE_UNITS])] = &[
+    ("m", 1.0, [1, 0, 0, 0, 0, 0, 0]),
+    ("km", 1e3, [1, 0, 0, 0, 0, 0, 0]),
+    ("cm", 1e-2, [1, 0, 0, 0, 0, 0, 0]),
+    ("mm", 1e-3, [1, 0, 0, 0, 0, 0, 0]),
+    ("kg", 1.0, [0, 1, 0, 0, 0, 0, 0]),
+    ("g", 1e-3, [0, 1, 0, 0, 0, 0, 0]),
+    ("mg", 1e-6, [0, 1, 0, 0, 0, 0, 0]),
+    ("t", 1e3, [0, 1, 0, 0, 0, 0, 0]),
+    ("s", 1.0, [0, 0, 1, 0, 0, 0, 0]),
+    ("ms", 1e-3, [0, 0, 1, 0, 0, 0, 0]),
+    ("min", 60.0, [0, 0, 1, 0, 0, 0, 0]),
+    ("h", 3600.0, [0, 0, 1, 0, 0, 0, 0]),
+    ("A", 1.0, [0, 0, 0, 1, 0, 0, 0]),
+    ("mA", 1e-3, [0, 0, 0, 1, 0, 0, 0]),
+    ("K", 1.0, [0, 0, 0, 0, 1, 0, 0]),
+    ("mol", 1.0, [0, 0, 0, 0, 0, 1, 0]),
+    ("cd", 1.0, [0, 0, 0, 0, 0, 0, 1]),
+    ("L", 1e-3, [3, 0, 0, 0, 0, 0, 0]),
+    ("mL", 1e-6, [3, 0, 0, 0, 0, 0, 0]),
+    ("Hz", 1.0, [0, 0, -1, 0, 0, 0, 0]),
+    ("N", 1.0, [1, 1, -2, 0, 0, 0, 0]),
+    ("kN", 1e3, [1, 1, -2, 0, 0, 0, 0]),
+    ("J", 1.0, [2, 1, -2, 0, 0, 0, 0]),
+    ("kJ", 1e3, [2, 1, -2, 0, 0, 0, 0]),
+    ("W", 1.0, [2, 1, -3, 0, 0, 0, 0]),
+    ("kW", 1e3, [2, 1, -3, 0, 0, 0, 0]),
+    ("Pa", 1.0, [-1, 1, -2, 0, 0, 0, 0]),
+    ("kPa", 1e3, [-1, 1, -2, 0, 0, 0, 0]),
+    ("bar", 1e5, [-1, 1, -2, 0, 0, 0, 0]),
+    ("C", 1.0, [0, 0, 1, 1, 0, 0, 0]),
+    ("V", 1.0, [2, 1, -3, -1, 0, 0, 0])
+];
+
+/// The derived SI units that are used to name the dimensions of computed results (e.g. "N" instead of "m*kg/s^2").
+const DERIVED_UNITS : [&'static str; 6] = ["N", "J", "W", "Pa", "C", "V"];
+
+/// Defines the physical dimension of a quantity by the exponents of the SI base units (e.g. m/s^2 is [1, 0, -2, 0, 0, 0, 0]).
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Dimension {
+    pub exponents: [i32; N_BASE_UNITS]
+}
+
+impl Dimension {
+
+    /// Checks whether the dimension is dimensionless (all exponents are zero).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::unit::{Dimension, Unit};
+    ///
+    /// assert!(Dimension::default().is_dimensionless());
+    /// assert!(!Unit::parse("m/s").unwrap().dimension.is_dimensionless());
+    /// ```
+    pub fn is_dimensionless(& self) -> bool {
+        self.exponents.iter().all(|e| *e == 0)
+    }
+
+    /// Returns the dimension of the product of quantities of this and the specified dimension.
+    pub fn mul(& self, other: & Dimension) -> Dimension {
+        let mut d = *self;
+        for (e, o) in d.exponents.iter_mut().zip(other.exponents.iter()) {
+            *e += *o;
+        }
+        d
+    }
+
+    /// Returns the dimension of the quotient of quantities of this and the specified dimension.
+    pub fn div(& self, other: & Dimension) -> Dimension {
+        let mut d = *self;
+        for (e, o) in d.exponents.iter_mut().zip(other.exponents.iter()) {
+            *e -= *o;
+        }
+        d
+    }
+
+    /// Returns the dimension of a quantity of this dimension raised to the specified integer power.
+    pub fn powi(& self, p: i32) -> Dimension {
+        let mut d = *self;
+        for e in d.exponents.iter_mut() {
+            *e *= p;
+        }
+        d
+    }
+
+    /// Returns the dimension of a quantity of this dimension raised to the specified power.
+    /// Returns None if an exponent of the resulting dimension is not an integer (e.g. the square root of m).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::unit::Unit;
+    ///
+    /// let area = Unit::parse("m^2").unwrap().dimension;
+    /// assert!(area.pow(0.5) == Some(Unit::parse("m").unwrap().dimension));
+    /// assert!(area.pow(0.25) == None);
+    /// ```
+    pub fn pow(& self, p: f64) -> Option<Dimension> {
+        let mut d = *self;
+        for e in d.exponents.iter_mut() {
+            let x = *e as f64 * p;
+            if (x - x.round()).abs() > 1e-9 {
+                return None;
+            }
+            *e = x.round() as i32;
+        }
+        Some(d)
+    }
+}
+
+impl fmt::Display for Dimension {
+
+    /// Formats the dimension by the SI base units (e.g. "m/s^2"). Dimensionless quantities are formatted as "1".
+    fn fmt(& self, f: & mut fmt::Formatter) -> fmt::Result {
+        let fmt_power = |name: & str, e: i32| if e == 1 { name.to_string() } else { format!("{0}^{1}", name, e) };
+        let numerator : Vec<String> = BASE_UNITS.iter().zip(self.exponents.iter()).filter(|&(_, e)| *e > 0)
+            .map(|(name, e)| fmt_power(name, *e)).collect();
+        let denominator : Vec<String> = BASE_UNITS.iter().zip(self.exponents.iter()).filter(|&(_, e)| *e < 0)
+            .map(|(name, e)| format!("/{0}", fmt_power(name, -*e))).collect();
+        let numerator = if numerator.is_empty() { String::from("1") } else { numerator.join("*") };
+        write!(f, "{0}{1}", numerator, denominator.concat())
+    }
+}
+
+/// Defines the unit of a quantity: the name of the unit that is used for the output (e.g. "km/h"), the scale of the unit
+/// (the value of the unit in SI base units) and the physical dimension.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Unit {
+    pub name: String,
+    pub scale: f64,
+    pub dimension: Dimension
+}
+
+impl Unit {
+
+    /// Creates a new instance of the Unit struct.
+    pub fn new(name: String, scale: f64, dimension: Dimension) -> Unit {
+        Unit {name: name, scale: scale, dimension: dimension}
+    }
+
+    /// Returns the unit of the specified name (e.g. "km").
+    pub fn lookup(name: & str) -> Option<Unit> {
+        UNITS.iter().find(|u| u.0 == name).map(|u| Unit::new(name.to_string(), u.1, Dimension {exponents: u.2}))
+    }
+
+    /// Returns the SI unit of the specified dimension, which is named by a derived SI unit (e.g. "N") if possible.
+    /// Returns None for dimensionless quantities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::unit::Unit;
+    ///
+    /// let force = Unit::parse("kg*m/s^2").unwrap().dimension;
+    /// assert!(Unit::from_dimension(force).unwrap().name == "N");
+    /// let speed = Unit::parse("km/h").unwrap().dimension;
+    /// assert!(Unit::from_dimension(speed).unwrap().name == "m/s");
+    /// ```
+    pub fn from_dimension(dimension: Dimension) -> Option<Unit> {
+        if dimension.is_dimensionless() {
+            return None;
+        }
+        let name = DERIVED_UNITS.iter().filter_map(|n| Unit::lookup(n)).find(|u| u.dimension == dimension)
+            .map_or(dimension.to_string(), |u| u.name);
+        Some(Unit::new(name, 1.0, dimension))
+    }
+
+    /// Parses a unit expression, which consists of units that are combined by "*" and "/" and may be raised to integer
+    /// powers (e.g. "m/s^2" or "kg*m^2/s^2"). Returns None if the expression is not a valid unit expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_model::unit::Unit;
+    ///
+    /// let u = Unit::parse("km/h").unwrap();
+    /// assert!(u.name == "km/h");
+    /// assert!(u.scale == 1000.0 / 3600.0);
+    /// assert!(Unit::parse("m/x").is_none());
+    /// ```
+    pub fn parse(s: & str) -> Option<Unit> {
+        if match_unit(s) != s.len() || s.is_empty() {
+            return None;
+        }
+
+        let mut scale = 1.0;
+        let mut dimension = Dimension::default();
+        let mut rest = s;
+        let mut is_divisor = false;
+        while !rest.is_empty() {
+            let (name, p, len) = match_atom(rest)?;
+            let u = Unit::lookup(name)?;
+            let power = u.dimension.powi(p);
+            if is_divisor {
+                scale /= u.scale.powi(p);
+                dimension = dimension.div(& power);
+            }
+            else {
+                scale *= u.scale.powi(p);
+                dimension = dimension.mul(& power);
+            }
+            rest = & rest[len..];
+            is_divisor = rest.starts_with('/');
+            if !rest.is_empty() {
+                rest = & rest[1..];
+            }
+        }
+        Some(Unit::new(s.to_string(), scale, dimension))
+    }
+}
+
+/// Returns the length (in bytes) of the unit expression at the beginning of the specified string (e.g. 5 for "m/s^2 + 1").
+/// The unit expression must not contain whitespace and it is not followed by a literal or an opening parenthesis,
+/// so that e.g. "sin" in "m/sin(x)" is not read as a unit. Returns 0 if the string does not start with a unit expression.
+pub fn match_unit(s: & str) -> usize {
+    let mut len = 0;
+    loop {
+        let rest = if len == 0 { s } else { & s[len + 1..] }; // skip the "*" or "/" between the units
+        let atom_len = match match_atom(rest) {
+            Some((name, _, atom_len)) if Unit::lookup(name).is_some() => atom_len,
+            _ => return len
+        };
+        len = if len == 0 { atom_len } else { len + 1 + atom_len };
+        match s[len..].chars().next() {
+            Some('*') | Some('/') => (),
+            _ => return len
+        }
+    }
+}
+
+/// Reads a unit name with an optional integer power (e.g. "s^-2") at the beginning of the specified string.
+/// Returns the name, the power and the length (in bytes) of the unit or None if the string does not start with a name.
+fn match_atom(s: & str) -> Option<(& str, i32, usize)> {
+    let name_len = s.find(|c: char| !c.is_alphanumeric() && c != '_').unwrap_or(s.len());
+    if name_len == 0 || s[..name_len].starts_with(|c: char| c.is_numeric()) || s[name_len..].starts_with('(') {
+        return None;
+    }
+
+    let name = & s[..name_len];
+    if !s[name_len..].starts_with('^') {
+        return Some((name, 1, name_len));
+    }
+    let power = & s[name_len + 1..];
+    let sign_len = if power.starts_with('-') { 1 } else { 0 };
+    let digits_len = power[sign_len..].find(|c: char| !c.is_digit(10)).unwrap_or(power.len() - sign_len);
+    if digits_len == 0 || power[sign_len + digits_len..].starts_with('.') {
+        return Some((name, 1, name_len)); // e.g. "m^x" or "m^1.5": the power is not part of the unit
+    }
+    let p = power[..sign_len + digits_len].parse::<i32>().ok()?;
+    Some((name, p, name_len + 1 + sign_len + digits_len))
+}
@@ -0,0 +1,258 @@
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{self, Path};
+use colored::*;
+use rustyline::completion::Completer;
+use rustyline::Result;
+
+/// Defines the commands whose argument is a file path.
+static PATH_COMMANDS : [&'static str; 3] = ["save", "load", "run"];
+
+/// Defines the commands that are completed at the beginning of the input.
+static COMMANDS : [&'static str; 27] = ["compare", "del", "diff", "exit", "format", "freeze", "gc", "get", "history", "identity",
+    "info", "iterate", "latex", "load", "mode", "numbering", "plot", "precision", "replay", "run", "save", "set", "simplify", "sympy", "whatis",
+    "why", "with"];
+
+/// Defines the maximum number of rows of a page of the completion menu.
+static MENU_MAX_ROWS : usize = 10;
+
+/// Defines the terminal width that is used for the completion menu if the width is unknown.
+static DEFAULT_TERMINAL_WIDTH : usize = 80;
+
+/// Defines the number of spaces between the columns of the completion menu.
+static MENU_COLUMN_GAP : usize = 2;
+
+/// Defines the auto-completer of the terminal ui.
+/// The arguments of the "save", "load" and "run" commands are completed as (optionally quoted) file paths that may
+/// contain spaces. All other input is completed with the names of the functions and constants and with the commands.
+/// If there are several candidates, they are listed in a multi-column menu above the prompt. Repeated completions of
+/// the same input show the next page of the menu. If there is nothing to complete inside the arguments of a function call,
+/// the signature of the function is shown as a hint above the prompt.
+pub struct InputCompleter {
+    /// The names of the functions and constants that are completed.
+    symbols: Vec<String>,
+    /// The call signatures of the functions (e.g. "pow(x, y)").
+    signatures: Vec<String>,
+    /// The input of the last completion and the page of the menu that was shown for it.
+    menu_state: RefCell<(String, usize)>
+}
+
+impl InputCompleter {
+    /// Creates a new InputCompleter instance without any function and constant names.
+    pub fn new() -> InputCompleter {
+        InputCompleter::with_symbols(Vec::new(), Vec::new())
+    }
+
+    /// Creates a new InputCompleter instance that completes the specified function and constant names and shows the
+    /// specified call signatures of the functions.
+    pub fn with_symbols(symbols: Vec<String>, signatures: Vec<String>) -> InputCompleter {
+        InputCompleter {symbols: symbols, signatures: signatures, menu_state: RefCell::new((String::new(), 0))}
+    }
+
+    /// Prints the completion menu of the specified candidates. The page advances if the input is the same as the input
+    /// of the last completion and starts with the first page otherwise.
+    fn show_menu(&self, line: &str, candidates: &[String]) {
+        let mut state = self.menu_state.borrow_mut();
+        let page = if state.0 == line { state.1 + 1 } else { 0 };
+        *state = (line.to_string(), page);
+
+        print_above_prompt(&format_completion_menu(candidates, get_terminal_width(), MENU_MAX_ROWS, page));
+    }
+}
+
+/// Prints the specified lines above the prompt, which is printed again by the line editor.
+fn print_above_prompt(lines: &[String]) {
+    // the terminal is in raw mode while the line is edited, so each line ends with a carriage return
+    let mut output = String::from("\r\n");
+    for line in lines {
+        output.push_str(line);
+        output.push_str("\r\n");
+    }
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    let _ = handle.write_all(output.as_bytes()).and_then(|_| handle.flush());
+}
+
+impl Completer for InputCompleter {
+    fn complete(&self, line: &str, pos: usize) -> Result<(usize, Vec<String>)> {
+        let (start, candidates) = match find_path_argument(&line[..pos]) {
+            Some(start) => (start, complete_path_argument(&line[start..pos])),
+            None => {
+                let start = find_name_start(&line[..pos]);
+                (start, complete_name(&line[..pos], start, &self.symbols))
+            }
+        };
+
+        if candidates.len() > 1 {
+            self.show_menu(&line[..pos], &candidates);
+        }
+        else if candidates.is_empty() {
+            if let Some(signature) = find_signature(&line[..pos], &self.signatures) {
+                print_above_prompt(&[signature.dimmed().to_string()]);
+            }
+        }
+        Ok((start, candidates))
+    }
+}
+
+/// Returns the width of the terminal given by the COLUMNS environment variable or DEFAULT_TERMINAL_WIDTH.
+fn get_terminal_width() -> usize {
+    env::var("COLUMNS").ok().and_then(|c| c.parse::<usize>().ok()).filter(|&w| w > 0).unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+/// Returns the rows of the specified page of the completion menu for the specified candidates.
+/// The candidates are arranged in columns (top to bottom, then left to right) that fit into the specified width.
+/// A page has at most the specified number of rows. If there are several pages, a footer with the page number is
+/// appended and the page number wraps around after the last page.
+pub fn format_completion_menu(candidates: &[String], width: usize, max_rows: usize, page: usize) -> Vec<String> {
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    let column_width = candidates.iter().map(|c| c.chars().count()).max().unwrap_or(0) + MENU_COLUMN_GAP;
+    let columns = (width / column_width).max(1);
+    let page_size = columns * max_rows.max(1);
+    let pages = (candidates.len() + page_size - 1) / page_size;
+    let page = page % pages;
+
+    let items = &candidates[page * page_size..candidates.len().min((page + 1) * page_size)];
+    let rows = (items.len() + columns - 1) / columns;
+    let mut result = Vec::new();
+    for r in 0..rows {
+        let mut row = String::new();
+        for item in items.iter().skip(r).step_by(rows) {
+            row.push_str(&format!("{0:1$}", item, column_width));
+        }
+        result.push(row.trim_end().to_string());
+    }
+
+    if pages > 1 {
+        result.push(format!("-- page {0}/{1} (tab for more) --", page + 1, pages));
+    }
+    result
+}
+
+/// Returns the byte offset of the (partial) name at the end of the specified line.
+pub fn find_name_start(line: &str) -> usize {
+    match line.char_indices().rev().find(|&(_, c)| !(c.is_alphanumeric() || c == '_')) {
+        Some((idx, c)) => idx + c.len_utf8(),
+        None => 0
+    }
+}
+
+/// Returns the sorted completion candidates for the (partial) name that starts at the specified byte offset of the line.
+/// The candidates are the matching symbols and, at the beginning of the line, the matching commands.
+pub fn complete_name(line: &str, start: usize, symbols: &[String]) -> Vec<String> {
+    let name = &line[start..];
+    if name.is_empty() {
+        return Vec::new();
+    }
+
+    let mut candidates : Vec<String> = symbols.iter().filter(|s| s.starts_with(name)).cloned().collect();
+    if line[..start].trim().is_empty() {
+        candidates.extend(COMMANDS.iter().filter(|c| c.starts_with(name)).map(|c| c.to_string()));
+    }
+
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+/// Returns the name of the innermost function call that is not closed at the end of the specified line (e.g. "pow" for
+/// "2*pow(3, "). Returns None if the line ends outside of the arguments of a function call.
+pub fn find_open_call(line: &str) -> Option<&str> {
+    let mut depth = 0;
+    for (idx, c) in line.char_indices().rev() {
+        match c {
+            ')' => depth += 1,
+            '(' if depth == 0 => {
+                let start = find_name_start(&line[..idx]);
+                return if start < idx { Some(&line[start..idx]) } else { None };
+            },
+            '(' => depth -= 1,
+            _ => ()
+        }
+    }
+    None
+}
+
+/// Returns the signature of the innermost function call that is not closed at the end of the specified line.
+/// Returns None if there is no such call or if the signature of the function is unknown.
+pub fn find_signature(line: &str, signatures: &[String]) -> Option<String> {
+    find_open_call(line).and_then(|name| {
+        let prefix = format!("{0}(", name);
+        signatures.iter().find(|s| s.starts_with(&prefix)).cloned()
+    })
+}
+
+/// Returns the byte offset of the path argument if the specified line is a "save" or "load" command
+/// followed by at least one whitespace. Returns None otherwise.
+pub fn find_path_argument(line: &str) -> Option<usize> {
+    let cmd_start = line.len() - line.trim_start().len();
+    let cmd_line = &line[cmd_start..];
+
+    for cmd in PATH_COMMANDS.iter() {
+        if cmd_line.starts_with(cmd) {
+            let rest = &cmd_line[cmd.len()..];
+            let arg = rest.trim_start();
+            if arg.len() < rest.len() {
+                return Some(line.len() - arg.len());
+            }
+        }
+    }
+    None
+}
+
+/// Returns the sorted completion candidates for the specified (partial) path argument.
+/// The candidates replace the whole argument. A candidate is put into double quotes if the argument starts
+/// with a quote or if the candidate contains whitespace. The closing quote is omitted for directories so that
+/// the completion can be continued inside the directory.
+pub fn complete_path_argument(arg: &str) -> Vec<String> {
+    let quoted = arg.starts_with('"');
+    let path = if quoted { &arg[1..] } else { arg };
+
+    // split the path into the directory part (including the trailing separator) and the partial file name
+    let (dir_name, file_name) = match path.rfind(is_separator) {
+        Some(idx) => path.split_at(idx + 1),
+        None => ("", path)
+    };
+    let dir_path = if dir_name.is_empty() { Path::new(".") } else { Path::new(dir_name) };
+
+    let mut candidates = Vec::new();
+    let entries = match fs::read_dir(dir_path) {
+        Ok(entries) => entries,
+        Err(_) => return candidates
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name();
+        let name = match name.to_str() {
+            Some(n) => n,
+            None => continue
+        };
+        if !name.starts_with(file_name) {
+            continue;
+        }
+
+        let is_dir = entry.path().is_dir();
+        let mut candidate = format!("{0}{1}", dir_name, name);
+        if is_dir {
+            candidate.push(path::MAIN_SEPARATOR);
+        }
+
+        if quoted || candidate.chars().any(char::is_whitespace) {
+            candidate = if is_dir { format!("\"{0}", candidate) } else { format!("\"{0}\"", candidate) };
+        }
+        candidates.push(candidate);
+    }
+
+    candidates.sort();
+    candidates
+}
+
+/// Checks whether the specified character separates path components.
+fn is_separator(c: char) -> bool {
+    c == path::MAIN_SEPARATOR || c == '/'
+}
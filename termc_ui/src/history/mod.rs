@@ -0,0 +1,75 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use rustyline::history::History;
+
+/// Defines the settings of the user input history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistorySettings {
+    /// The soft limit: the maximum number of entries that are kept in the history during a session.
+    pub max_entries: usize,
+    /// The hard limit: the maximum number of entries that are kept in the history file.
+    pub max_file_entries: usize,
+    /// If true, duplicate entries are ignored. Consecutive duplicates are ignored during the session,
+    /// all older duplicates of an entry are removed when the history file is saved.
+    pub ignore_dups: bool,
+    /// If true, entries that start with a whitespace are ignored.
+    pub ignore_space: bool,
+    /// If true, the entries are normalized before they are added to the history (e.g. "1+1" is added as "1 + 1"),
+    /// so that inputs that only differ in whitespace or redundant parentheses are treated as duplicates.
+    pub normalize: bool
+}
+
+impl Default for HistorySettings {
+    fn default() -> HistorySettings {
+        HistorySettings {max_entries: 250, max_file_entries: 250, ignore_dups: true, ignore_space: true, normalize: false}
+    }
+}
+
+impl HistorySettings {
+
+    /// Prunes the specified history entries according to the settings. Returns the (at most max_file_entries) most recent
+    /// entries in their original order. If ignore_dups is set, only the most recent occurrence of an entry is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::HistorySettings;
+    ///
+    /// let settings = HistorySettings {max_file_entries: 2, .. HistorySettings::default()};
+    /// let entries = vec!["1+1".to_string(), "sin(2)".to_string(), "1+1".to_string()];
+    /// assert!(settings.prune(&entries) == vec!["sin(2)".to_string(), "1+1".to_string()]);
+    /// ```
+    pub fn prune(&self, entries: &[String]) -> Vec<String> {
+        let mut pruned : Vec<String> = Vec::new();
+
+        // iterate from the most recent entry backwards, so that the most recent occurrence of a duplicate is kept
+        for entry in entries.iter().rev() {
+            if pruned.len() >= self.max_file_entries {
+                break;
+            }
+            if self.ignore_space && entry.starts_with(char::is_whitespace) {
+                continue;
+            }
+            if self.ignore_dups && pruned.contains(entry) {
+                continue;
+            }
+            pruned.push(entry.clone());
+        }
+
+        pruned.reverse();
+        pruned
+    }
+}
+
+/// Saves the specified history to the specified file. The saved entries are pruned according to the specified settings.
+pub fn save_history(history: &History, settings: &HistorySettings, path: &Path) -> io::Result<()> {
+    let entries : Vec<String> = (0..history.len()).filter_map(|i| history.get(i).cloned()).collect();
+
+    let mut wtr = BufWriter::new(File::create(path)?);
+    for entry in settings.prune(&entries) {
+        wtr.write_all(entry.as_bytes())?;
+        wtr.write_all(b"\n")?;
+    }
+    Ok(())
+}
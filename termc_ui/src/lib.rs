@@ -3,15 +3,27 @@ extern crate rustyline;
 extern crate app_dirs;
 extern crate colored;
 
+mod completion;
+mod history;
+mod sink;
+#[cfg(test)]
+mod test;
+
+use std::cell::RefCell;
 use std::error::Error;
 use std::fmt;
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::path::PathBuf;
 use app_dirs::*;
 use colored::*;
 use rustyline::Editor;
-use rustyline::completion::FilenameCompleter;
 use rustyline::error::ReadlineError;
-use termc_model::math_result::FormatIEEE754;
+use termc_model::math_context::MathContext;
+use termc_model::math_result::{FormatIEEE754, FormatFraction, FormatRadix, FormatPolar};
+use completion::InputCompleter;
+
+pub use history::HistorySettings;
+pub use sink::{OutputKind, OutputSink, StdoutSink, BufferSink, CallbackSink};
 
 /// Defines the prompt.
 static PROMPT : &'static str = ">>> ";
@@ -19,8 +31,11 @@ static PROMPT : &'static str = ">>> ";
 /// Defines the answer prefix
 static ANS_PREFIX : &'static str = "ans = ";
 
-/// Defines the maximum number of entries in the command history file.
-static MAX_HISTORY_SIZE : usize = 250;
+/// Defines the maximum denominator of the fraction format if no maximum denominator is specified.
+pub const DEFAULT_MAX_DENOMINATOR : u64 = 1000;
+
+/// Defines the radix of the base format if no radix is specified.
+pub const DEFAULT_RADIX : u32 = 36;
 
 /// Information about the application.
 static APP_INFO : AppInfo = AppInfo{name: "termc", author: "Jonas Kantic"};
@@ -40,43 +55,84 @@ pub enum FormatType {
     IEEE754,
     /// Scientific exponential representation.
     Exp,
+    /// Fraction representation (maximum denominator). Numbers that are not close to such a fraction are shown as decimal numbers.
+    Frac(u64),
+    /// Representation in an arbitrary radix from 2 to 36 (radix), e.g. "0r36:z1".
+    Base(u32),
+    /// Polar representation by the modulus and the argument in radians, e.g. "2*exp(1.5707963267948966i)".
+    Polar,
     /// Undefined representation.
     Undefined
 }
 
+/// Defines the names of the format types as they are used in the format command and their aliases.
+static FORMAT_NAMES : [(&'static str, &'static [&'static str]); 9] = [
+    ("dec", &["decimal", "b10"]),
+    ("bin", &["binary", "b2"]),
+    ("oct", &["octal", "b8"]),
+    ("hex", &["hexadecimal", "b16"]),
+    ("exp", &["sci", "scientific"]),
+    ("ieee754", &["ieee"]),
+    ("frac", &["fraction"]),
+    ("base", &["radix"]),
+    ("polar", &[])
+];
+
+/// Returns the names of the format types as they are used in the format command (without aliases).
+pub fn get_format_names() -> Vec<&'static str> {
+    FORMAT_NAMES.iter().map(|&(name, _)| name).collect()
+}
+
 impl<'a> From<&'a str> for FormatType {
+    /// Parses the name of a format type or one of its aliases (e.g. "hex", "hexadecimal" or "b16") regardless of the case.
+    /// Unknown names are parsed as FormatType::Undefined.
     fn from(s: &'a str) -> FormatType {
-        if s == "bin" {
-            FormatType::Bin
-        }
-        else if s == "oct" {
-            FormatType::Oct
-        }
-        else if s == "hex" {
-            FormatType::Hex
-        }
-        else if s == "ieee754" {
-            FormatType::IEEE754
-        }
-        else if s == "exp" {
-            FormatType::Exp
-        }
-        else if s == "dec" {
-            FormatType::Dec
-        }
-        else {
-            FormatType::Undefined
+        let s = s.to_lowercase();
+        let name = FORMAT_NAMES.iter().find(|&&(name, aliases)| name == s || aliases.contains(&s.as_str())).map(|&(name, _)| name);
+        match name {
+            Some("dec") => FormatType::Dec,
+            Some("bin") => FormatType::Bin,
+            Some("oct") => FormatType::Oct,
+            Some("hex") => FormatType::Hex,
+            Some("exp") => FormatType::Exp,
+            Some("ieee754") => FormatType::IEEE754,
+            Some("frac") => FormatType::Frac(DEFAULT_MAX_DENOMINATOR),
+            Some("base") => FormatType::Base(DEFAULT_RADIX),
+            Some("polar") => FormatType::Polar,
+            _ => FormatType::Undefined
         }
     }
 }
 
+impl fmt::Display for FormatType {
+    /// Writes the name of the format type as it is used in the format command (e.g. "hex").
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            FormatType::Dec => "dec",
+            FormatType::Oct => "oct",
+            FormatType::Hex => "hex",
+            FormatType::Bin => "bin",
+            FormatType::IEEE754 => "ieee754",
+            FormatType::Exp => "exp",
+            FormatType::Frac(max_denominator) => return write!(f, "frac {0}", max_denominator),
+            FormatType::Base(radix) => return write!(f, "base {0}", radix),
+            FormatType::Polar => "polar",
+            FormatType::Undefined => "undefined"
+        };
+        write!(f, "{0}", name)
+    }
+}
+
 // The mode of the terminal ui.
 #[derive(PartialEq)]
 pub enum TerminalMode {
     /// In Interactive mode, readline will be used.
     Interactive,
     /// In Call mode, the results will be printed as a list (separated with ';').
-    Call
+    Call,
+    /// In Batch mode, the user inputs are read line by line from stdin (e.g. from a pipe) and each result is printed
+    /// on a separate line.
+    Batch
 }
 
 #[macro_export]
@@ -92,6 +148,9 @@ macro_rules! format_result {
             FormatType::Oct => format!("{0:#o}", $res),
             FormatType::Exp => format!("{0:E}", $res),
             FormatType::IEEE754 => format!("{0}", $res.ieee754_fmt()),
+            FormatType::Frac(max_denominator) => $res.fraction_fmt(max_denominator),
+            FormatType::Base(radix) => $res.radix_fmt(radix),
+            FormatType::Polar => $res.polar_fmt(),
         }
     }};
     ($typ:expr, $res:ident, $ans_prefix:ident) => {{
@@ -105,14 +164,68 @@ macro_rules! format_result {
             FormatType::Hex => format!("{0}{1:#x}", $ans_prefix, $res),
             FormatType::Oct => format!("{0}{1:#o}", $ans_prefix, $res),
             FormatType::Exp => format!("{0}{1:E}", $ans_prefix, $res),
-            FormatType::IEEE754 => format!("{0}{1}", $ans_prefix, $res.ieee754_fmt())
+            FormatType::IEEE754 => format!("{0}{1}", $ans_prefix, $res.ieee754_fmt()),
+            FormatType::Frac(max_denominator) => format!("{0}{1}", $ans_prefix, $res.fraction_fmt(max_denominator)),
+            FormatType::Base(radix) => format!("{0}{1}", $ans_prefix, $res.radix_fmt(radix)),
+            FormatType::Polar => format!("{0}{1}", $ans_prefix, $res.polar_fmt())
         }
     }}
 }
 
-/// Prints the specified error.
-fn print_error<T: Error>(err: T) {
-        println!("{0}\n", err.to_string().red());
+/// Inserts the specified separator into the integer parts of the numbers of the specified output in the specified radix
+/// (e.g. "1_234_567.89" or "0xff_ffff"). The digits of decimal and octal numbers are grouped by three, all other digits
+/// by four. The prefixes, the fractional parts and the exponents of the numbers are not grouped.
+pub fn group_digits(s: &str, separator: char, radix: u32) -> String {
+    let size = if radix == 10 || radix == 8 { 3 } else { 4 };
+    let chars : Vec<char> = s.chars().collect();
+    let mut result = String::with_capacity(s.len());
+
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_digit(radix) {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i].is_digit(radix) {
+            i += 1;
+        }
+        let run = &chars[start..i];
+
+        // the prefix letters of radixes above 10 are digits themselves, so only the digits after the prefix are grouped
+        let is_integer_part = match if start > 0 { Some(chars[start - 1]) } else { None } {
+            Some('.') => false,
+            Some('E') | Some('e') if radix == 10 => false,
+            Some(c) => radix <= 10 || c == 'x' || c == ':',
+            None => radix <= 10
+        };
+        // the imaginary unit is a digit in radixes above 18, so the grouping of the last number would be ambiguous
+        let is_ambiguous = radix > 18 && i == chars.len() && run.last() == Some(&'i');
+
+        for (k, c) in run.iter().enumerate() {
+            if k > 0 && is_integer_part && !is_ambiguous && (run.len() - k) % size == 0 {
+                result.push(separator);
+            }
+            result.push(*c);
+        }
+    }
+    result
+}
+
+/// Normalizes text that has been read from a file or from stdin (e.g. a script created on Windows): a leading byte order
+/// mark is removed and the line endings "\r\n" and "\r" are replaced by "\n".
+pub fn normalize_text(s: &str) -> String {
+    s.trim_start_matches('\u{feff}').replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Returns the output of an evaluation without a result in the specified terminal mode.
+fn get_empty_result_output(mode: &TerminalMode) -> &'static str {
+    match *mode {
+        TerminalMode::Interactive => "\n",
+        TerminalMode::Call | TerminalMode::Batch => ""
+    }
 }
 
 /// Prints the specified error message.
@@ -123,8 +236,18 @@ fn print_error_str(err: String) {
 /// Defines a handle for the terminal and provides functionalities for reading user input and writing results and error messages.
 pub struct TerminalUI {
     mode: TerminalMode,
-    editor: Option<Editor<FilenameCompleter>>,
-    format_type: FormatType
+    editor: Option<Editor<InputCompleter>>,
+    /// The input of the batch mode (stdin by default).
+    input: Box<BufRead>,
+    /// The destination of the results, errors and messages (stdout and stderr by default).
+    sink: RefCell<Box<OutputSink>>,
+    history_settings: HistorySettings,
+    format_type: FormatType,
+    numbering: bool,
+    input_number: usize,
+    dual_display: bool,
+    /// The separator of the digit groups in the decimal and radix formats (None if the digits are not grouped).
+    digit_separator: Option<char>
 }
 
 impl TerminalUI {
@@ -138,8 +261,27 @@ impl TerminalUI {
     /// let tui = TerminalUI::new(TerminalMode::Interactive);
     /// ```
     pub fn new(mode: TerminalMode) -> Self {
-        match mode {
-            TerminalMode::Call => TerminalUI {mode: mode, editor: None, format_type: FormatType::Dec},
+        TerminalUI::with_history_settings(mode, HistorySettings::default())
+    }
+
+    /// Creates a new TerminalUI instance that uses the specified history settings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode, HistorySettings};
+    ///
+    /// let settings = HistorySettings {max_entries: 100, ignore_dups: false, .. HistorySettings::default()};
+    /// let tui = TerminalUI::with_history_settings(TerminalMode::Interactive, settings);
+    /// ```
+    pub fn with_history_settings(mode: TerminalMode, history_settings: HistorySettings) -> Self {
+        // the output is not colored if it is redirected (e.g. to a file), so that it only contains plain text
+        if !io::stdout().is_terminal() {
+            colored::control::set_override(false);
+        }
+
+        let editor = match mode {
+            TerminalMode::Call | TerminalMode::Batch => None,
 
             TerminalMode::Interactive => {
 
@@ -154,12 +296,12 @@ impl TerminalUI {
 
                 // create readline editor and configure history parameters
                 let mut editor = Editor::new();
-                editor = editor.history_ignore_dups(true)
-                .history_ignore_space(true);
-                editor.set_history_max_len(MAX_HISTORY_SIZE);
+                editor = editor.history_ignore_dups(history_settings.ignore_dups)
+                .history_ignore_space(history_settings.ignore_space);
+                editor.set_history_max_len(history_settings.max_entries);
                 
                 // set the user input auto-completer
-                let completer = FilenameCompleter::new();
+                let completer = InputCompleter::new();
                 editor.set_completer(Some(completer));
 
                 // load the history file if it exists and can be accessed
@@ -177,13 +319,82 @@ impl TerminalUI {
                     Err(e) => print_error_str(format!("Error: Could not load command history ({0}).", e))
                 }
 
-                TerminalUI {mode: mode, editor: Some(editor), format_type: FormatType::Dec}
+                Some(editor)
             }
-        }
+        };
+
+        TerminalUI {mode: mode, editor: editor, input: Box::new(io::BufReader::new(io::stdin())), 
+                    sink: RefCell::new(Box::new(StdoutSink::new())), history_settings: history_settings, format_type: FormatType::Dec,
+                    numbering: false, input_number: 1, dual_display: false, digit_separator: None}
     }
 
-    /// Retrieves the user input. This method should be used only in interactive mode, as otherwise the user will not be able to enter anything.
-    /// Therefore, this method returns an empty String when it is called in call mode.
+    /// Creates a new TerminalUI instance that reads the user inputs from the specified input and writes to the specified outputs
+    /// instead of stdin, stdout and stderr (e.g. to run a session with scripted inputs). The input is only used in batch mode,
+    /// as the interactive mode reads the user inputs with readline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{self, Cursor};
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let mut tui = TerminalUI::with_io(TerminalMode::Batch, Box::new(Cursor::new("1+2\n")), Box::new(io::sink()), Box::new(io::sink()));
+    /// assert!(tui.get_user_input() == "1+2\n");
+    /// assert!(tui.get_user_input() == "exit");
+    /// ```
+    pub fn with_io(mode: TerminalMode, input: Box<BufRead>, output: Box<Write>, error_output: Box<Write>) -> Self {
+        let mut tui = TerminalUI::new(mode);
+        tui.input = input;
+        tui.sink = RefCell::new(Box::new(StdoutSink::with_writers(output, error_output)));
+        tui
+    }
+
+    /// Creates a new TerminalUI instance that passes the results, errors, warnings and acknowledges to the specified sink
+    /// instead of printing them (e.g. to embed the calculator in a GUI). The texts are passed without colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use termc_ui::{TerminalUI, TerminalMode, BufferSink, OutputKind};
+    ///
+    /// let sink = BufferSink::new();
+    /// let tui = TerminalUI::with_sink(TerminalMode::Batch, Box::new(Cursor::new("")), Box::new(sink.clone()));
+    /// tui.print_warning("Warning: Oh dear!");
+    /// assert!(sink.get_entries() == vec![(OutputKind::Warning, String::from("Warning: Oh dear!\n"))]);
+    /// ```
+    pub fn with_sink(mode: TerminalMode, input: Box<BufRead>, sink: Box<OutputSink>) -> Self {
+        let mut tui = TerminalUI::new(mode);
+        tui.input = input;
+        tui.set_output_sink(sink);
+        tui
+    }
+
+    /// Sets the sink that receives the results, errors, warnings and acknowledges (see TerminalUI::with_sink).
+    /// The texts are passed without colors from now on, so that they can be displayed by the embedding application.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode, CallbackSink, OutputKind};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Call);
+    /// tui.set_output_sink(Box::new(CallbackSink::new(Box::new(|kind, text| {
+    ///     assert!(kind == OutputKind::Message && text == "Hello World!\n");
+    /// }))));
+    /// tui.print("Hello World!\n");
+    /// ```
+    pub fn set_output_sink(&mut self, sink: Box<OutputSink>) {
+        colored::control::set_override(false);
+        self.sink = RefCell::new(sink);
+    }
+
+
+
+    /// Retrieves the user input. This method should be used only in interactive mode or batch mode, as otherwise the user will
+    /// not be able to enter anything. Therefore, this method returns an empty String when it is called in call mode.
+    /// In batch mode, the next line of stdin is returned (the exit command at the end of the input). The line is normalized
+    /// (see normalize_text), so that the inputs of files created on Windows are accepted.
     ///
     /// # Examples
     ///
@@ -200,14 +411,31 @@ impl TerminalUI {
             // return an empty string in call mode
             TerminalMode::Call => String::from(""),
 
+            // read the next line without a prompt
+            TerminalMode::Batch => {
+                let mut line = String::new();
+                match self.input.read_line(&mut line) {
+                    Ok(n) if n > 0 => normalize_text(&line),
+                    _ => String::from("exit")
+                }
+            },
+
             // get the user input in ineractive mode by showing a prompt
             // save the user input in the history so that it can be saved in the history file when the program exits
             TerminalMode::Interactive => {
-                let input = self.editor.as_mut().unwrap().readline(PROMPT);
+                let prompt = self.get_prompt();
+                let input = self.editor.as_mut().unwrap().readline(&prompt);
 
                 match input {
                     Ok(line) => {
-                        self.editor.as_mut().unwrap().add_history_entry(line.as_ref());
+                        // inputs that start with a whitespace are not normalized, so that they are still ignored
+                        let entry = if self.history_settings.normalize && !line.starts_with(char::is_whitespace) {
+                            termc_model::normalize_input(&line, &MathContext::new())
+                        }
+                        else {
+                            line.clone()
+                        };
+                        self.editor.as_mut().unwrap().add_history_entry(entry.as_ref());
                         line
                     },
 
@@ -224,7 +452,36 @@ impl TerminalUI {
         }
     }
 
-    /// Prints the specified error in red color on the screen.
+    /// Asks the user the specified yes/no question and returns true if the user confirms it with "y" or "yes".
+    /// In call mode and batch mode, the user can not be asked, so this method returns false.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Call);
+    /// assert!(!tui.confirm("Delete all definitions?"));
+    /// ```
+    pub fn confirm(&mut self, question: &str) -> bool {
+
+        match self.mode {
+            TerminalMode::Call | TerminalMode::Batch => false,
+
+            // the answer is not added to the history
+            TerminalMode::Interactive => {
+                match self.editor.as_mut().unwrap().readline(&format!("{0} [y/N] ", question)) {
+                    Ok(answer) => {
+                        let answer = answer.trim().to_lowercase();
+                        answer == "y" || answer == "yes"
+                    },
+                    Err(_) => false
+                }
+            }
+        }
+    }
+
+    /// Prints the specified error in red color on the screen. In batch mode, the error is printed to stderr without color.
     /// NOTE: Coloring does not work in the CMD on Windows, but it works using PowerShell!
     ///
     /// # Examples
@@ -238,10 +495,31 @@ impl TerminalUI {
     /// tui.print_error(pseudo_error);
     /// ```
     pub fn print_error<T: Error>(&self, err: T) {
-        print_error(err);
+        match self.mode {
+            TerminalMode::Batch => self.write_error(OutputKind::Error, &format!("{0}\n", err)),
+            _ => self.write_output(OutputKind::Error, &format!("{0}\n\n", err.to_string().red()))
+        }
+    }
+
+    /// Prints the specified warning dimmed, so that it stands out less than the results and the errors.
+    /// In call mode and batch mode, the warning is printed to the error output, so that the output only contains the results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let tui = TerminalUI::new(TerminalMode::Call);
+    /// tui.print_warning("Warning: The parameter \"y\" of the function \"f\" is not used.");
+    /// ```
+    pub fn print_warning(&self, warning: &str) {
+        match self.mode {
+            TerminalMode::Interactive => self.write_output(OutputKind::Warning, &format!("{0}\n", warning.dimmed())),
+            _ => self.write_error(OutputKind::Warning, &format!("{0}\n", warning))
+        }
     }
 
-    /// Prints the specified result. The result is prefixed with ANS_PREFIX.
+    /// Prints the specified result. The result is prefixed with ANS_PREFIX (except in batch mode).
     /// NOTE: This method should be used only in interactive mode because of the ANS_PREFIX. In call mode, use TerminalUI::print_results.
     ///
     /// # Examples
@@ -263,9 +541,88 @@ impl TerminalUI {
     /// }
     /// ```
     pub fn print_result<T: fmt::Display + fmt::Binary + fmt::LowerHex + fmt::UpperHex + fmt::Octal
-                    + FormatIEEE754 + fmt::LowerExp + fmt::UpperExp>(&self, result: &T) {
+                    + FormatIEEE754 + FormatFraction + FormatRadix + FormatPolar + fmt::LowerExp + fmt::UpperExp>(&self, result: &T) {
+
+        match self.mode {
+            // print only the result, so that the output can be processed line by line
+            TerminalMode::Batch => self.write_output(OutputKind::Result, &format!("{0}{1}\n", self.format_result(result), self.get_decimal_suffix(result))),
+            _ => self.write_output(OutputKind::Result, &format!("{0}{1}{2}\n\n", ANS_PREFIX, self.format_result(result), self.get_decimal_suffix(result)))
+        }
+    }
+
+    /// Formats the specified result in the current format without the answer prefix (e.g. "0xff" in hexadecimal format).
+    /// The digits are grouped if the digit grouping is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate termc_ui;
+    /// extern crate termc_model;
+    ///
+    /// use termc_ui::{TerminalUI, TerminalMode, FormatType};
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// fn main() {
+    ///     let mut tui = TerminalUI::new(TerminalMode::Call);
+    ///     tui.set_format_type(FormatType::Hex);
+    ///     assert!(tui.format_result(&MathResult::from(255.0)) == "0xff");
+    /// }
+    /// ```
+    pub fn format_result<T: fmt::Display + fmt::Binary + fmt::LowerHex + fmt::UpperHex + fmt::Octal
+                     + FormatIEEE754 + FormatFraction + FormatRadix + FormatPolar + fmt::LowerExp + fmt::UpperExp>(&self, result: &T) -> String {
+        let output = format_result!(self.format_type, result);
+        match (self.digit_separator, self.get_grouping_radix()) {
+            (Some(separator), Some(radix)) => group_digits(&output, separator, radix),
+            _ => output
+        }
+    }
+
+    /// Prints the acknowledge of an evaluation without a result (e.g. an assignment).
+    /// In interactive mode, an empty line separates the input from the next prompt like after a result. In call mode and
+    /// batch mode, nothing is printed, so that the output only contains the results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let tui = TerminalUI::new(TerminalMode::Call);
+    /// tui.print_empty_result();
+    /// // Output will be: ""
+    /// ```
+    pub fn print_empty_result(&self) {
+        self.write_output(OutputKind::Ack, get_empty_result_output(&self.mode));
+    }
+
+    /// Prints the specified result of the evaluation with the specified number.
+    /// If the numbering is enabled, the result is prefixed with "Out[n] = ", otherwise it is printed like TerminalUI::print_result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate termc_ui;
+    /// extern crate termc_model;
+    ///
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// fn main() {
+    ///     let mut tui = TerminalUI::new(TerminalMode::Call);
+    ///     tui.set_numbering(true);
+    ///     tui.print_numbered_result(3, &MathResult::from(4.0));
+    ///     // Output will be: "Out[3] = 4"
+    /// }
+    /// ```
+    pub fn print_numbered_result<T: fmt::Display + fmt::Binary + fmt::LowerHex + fmt::UpperHex + fmt::Octal
+                    + FormatIEEE754 + FormatFraction + FormatRadix + FormatPolar + fmt::LowerExp + fmt::UpperExp>(&self, n: usize, result: &T) {
 
-        println!("{0}\n", &format_result!(self.format_type, result, ANS_PREFIX));
+        if self.numbering && self.mode != TerminalMode::Batch {
+            let out_prefix = format!("Out[{0}] = ", n);
+            self.write_output(OutputKind::Result, &format!("{0}{1}{2}\n\n", out_prefix, self.format_result(result), self.get_decimal_suffix(result)));
+        }
+        else {
+            self.print_result(result);
+        }
     }
 
     /// Prints the specified results seperated with ';'.
@@ -291,25 +648,15 @@ impl TerminalUI {
     /// }
     /// ```
     pub fn print_results<T: fmt::Display + fmt::Binary + fmt::LowerHex + fmt::UpperHex + fmt::Octal
-                     + FormatIEEE754 + fmt::LowerExp + fmt::UpperExp>(&self, results: &Vec<T>) {
+                     + FormatIEEE754 + FormatFraction + FormatRadix + FormatPolar + fmt::LowerExp + fmt::UpperExp>(&self, results: &Vec<T>) {
 
         match self.mode {
             TerminalMode::Call => {
-                let mut conc = String::from("");
-                for r in results {
-                    conc.push_str(&format_result!(self.format_type, r));
-                    conc.push(';');
-                }
-
-                // pop the last ';'
-                if conc.len() > 0 {
-                    conc.pop();
-                }
-
-                println!("{0}", conc);
+                let formatted : Vec<String> = results.iter().map(|r| self.format_result(r)).collect();
+                self.print_formatted_results(&formatted);
             },
 
-            TerminalMode::Interactive => {
+            TerminalMode::Interactive | TerminalMode::Batch => {
                 // print each result seperately
                 for r in results {
                     self.print_result(r);
@@ -318,6 +665,79 @@ impl TerminalUI {
         }
     }
 
+    /// Prints the specified results, which are already formatted (see TerminalUI::format_result), seperated with ';'.
+    /// NOTE: This method should be used only in call mode, e.g. for results that were formatted with different formats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let tui = TerminalUI::new(TerminalMode::Call);
+    /// tui.print_formatted_results(&[String::from("12"), String::from("0xff")]);
+    /// // Output will be: "12;0xff"
+    /// ```
+    pub fn print_formatted_results(&self, results: &[String]) {
+        self.write_output(OutputKind::Result, &format!("{0}\n", results.join(";")));
+    }
+
+    /// Prints each of the specified results on a separate line, prefixed with the specified number (e.g. the number of the
+    /// input the result belongs to).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate termc_ui;
+    /// extern crate termc_model;
+    ///
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// fn main() {
+    ///     let results = vec![(1, MathResult::from(12.0)), (3, MathResult::from(3.5))];
+    ///
+    ///     let tui = TerminalUI::new(TerminalMode::Call);
+    ///     tui.print_indexed_results(&results);
+    ///     // Output will be: "1: 12" and "3: 3.5" on separate lines
+    /// }
+    /// ```
+    pub fn print_indexed_results<T: fmt::Display + fmt::Binary + fmt::LowerHex + fmt::UpperHex + fmt::Octal
+                     + FormatIEEE754 + FormatFraction + FormatRadix + FormatPolar + fmt::LowerExp + fmt::UpperExp>(&self, results: &[(usize, T)]) {
+        for &(n, ref r) in results {
+            self.write_output(OutputKind::Result, &format!("{0}: {1}\n", n, self.format_result(r)));
+        }
+    }
+
+    /// Prints the specified input followed by its result (e.g. "1+2 = 3"), optionally prefixed with the specified number.
+    /// Inputs without a result (e.g. assignments) are printed alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate termc_ui;
+    /// extern crate termc_model;
+    ///
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// fn main() {
+    ///     let tui = TerminalUI::new(TerminalMode::Call);
+    ///     tui.print_echo(None, "1+2", Some(&MathResult::from(3.0)));
+    ///     // Output will be: "1+2 = 3"
+    ///     tui.print_echo(Some(2), "x = 4", None::<&MathResult>);
+    ///     // Output will be: "2: x = 4"
+    /// }
+    /// ```
+    pub fn print_echo<T: fmt::Display + fmt::Binary + fmt::LowerHex + fmt::UpperHex + fmt::Octal
+                     + FormatIEEE754 + FormatFraction + FormatRadix + FormatPolar + fmt::LowerExp + fmt::UpperExp>(&self, n: Option<usize>,
+                                                                                                               input: &str, result: Option<&T>) {
+        let prefix = n.map_or(String::new(), |n| format!("{0}: ", n));
+        match result {
+            Some(r) => self.write_output(OutputKind::Result, &format!("{0}{1} = {2}{3}\n", prefix, input, self.format_result(r), self.get_decimal_suffix(r))),
+            None => self.write_output(OutputKind::Message, &format!("{0}{1}\n", prefix, input))
+        }
+    }
+
     /// Prints the specified string.
     ///
     /// # Examples
@@ -329,12 +749,13 @@ impl TerminalUI {
     /// tui.print("Hello World!\n");
     /// ```
     pub fn print(&self, s: &str) {
-        print!("{0}", s);
+        self.write_output(OutputKind::Message, s);
     }
 
     /// Prints an acknowledge in green color.
     /// The intend of this method is to inform the user that a command has been executed successfully.
     /// Therefore, this method should be called after successful execution of a command.
+    /// In call mode and batch mode, nothing is printed, so that the output only contains the results.
     /// NOTE: Coloring does not work in the CMD on Windows, but it works using PowerShell!
     ///
     /// # Examples
@@ -346,10 +767,13 @@ impl TerminalUI {
     /// tui.print_cmd_ack();
     /// ```
     pub fn print_cmd_ack(&self) {
-        println!("{0}\n", "Ok!".green());
+        if self.mode == TerminalMode::Interactive {
+            self.write_output(OutputKind::Ack, &format!("{0}\n\n", "Ok!".green()));
+        }
     }
 
     /// Saves the user input history to the user config directory.
+    /// The saved history is pruned according to the history settings of the terminal.
     /// NOTE: This method should only be used in interactive mode. In call mode, this method just returns Ok(()).
     ///
     /// # Examples
@@ -365,11 +789,30 @@ impl TerminalUI {
         if self.mode == TerminalMode::Interactive {
             let history_path_buf = get_history_file_path()?;
             let path = history_path_buf.as_path();
-            self.editor.as_mut().unwrap().save_history(path).ok();
+            let history = self.editor.as_mut().unwrap().get_history();
+            history::save_history(history, &self.history_settings, path).ok();
         }
         Ok(())
     }
 
+    /// Returns the entries of the user input history (the oldest entry first).
+    /// Returns None if the terminal has no history (in call mode and batch mode).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Call);
+    /// assert!(tui.get_history_entries().is_none());
+    /// ```
+    pub fn get_history_entries(&mut self) -> Option<Vec<String>> {
+        self.editor.as_mut().map(|editor| {
+            let history = editor.get_history();
+            (0..history.len()).filter_map(|i| history.get(i).cloned()).collect()
+        })
+    }
+
     /// Sets the format type with which all further results are formatted.
     ///
     /// # Examples
@@ -395,10 +838,263 @@ impl TerminalUI {
     pub fn set_format_type(&mut self, ft: FormatType) {
         self.format_type = ft;
     }
+
+    /// Gets the format type of the numbers that are printed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode, FormatType};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Call);
+    /// tui.set_format_type(FormatType::Hex);
+    /// assert!(tui.get_format_type().to_string() == "hex");
+    /// ```
+    pub fn get_format_type(&self) -> FormatType {
+        self.format_type.clone()
+    }
+
+    /// Enables or disables the numbering of the evaluations ("In[n]: " prompt and "Out[n] = " result prefix).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Interactive);
+    /// tui.set_numbering(true);
+    /// tui.set_input_number(4);
+    /// // the next prompt will be "In[4]: "
+    /// ```
+    pub fn set_numbering(&mut self, numbering: bool) {
+        self.numbering = numbering;
+    }
+
+    /// Enables or disables the dual display. If the dual display is enabled, results in non-decimal formats are followed
+    /// by their decimal value in parentheses (e.g. "ans = 0xff (255)").
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate termc_ui;
+    /// extern crate termc_model;
+    ///
+    /// use termc_ui::{TerminalUI, TerminalMode, FormatType};
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// fn main() {
+    ///     let mut tui = TerminalUI::new(TerminalMode::Call);
+    ///     tui.set_format_type(FormatType::Hex);
+    ///     tui.set_dual_display(true);
+    ///     tui.print_result(&MathResult::from(255.0));
+    ///     // Output will be: "ans = 0xff (255)"
+    /// }
+    /// ```
+    pub fn set_dual_display(&mut self, dual_display: bool) {
+        self.dual_display = dual_display;
+    }
+
+    /// Sets the separator of the digit groups in the decimal and radix formats (e.g. '_' for "1_234_567.89").
+    /// The digits are not grouped if the separator is None.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate termc_ui;
+    /// extern crate termc_model;
+    ///
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    /// use termc_model::math_result::MathResult;
+    ///
+    /// fn main() {
+    ///     let mut tui = TerminalUI::new(TerminalMode::Call);
+    ///     tui.set_digit_grouping(Some(','));
+    ///     assert!(tui.format_result(&MathResult::from(1234567.5)) == "1,234,567.5");
+    /// }
+    /// ```
+    pub fn set_digit_grouping(&mut self, separator: Option<char>) {
+        self.digit_separator = separator;
+    }
+
+    /// Enables or disables the normalization of the user inputs before they are added to the history
+    /// (e.g. "1+1" is added as "1 + 1").
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Interactive);
+    /// tui.set_history_normalization(true);
+    /// ```
+    pub fn set_history_normalization(&mut self, normalize: bool) {
+        self.history_settings.normalize = normalize;
+    }
+
+    /// Returns the settings of the user input history.
+    pub fn get_history_settings(&self) -> HistorySettings {
+        self.history_settings.clone()
+    }
+
+    /// Sets the maximum number of entries that are kept in the history during a session (the soft limit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Interactive);
+    /// tui.set_history_size(1000);
+    /// assert!(tui.get_history_settings().max_entries == 1000);
+    /// ```
+    pub fn set_history_size(&mut self, n: usize) {
+        self.history_settings.max_entries = n;
+        self.configure_history();
+    }
+
+    /// Sets the maximum number of entries that are saved in the history file (the hard limit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Interactive);
+    /// tui.set_history_file_size(100);
+    /// assert!(tui.get_history_settings().max_file_entries == 100);
+    /// assert!(tui.get_history_settings().max_entries == 250);
+    /// ```
+    pub fn set_history_file_size(&mut self, n: usize) {
+        self.history_settings.max_file_entries = n;
+    }
+
+    /// Enables or disables ignoring duplicate history entries.
+    pub fn set_history_ignore_dups(&mut self, ignore_dups: bool) {
+        self.history_settings.ignore_dups = ignore_dups;
+        self.configure_history();
+    }
+
+    /// Enables or disables ignoring the user inputs that start with a whitespace in the history.
+    pub fn set_history_ignore_space(&mut self, ignore_space: bool) {
+        self.history_settings.ignore_space = ignore_space;
+        self.configure_history();
+    }
+
+    /// Applies the history settings to the readline editor of the interactive mode.
+    fn configure_history(&mut self) {
+        if let Some(editor) = self.editor.take() {
+            let mut editor = editor.history_ignore_dups(self.history_settings.ignore_dups)
+                .history_ignore_space(self.history_settings.ignore_space);
+            editor.set_history_max_len(self.history_settings.max_entries);
+            self.editor = Some(editor);
+        }
+    }
+
+    /// Sets the number of the next evaluation that is shown in the prompt if the numbering is enabled.
+    pub fn set_input_number(&mut self, n: usize) {
+        self.input_number = n;
+    }
+
+    /// Prints the specified input after the prompt, as if the user had typed it (e.g. when inputs are replayed).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let tui = TerminalUI::new(TerminalMode::Call);
+    /// tui.print_input("2 + 3");
+    /// // Output will be: ">>> 2 + 3"
+    /// ```
+    pub fn print_input(&self, input: &str) {
+        self.write_output(OutputKind::Message, &format!("{0}{1}\n", self.get_prompt(), input));
+    }
+
+    /// Sets the names of the functions and constants that are completed in interactive mode (e.g. after each evaluation)
+    /// and the call signatures of the functions that are shown as hints inside the arguments of a function call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use termc_ui::{TerminalUI, TerminalMode};
+    ///
+    /// let mut tui = TerminalUI::new(TerminalMode::Interactive);
+    /// tui.set_completion_symbols(vec![String::from("pi"), String::from("sin")], vec![String::from("sin(x)")]);
+    /// ```
+    pub fn set_completion_symbols(&mut self, symbols: Vec<String>, signatures: Vec<String>) {
+        if let Some(ref mut editor) = self.editor {
+            editor.set_completer(Some(InputCompleter::with_symbols(symbols, signatures)));
+        }
+    }
+
+    /// Writes the specified string of the specified kind to the output of the sink. Empty strings are not passed to the sink.
+    fn write_output(&self, kind: OutputKind, s: &str) {
+        if !s.is_empty() {
+            self.sink.borrow_mut().write_output(kind, s);
+        }
+    }
+
+    /// Writes the specified string of the specified kind to the error output of the sink.
+    fn write_error(&self, kind: OutputKind, s: &str) {
+        if !s.is_empty() {
+            self.sink.borrow_mut().write_error(kind, s);
+        }
+    }
+
+    /// Gets the decimal value of the specified result in parentheses if the dual display is enabled and the format is not decimal.
+    /// Otherwise, an empty string is returned.
+    fn get_decimal_suffix<T: fmt::Display>(&self, result: &T) -> String {
+        match self.format_type {
+            FormatType::Dec | FormatType::Undefined => String::new(),
+            _ if self.dual_display => match self.digit_separator {
+                Some(separator) => format!(" ({0})", group_digits(&result.to_string(), separator, 10)),
+                None => format!(" ({0})", result)
+            },
+            _ => String::new()
+        }
+    }
+
+    /// Gets the radix of the digits of the current format if the digits of the format can be grouped. Returns None otherwise.
+    fn get_grouping_radix(&self) -> Option<u32> {
+        match self.format_type {
+            FormatType::Dec | FormatType::Undefined => Some(10),
+            FormatType::Bin => Some(2),
+            FormatType::Oct => Some(8),
+            FormatType::Hex => Some(16),
+            FormatType::Base(radix) => Some(radix),
+            _ => None
+        }
+    }
+
+    /// Gets the prompt ("In[n]: " if the numbering is enabled).
+    fn get_prompt(&self) -> String {
+        if self.numbering {
+            format!("In[{0}]: ", self.input_number)
+        }
+        else {
+            PROMPT.to_string()
+        }
+    }
+}
+
+/// Gets the path of the user data directory of termc (e.g. "~/.local/share/termc" on linux).
+/// The directory is created if it does not exist.
+pub fn get_user_data_dir() -> Result<PathBuf, AppDirsError> {
+    app_root(AppDataType::UserData, &APP_INFO)
 }
 
 /// Gets the file path of the user input history file.
 fn get_history_file_path() -> Result<PathBuf, AppDirsError> {
+    get_config_file_path("history", "txt")
+}
+
+/// Gets the file path of the user settings file (e.g. "~/.config/termc/settings.json" on linux).
+pub fn get_settings_file_path() -> Result<PathBuf, AppDirsError> {
+    get_config_file_path("settings", "json")
+}
+
+/// Gets the path of the file with the specified name and extension in the user config directory.
+fn get_config_file_path(file_name: &str, extension: &str) -> Result<PathBuf, AppDirsError> {
 
     let config_sub_dir = "termc";
     let mut path_buf = match get_app_dir(AppDataType::UserConfig, &APP_INFO, config_sub_dir) {
@@ -406,8 +1102,8 @@ fn get_history_file_path() -> Result<PathBuf, AppDirsError> {
         Err(_) => app_dir(AppDataType::UserConfig, &APP_INFO, config_sub_dir)?
     };
 
-    path_buf.set_file_name("history");
-    path_buf.set_extension("txt");
+    path_buf.set_file_name(file_name);
+    path_buf.set_extension(extension);
 
     Ok(path_buf)
 }
@@ -0,0 +1,111 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// Defines the kinds of the texts that are printed by the terminal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputKind {
+    /// A result of an evaluation (e.g. "ans = 3").
+    Result,
+    /// An error message.
+    Error,
+    /// A warning (e.g. about an unused parameter).
+    Warning,
+    /// The acknowledge of a successful command or of an evaluation without a result.
+    Ack,
+    /// Any other text (e.g. the output of the help command or an echoed input).
+    Message
+}
+
+/// Defines the destination of the texts that are printed by the terminal (e.g. stdout or a GUI that embeds the calculator).
+/// The texts are passed as they would be printed on the terminal (including the trailing new lines), so that a sink that
+/// only concatenates them reproduces the terminal output.
+pub trait OutputSink {
+    /// Writes the specified text of the specified kind to the output.
+    fn write_output(&mut self, kind: OutputKind, text: &str);
+
+    /// Writes the specified text of the specified kind to the error output (e.g. the errors in batch mode).
+    /// By default, the text is written to the output.
+    fn write_error(&mut self, kind: OutputKind, text: &str) {
+        self.write_output(kind, text);
+    }
+}
+
+/// Writes the texts to stdout and stderr (or to the specified writers instead).
+pub struct StdoutSink {
+    output: Box<Write>,
+    error_output: Box<Write>
+}
+
+impl StdoutSink {
+    /// Creates a new StdoutSink that writes to stdout and stderr.
+    pub fn new() -> Self {
+        StdoutSink::with_writers(Box::new(io::stdout()), Box::new(io::stderr()))
+    }
+
+    /// Creates a new StdoutSink that writes to the specified output and error output instead of stdout and stderr.
+    pub fn with_writers(output: Box<Write>, error_output: Box<Write>) -> Self {
+        StdoutSink {output: output, error_output: error_output}
+    }
+}
+
+impl OutputSink for StdoutSink {
+    fn write_output(&mut self, _: OutputKind, text: &str) {
+        // nothing can be reported if writing to the output fails
+        self.output.write_all(text.as_bytes()).ok();
+        self.output.flush().ok();
+    }
+
+    fn write_error(&mut self, _: OutputKind, text: &str) {
+        self.error_output.write_all(text.as_bytes()).ok();
+        self.error_output.flush().ok();
+    }
+}
+
+/// Collects the texts with their kinds in memory. The clones of a BufferSink share the collected texts, so that a clone
+/// can be passed to the terminal and the texts can be read from the original.
+#[derive(Clone, Default)]
+pub struct BufferSink {
+    entries: Rc<RefCell<Vec<(OutputKind, String)>>>
+}
+
+impl BufferSink {
+    /// Creates a new empty BufferSink.
+    pub fn new() -> Self {
+        BufferSink::default()
+    }
+
+    /// Gets a copy of the collected texts with their kinds.
+    pub fn get_entries(&self) -> Vec<(OutputKind, String)> {
+        self.entries.borrow().clone()
+    }
+
+    /// Removes the collected texts and returns them with their kinds.
+    pub fn take_entries(&self) -> Vec<(OutputKind, String)> {
+        self.entries.borrow_mut().drain(..).collect()
+    }
+}
+
+impl OutputSink for BufferSink {
+    fn write_output(&mut self, kind: OutputKind, text: &str) {
+        self.entries.borrow_mut().push((kind, String::from(text)));
+    }
+}
+
+/// Passes the texts with their kinds to the specified callback.
+pub struct CallbackSink {
+    callback: Box<FnMut(OutputKind, &str)>
+}
+
+impl CallbackSink {
+    /// Creates a new CallbackSink that calls the specified callback for every text.
+    pub fn new(callback: Box<FnMut(OutputKind, &str)>) -> Self {
+        CallbackSink {callback: callback}
+    }
+}
+
+impl OutputSink for CallbackSink {
+    fn write_output(&mut self, kind: OutputKind, text: &str) {
+        (self.callback)(kind, text);
+    }
+}
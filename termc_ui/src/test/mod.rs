@@ -0,0 +1,174 @@
+use std::env;
+use std::fs;
+use std::cell::RefCell;
+use std::io::{self, Cursor};
+use std::rc::Rc;
+use std::path::MAIN_SEPARATOR;
+use completion::{find_path_argument, complete_path_argument, find_name_start, complete_name, format_completion_menu, find_open_call,
+                 find_signature};
+use history::HistorySettings;
+use termc_model::math_result::MathResult;
+use super::{TerminalUI, TerminalMode, FormatType, OutputKind, BufferSink, CallbackSink, get_empty_result_output, get_format_names,
+            group_digits, normalize_text};
+
+#[test]
+fn tst_find_path_argument() {
+    assert!(find_path_argument("save ") == Some(5));
+    assert!(find_path_argument("  load   \"My Wo") == Some(9));
+    assert!(find_path_argument("save") == None);
+    assert!(find_path_argument("saved ") == None);
+    assert!(find_path_argument("sin(3) ") == None);
+}
+
+#[test]
+fn tst_complete_name() {
+    let symbols : Vec<String> = vec!["sin", "sinh", "sqrt", "sigma", "pi"].iter().map(|s| s.to_string()).collect();
+
+    assert!(find_name_start("2*si") == 2);
+    assert!(find_name_start("sig") == 0);
+    assert!(find_name_start("f(x) = ") == 7);
+
+    // symbols are completed anywhere, commands only at the beginning of the line
+    assert!(complete_name("2*si", 2, &symbols) == vec!["sigma", "sin", "sinh"]);
+    assert!(complete_name("s", 0, &symbols) == vec!["save", "set", "sigma", "simplify", "sin", "sinh", "sqrt", "sympy"]);
+    assert!(complete_name("1 + s", 4, &symbols) == vec!["sigma", "sin", "sinh", "sqrt"]);
+    assert!(complete_name("f(x) = ", 7, &symbols).is_empty());
+}
+
+#[test]
+fn tst_format_completion_menu() {
+    let candidates : Vec<String> = vec!["sigma", "sin", "sinh", "sqrt", "sympy"].iter().map(|s| s.to_string()).collect();
+
+    // the candidates are arranged top to bottom, then left to right
+    assert!(format_completion_menu(&candidates, 20, 10, 0) == vec!["sigma  sqrt", "sin    sympy", "sinh"]);
+    assert!(format_completion_menu(&candidates, 5, 10, 0) == candidates);
+
+    // the pages wrap around after the last page
+    assert!(format_completion_menu(&candidates, 20, 1, 0) == vec!["sigma  sin", "-- page 1/3 (tab for more) --"]);
+    assert!(format_completion_menu(&candidates, 20, 1, 2) == vec!["sympy", "-- page 3/3 (tab for more) --"]);
+    assert!(format_completion_menu(&candidates, 20, 1, 3) == format_completion_menu(&candidates, 20, 1, 0));
+    assert!(format_completion_menu(&[], 20, 10, 0).is_empty());
+}
+
+#[test]
+fn tst_find_signature() {
+    let signatures : Vec<String> = vec!["pow(x, y)", "pi_sum(n)", "sin(x)"].iter().map(|s| s.to_string()).collect();
+
+    assert!(find_open_call("2*pow(3, ") == Some("pow"));
+    assert!(find_open_call("pow(sin(1), ") == Some("pow"));
+    assert!(find_open_call("pow(sin(") == Some("sin"));
+    assert!(find_open_call("pow(2, 3) + ") == None);
+    assert!(find_open_call("2*(1 + ") == None);
+
+    assert!(find_signature("1 + pow(", &signatures) == Some(String::from("pow(x, y)")));
+    assert!(find_signature("pi_sum(4) + sin(", &signatures) == Some(String::from("sin(x)")));
+    assert!(find_signature("cos(", &signatures) == None);
+}
+
+#[test]
+fn tst_complete_path_argument() {
+    let base = env::temp_dir().join("termc_tst_complete_path_argument");
+    let work = base.join("My Work");
+    fs::create_dir_all(&work).unwrap();
+    fs::File::create(work.join("ctx.json")).unwrap();
+    let base = format!("{0}{1}", base.to_str().unwrap(), MAIN_SEPARATOR);
+
+    // directories keep the quote open, files close it
+    let cands = complete_path_argument(&format!("\"{0}My Wo", base));
+    assert!(cands == vec![format!("\"{0}My Work{1}", base, MAIN_SEPARATOR)]);
+    let cands = complete_path_argument(&format!("\"{0}My Work{1}c", base, MAIN_SEPARATOR));
+    assert!(cands == vec![format!("\"{0}My Work{1}ctx.json\"", base, MAIN_SEPARATOR)]);
+
+    // unquoted arguments are quoted if the candidate contains whitespace
+    let cands = complete_path_argument(&format!("{0}My", base));
+    assert!(cands == vec![format!("\"{0}My Work{1}", base, MAIN_SEPARATOR)]);
+
+    // no candidates for non-existing directories
+    assert!(complete_path_argument(&format!("\"{0}None{1}x", base, MAIN_SEPARATOR)).is_empty());
+}
+
+#[test]
+fn tst_prune_history() {
+    let entries : Vec<String> = vec!["1+1", " x=2", "sin(2)", "1+1", "sin(2)", "cos(0)"].iter().map(|s| s.to_string()).collect();
+
+    let settings = HistorySettings::default();
+    assert!(settings.prune(&entries) == vec!["1+1", "sin(2)", "cos(0)"]);
+
+    // the hard limit keeps the most recent entries
+    let settings = HistorySettings {max_file_entries: 2, .. HistorySettings::default()};
+    assert!(settings.prune(&entries) == vec!["sin(2)", "cos(0)"]);
+
+    // keep duplicates and entries starting with a whitespace
+    let settings = HistorySettings {ignore_dups: false, ignore_space: false, .. HistorySettings::default()};
+    assert!(settings.prune(&entries) == entries);
+}
+
+#[test]
+fn tst_empty_result_output() {
+    // only the interactive mode separates the next prompt with an empty line
+    assert!(get_empty_result_output(&TerminalMode::Interactive) == "\n");
+    assert!(get_empty_result_output(&TerminalMode::Call) == "");
+    assert!(get_empty_result_output(&TerminalMode::Batch) == "");
+}
+
+#[test]
+fn tst_format_type_from() {
+    // the names, their aliases and any case are accepted
+    let cases = vec![("hex", "hex"), ("HEX", "hex"), ("hexadecimal", "hex"), ("b16", "hex"), ("Binary", "bin"), ("b2", "bin"),
+                     ("octal", "oct"), ("sci", "exp"), ("Scientific", "exp"), ("ieee", "ieee754"), ("decimal", "dec"),
+                     ("fraction", "frac 1000"), ("radix", "base 36")];
+    for (name, expected) in cases {
+        assert!(FormatType::from(name).to_string() == expected);
+    }
+    assert!(FormatType::from("b17").to_string() == "undefined");
+
+    // each name is parsed as its own format type
+    for name in get_format_names() {
+        assert!(FormatType::from(name).to_string().starts_with(name));
+    }
+}
+
+#[test]
+fn tst_group_digits() {
+    assert!(group_digits("1234567.891", '_', 10) == "1_234_567.891");
+    assert!(group_digits("-123456", ',', 10) == "-123,456");
+    assert!(group_digits("1234+56789i", ',', 10) == "1,234+56,789i");
+    assert!(group_digits("[1000, 100]", '_', 10) == "[1_000, 100]");
+    assert!(group_digits("1.2345678E12345", '_', 10) == "1.2345678E12345");
+
+    // the prefixes are not grouped
+    assert!(group_digits("0xffffff", '_', 16) == "0xff_ffff");
+    assert!(group_digits("0b101010", '_', 2) == "0b10_1010");
+    assert!(group_digits("0o12345", '_', 8) == "0o12_345");
+    assert!(group_digits("-0r36:zzzzz.zzzzz", '_', 36) == "-0r36:z_zzzz.zzzzz");
+    assert!(group_digits("0r20:12345+0r20:12345i", '_', 20) == "0r20:1_2345+0r20:12345i");
+}
+
+#[test]
+fn tst_normalize_text() {
+    assert!(normalize_text("\u{feff}x = 2\r\nx^2\r\n") == "x = 2\nx^2\n");
+    assert!(normalize_text("1\r2\n3") == "1\n2\n3");
+    assert!(normalize_text("sin(0)") == "sin(0)");
+}
+
+#[test]
+fn tst_output_sinks() {
+    let sink = BufferSink::new();
+    let tui = TerminalUI::with_sink(TerminalMode::Batch, Box::new(Cursor::new("")), Box::new(sink.clone()));
+    tui.print_result(&MathResult::from(3.0));
+    tui.print_error(io::Error::new(io::ErrorKind::Other, "Error: Oh dear!"));
+    tui.print_warning("Warning: Careful!");
+    tui.print_empty_result();
+    tui.print("Hello\n");
+    assert!(sink.take_entries() == vec![(OutputKind::Result, String::from("3\n")), (OutputKind::Error, String::from("Error: Oh dear!\n")),
+                                        (OutputKind::Warning, String::from("Warning: Careful!\n")), (OutputKind::Message, String::from("Hello\n"))]);
+    assert!(sink.get_entries().is_empty());
+
+    let kinds = Rc::new(RefCell::new(Vec::new()));
+    let kinds_clone = kinds.clone();
+    let tui = TerminalUI::with_sink(TerminalMode::Call, Box::new(io::empty()),
+                                    Box::new(CallbackSink::new(Box::new(move |kind, _| kinds_clone.borrow_mut().push(kind)))));
+    tui.print_results(&vec![MathResult::from(1.0), MathResult::from(2.0)]);
+    tui.print_cmd_ack();
+    assert!(*kinds.borrow() == vec![OutputKind::Result]);
+}
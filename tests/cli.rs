@@ -0,0 +1,70 @@
+mod common;
+
+use std::path::Path;
+use common::{run, run_in_home, run_termc, temp_file, temp_home};
+
+#[test]
+fn tst_call_mode() {
+    assert!(run_termc(&["1+2", "5*7", "cos(pi)"], "") == "3;35;-1\n");
+    assert!(run_termc(&["--define", "g=9.81", "--define=f(x)=x^2", "f(2)", "2*g"], "") == "4;19.62\n");
+    assert!(run_termc(&["-"], "1+2\n5*7\n") == "3;35\n");
+
+    // the evaluation stops at the first error
+    let stdout = run_termc(&["1+1", "pow(2", "2+2"], "");
+    assert!(stdout.contains("In input 2:") && stdout.contains("Error: Expected symbol \")\"."));
+    assert!(!stdout.contains("4"));
+}
+
+#[test]
+fn tst_commands() {
+    // commands change the settings of the following expressions
+    assert!(run_termc(&["format hex", "255", "5.75"], "") == "0xff;0x5.c\n");
+    assert!(run_termc(&["compare modulus", "3+4i > 4"], "") == "1\n");
+    assert!(run_termc(&["precision exact", "1/3 + 1/6"], "") == "1/2\n");
+    assert!(run_termc(&["mode deg", "sin(90)"], "") == "1\n");
+
+    // the exit command stops the evaluation
+    assert!(run_termc(&["1", "exit", "2"], "") == "1\n");
+
+    // commands in batch mode
+    assert!(run_termc(&[], "format oct\n8\nformat dec\n8\n") == "0o10\n8\n");
+    assert!(run_termc(&[], "latex sqrt(x)/2\n") == "\\frac{\\sqrt{x}}{2}\n");
+}
+
+#[test]
+fn tst_save_load() {
+    let path = temp_file("termc_tst_save_load", "context.json");
+    let path = path.to_str().unwrap();
+
+    assert!(run_termc(&["c = 79.882", "f(x) = x^2 + c", &format!("save {0}", path)], "") == "\n");
+    assert!(run_termc(&[&format!("load {0}", path), "f(1)"], "") == "80.882\n");
+    assert!(run_termc(&[], &format!("load {0}\nc\n", path)) == "79.882\n");
+
+    // the file is replaced atomically, no temporary file is left behind
+    assert!(run_termc(&["c = 1", &format!("save {0}", path)], "") == "\n");
+    assert!(run_termc(&[&format!("load {0}", path), "c"], "") == "1\n");
+    assert!(!Path::new(&format!("{0}.tmp", path)).exists());
+}
+
+#[test]
+fn tst_call_mode_settings() {
+    let home = temp_home();
+    let stdout = |args: &[&str], stdin: &str| String::from_utf8(run_in_home(args, stdin, &home).stdout).unwrap();
+    assert!(stdout(&[], "set format hex\n").is_empty());
+
+    // the call mode only applies the stored settings with "--load"
+    assert!(stdout(&["255"], "") == "255\n");
+    assert!(stdout(&["--load", "255"], "") == "0xff\n");
+    assert!(stdout(&["--load", "--sandbox", "255"], "") == "255\n");
+    assert!(stdout(&[], "255\n") == "0xff\n");
+}
+
+#[test]
+fn tst_exit_codes() {
+    assert!(run(&["1+2"], "").status.code() == Some(0));
+    assert!(run(&[], "1+2\n").status.code() == Some(0));
+
+    // the timeout aborts a long evaluation
+    let output = run(&["--timeout", "0.2", "sum(sum(k, k, 1, 1000000), j, 1, 1000000)"], "");
+    assert!(output.status.code() == Some(124));
+}
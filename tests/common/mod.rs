@@ -0,0 +1,53 @@
+#![allow(dead_code)]
+
+use std::env;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts the home directories created by this test binary.
+static HOME_COUNTER : AtomicUsize = AtomicUsize::new(0);
+
+/// Creates a fresh home directory, so that the settings, the history and the saved contexts of the user
+/// (or of other tests) do not affect the test.
+pub fn temp_home() -> PathBuf {
+    let n = HOME_COUNTER.fetch_add(1, Ordering::SeqCst);
+    let dir = env::temp_dir().join(format!("termc_tst_home_{0}_{1}", std::process::id(), n));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the termc binary with the specified arguments and stdin in a fresh home directory and returns its output.
+pub fn run(args: &[&str], stdin: &str) -> Output {
+    run_in_home(args, stdin, &temp_home())
+}
+
+/// Runs the termc binary with the specified arguments and stdin in the specified home directory and returns its output.
+pub fn run_in_home(args: &[&str], stdin: &str, home: &Path) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_termc"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("XDG_DATA_HOME", home.join(".local").join("share"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Runs the termc binary with the specified arguments and stdin and returns its stdout.
+pub fn run_termc(args: &[&str], stdin: &str) -> String {
+    String::from_utf8(run(args, stdin).stdout).unwrap()
+}
+
+/// Returns the path of the specified file in a temporary directory of the specified test.
+pub fn temp_file(test: &str, name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(test);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
@@ -0,0 +1,17 @@
+mod common;
+
+use common::run_termc;
+
+#[test]
+fn tst_call_mode_output() {
+    // assignments do not add results, the results are printed on a single line
+    assert!(run_termc(&["x=2", "x^2", "3*x"], "") == "4;6\n");
+    assert!(run_termc(&["x=2"], "") == "\n");
+}
+
+#[test]
+fn tst_batch_mode_output() {
+    // assignments and empty lines print nothing, each result is printed on a separate line
+    assert!(run_termc(&[], "x = 2\n\nx^10\nf(y) = y + 1\nf(x)\n") == "1024\n3\n");
+    assert!(run_termc(&[], "x = 2\n") == "");
+}